@@ -0,0 +1,53 @@
+// lexes the egos2k sources concatenated 1x..16x. throughput (bytes/s) should
+// stay flat as the input grows if the lexer is linear in its input.
+//   cargo bench --bench lexer
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use picoc089::lexer::{LexOpts, Lexer};
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+
+const EGOS_DIR: &str = "tests/fixtures/smoke/egos2k";
+
+fn sources(dir: &Path, out: &mut Vec<String>) {
+    let mut entries = fs::read_dir(dir)
+        .expect("egos2k dne")
+        .map(|e| e.unwrap().path())
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    for p in entries {
+        if p.is_dir() {
+            sources(&p, out);
+        } else if matches!(p.extension().and_then(|e| e.to_str()), Some("c" | "h")) {
+            out.push(String::from_utf8_lossy(&fs::read(&p).unwrap()).into_owned());
+        }
+    }
+}
+
+fn lex(c: &mut Criterion) {
+    let mut srcs = vec![];
+    sources(Path::new(EGOS_DIR), &mut srcs);
+    let egos = srcs.join("\n");
+
+    // the sources are lexed raw, without preprocessing, so the
+    // stream carries errors. they are counted like tokens: the lexer skips
+    // the offending character and keeps going either way
+    let opts = LexOpts {
+        line_comments: true,
+        ..LexOpts::default()
+    };
+
+    let mut group = c.benchmark_group("lex_egos2k");
+    for scale in [1, 2, 4, 8, 16] {
+        let input = egos.repeat(scale);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(scale), &input, |b, input| {
+            b.iter(|| Lexer::new(black_box(input), 0, opts).count())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lex);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+/* float.h: characteristics of floating types (C89 2.2.4.2.2) for RV32.
+   float and double are IEEE 754 binary32 and binary64, and long double is
+   a double */
+#ifndef __PICOC_FLOAT_H
+#define __PICOC_FLOAT_H
+
+#define FLT_RADIX 2
+#define FLT_ROUNDS 1
+
+#define FLT_MANT_DIG 24
+#define FLT_DIG 6
+#define FLT_EPSILON 1.19209290E-07F
+#define FLT_MIN_EXP (-125)
+#define FLT_MIN 1.17549435E-38F
+#define FLT_MIN_10_EXP (-37)
+#define FLT_MAX_EXP 128
+#define FLT_MAX 3.40282347E+38F
+#define FLT_MAX_10_EXP 38
+
+#define DBL_MANT_DIG 53
+#define DBL_DIG 15
+#define DBL_EPSILON 2.2204460492503131E-16
+#define DBL_MIN_EXP (-1021)
+#define DBL_MIN 2.2250738585072014E-308
+#define DBL_MIN_10_EXP (-307)
+#define DBL_MAX_EXP 1024
+#define DBL_MAX 1.7976931348623157E+308
+#define DBL_MAX_10_EXP 308
+
+#define LDBL_MANT_DIG DBL_MANT_DIG
+#define LDBL_DIG DBL_DIG
+#define LDBL_EPSILON 2.2204460492503131E-16L
+#define LDBL_MIN_EXP DBL_MIN_EXP
+#define LDBL_MIN 2.2250738585072014E-308L
+#define LDBL_MIN_10_EXP DBL_MIN_10_EXP
+#define LDBL_MAX_EXP DBL_MAX_EXP
+#define LDBL_MAX 1.7976931348623157E+308L
+#define LDBL_MAX_10_EXP DBL_MAX_10_EXP
+
+#endif
@@ -0,0 +1,27 @@
+/* limits.h: sizes of integral types (C89 2.2.4.2.1) for RV32 (ILP32).
+   plain char is unsigned */
+#ifndef __PICOC_LIMITS_H
+#define __PICOC_LIMITS_H
+
+#define CHAR_BIT 8
+#define MB_LEN_MAX 1
+
+#define SCHAR_MIN (-128)
+#define SCHAR_MAX 127
+#define UCHAR_MAX 255
+#define CHAR_MIN 0
+#define CHAR_MAX UCHAR_MAX
+
+#define SHRT_MIN (-32767 - 1)
+#define SHRT_MAX 32767
+#define USHRT_MAX 65535
+
+#define INT_MIN (-2147483647 - 1)
+#define INT_MAX 2147483647
+#define UINT_MAX 4294967295U
+
+#define LONG_MIN (-2147483647L - 1)
+#define LONG_MAX 2147483647L
+#define ULONG_MAX 4294967295UL
+
+#endif
@@ -0,0 +1,19 @@
+/* stdarg.h: variable arguments (C89 4.8) for RV32 (ILP32).
+   a variadic function spills a0-a7 next to its stack arguments, so every
+   argument is one word-aligned slot in memory after the last named one.
+   doubles take two words starting at an even one */
+#ifndef __PICOC_STDARG_H
+#define __PICOC_STDARG_H
+
+typedef char *va_list;
+
+#define __va_size(type) ((sizeof(type) + 3) & ~3)
+#define __va_align(ap, type) \
+    ((char *)(((unsigned long)(ap) + __va_size(type) - 1) & ~(unsigned long)(__va_size(type) - 1)))
+
+#define va_start(ap, last) ((ap) = (char *)&(last) + __va_size(last))
+#define va_arg(ap, type) \
+    ((ap) = __va_align(ap, type) + __va_size(type), *(type *)((ap) - __va_size(type)))
+#define va_end(ap) ((void)0)
+
+#endif
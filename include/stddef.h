@@ -0,0 +1,15 @@
+/* stddef.h: common definitions (C89 4.1.5) for RV32 (ILP32) */
+#ifndef __PICOC_STDDEF_H
+#define __PICOC_STDDEF_H
+
+typedef int ptrdiff_t;
+#ifndef __PICOC_SIZE_T
+#define __PICOC_SIZE_T
+typedef unsigned int size_t;
+#endif
+typedef int wchar_t;
+
+#define NULL ((void *)0)
+#define offsetof(type, member) ((size_t)&((type *)0)->member)
+
+#endif
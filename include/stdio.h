@@ -0,0 +1,36 @@
+/* stdio.h: input/output (C89 4.9), the part picoc's runtime provides.
+   streams are unbuffered and map to file descriptors 0, 1 and 2 */
+#ifndef __PICOC_STDIO_H
+#define __PICOC_STDIO_H
+
+#ifndef __PICOC_SIZE_T
+#define __PICOC_SIZE_T
+typedef unsigned int size_t;
+#endif
+
+typedef struct __picoc_file FILE;
+
+#define NULL ((void *)0)
+#define EOF (-1)
+
+extern FILE *stdin;
+extern FILE *stdout;
+extern FILE *stderr;
+
+/* the va_list arguments are char * (see stdarg.h) */
+int printf(const char *format, ...);
+int fprintf(FILE *stream, const char *format, ...);
+int sprintf(char *s, const char *format, ...);
+int vprintf(const char *format, char *arg);
+int vfprintf(FILE *stream, const char *format, char *arg);
+int vsprintf(char *s, const char *format, char *arg);
+
+int fputc(int c, FILE *stream);
+int fputs(const char *s, FILE *stream);
+int putchar(int c);
+int puts(const char *s);
+int fgetc(FILE *stream);
+int getchar(void);
+int fflush(FILE *stream);
+
+#endif
@@ -0,0 +1,34 @@
+/* stdlib.h: general utilities (C89 4.10), the part picoc's runtime
+   provides */
+#ifndef __PICOC_STDLIB_H
+#define __PICOC_STDLIB_H
+
+#ifndef __PICOC_SIZE_T
+#define __PICOC_SIZE_T
+typedef unsigned int size_t;
+#endif
+
+#define NULL ((void *)0)
+#define EXIT_SUCCESS 0
+#define EXIT_FAILURE 1
+#define RAND_MAX 32767
+
+void *malloc(size_t size);
+void *calloc(size_t nmemb, size_t size);
+void *realloc(void *ptr, size_t size);
+void free(void *ptr);
+
+int atoi(const char *nptr);
+long atol(const char *nptr);
+long strtol(const char *nptr, char **endptr, int base);
+int abs(int j);
+long labs(long j);
+int rand(void);
+void srand(unsigned int seed);
+
+void qsort(void *base, size_t nmemb, size_t size, int (*compar)(const void *, const void *));
+
+void exit(int status);
+void abort(void);
+
+#endif
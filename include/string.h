@@ -0,0 +1,30 @@
+/* string.h: string handling (C89 4.11), the part picoc's runtime
+   provides */
+#ifndef __PICOC_STRING_H
+#define __PICOC_STRING_H
+
+#ifndef __PICOC_SIZE_T
+#define __PICOC_SIZE_T
+typedef unsigned int size_t;
+#endif
+
+#define NULL ((void *)0)
+
+void *memcpy(void *s1, const void *s2, size_t n);
+void *memmove(void *s1, const void *s2, size_t n);
+void *memset(void *s, int c, size_t n);
+int memcmp(const void *s1, const void *s2, size_t n);
+void *memchr(const void *s, int c, size_t n);
+
+size_t strlen(const char *s);
+char *strcpy(char *s1, const char *s2);
+char *strncpy(char *s1, const char *s2, size_t n);
+char *strcat(char *s1, const char *s2);
+char *strncat(char *s1, const char *s2, size_t n);
+int strcmp(const char *s1, const char *s2);
+int strncmp(const char *s1, const char *s2, size_t n);
+char *strchr(const char *s, int c);
+char *strrchr(const char *s, int c);
+char *strstr(const char *s1, const char *s2);
+
+#endif
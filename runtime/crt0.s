@@ -0,0 +1,13 @@
+# program entry under the proxy kernel: argc is at 0(sp) and argv follows
+# it. main's return value goes to exit, which never returns
+.text
+.globl _start
+_start:
+.option push
+.option norelax
+la gp, __global_pointer$
+.option pop
+lw a0, 0(sp)
+addi a1, sp, 4
+call main
+call exit
@@ -0,0 +1,478 @@
+/* input/output (C89 4.9). streams are file descriptors without buffers.
+   the printf family formats through a sink that collects output and writes
+   it out in chunks */
+#include <float.h>
+#include <stdarg.h>
+#include <stdio.h>
+#include <string.h>
+#include "syscall.h"
+
+struct __picoc_file {
+    int fd;
+};
+
+static FILE files[3] = {{0}, {1}, {2}};
+FILE *stdin = &files[0];
+FILE *stdout = &files[1];
+FILE *stderr = &files[2];
+
+int fputc(int c, FILE *stream)
+{
+    unsigned char b;
+
+    b = (unsigned char)c;
+    if (__picoc_syscall(SYS_write, stream->fd, (long)&b, 1) != 1)
+        return EOF;
+    return b;
+}
+
+int fputs(const char *s, FILE *stream)
+{
+    long n;
+
+    n = strlen(s);
+    if (__picoc_syscall(SYS_write, stream->fd, (long)s, n) != n)
+        return EOF;
+    return 0;
+}
+
+int putchar(int c)
+{
+    return fputc(c, stdout);
+}
+
+int puts(const char *s)
+{
+    if (fputs(s, stdout) == EOF)
+        return EOF;
+    return fputc('\n', stdout);
+}
+
+int fgetc(FILE *stream)
+{
+    unsigned char b;
+
+    if (__picoc_syscall(SYS_read, stream->fd, (long)&b, 1) != 1)
+        return EOF;
+    return b;
+}
+
+int getchar(void)
+{
+    return fgetc(stdin);
+}
+
+int fflush(FILE *stream)
+{
+    return 0;
+}
+
+/* where formatted output goes: a stream (through buf) or the string s */
+struct sink {
+    FILE *stream;
+    char *s;
+    char buf[128];
+    int len;
+    int n; /* characters produced so far */
+};
+
+static void flush(struct sink *out)
+{
+    if (out->stream != NULL && out->len > 0)
+        __picoc_syscall(SYS_write, out->stream->fd, (long)out->buf, out->len);
+    out->len = 0;
+}
+
+static void put(struct sink *out, int c)
+{
+    if (out->stream == NULL) {
+        out->s[out->n] = (char)c;
+    } else {
+        if (out->len == sizeof(out->buf))
+            flush(out);
+        out->buf[out->len++] = (char)c;
+    }
+    out->n++;
+}
+
+#define LEFT 1
+#define ZERO 2
+#define PLUS 4
+#define SPACE 8
+#define ALT 16
+
+/* a conversion specification: flags, minimum width and precision (-1 if
+   none was given) */
+struct spec {
+    int flags;
+    int width;
+    int prec;
+};
+
+/* writes prefix (sign, 0x) and then s, padded to the field width */
+static void field(struct sink *out, struct spec *sp, const char *prefix, const char *s, int n)
+{
+    int pad;
+
+    pad = sp->width - (int)strlen(prefix) - n;
+    if (!(sp->flags & (LEFT | ZERO)))
+        for (; pad > 0; pad--)
+            put(out, ' ');
+    for (; *prefix; prefix++)
+        put(out, *prefix);
+    if ((sp->flags & (LEFT | ZERO)) == ZERO)
+        for (; pad > 0; pad--)
+            put(out, '0');
+    for (; n > 0; n--)
+        put(out, *s++);
+    for (; pad > 0; pad--)
+        put(out, ' ');
+}
+
+static const char *sign(struct spec *sp, int neg)
+{
+    if (neg)
+        return "-";
+    if (sp->flags & PLUS)
+        return "+";
+    if (sp->flags & SPACE)
+        return " ";
+    return "";
+}
+
+/* %d %i %u %o %x %X. the precision is the minimum number of digits, and
+   the value 0 with precision 0 has none */
+static void integer(struct sink *out, struct spec *sp, unsigned long v, int neg, int base, int conv)
+{
+    const char *digits;
+    const char *prefix;
+    char buf[72];
+    int n;
+    int prec;
+
+    digits = conv == 'X' ? "0123456789ABCDEF" : "0123456789abcdef";
+    prec = sp->prec < 0 ? 1 : sp->prec;
+    if (prec > 64)
+        prec = 64;
+
+    n = sizeof(buf);
+    for (; v != 0; v /= base)
+        buf[--n] = digits[v % base];
+    while ((int)sizeof(buf) - n < prec)
+        buf[--n] = '0';
+    if ((sp->flags & ALT) && base == 8 && (n == sizeof(buf) || buf[n] != '0'))
+        buf[--n] = '0';
+
+    prefix = sign(sp, neg);
+    if ((sp->flags & ALT) && base == 16 && n != sizeof(buf) && conv != 'p')
+        prefix = conv == 'X' ? "0X" : "0x";
+    if (conv == 'p')
+        prefix = "0x";
+    if (sp->prec >= 0)
+        sp->flags &= ~ZERO;
+    field(out, sp, prefix, buf + n, sizeof(buf) - n);
+}
+
+/* the digits of v (finite, >= 0) with prec of them after the point, in %f
+   style or, with exp non-null, in %e style where *exp gets the exponent.
+   digits come out of repeated scaling, so the last few of a long expansion
+   can be off, and halfway cases round up rather than to even */
+static int fdigits(char *buf, double v, int prec, int *exp, int alt)
+{
+    double scale;
+    double round;
+    int n;
+    int e;
+    int d;
+    int i;
+
+    e = 0;
+    if (exp != NULL && v != 0) {
+        for (; v >= 10; e++)
+            v /= 10;
+        for (; v < 1; e--)
+            v *= 10;
+    }
+
+    round = 0.5;
+    for (i = 0; i < prec; i++)
+        round /= 10;
+    v += round;
+    if (exp != NULL && v >= 10) {
+        v /= 10;
+        e++;
+    }
+
+    scale = 1;
+    while (scale * 10 <= v)
+        scale *= 10;
+
+    n = 0;
+    for (; scale >= 1; scale /= 10) {
+        d = (int)(v / scale);
+        if (d > 9)
+            d = 9;
+        buf[n++] = (char)('0' + d);
+        v -= d * scale;
+    }
+    if (prec > 0 || alt)
+        buf[n++] = '.';
+    for (i = 0; i < prec; i++) {
+        v *= 10;
+        d = (int)v;
+        if (d > 9)
+            d = 9;
+        buf[n++] = (char)('0' + d);
+        v -= d;
+    }
+
+    if (exp != NULL)
+        *exp = e;
+    return n;
+}
+
+/* %f %e %E %g %G */
+static void floating(struct sink *out, struct spec *sp, double v, int conv)
+{
+    char buf[400];
+    int neg;
+    int prec;
+    int n;
+    int e;
+    int lower;
+
+    lower = conv == 'f' || conv == 'e' || conv == 'g';
+    neg = v < 0;
+    if (neg)
+        v = -v;
+    if (v != v || v > DBL_MAX) {
+        sp->flags &= ~ZERO;
+        field(out, sp, sign(sp, neg && v == v), v != v ? (lower ? "nan" : "NAN") : (lower ? "inf" : "INF"), 3);
+        return;
+    }
+
+    prec = sp->prec < 0 ? 6 : sp->prec;
+    if (prec > 80)
+        prec = 80;
+
+    /* %g is %e when the exponent is below -4 or not below the precision,
+       and %f otherwise. trailing zeros go unless # is given */
+    if (conv == 'g' || conv == 'G') {
+        if (prec == 0)
+            prec = 1;
+        fdigits(buf, v, prec - 1, &e, 0);
+        if (e < -4 || e >= prec) {
+            prec -= 1;
+            conv = lower ? 'e' : 'E';
+        } else {
+            prec -= e + 1;
+            conv = 'f';
+        }
+        if (!(sp->flags & ALT)) {
+            n = conv == 'f' ? fdigits(buf, v, prec, NULL, 0) : fdigits(buf, v, prec, &e, 0);
+            for (; prec > 0 && buf[n - 1] == '0'; prec--)
+                n--;
+        }
+    }
+
+    if (conv == 'f') {
+        n = fdigits(buf, v, prec, NULL, sp->flags & ALT);
+    } else {
+        n = fdigits(buf, v, prec, &e, sp->flags & ALT);
+        buf[n++] = conv == 'e' ? 'e' : 'E';
+        buf[n++] = e < 0 ? '-' : '+';
+        if (e < 0)
+            e = -e;
+        if (e >= 100)
+            buf[n++] = (char)('0' + e / 100);
+        buf[n++] = (char)('0' + e / 10 % 10);
+        buf[n++] = (char)('0' + e % 10);
+    }
+
+    field(out, sp, sign(sp, neg), buf, n);
+}
+
+static int format(struct sink *out, const char *fmt, va_list ap)
+{
+    struct spec sp;
+    const char *s;
+    long v;
+    unsigned long u;
+    char c;
+    int n, h;
+
+    for (; *fmt; fmt++) {
+        if (*fmt != '%') {
+            put(out, *fmt);
+            continue;
+        }
+
+        sp.flags = 0;
+        for (;; fmt++) {
+            if (fmt[1] == '-')
+                sp.flags |= LEFT;
+            else if (fmt[1] == '0')
+                sp.flags |= ZERO;
+            else if (fmt[1] == '+')
+                sp.flags |= PLUS;
+            else if (fmt[1] == ' ')
+                sp.flags |= SPACE;
+            else if (fmt[1] == '#')
+                sp.flags |= ALT;
+            else
+                break;
+        }
+
+        /* a * takes the width or precision from the arguments. a negative
+           width is the - flag, a negative precision is none */
+        sp.width = 0;
+        if (fmt[1] == '*') {
+            fmt++;
+            sp.width = va_arg(ap, int);
+            if (sp.width < 0) {
+                sp.flags |= LEFT;
+                sp.width = -sp.width;
+            }
+        }
+        for (; fmt[1] >= '0' && fmt[1] <= '9'; fmt++)
+            sp.width = sp.width * 10 + fmt[1] - '0';
+
+        sp.prec = -1;
+        if (fmt[1] == '.') {
+            fmt++;
+            sp.prec = 0;
+            if (fmt[1] == '*') {
+                fmt++;
+                sp.prec = va_arg(ap, int);
+            }
+            for (; fmt[1] >= '0' && fmt[1] <= '9'; fmt++)
+                sp.prec = sp.prec * 10 + fmt[1] - '0';
+        }
+
+        /* int and long are the same width, and long double is a double. a
+           short arrives promoted to int, and h converts it back */
+        h = fmt[1] == 'h';
+        if (fmt[1] == 'h' || fmt[1] == 'l' || fmt[1] == 'L')
+            fmt++;
+
+        fmt++;
+        switch (*fmt) {
+        case 'd':
+        case 'i':
+            v = va_arg(ap, long);
+            if (h)
+                v = (short)v;
+            integer(out, &sp, v < 0 ? -(unsigned long)v : (unsigned long)v, v < 0, 10, *fmt);
+            break;
+        case 'u':
+        case 'o':
+        case 'x':
+        case 'X':
+            u = va_arg(ap, unsigned long);
+            if (h)
+                u = (unsigned short)u;
+            integer(out, &sp, u, 0, *fmt == 'u' ? 10 : *fmt == 'o' ? 8 : 16, *fmt);
+            break;
+        case 'p':
+            integer(out, &sp, (unsigned long)va_arg(ap, void *), 0, 16, *fmt);
+            break;
+        case 'c':
+            c = (char)va_arg(ap, int);
+            sp.flags &= ~ZERO;
+            field(out, &sp, "", &c, 1);
+            break;
+        case 's':
+            s = va_arg(ap, const char *);
+            if (s == NULL)
+                s = "(null)";
+            for (n = 0; s[n] && (sp.prec < 0 || n < sp.prec); n++)
+                ;
+            sp.flags &= ~ZERO;
+            field(out, &sp, "", s, n);
+            break;
+        case 'f':
+        case 'e':
+        case 'E':
+        case 'g':
+        case 'G':
+            floating(out, &sp, va_arg(ap, double), *fmt);
+            break;
+        case '%':
+            put(out, '%');
+            break;
+        default: /* undefined: the specification is written out as is */
+            put(out, '%');
+            if (*fmt == '\0')
+                return out->n;
+            put(out, *fmt);
+        }
+    }
+
+    return out->n;
+}
+
+int vfprintf(FILE *stream, const char *format_, va_list arg)
+{
+    struct sink out;
+    int n;
+
+    out.stream = stream;
+    out.s = NULL;
+    out.len = 0;
+    out.n = 0;
+    n = format(&out, format_, arg);
+    flush(&out);
+    return n;
+}
+
+int vprintf(const char *format_, va_list arg)
+{
+    return vfprintf(stdout, format_, arg);
+}
+
+int vsprintf(char *s, const char *format_, va_list arg)
+{
+    struct sink out;
+    int n;
+
+    out.stream = NULL;
+    out.s = s;
+    out.len = 0;
+    out.n = 0;
+    n = format(&out, format_, arg);
+    s[n] = '\0';
+    return n;
+}
+
+int printf(const char *format_, ...)
+{
+    va_list ap;
+    int n;
+
+    va_start(ap, format_);
+    n = vfprintf(stdout, format_, ap);
+    va_end(ap);
+    return n;
+}
+
+int fprintf(FILE *stream, const char *format_, ...)
+{
+    va_list ap;
+    int n;
+
+    va_start(ap, format_);
+    n = vfprintf(stream, format_, ap);
+    va_end(ap);
+    return n;
+}
+
+int sprintf(char *s, const char *format_, ...)
+{
+    va_list ap;
+    int n;
+
+    va_start(ap, format_);
+    n = vsprintf(s, format_, ap);
+    va_end(ap);
+    return n;
+}
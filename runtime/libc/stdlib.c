@@ -0,0 +1,290 @@
+/* general utilities (C89 4.10) */
+#include <limits.h>
+#include <stdlib.h>
+#include <string.h>
+#include "syscall.h"
+
+/* the allocator keeps free blocks in a circular list sorted by address and
+   merges neighbours on free (K&R 8.7). a block is a header followed by its
+   payload, measured in header-sized units so that payloads stay aligned for
+   any type, doubles included */
+union header {
+    struct {
+        union header *next;
+        size_t units; /* header included */
+    } s;
+    double align;
+};
+
+static union header base;
+static union header *freep;
+static char *heap_end;
+
+#define NALLOC 1024 /* smallest number of units to ask the kernel for */
+
+/* grows the heap with brk. the kernel answers with the new break, or the old
+   one when it refuses */
+static void *sbrk(size_t n)
+{
+    char *old;
+
+    if (heap_end == NULL)
+        heap_end = (char *)__picoc_syscall(SYS_brk, 0, 0, 0);
+    old = heap_end;
+    if ((char *)__picoc_syscall(SYS_brk, (long)(old + n), 0, 0) != old + n)
+        return NULL;
+    heap_end = old + n;
+    return old;
+}
+
+static union header *morecore(size_t units)
+{
+    union header *p;
+
+    if (units < NALLOC)
+        units = NALLOC;
+    p = sbrk(units * sizeof(union header));
+    if (p == NULL)
+        return NULL;
+    p->s.units = units;
+    free(p + 1);
+    return freep;
+}
+
+void *malloc(size_t size)
+{
+    union header *p;
+    union header *prev;
+    size_t units;
+
+    units = (size + sizeof(union header) - 1) / sizeof(union header) + 1;
+    if (freep == NULL) {
+        base.s.next = &base;
+        base.s.units = 0;
+        freep = &base;
+    }
+
+    prev = freep;
+    for (p = prev->s.next;; prev = p, p = p->s.next) {
+        if (p->s.units >= units) {
+            if (p->s.units == units) {
+                prev->s.next = p->s.next;
+            } else {
+                p->s.units -= units;
+                p += p->s.units;
+                p->s.units = units;
+            }
+            freep = prev;
+            return p + 1;
+        }
+        if (p == freep && (p = morecore(units)) == NULL)
+            return NULL;
+    }
+}
+
+void free(void *ptr)
+{
+    union header *b;
+    union header *p;
+
+    if (ptr == NULL)
+        return;
+
+    b = (union header *)ptr - 1;
+    for (p = freep; !(b > p && b < p->s.next); p = p->s.next)
+        if (p >= p->s.next && (b > p || b < p->s.next))
+            break; /* at either end of the arena */
+
+    if (b + b->s.units == p->s.next) {
+        b->s.units += p->s.next->s.units;
+        b->s.next = p->s.next->s.next;
+    } else {
+        b->s.next = p->s.next;
+    }
+    if (p + p->s.units == b) {
+        p->s.units += b->s.units;
+        p->s.next = b->s.next;
+    } else {
+        p->s.next = b;
+    }
+    freep = p;
+}
+
+void *calloc(size_t nmemb, size_t size)
+{
+    void *p;
+
+    if (size != 0 && nmemb > (size_t)-1 / size)
+        return NULL;
+    p = malloc(nmemb * size);
+    if (p != NULL)
+        memset(p, 0, nmemb * size);
+    return p;
+}
+
+void *realloc(void *ptr, size_t size)
+{
+    union header *b;
+    size_t old;
+    void *p;
+
+    if (ptr == NULL)
+        return malloc(size);
+    if (size == 0) {
+        free(ptr);
+        return NULL;
+    }
+
+    b = (union header *)ptr - 1;
+    old = (b->s.units - 1) * sizeof(union header);
+    if (size <= old)
+        return ptr;
+
+    p = malloc(size);
+    if (p != NULL) {
+        memcpy(p, ptr, old);
+        free(ptr);
+    }
+    return p;
+}
+
+static int digit(int c)
+{
+    if (c >= '0' && c <= '9')
+        return c - '0';
+    if (c >= 'a' && c <= 'z')
+        return c - 'a' + 10;
+    if (c >= 'A' && c <= 'Z')
+        return c - 'A' + 10;
+    return 36;
+}
+
+/* out-of-range values saturate at LONG_MIN and LONG_MAX. there is no errno */
+long strtol(const char *nptr, char **endptr, int base)
+{
+    const char *s;
+    unsigned long n;
+    unsigned long limit;
+    int neg;
+    int any;
+    int over;
+
+    s = nptr;
+    while (*s == ' ' || (*s >= '\t' && *s <= '\r'))
+        s++;
+    neg = *s == '-';
+    if (*s == '-' || *s == '+')
+        s++;
+
+    if ((base == 0 || base == 16) && s[0] == '0' && (s[1] == 'x' || s[1] == 'X')
+        && digit(s[2]) < 16) {
+        s += 2;
+        base = 16;
+    } else if (base == 0) {
+        base = *s == '0' ? 8 : 10;
+    }
+
+    limit = neg ? (unsigned long)LONG_MAX + 1 : (unsigned long)LONG_MAX;
+    n = 0;
+    any = 0;
+    over = 0;
+    for (; digit(*s) < base; s++) {
+        any = 1;
+        if (n > (limit - digit(*s)) / base)
+            over = 1;
+        else
+            n = n * base + digit(*s);
+    }
+
+    if (endptr != NULL)
+        *endptr = (char *)(any ? s : nptr);
+    if (over)
+        return neg ? LONG_MIN : LONG_MAX;
+    return neg ? -(long)(n - 1) - 1 : (long)n;
+}
+
+long atol(const char *nptr)
+{
+    return strtol(nptr, NULL, 10);
+}
+
+int atoi(const char *nptr)
+{
+    return (int)strtol(nptr, NULL, 10);
+}
+
+int abs(int j)
+{
+    return j < 0 ? -j : j;
+}
+
+long labs(long j)
+{
+    return j < 0 ? -j : j;
+}
+
+/* the portable generator from C89 4.10.2.2 */
+static unsigned long next = 1;
+
+int rand(void)
+{
+    next = next * 1103515245 + 12345;
+    return (unsigned int)(next / 65536) % 32768;
+}
+
+void srand(unsigned int seed)
+{
+    next = seed;
+}
+
+static void swap(char *a, char *b, size_t size)
+{
+    char t;
+
+    while (size--) {
+        t = *a;
+        *a++ = *b;
+        *b++ = t;
+    }
+}
+
+/* quicksort on the middle element, recursing into the smaller side so the
+   stack stays logarithmic */
+void qsort(void *base, size_t nmemb, size_t size, int (*compar)(const void *, const void *))
+{
+    char *a;
+    size_t i;
+    size_t last;
+
+    a = base;
+    while (nmemb > 1) {
+        swap(a, a + nmemb / 2 * size, size);
+        last = 0;
+        for (i = 1; i < nmemb; i++)
+            if (compar(a + i * size, a) < 0)
+                swap(a + ++last * size, a + i * size, size);
+        swap(a, a + last * size, size);
+
+        if (last < nmemb - last - 1) {
+            qsort(a, last, size, compar);
+            a += (last + 1) * size;
+            nmemb -= last + 1;
+        } else {
+            qsort(a + (last + 1) * size, nmemb - last - 1, size, compar);
+            nmemb = last;
+        }
+    }
+}
+
+/* output is unbuffered, so there is nothing to flush */
+void exit(int status)
+{
+    for (;;)
+        __picoc_syscall(SYS_exit, status, 0, 0);
+}
+
+/* the status a shell reports for SIGABRT */
+void abort(void)
+{
+    exit(134);
+}
@@ -0,0 +1,167 @@
+/* string handling (C89 4.11) */
+#include <string.h>
+
+void *memcpy(void *s1, const void *s2, size_t n)
+{
+    char *d;
+    const char *s;
+
+    d = s1;
+    s = s2;
+    while (n--)
+        *d++ = *s++;
+    return s1;
+}
+
+/* copies backwards when the destination starts inside the source */
+void *memmove(void *s1, const void *s2, size_t n)
+{
+    char *d;
+    const char *s;
+
+    d = s1;
+    s = s2;
+    if (d > s && d < s + n) {
+        d += n;
+        s += n;
+        while (n--)
+            *--d = *--s;
+    } else {
+        while (n--)
+            *d++ = *s++;
+    }
+    return s1;
+}
+
+void *memset(void *s, int c, size_t n)
+{
+    unsigned char *d;
+
+    d = s;
+    while (n--)
+        *d++ = (unsigned char)c;
+    return s;
+}
+
+int memcmp(const void *s1, const void *s2, size_t n)
+{
+    const unsigned char *a;
+    const unsigned char *b;
+
+    a = s1;
+    b = s2;
+    for (; n; n--, a++, b++)
+        if (*a != *b)
+            return *a - *b;
+    return 0;
+}
+
+void *memchr(const void *s, int c, size_t n)
+{
+    const unsigned char *p;
+
+    for (p = s; n; n--, p++)
+        if (*p == (unsigned char)c)
+            return (void *)p;
+    return NULL;
+}
+
+size_t strlen(const char *s)
+{
+    const char *p;
+
+    for (p = s; *p; p++)
+        ;
+    return p - s;
+}
+
+char *strcpy(char *s1, const char *s2)
+{
+    char *d;
+
+    d = s1;
+    while ((*d++ = *s2++) != '\0')
+        ;
+    return s1;
+}
+
+/* pads with null characters up to n when s2 is shorter */
+char *strncpy(char *s1, const char *s2, size_t n)
+{
+    char *d;
+
+    d = s1;
+    for (; n && *s2; n--)
+        *d++ = *s2++;
+    for (; n; n--)
+        *d++ = '\0';
+    return s1;
+}
+
+char *strcat(char *s1, const char *s2)
+{
+    strcpy(s1 + strlen(s1), s2);
+    return s1;
+}
+
+char *strncat(char *s1, const char *s2, size_t n)
+{
+    char *d;
+
+    d = s1 + strlen(s1);
+    for (; n && *s2; n--)
+        *d++ = *s2++;
+    *d = '\0';
+    return s1;
+}
+
+int strcmp(const char *s1, const char *s2)
+{
+    for (; *s1 && *s1 == *s2; s1++, s2++)
+        ;
+    return (unsigned char)*s1 - (unsigned char)*s2;
+}
+
+int strncmp(const char *s1, const char *s2, size_t n)
+{
+    for (; n && *s1 && *s1 == *s2; n--, s1++, s2++)
+        ;
+    if (n == 0)
+        return 0;
+    return (unsigned char)*s1 - (unsigned char)*s2;
+}
+
+/* the terminating null character counts as part of the string */
+char *strchr(const char *s, int c)
+{
+    for (;; s++) {
+        if (*s == (char)c)
+            return (char *)s;
+        if (*s == '\0')
+            return NULL;
+    }
+}
+
+char *strrchr(const char *s, int c)
+{
+    const char *last;
+
+    last = NULL;
+    for (;; s++) {
+        if (*s == (char)c)
+            last = s;
+        if (*s == '\0')
+            return (char *)last;
+    }
+}
+
+char *strstr(const char *s1, const char *s2)
+{
+    size_t n;
+
+    n = strlen(s2);
+    for (; *s1; s1++)
+        if (strncmp(s1, s2, n) == 0)
+            return (char *)s1;
+    return n == 0 ? (char *)s1 : NULL;
+}
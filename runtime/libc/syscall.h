@@ -0,0 +1,12 @@
+/* system calls of the proxy kernel (riscv-pk), numbered as on linux */
+#ifndef __PICOC_SYSCALL_H
+#define __PICOC_SYSCALL_H
+
+#define SYS_read 63
+#define SYS_write 64
+#define SYS_exit 93
+#define SYS_brk 214
+
+long __picoc_syscall(long n, long a0, long a1, long a2);
+
+#endif
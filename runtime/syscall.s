@@ -0,0 +1,11 @@
+# long __picoc_syscall(long n, long a0, long a1, long a2)
+# the proxy kernel takes the call number in a7 and returns in a0
+.text
+.globl __picoc_syscall
+__picoc_syscall:
+mv a7, a0
+mv a0, a1
+mv a1, a2
+mv a2, a3
+ecall
+ret
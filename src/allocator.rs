@@ -1,4 +1,4 @@
-use crate::{OptLevel, TQuad, Temp};
+use crate::{Global, IData, OptLevel, PseudoOp, Section, TFmt, TImmOp, TMemOp, TQuad, Temp, Width};
 
 pub fn allocate(abs_as: &[TQuad], opt: OptLevel) -> Vec<String> {
     match opt {
@@ -8,11 +8,101 @@ pub fn allocate(abs_as: &[TQuad], opt: OptLevel) -> Vec<String> {
     }
 }
 
+// the range of addi's immediates and of load and store offsets
+const IMM12: std::ops::Range<i32> = -2048..2048;
+
 const POP_IMM_T0: &str = POP_RIGHT_T0;
 const POP_RIGHT_T0: &str = "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack";
 const POP_LEFT_T1: &str = "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack";
 const PUSH_T2: &str = "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n";
 
+// slots are 8 bytes wide, so a double takes one slot like every other
+// value. its low word sits at the lower address, as it would in memory
+fn pop_f(r: &str, fmt: &TFmt) -> String {
+    let l = if *fmt == TFmt::S { "flw" } else { "fld" };
+    format!("{l} {r}, 0(sp) # {r} <- pop\naddi sp,sp,8 # shrink stack")
+}
+
+fn push_f(r: &str, fmt: &TFmt) -> String {
+    let s = if *fmt == TFmt::S { "fsw" } else { "fsd" };
+    format!("addi sp,sp,-8 # grow stack\n{s} {r}, 0(sp) # push {r} ->\n")
+}
+
+// a value of one or two words into (or out of) consecutive registers
+fn pop_words(r: usize, words: usize, prefix: &str) -> String {
+    match words {
+        1 => format!("lw {prefix}{r}, 0(sp) # {prefix}{r} <- pop\naddi sp,sp,8 # shrink stack"),
+        _ => format!(
+            "lw {prefix}{r}, 0(sp)\nlw {prefix}{}, 4(sp) # {prefix}{r}:{prefix}{} <- pop\naddi sp,sp,8 # shrink stack",
+            r + 1,
+            r + 1
+        ),
+    }
+}
+
+fn push_words(r: usize, words: usize, prefix: &str) -> String {
+    match words {
+        1 => format!("addi sp,sp,-8 # grow stack\nsw {prefix}{r}, 0(sp) # push {prefix}{r} ->\n"),
+        _ => format!(
+            "addi sp,sp,-8 # grow stack\nsw {prefix}{r}, 0(sp)\nsw {prefix}{}, 4(sp) # push {prefix}{r}:{prefix}{} ->\n",
+            r + 1,
+            r + 1
+        ),
+    }
+}
+
+// a call by the given instruction. a call through a pointer finds it under
+// its arguments, and loads it to t1 once they are in place
+fn call(instr: &str, args: &[usize], ret: usize, ptr: bool) -> Vec<String> {
+    let words = args.iter().sum::<usize>();
+    let push = (ret > 0).then(|| push_words(0, ret, "a"));
+    if words > 8 {
+        // the words past a7 go to an area at the bottom of the stack,
+        // where the callee finds them at its fp. each argument is
+        // read from its slot, the last one on top, rather than popped
+        let area = (4 * (words - 8)).next_multiple_of(8);
+        let slots = args.iter().rev().enumerate().map(|(k, w)| (8 * k, *w));
+        let offs = slots
+            .rev()
+            .flat_map(|(slot, w)| (0..w).map(move |j| area + slot + 4 * j))
+            .collect::<Vec<_>>();
+
+        let moves = offs.iter().enumerate().map(|(i, off)| match i {
+            0..8 => format!("lw a{i}, {off}(sp)"),
+            _ => format!("lw t0, {off}(sp)\nsw t0, {}(sp)", 4 * (i - 8)),
+        });
+        let load = ptr.then(|| format!("lw t1, {}(sp)", area + 8 * args.len()));
+        let slots = args.len() + ptr as usize;
+        return vec![format!("addi sp,sp,-{area} # grow stack for arguments")]
+            .into_iter()
+            .chain(moves)
+            .chain(load)
+            .chain(vec![
+                instr.to_owned(),
+                format!("addi sp,sp,{} # drop arguments", area + 8 * slots),
+            ])
+            .chain(push)
+            .collect();
+    }
+
+    // arguments fill a0..a7 in order, two registers for a double
+    let regs = args.iter().scan(0, |next, w| {
+        *next += w;
+        Some(*next - w)
+    });
+    let pops = regs
+        .zip(args)
+        .map(|(r, w)| pop_words(r, *w, "a"))
+        .collect::<Vec<_>>();
+    let load = ptr.then(|| pop_words(1, 1, "t"));
+    pops.into_iter()
+        .rev()
+        .chain(load)
+        .chain(vec![instr.to_owned()])
+        .chain(push)
+        .collect()
+}
+
 fn allocate_1ac(abs_as: &[TQuad]) -> Vec<String> {
     let instrs = abs_as
         .iter()
@@ -44,6 +134,17 @@ fn allocate_1ac(abs_as: &[TQuad]) -> Vec<String> {
                         format!("{} {}, t0, {}", timm_op.to_string(), pr.to_string(), imm),
                     ] // no push b/c we're using pr
                 }
+                // a frame too large for a 12-bit immediate is sized in t0
+                (Temp::PointerReg(dpr), Temp::PointerReg(lpr)) if !IMM12.contains(imm) => {
+                    let op = match timm_op {
+                        TImmOp::AddI => "add",
+                        TImmOp::SubI => "sub",
+                        TImmOp::AndI => "and",
+                        TImmOp::OrI => "or",
+                        TImmOp::XorI => "xor",
+                    };
+                    vec![format!("li t0, {imm}\n{op} {dpr}, {lpr}, t0")]
+                }
                 (Temp::PointerReg(dpr), Temp::PointerReg(lpr)) => vec![format!(
                     "{} {}, {}, {}",
                     timm_op.to_string(),
@@ -52,29 +153,128 @@ fn allocate_1ac(abs_as: &[TQuad]) -> Vec<String> {
                     imm,
                 )],
             },
-            TQuad::Mem(tmem_op, temp, offset, base) => match temp {
-                Temp::UserTemp(_) => todo!(),
-                Temp::MachineTemp(_) => todo!(),
-                Temp::PointerReg(riscv_pointer_reg) => {
-                    vec![format!(
-                        "{} {}, {}({})",
-                        tmem_op.to_string(),
-                        riscv_pointer_reg.to_string(),
-                        offset,
-                        base.to_string()
-                    )]
+            TQuad::Mem(tmem_op, w, temp, offset, base) => match (tmem_op, temp, base) {
+                (_, Temp::PointerReg(pr), Temp::PointerReg(br)) => {
+                    let op = match tmem_op {
+                        TMemOp::Load => w.load(),
+                        TMemOp::Store => w.store(),
+                    };
+                    match IMM12.contains(&(*offset as i32)) {
+                        true => vec![format!("{op} {pr}, {offset}({br})")],
+                        false => vec![format!("li t0, {offset}\nadd t0, {br}, t0\n{op} {pr}, 0(t0)")],
+                    }
                 }
+                // the address is on top of the stack, above the value to store
+                (TMemOp::Load, Temp::MachineTemp(_), Temp::MachineTemp(_)) => match w {
+                    Width::D => vec![
+                        POP_RIGHT_T0.to_owned(),
+                        format!("lw t1, {offset}(t0)\nlw t2, {}(t0) # load", offset + 4),
+                        push_words(1, 2, "t"),
+                    ],
+                    _ => vec![
+                        POP_RIGHT_T0.to_owned(),
+                        format!("{} t2, {offset}(t0) # load", w.load()),
+                        PUSH_T2.to_owned(),
+                    ],
+                },
+                (TMemOp::Store, Temp::MachineTemp(_), Temp::MachineTemp(_)) => match w {
+                    Width::D => vec![
+                        POP_RIGHT_T0.to_owned(),
+                        pop_words(1, 2, "t"),
+                        format!("sw t1, {offset}(t0)\nsw t2, {}(t0) # store", offset + 4),
+                    ],
+                    _ => vec![
+                        POP_RIGHT_T0.to_owned(),
+                        POP_LEFT_T1.to_owned(),
+                        format!("{} t1, {offset}(t0) # store", w.store()),
+                    ],
+                },
+                _ => todo!(),
             },
+            // the source address is on top of the stack, above the destination.
+            // short copies are unrolled
+            TQuad::Copy(w, n, _dt, _st) => {
+                let b = w.bytes();
+                let copy = match n {
+                    0..=16 => (0..*n)
+                        .map(|i| {
+                            let (l, s) = (w.load(), w.store());
+                            format!("{l} t2, {}(t0)\n{s} t2, {}(t1)", i * b, i * b)
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    _ => format!(
+                        "li t3, {n}\n1: {} t2, 0(t0)\n{} t2, 0(t1)\naddi t0, t0, {b}\naddi t1, t1, {b}\naddi t3, t3, -1\nbnez t3, 1b",
+                        w.load(),
+                        w.store()
+                    ),
+                };
+                vec![
+                    POP_RIGHT_T0.to_owned(),
+                    POP_LEFT_T1.to_owned(),
+                    format!("{copy} # copy"),
+                ]
+            }
+            TQuad::FImm(fmt, _dt, bits) => match fmt {
+                TFmt::S => vec![format!("li t2, {}", *bits as i32), PUSH_T2.to_owned()],
+                TFmt::D => vec![
+                    format!("li t1, {}", *bits as i32),
+                    format!("li t2, {}", (*bits >> 32) as i32),
+                    push_words(1, 2, "t"),
+                ],
+            },
+            TQuad::FReg(tfreg_op, fmt, _dt, _lt, _rt) if tfreg_op.is_cmp() => vec![
+                pop_f("ft0", fmt),
+                pop_f("ft1", fmt),
+                format!("{tfreg_op}.{fmt} t2, ft1, ft0 # compare"),
+                PUSH_T2.to_owned(),
+            ],
+            TQuad::FReg(tfreg_op, fmt, _dt, _lt, _rt) => vec![
+                pop_f("ft0", fmt),
+                pop_f("ft1", fmt),
+                format!("{tfreg_op}.{fmt} ft2, ft1, ft0 # operate"),
+                push_f("ft2", fmt),
+            ],
+            TQuad::FNeg(fmt, _dt, _st) => vec![
+                pop_f("ft0", fmt),
+                format!("fneg.{fmt} ft2, ft0 # negate"),
+                push_f("ft2", fmt),
+            ],
+            TQuad::FCvt(tfcvt_op, _dt, _st) => {
+                let (pop, s) = match tfcvt_op.fmts().0 {
+                    Some(fmt) => (pop_f("ft0", &fmt), "ft0"),
+                    None => (POP_RIGHT_T0.to_owned(), "t0"),
+                };
+                let (push, cvt) = match tfcvt_op.fmts().1 {
+                    Some(fmt) => (push_f("ft2", &fmt), format!("{tfcvt_op} ft2, {s}")),
+                    None => (PUSH_T2.to_owned(), format!("{tfcvt_op} t2, {s}, rtz")),
+                };
+                vec![pop, format!("{cvt} # convert"), push]
+            }
+            TQuad::Call(l, args, ret) => call(&format!("call {l}"), args, *ret, false),
+            TQuad::CallPtr(args, ret) => call("jalr t1", args, *ret, true),
+            TQuad::RetVal(words) => vec![pop_words(0, *words, "a")],
+            TQuad::Jump(l) => vec![format!("j {l}")],
+            TQuad::CJump(_ct, t, f) => vec![
+                POP_RIGHT_T0.to_owned(),
+                format!("bnez t0, {t}\nj {f} # branch"),
+            ],
+            TQuad::Pop(_t) => vec!["addi sp,sp,8 # drop".to_owned()],
+            TQuad::Pseudo(PseudoOp::Li(_dt, imm)) => {
+                vec![format!("li t2, {imm}"), PUSH_T2.to_owned()]
+            }
+            TQuad::Pseudo(PseudoOp::La(_dt, l)) => vec![
+                format!("lui t2, %hi({l})\naddi t2, t2, %lo({l}) # address"),
+                PUSH_T2.to_owned(),
+            ],
             TQuad::Pseudo(pseudo_op) => vec![pseudo_op.to_string()],
             TQuad::Label(l) => vec![format!("{}:", l.to_string())],
+            TQuad::Globl(l) => vec![format!(".globl {l}")],
+            TQuad::Data(g) => data(g),
         })
         .collect::<Vec<_>>();
 
-    let prg_prologue = vec![
-        ".text".to_owned(),
-        ".globl main".to_owned(),
-        ".section .text".to_owned(),
-    ];
+    let prg_prologue = vec![".text".to_owned(), ".section .text".to_owned()];
 
     let prg_epilogue = vec!["\n".to_owned()];
 
@@ -85,6 +285,48 @@ fn allocate_1ac(abs_as: &[TQuad]) -> Vec<String> {
         .collect()
 }
 
+// a global in its section, after which the text section resumes
+fn data(g: &Global) -> Vec<String> {
+    let section = match g.section {
+        Section::Data => ".data",
+        Section::Bss => ".bss",
+        Section::RoData => ".section .rodata",
+    };
+    let l = &g.label;
+    let export = g.exported.then(|| format!(".globl {l}"));
+    let contents = g.data.iter().map(|d| match d {
+        IData::Value(w, n) => match w.bytes() {
+            1 => format!(".byte {}", *n as u8),
+            2 => format!(".half {}", *n as u16),
+            _ => format!(".word {}", *n as u32),
+        },
+        IData::Ascii(bytes) => format!(".ascii \"{}\"", ascii(bytes)),
+        IData::Zero(n) => format!(".zero {n}"),
+        IData::Addr(l, 0) => format!(".word {l}"),
+        IData::Addr(l, off) => format!(".word {l}{off:+}"),
+    });
+
+    [section.to_owned()]
+        .into_iter()
+        .chain(export)
+        .chain([format!(".balign {}", g.align), format!("{l}:")])
+        .chain(contents)
+        .chain([".text".to_owned()])
+        .collect()
+}
+
+// octal escapes, since a hex one would run into the digits after it
+fn ascii(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| match b {
+            b'"' | b'\\' => format!("\\{}", *b as char),
+            b' '..=b'~' => (*b as char).to_string(),
+            b => format!("\\{b:03o}"),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test_arith {
     use crate::lexer;
@@ -92,29 +334,26 @@ mod test_arith {
     use crate::selector;
     use crate::translator;
     use crate::typer;
+    use crate::FloatMode;
     use std::fs;
 
     const TEST_DIR: &str = "tests/fixtures/snap/shared/arith";
 
     #[test]
     fn add() {
-        let chars = fs::read(format!("{TEST_DIR}/add.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/add.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let src_tree = parser_ast::parse_prg(&tokens).unwrap();
-        let _ = typer::type_prg(&src_tree).unwrap();
-        let trgt_tree = translator::translate(&src_tree);
-        let abs_as = selector::select(&trgt_tree);
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = translator::translate(&typed_tree).unwrap();
+        let abs_as = selector::select(&trgt_tree, FloatMode::Soft);
         let assembly = super::allocate(&abs_as, super::OptLevel::O0);
         insta::assert_yaml_snapshot!(assembly, @r###"
         ---
         - ".text"
-        - ".globl main"
         - ".section .text"
+        - ".globl main"
         - "main:"
         - "addi sp, sp, -16"
         - "sw ra, 12(sp)"
@@ -128,8 +367,103 @@ mod test_arith {
         - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
         - "add t2, t1, t0 # operate"
         - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw a0, 0(sp) # a0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw ra, 12(sp)"
+        - "lw fp, 8(sp)"
+        - "addi sp, sp, 16"
+        - ret
+        - "\n"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_floats {
+    use crate::lexer;
+    use crate::parser_ast;
+    use crate::selector;
+    use crate::translator;
+    use crate::typer;
+    use crate::FloatMode;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/floats";
+
+    fn compile(name: &str, fm: FloatMode) -> Vec<String> {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/{name}")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = translator::translate(&typed_tree).unwrap();
+        let abs_as = selector::select(&trgt_tree, fm);
+        super::allocate(&abs_as, super::OptLevel::O0)
+    }
+
+    #[test]
+    fn soft() {
+        insta::assert_yaml_snapshot!(compile("mixed.c", FloatMode::Soft), @r###"
+        ---
+        - ".text"
+        - ".section .text"
+        - ".globl main"
+        - "main:"
+        - "addi sp, sp, -16"
+        - "sw ra, 12(sp)"
+        - "sw fp, 8(sp)"
+        - "addi fp, sp, 16"
+        - "li t1, 0"
+        - "li t2, 1073217536"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "addi t2, zero, 2"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw a0, 0(sp) # a0 <- pop\naddi sp,sp,8 # shrink stack"
+        - call __floatsidf
+        - "addi sp,sp,-8 # grow stack\nsw a0, 0(sp)\nsw a1, 4(sp) # push a0:a1 ->\n"
+        - "lw a2, 0(sp)\nlw a3, 4(sp) # a2:a3 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw a0, 0(sp)\nlw a1, 4(sp) # a0:a1 <- pop\naddi sp,sp,8 # shrink stack"
+        - call __adddf3
+        - "addi sp,sp,-8 # grow stack\nsw a0, 0(sp)\nsw a1, 4(sp) # push a0:a1 ->\n"
+        - "lw a0, 0(sp)\nlw a1, 4(sp) # a0:a1 <- pop\naddi sp,sp,8 # shrink stack"
+        - call __fixdfsi
+        - "addi sp,sp,-8 # grow stack\nsw a0, 0(sp) # push a0 ->\n"
+        - "lw a0, 0(sp) # a0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw ra, 12(sp)"
+        - "lw fp, 8(sp)"
+        - "addi sp, sp, 16"
+        - ret
+        - "\n"
+        "###);
+    }
+
+    #[test]
+    fn hard() {
+        insta::assert_yaml_snapshot!(compile("mixed.c", FloatMode::Hard), @r###"
+        ---
+        - ".text"
+        - ".section .text"
+        - ".globl main"
+        - "main:"
+        - "addi sp, sp, -16"
+        - "sw ra, 12(sp)"
+        - "sw fp, 8(sp)"
+        - "addi fp, sp, 16"
+        - "li t1, 0"
+        - "li t2, 1073217536"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "addi t2, zero, 2"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
         - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
-        - "addi a0, t0, 0"
+        - "fcvt.d.w ft2, t0 # convert"
+        - "addi sp,sp,-8 # grow stack\nfsd ft2, 0(sp) # push ft2 ->\n"
+        - "fld ft0, 0(sp) # ft0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fld ft1, 0(sp) # ft1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fadd.d ft2, ft1, ft0 # operate"
+        - "addi sp,sp,-8 # grow stack\nfsd ft2, 0(sp) # push ft2 ->\n"
+        - "fld ft0, 0(sp) # ft0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fcvt.w.d t2, ft0, rtz # convert"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw a0, 0(sp) # a0 <- pop\naddi sp,sp,8 # shrink stack"
         - "lw ra, 12(sp)"
         - "lw fp, 8(sp)"
         - "addi sp, sp, 16"
@@ -137,4 +471,506 @@ mod test_arith {
         - "\n"
         "###);
     }
+
+    #[test]
+    fn hard_single() {
+        insta::assert_yaml_snapshot!(compile("single.c", FloatMode::Hard), @r###"
+        ---
+        - ".text"
+        - ".section .text"
+        - ".globl main"
+        - "main:"
+        - "addi sp, sp, -16"
+        - "sw ra, 12(sp)"
+        - "sw fp, 8(sp)"
+        - "addi fp, sp, 16"
+        - "li t2, 1075838976"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "li t2, 1073741824"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "flw ft0, 0(sp) # ft0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "flw ft1, 0(sp) # ft1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fmul.s ft2, ft1, ft0 # operate"
+        - "addi sp,sp,-8 # grow stack\nfsw ft2, 0(sp) # push ft2 ->\n"
+        - "addi t2, zero, 1"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fcvt.s.wu ft2, t0 # convert"
+        - "addi sp,sp,-8 # grow stack\nfsw ft2, 0(sp) # push ft2 ->\n"
+        - "flw ft0, 0(sp) # ft0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "flw ft1, 0(sp) # ft1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fsub.s ft2, ft1, ft0 # operate"
+        - "addi sp,sp,-8 # grow stack\nfsw ft2, 0(sp) # push ft2 ->\n"
+        - "flw ft0, 0(sp) # ft0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fcvt.w.s t2, ft0, rtz # convert"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw a0, 0(sp) # a0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw ra, 12(sp)"
+        - "lw fp, 8(sp)"
+        - "addi sp, sp, 16"
+        - ret
+        - "\n"
+        "###);
+    }
+
+    #[test]
+    fn soft_compare() {
+        insta::assert_yaml_snapshot!(compile("compare.c", FloatMode::Soft), @r###"
+        ---
+        - ".text"
+        - ".section .text"
+        - ".globl main"
+        - "main:"
+        - "addi sp, sp, -16"
+        - "sw ra, 12(sp)"
+        - "sw fp, 8(sp)"
+        - "addi fp, sp, 16"
+        - "li t1, 0"
+        - "li t2, 1071644672"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "lw a0, 0(sp)\nlw a1, 4(sp) # a0:a1 <- pop\naddi sp,sp,8 # shrink stack"
+        - call __negdf2
+        - "addi sp,sp,-8 # grow stack\nsw a0, 0(sp)\nsw a1, 4(sp) # push a0:a1 ->\n"
+        - "addi t2, fp, -16"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp)\nlw t2, 4(sp) # t1:t2 <- pop\naddi sp,sp,8 # shrink stack"
+        - "sw t1, 0(t0)\nsw t2, 4(t0) # store"
+        - "addi t2, fp, -16"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(t0)\nlw t2, 4(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "li t1, 0"
+        - "li t2, 0"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "lw a2, 0(sp)\nlw a3, 4(sp) # a2:a3 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw a0, 0(sp)\nlw a1, 4(sp) # a0:a1 <- pop\naddi sp,sp,8 # shrink stack"
+        - call __eqdf2
+        - "addi sp,sp,-8 # grow stack\nsw a0, 0(sp) # push a0 ->\n"
+        - "addi t2, zero, 1"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "sltu t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "addi t2, zero, 1"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "xor t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "bnez t0, .L1\nj .L2 # branch"
+        - ".L1:"
+        - "addi t2, fp, -16"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(t0)\nlw t2, 4(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "li t1, 0"
+        - "li t2, 1070596096"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "lw a2, 0(sp)\nlw a3, 4(sp) # a2:a3 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw a0, 0(sp)\nlw a1, 4(sp) # a0:a1 <- pop\naddi sp,sp,8 # shrink stack"
+        - call __ltdf2
+        - "addi sp,sp,-8 # grow stack\nsw a0, 0(sp) # push a0 ->\n"
+        - "addi t2, zero, 0"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "slt t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "li t1, 0"
+        - "li t2, 1071644672"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "addi t2, fp, -16"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(t0)\nlw t2, 4(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "lw a0, 0(sp)\nlw a1, 4(sp) # a0:a1 <- pop\naddi sp,sp,8 # shrink stack"
+        - call __negdf2
+        - "addi sp,sp,-8 # grow stack\nsw a0, 0(sp)\nsw a1, 4(sp) # push a0:a1 ->\n"
+        - "lw a2, 0(sp)\nlw a3, 4(sp) # a2:a3 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw a0, 0(sp)\nlw a1, 4(sp) # a0:a1 <- pop\naddi sp,sp,8 # shrink stack"
+        - call __ledf2
+        - "addi sp,sp,-8 # grow stack\nsw a0, 0(sp) # push a0 ->\n"
+        - "addi t2, zero, 1"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "slt t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "add t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "addi t2, fp, -16"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(t0)\nlw t2, 4(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "addi t2, fp, -16"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(t0)\nlw t2, 4(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "lw a2, 0(sp)\nlw a3, 4(sp) # a2:a3 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw a0, 0(sp)\nlw a1, 4(sp) # a0:a1 <- pop\naddi sp,sp,8 # shrink stack"
+        - call __eqdf2
+        - "addi sp,sp,-8 # grow stack\nsw a0, 0(sp) # push a0 ->\n"
+        - "addi t2, zero, 1"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "sltu t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "addi t2, zero, 1"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "xor t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "add t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw a0, 0(sp) # a0 <- pop\naddi sp,sp,8 # shrink stack"
+        - j .L0
+        - j .L3
+        - ".L2:"
+        - ".L3:"
+        - "addi t2, zero, 0"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw a0, 0(sp) # a0 <- pop\naddi sp,sp,8 # shrink stack"
+        - ".L0:"
+        - "lw ra, 12(sp)"
+        - "lw fp, 8(sp)"
+        - "addi sp, sp, 16"
+        - ret
+        - "\n"
+        "###);
+    }
+
+    #[test]
+    fn hard_compare() {
+        insta::assert_yaml_snapshot!(compile("compare.c", FloatMode::Hard), @r###"
+        ---
+        - ".text"
+        - ".section .text"
+        - ".globl main"
+        - "main:"
+        - "addi sp, sp, -16"
+        - "sw ra, 12(sp)"
+        - "sw fp, 8(sp)"
+        - "addi fp, sp, 16"
+        - "li t1, 0"
+        - "li t2, 1071644672"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "fld ft0, 0(sp) # ft0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fneg.d ft2, ft0 # negate"
+        - "addi sp,sp,-8 # grow stack\nfsd ft2, 0(sp) # push ft2 ->\n"
+        - "addi t2, fp, -16"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp)\nlw t2, 4(sp) # t1:t2 <- pop\naddi sp,sp,8 # shrink stack"
+        - "sw t1, 0(t0)\nsw t2, 4(t0) # store"
+        - "addi t2, fp, -16"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(t0)\nlw t2, 4(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "li t1, 0"
+        - "li t2, 0"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "fld ft0, 0(sp) # ft0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fld ft1, 0(sp) # ft1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "feq.d t2, ft1, ft0 # compare"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "addi t2, zero, 1"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "xor t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "bnez t0, .L1\nj .L2 # branch"
+        - ".L1:"
+        - "addi t2, fp, -16"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(t0)\nlw t2, 4(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "li t1, 0"
+        - "li t2, 1070596096"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "fld ft0, 0(sp) # ft0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fld ft1, 0(sp) # ft1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "flt.d t2, ft1, ft0 # compare"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "li t1, 0"
+        - "li t2, 1071644672"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "addi t2, fp, -16"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(t0)\nlw t2, 4(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "fld ft0, 0(sp) # ft0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fneg.d ft2, ft0 # negate"
+        - "addi sp,sp,-8 # grow stack\nfsd ft2, 0(sp) # push ft2 ->\n"
+        - "fld ft0, 0(sp) # ft0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fld ft1, 0(sp) # ft1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fle.d t2, ft1, ft0 # compare"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "add t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "addi t2, fp, -16"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(t0)\nlw t2, 4(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "addi t2, fp, -16"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(t0)\nlw t2, 4(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t1, 0(sp)\nsw t2, 4(sp) # push t1:t2 ->\n"
+        - "fld ft0, 0(sp) # ft0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "fld ft1, 0(sp) # ft1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "feq.d t2, ft1, ft0 # compare"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "addi t2, zero, 1"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "xor t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "add t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw a0, 0(sp) # a0 <- pop\naddi sp,sp,8 # shrink stack"
+        - j .L0
+        - j .L3
+        - ".L2:"
+        - ".L3:"
+        - "addi t2, zero, 0"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw a0, 0(sp) # a0 <- pop\naddi sp,sp,8 # shrink stack"
+        - ".L0:"
+        - "lw ra, 12(sp)"
+        - "lw fp, 8(sp)"
+        - "addi sp, sp, 16"
+        - ret
+        - "\n"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_globals {
+    use crate::lexer;
+    use crate::parser_ast;
+    use crate::selector;
+    use crate::translator;
+    use crate::typer;
+    use crate::FloatMode;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/globals";
+
+    #[test]
+    fn tentative() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/tentative.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = translator::translate(&typed_tree).unwrap();
+        let abs_as = selector::select(&trgt_tree, FloatMode::Soft);
+        let assembly = super::allocate(&abs_as, super::OptLevel::O0);
+        insta::assert_yaml_snapshot!(assembly, @r###"
+        ---
+        - ".text"
+        - ".section .text"
+        - ".globl bump"
+        - "bump:"
+        - "addi sp, sp, -16"
+        - "sw ra, 12(sp)"
+        - "sw fp, 8(sp)"
+        - "addi fp, sp, 16"
+        - "lui t2, %hi(n)\naddi t2, t2, %lo(n) # address"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t2, 0(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "addi t2, zero, 1"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "add t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lui t2, %hi(n)\naddi t2, t2, %lo(n) # address"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "sw t1, 0(t0) # store"
+        - "lui t2, %hi(n)\naddi t2, t2, %lo(n) # address"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t2, 0(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "addi sp,sp,8 # drop"
+        - "lui t2, %hi(hidden)\naddi t2, t2, %lo(hidden) # address"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t2, 0(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "addi t2, zero, 2"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "add t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lui t2, %hi(hidden)\naddi t2, t2, %lo(hidden) # address"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "sw t1, 0(t0) # store"
+        - "lui t2, %hi(hidden)\naddi t2, t2, %lo(hidden) # address"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t2, 0(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "addi sp,sp,8 # drop"
+        - "lui t2, %hi(n)\naddi t2, t2, %lo(n) # address"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t2, 0(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lui t2, %hi(hidden)\naddi t2, t2, %lo(hidden) # address"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t2, 0(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "add t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw a0, 0(sp) # a0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw ra, 12(sp)"
+        - "lw fp, 8(sp)"
+        - "addi sp, sp, 16"
+        - ret
+        - ".globl main"
+        - "main:"
+        - "addi sp, sp, -16"
+        - "sw ra, 12(sp)"
+        - "sw fp, 8(sp)"
+        - "addi fp, sp, 16"
+        - call bump
+        - "addi sp,sp,-8 # grow stack\nsw a0, 0(sp) # push a0 ->\n"
+        - "addi sp,sp,8 # drop"
+        - call bump
+        - "addi sp,sp,-8 # grow stack\nsw a0, 0(sp) # push a0 ->\n"
+        - "lui t2, %hi(total)\naddi t2, t2, %lo(total) # address"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t2, 0(t0) # load"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw t0, 0(sp) # t0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw t1, 0(sp) # t1 <- pop\naddi sp,sp,8 # shrink stack"
+        - "add t2, t1, t0 # operate"
+        - "addi sp,sp,-8 # grow stack\nsw t2, 0(sp) # push t2 ->\n"
+        - "lw a0, 0(sp) # a0 <- pop\naddi sp,sp,8 # shrink stack"
+        - "lw ra, 12(sp)"
+        - "lw fp, 8(sp)"
+        - "addi sp, sp, 16"
+        - ret
+        - ".bss"
+        - ".globl n"
+        - ".balign 4"
+        - "n:"
+        - ".zero 4"
+        - ".text"
+        - ".data"
+        - ".globl total"
+        - ".balign 4"
+        - "total:"
+        - ".word 5"
+        - ".text"
+        - ".bss"
+        - ".balign 4"
+        - "hidden:"
+        - ".zero 4"
+        - ".text"
+        - "\n"
+        "###);
+    }
+
+    #[test]
+    fn linkage() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/linkage.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = translator::translate(&typed_tree).unwrap();
+        let abs_as = selector::select(&trgt_tree, FloatMode::Soft);
+        let assembly = super::allocate(&abs_as, super::OptLevel::O0);
+        let exported = assembly
+            .iter()
+            .filter(|l| l.starts_with(".globl"))
+            .collect::<Vec<_>>();
+        insta::assert_yaml_snapshot!(exported, @r###"
+        ---
+        - ".globl api"
+        - ".globl main"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_frames {
+    use crate::lexer;
+    use crate::parser_ast;
+    use crate::selector;
+    use crate::translator;
+    use crate::typer;
+    use crate::FloatMode;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/pointers";
+
+    #[test]
+    fn large() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/large_frame.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = translator::translate(&typed_tree).unwrap();
+        let abs_as = selector::select(&trgt_tree, FloatMode::Soft);
+        let asm = super::allocate(&abs_as, super::OptLevel::O0);
+
+        // the prologue and epilogue of fill, whose frame is past addi's reach
+        let prologue = &asm[3..8];
+        let ret = asm.iter().position(|l| l == "ret").unwrap();
+        let epilogue = &asm[ret - 3..=ret];
+        insta::assert_yaml_snapshot!([prologue, epilogue], @r###"
+        ---
+        - - "fill:"
+          - "li t0, -4032\nadd sp, sp, t0"
+          - "li t0, 4028\nadd t0, sp, t0\nsw ra, 0(t0)"
+          - "li t0, 4024\nadd t0, sp, t0\nsw fp, 0(t0)"
+          - "li t0, 4032\nadd fp, sp, t0"
+        - - "li t0, 4028\nadd t0, sp, t0\nlw ra, 0(t0)"
+          - "li t0, 4024\nadd t0, sp, t0\nlw fp, 0(t0)"
+          - "li t0, 4032\nadd sp, sp, t0"
+          - ret
+        "###);
+    }
 }
@@ -0,0 +1,534 @@
+use crate::source::{SourceMap, Span};
+use serde::Serialize;
+use std::fmt;
+use std::io;
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+// every kind of diagnostic has a code that stays the same across releases,
+// for tools (and tests) to match on instead of the message. warnings also
+// have the name -W options refer to them by
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Code {
+    Internal,    // an error that carries no location
+    Lex,         // a character or literal that makes no token
+    Preprocess,  // a bad directive, or an #error
+    Syntax,      // tokens that do not parse
+    Type,        // an operation on operands of the wrong type
+    Mismatch,    // a value where an incompatible type is expected
+    Undeclared,  // an identifier used without a declaration in scope
+    Redeclared,  // a second declaration where only one is allowed
+    Conflicting, // declarations of one name that disagree on its type
+    NoMain,      // a program without main
+    Unsupported, // valid C the backend cannot compile yet
+    Arity,       // a call with the wrong number of arguments
+    Depth,       // statements or expressions nested deeper than the compiler recurses
+    ImplicitFunctionDeclaration,
+}
+
+impl Code {
+    #[rustfmt::skip]
+    pub fn id(self) -> &'static str {
+        match self {
+            Code::Internal => "E0000", Code::Lex => "E0001", Code::Preprocess => "E0002",
+            Code::Syntax => "E0003", Code::Type => "E0004", Code::Mismatch => "E0005",
+            Code::Undeclared => "E0006", Code::Redeclared => "E0007",
+            Code::Conflicting => "E0008", Code::NoMain => "E0009", Code::Unsupported => "E0010",
+            Code::Arity => "E0011", Code::Depth => "E0012",
+            Code::ImplicitFunctionDeclaration => "W0001",
+        }
+    }
+
+    // the warning's name, None for an error
+    pub fn name(self) -> Option<&'static str> {
+        match self {
+            Code::ImplicitFunctionDeclaration => Some("implicit-function-declaration"),
+            _ => None,
+        }
+    }
+}
+
+// every stage still speaks io::Error, so a diagnostic travels inside one
+// (io::Error::other) and the driver downcasts it back for rendering. its
+// notes point at secondary spans, or at the dummy span when they have none
+#[derive(Clone, Debug, Error, PartialEq)]
+#[error("{msg}")]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Code,
+    pub msg: String,
+    pub span: Span,
+    pub notes: Vec<(Span, String)>,
+}
+
+// every error a stage that recovers from them found, in order
+#[derive(Clone, Debug, Error, PartialEq)]
+#[error("{}", .0.iter().map(|d| d.msg.as_str()).collect::<Vec<_>>().join("\n"))]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl Diagnostic {
+    // a warning when the code is a warning's, an error otherwise
+    pub fn new(code: Code, span: Span, msg: impl Into<String>) -> Self {
+        Self {
+            severity: match code.name() {
+                Some(_) => Severity::Warning,
+                None => Severity::Error,
+            },
+            code,
+            msg: msg.into(),
+            span,
+            notes: vec![],
+        }
+    }
+
+    pub fn label(mut self, span: Span, msg: impl Into<String>) -> Self {
+        self.notes.push((span, msg.into()));
+        self
+    }
+
+    pub fn note(self, msg: impl Into<String>) -> Self {
+        self.label(Span::default(), msg)
+    }
+
+    //  error[E0003]: expected: PuncSemiColon got: KeywordInt
+    //   --> foo.c:3:5
+    //    |
+    //  3 |     int x = 9
+    //    |     ^^^
+    //
+    // the primary span is followed by a note per macro it was expanded
+    // from (innermost first), then by its own notes
+    pub fn render(&self, sm: &SourceMap) -> String {
+        let mut out = format!(
+            "{}[{}]: {}{}\n",
+            self.severity,
+            self.code.id(),
+            self.msg,
+            self.option()
+        );
+        out.push_str(&snippet(self.span, sm));
+        for (msg, span) in self.children(sm) {
+            out.push_str(&format!("note: {msg}\n"));
+            out.push_str(&snippet(span, sm));
+        }
+        out
+    }
+
+    // the option that controls a warning (or made it an error), as gcc
+    // shows it
+    fn option(&self) -> String {
+        match (self.code.name(), self.severity) {
+            (Some(name), Severity::Error) => format!(" [-Werror={name}]"),
+            (Some(name), _) => format!(" [-W{name}]"),
+            (None, _) => String::new(),
+        }
+    }
+
+    // the notes under the primary span, the dummy span for those without one
+    fn children(&self, sm: &SourceMap) -> Vec<(String, Span)> {
+        let mut children = vec![];
+        let mut expn = self.span.expn;
+        while let Some(e) = sm.expansion(expn) {
+            children.push((format!("expanded from macro '{}'", e.name), e.def));
+            expn = e.call.expn;
+        }
+        children.extend(self.notes.iter().map(|(s, m)| (m.clone(), *s)));
+        children
+    }
+}
+
+// where a span starts, as a person reads it: the location honors #line
+fn location(span: Span, sm: &SourceMap) -> Option<Location> {
+    let file = sm.get(span.file).filter(|_| !span.is_dummy())?;
+    let (name, line) = file.presumed(span.line);
+    Some(Location {
+        file: name.to_owned(),
+        line,
+        column: span.col,
+    })
+}
+
+fn snippet(span: Span, sm: &SourceMap) -> String {
+    let Some(loc) = location(span, sm) else {
+        return String::new();
+    };
+    let mut out = format!(" --> {}:{}:{}\n", loc.file, loc.line, loc.column);
+
+    // the snippet is the physical line
+    let (line, col) = (span.line, span.col);
+    if let Some(text) = sm.get(span.file).and_then(|f| f.line(line)) {
+        let gutter = " ".repeat(line.to_string().len());
+        let pad = text
+            .chars()
+            .take(col - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        // the caret never runs past the end of the line it starts on
+        let rest = text.chars().count().saturating_sub(col - 1);
+        let width = (span.hi - span.lo).clamp(1, rest.max(1));
+
+        out.push_str(&format!("{gutter} |\n"));
+        out.push_str(&format!("{line} | {text}\n"));
+        out.push_str(&format!("{gutter} | {pad}{}\n", "^".repeat(width)));
+    }
+
+    out
+}
+
+// -fdiagnostics-format=json prints one array of these for the compilation
+#[derive(Serialize)]
+struct Json<'a> {
+    severity: Severity,
+    code: &'static str,
+    option: Option<String>,
+    message: &'a str,
+    location: Option<Location>,
+    children: Vec<JsonChild>,
+}
+
+#[derive(Serialize)]
+struct JsonChild {
+    severity: Severity,
+    message: String,
+    location: Option<Location>,
+}
+
+#[derive(Serialize)]
+struct Location {
+    file: String,
+    line: usize,
+    column: usize,
+}
+
+pub fn render_json(ds: &[Diagnostic], sm: &SourceMap) -> String {
+    let json = ds
+        .iter()
+        .map(|d| Json {
+            severity: d.severity,
+            code: d.code.id(),
+            option: d.code.name().map(|name| match d.severity {
+                Severity::Error => format!("-Werror={name}"),
+                _ => format!("-W{name}"),
+            }),
+            message: &d.msg,
+            location: location(d.span, sm),
+            children: d
+                .children(sm)
+                .into_iter()
+                .map(|(message, span)| JsonChild {
+                    severity: Severity::Note,
+                    message,
+                    location: location(span, sm),
+                })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string(&json).expect("diagnostics serialize") + "\n"
+}
+
+impl From<Diagnostic> for io::Error {
+    fn from(d: Diagnostic) -> Self {
+        io::Error::other(d)
+    }
+}
+
+impl From<Diagnostics> for io::Error {
+    fn from(ds: Diagnostics) -> Self {
+        io::Error::other(ds)
+    }
+}
+
+// the diagnostics an error coming out of a stage carries. errors without a
+// source location (io failures, internal errors) become one from their
+// message
+pub fn of(e: &io::Error) -> Vec<Diagnostic> {
+    let inner = e.get_ref();
+    if let Some(d) = inner.and_then(|e| e.downcast_ref::<Diagnostic>()) {
+        return vec![d.clone()];
+    }
+    match inner.and_then(|e| e.downcast_ref::<Diagnostics>()) {
+        Some(ds) => ds.0.clone(),
+        None => vec![Diagnostic::new(
+            Code::Internal,
+            Span::default(),
+            e.to_string(),
+        )],
+    }
+}
+
+pub fn render(e: &io::Error, sm: &SourceMap) -> String {
+    of(e).iter().map(|d| d.render(sm)).collect()
+}
+
+// what a stage fails each case with, a line per case for a snapshot to
+// hold: "int x = y;: E0006 1:9 'y' undeclared". the diagnostics of a case
+// that fails with several are joined by " | ", and one that passes is a bug
+#[cfg(test)]
+pub(crate) fn diags<'a, T>(
+    cases: impl IntoIterator<Item = &'a str>,
+    stage: impl Fn(&str) -> Result<T, io::Error>,
+) -> Vec<String> {
+    cases
+        .into_iter()
+        .map(|case| {
+            let Err(e) = stage(case) else {
+                panic!("{case}: no diagnostic");
+            };
+            let ds = of(&e)
+                .iter()
+                .map(|d| format!("{} {}:{} {}", d.code.id(), d.span.line, d.span.col, d.msg))
+                .collect::<Vec<_>>();
+            format!("{case}: {}", ds.join(" | "))
+        })
+        .collect()
+}
+
+// -Werror makes every warning an error, and -Wno-<name> silences one
+#[derive(Clone, Debug, Default)]
+pub struct DiagOpts {
+    pub werror: bool,
+    pub disabled: Vec<String>,
+    pub json: bool,
+}
+
+impl DiagOpts {
+    // false for a flag that is not about diagnostics
+    pub fn flag(&mut self, f: &str) -> bool {
+        match f {
+            "-Werror" => self.werror = true,
+            "-fdiagnostics-format=json" => self.json = true,
+            "-fdiagnostics-format=text" => self.json = false,
+            f if f.starts_with("-Wno-") => self.disabled.push(f["-Wno-".len()..].to_owned()),
+            _ => return false,
+        }
+        true
+    }
+
+    fn apply(&self, mut d: Diagnostic) -> Option<Diagnostic> {
+        match d.code.name() {
+            Some(name) if self.disabled.iter().any(|n| n == name) => None,
+            Some(_) if self.werror => {
+                d.severity = Severity::Error;
+                Some(d)
+            }
+            _ => Some(d),
+        }
+    }
+}
+
+// everything one compilation reports, in order. the driver runs each stage
+// through it, and stops after the first that leaves an error
+pub struct Report {
+    opts: DiagOpts,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Report {
+    pub fn new(opts: DiagOpts) -> Self {
+        Self {
+            opts,
+            diagnostics: vec![],
+        }
+    }
+
+    // the stage's result, once what it emitted and the errors it failed with
+    // are collected. None when there was an error
+    pub fn stage<T>(&mut self, r: Result<T, io::Error>) -> Option<T> {
+        self.warned(r.map(|v| (v, vec![])))
+    }
+
+    // the same for a stage that also returns the warnings it found
+    pub fn warned<T>(&mut self, r: Result<(T, Vec<Diagnostic>), io::Error>) -> Option<T> {
+        let (v, ds) = match r {
+            Ok((v, warnings)) => (Some(v), warnings),
+            Err(e) => (None, of(&e)),
+        };
+        self.diagnostics
+            .extend(ds.into_iter().filter_map(|d| self.opts.apply(d)));
+        v.filter(|_| !self.failed())
+    }
+
+    pub fn failed(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+    }
+
+    pub fn render(&self, sm: &SourceMap) -> String {
+        match self.opts.json {
+            true => render_json(&self.diagnostics, sm),
+            false => self.diagnostics.iter().map(|d| d.render(sm)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_render {
+    use super::{Code, Diagnostic};
+    use crate::lexer::{self, LexOpts};
+    use crate::preprocessor::{self, PpOpts};
+    use crate::source::{SourceMap, Span};
+    use crate::{parser_ast, typer};
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/lexical";
+
+    #[test]
+    fn at() {
+        let src = fs::read_to_string(format!("{TEST_DIR}/at.c")).expect("file dne");
+        let mut sm = SourceMap::new();
+        let file = sm.add("at.c", &src);
+
+        let tokens = lexer::lex_file(&src, file, LexOpts::default()).unwrap();
+        let err = parser_ast::parse_prg(&tokens).unwrap_err();
+        insta::assert_snapshot!(super::render(&err, &sm), @r###"
+        error[E0001]: stray '@' in program
+         --> at.c:2:13
+          |
+        2 |     return 0@1;
+          |             ^
+        error[E0003]: expected: PuncSemiColon got: LiteralInt `1`
+         --> at.c:2:14
+          |
+        2 |     return 0@1;
+          |              ^
+        "###);
+    }
+
+    #[test]
+    fn expansion() {
+        let path = "tests/fixtures/snap/preprocessor/expansion.c";
+        let mut sm = SourceMap::new();
+        let tokens = preprocessor::preprocess(&mut sm, path, &PpOpts::default()).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+
+        let err = typer::type_prg(&tree).unwrap_err();
+        insta::assert_snapshot!(super::render(&err, &sm), @r###"
+        error[E0005]: type error: expected Int, found Double
+         --> tests/fixtures/snap/preprocessor/expansion.c:4:12
+          |
+        4 |     return MOD(7);
+          |            ^^^
+        note: expanded from macro 'HALF'
+         --> tests/fixtures/snap/preprocessor/expansion.c:1:9
+          |
+        1 | #define HALF 0.5
+          |         ^^^^
+        note: expanded from macro 'MOD'
+         --> tests/fixtures/snap/preprocessor/expansion.c:2:9
+          |
+        2 | #define MOD(a) a % HALF
+          |         ^^^
+        "###);
+    }
+
+    #[test]
+    fn no_location() {
+        let sm = SourceMap::new();
+        let d = Diagnostic::new(Code::NoMain, Span::default(), "main function not found");
+        insta::assert_snapshot!(d.render(&sm), @"error[E0009]: main function not found");
+    }
+
+    fn report(opts: super::DiagOpts) -> String {
+        let path = "tests/fixtures/snap/shared/decls/reported.c";
+        let src = fs::read_to_string(path).expect("file dne");
+        let mut sm = SourceMap::new();
+        let file = sm.add("reported.c", &src);
+
+        let mut report = super::Report::new(opts);
+        let tokens = report.stage(lexer::lex_file(&src, file, LexOpts::default()));
+        let tree = report.stage(parser_ast::parse_prg(&tokens.unwrap()));
+        assert!(report.warned(typer::type_prg(&tree.unwrap())).is_none());
+        report.render(&sm)
+    }
+
+    #[test]
+    fn collected() {
+        insta::assert_snapshot!(report(super::DiagOpts::default()), @r###"
+        warning[W0001]: implicit declaration of function 'g' [-Wimplicit-function-declaration]
+         --> reported.c:8:12
+          |
+        8 |     return g(2) + y;
+          |            ^^^^
+        error[E0007]: redeclaration of 'x' with no linkage
+         --> reported.c:3:10
+          |
+        3 |     char x;
+          |          ^
+        note: previous declaration of 'x' was here
+         --> reported.c:2:9
+          |
+        2 |     int x = 1;
+          |         ^^^^^
+        error[E0006]: 'y' undeclared
+         --> reported.c:8:19
+          |
+        8 |     return g(2) + y;
+          |                   ^
+        "###);
+    }
+
+    #[test]
+    fn werror() {
+        let opts = super::DiagOpts {
+            werror: true,
+            ..Default::default()
+        };
+        insta::assert_snapshot!(report(opts), @r###"
+        error[W0001]: implicit declaration of function 'g' [-Werror=implicit-function-declaration]
+         --> reported.c:8:12
+          |
+        8 |     return g(2) + y;
+          |            ^^^^
+        error[E0007]: redeclaration of 'x' with no linkage
+         --> reported.c:3:10
+          |
+        3 |     char x;
+          |          ^
+        note: previous declaration of 'x' was here
+         --> reported.c:2:9
+          |
+        2 |     int x = 1;
+          |         ^^^^^
+        error[E0006]: 'y' undeclared
+         --> reported.c:8:19
+          |
+        8 |     return g(2) + y;
+          |                   ^
+        "###);
+    }
+
+    #[test]
+    fn wno() {
+        let mut opts = super::DiagOpts::default();
+        assert!(opts.flag("-Wno-implicit-function-declaration"));
+        assert!(!report(opts).contains("warning"));
+    }
+
+    #[test]
+    fn json() {
+        let mut opts = super::DiagOpts::default();
+        assert!(opts.flag("-fdiagnostics-format=json"));
+        insta::assert_snapshot!(report(opts), @r###"
+        [{"severity":"warning","code":"W0001","option":"-Wimplicit-function-declaration","message":"implicit declaration of function 'g'","location":{"file":"reported.c","line":8,"column":12},"children":[]},{"severity":"error","code":"E0007","option":null,"message":"redeclaration of 'x' with no linkage","location":{"file":"reported.c","line":3,"column":10},"children":[{"severity":"note","message":"previous declaration of 'x' was here","location":{"file":"reported.c","line":2,"column":9}}]},{"severity":"error","code":"E0006","option":null,"message":"'y' undeclared","location":{"file":"reported.c","line":8,"column":19},"children":[]}]
+        "###);
+    }
+}
@@ -1,242 +1,444 @@
+use crate::diagnostic::{Code, Diagnostic};
+use crate::source::Span;
 use serde::{Deserialize, Serialize};
 use std::io;
-use std::iter;
-
-// todo. remove allocations.
-// iterate on slices and iterators.
-// todo: change to iterative
 
 #[rustfmt::skip]
 #[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
-pub struct Token { pub lexeme: String, pub typ: TT }
+pub struct Token { pub lexeme: String, pub typ: TT, #[serde(skip)] pub span: Span, #[serde(skip)] pub bol: bool, #[serde(skip)] pub space: bool }
 
 #[rustfmt::skip]
 #[derive(Copy, Clone, PartialEq, Serialize, Deserialize, Debug)]
 pub enum TT {
-    LiteralInt, Alias, // introductions (values) RE: [0-9]+ and [a-zA-Z][a-zA-Z0-9]*
-    KeywordInt, KeywordChar, KeywordVoid, KeywordRet, KeywordIf, KeywordEls, KeywordFor, KeywordWhile, KeywordTrue, KeywordFalse, // keywords ⊂ identifiers
-    Plus, Minus, Star, Slash, LeftAngleBracket, RightAngleBracket, Equals, Bang, Amp, Bar, // eliminations (ops)
-    PuncLeftParen, PuncRightParen, PuncLeftBrace, PuncRightBrace, PuncSemiColon, PuncComma,// punctuation
+    LiteralInt, LiteralFloat, LiteralChar, LiteralString, Alias, // introductions (values) RE: pp-numbers, '...', "..." and [a-zA-Z_][a-zA-Z0-9_]*
+    KeywordAuto, KeywordBreak, KeywordCase, KeywordChar, KeywordConst, KeywordContinue, KeywordDefault, KeywordDo, // keywords ⊂ identifiers
+    KeywordDouble, KeywordEls, KeywordEnum, KeywordExtern, KeywordFloat, KeywordFor, KeywordGoto, KeywordIf,
+    KeywordInt, KeywordLong, KeywordRegister, KeywordRet, KeywordShort, KeywordSigned, KeywordSizeof, KeywordStatic,
+    KeywordStruct, KeywordSwitch, KeywordTypedef, KeywordUnion, KeywordUnsigned, KeywordVoid, KeywordVolatile, KeywordWhile,
+    KeywordTrue, KeywordFalse, // C0
+    Plus, Minus, Star, Slash, Percent, LeftAngleBracket, RightAngleBracket, Equals, Bang, Amp, Bar, Caret, Tilde, Question, Colon, Dot, // eliminations (ops)
+    PlusPlus, MinusMinus, Arrow, LeftShift, RightShift, LeftAngleBracketEquals, RightAngleBracketEquals, EqualsEquals, BangEquals, AmpAmp, BarBar,
+    PlusEquals, MinusEquals, StarEquals, SlashEquals, PercentEquals, LeftShiftEquals, RightShiftEquals, AmpEquals, BarEquals, CaretEquals,
+    PuncLeftParen, PuncRightParen, PuncLeftBrace, PuncRightBrace, PuncLeftBracket, PuncRightBracket, PuncSemiColon, PuncComma, PuncEllipsis, // punctuation
+    PuncHash, PuncHashHash, Pragma, // preprocessing. a pragma carries its whole directive as the lexeme
+    Other, // any other character is a pp-token of its own (C89 3.1), which the parser rejects
+}
+
+// extensions beyond C89 are opt-in
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct LexOpts {
+    pub line_comments: bool, // `// ...` (C99, gnu89)
+    pub c0: bool,            // true and false are keywords (C0). in C89 they are identifiers
 }
 
 //  1. variations are explicitly typed. Collapsing categories like keywords
 //     into one variant will lose information since lexeme : String, which
 //     will produce redundant work for the parser during syntactic analysis
 //  2. non-tokens: comments, preprocessor directives, macros, whitespace
+//  3. the lexer is a cursor over the source bytes that yields one token per
+//     call to next(). line and col are tracked as the cursor moves so spans
+//     cost nothing extra, and nothing is buffered besides each lexeme
+//  4. backslash-newline splices (translation phase 2) are skipped wherever
+//     the next character is read, so lexemes never contain them while spans
+//     still cover the raw source
+//  5. errors do not end the stream: the offending character is skipped so
+//     that callers can keep going (and report more than one error)
+//  6. each token records whether it starts a logical line (bol) and whether
+//     whitespace or a comment precedes it (space). the preprocessor needs
+//     both to find directives, tell `f(` from `f (` and respell its output
 
-pub fn lex(input: &[char]) -> Result<Vec<Token>, io::Error> {
-    let cs = skip_ws(input);
-
-    // literals and identifiers have arbitrary length
-    // operations and punctuations are single ASCII characters
-    match cs {
-        [] => Ok(vec![]),
-        [f, r @ ..] => match f {
-            '0'..='9' => scan_int(cs),
-            'a'..='z' | 'A'..='Z' => scan_id(cs),
-            '+' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from("+"), typ: TT::Plus };
-                Ok(iter::once(t).chain(lex(r)?).collect())
-            }
-            '-' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from("-"), typ: TT::Minus };
-                Ok(iter::once(t).chain(lex(r)?).collect())
-            }
-            '*' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from("*"), typ: TT::Star };
-                Ok(iter::once(t).chain(lex(r)?).collect())
-            }
-            '/' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from("/"), typ: TT::Slash };
-                Ok(iter::once(t).chain(lex(r)?).collect())
-            }
-            '<' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from("<"), typ: TT::LeftAngleBracket };
-                Ok(iter::once(t).chain(lex(r)?).collect())
-            }
-            '>' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from(">"), typ: TT::RightAngleBracket };
-                Ok(iter::once(t).chain(lex(r)?).collect())
-            }
-            '=' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from("="), typ: TT::Equals };
-                Ok(iter::once(t).chain(lex(r)?).collect())
-            }
-            '!' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from("!"), typ: TT::Bang };
-                Ok(iter::once(t).chain(lex(r)?).collect())
+pub struct Lexer<'a> {
+    src: &'a str,
+    file: usize,
+    opts: LexOpts,
+    pos: usize,
+    line: usize,
+    col: usize,
+    bol: bool,
+    last_bol: bool,
+}
+
+pub fn lex(input: &str) -> Result<Vec<Token>, io::Error> {
+    lex_file(input, 0, LexOpts::default())
+}
+
+// C0 sources lex like C89 sources, but for true and false
+pub fn lex_c0(input: &str) -> Result<Vec<Token>, io::Error> {
+    let opts = LexOpts {
+        c0: true,
+        ..LexOpts::default()
+    };
+    lex_file(input, 0, opts)
+}
+
+pub fn lex_file(input: &str, file: usize, opts: LexOpts) -> Result<Vec<Token>, io::Error> {
+    Lexer::new(input, file, opts)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(io::Error::from)
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Result<Token, Diagnostic>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.pos;
+        if let Err(e) = self.skip_ws() {
+            return Some(Err(e));
+        }
+        let (bol, space) = (self.bol, self.pos != pos);
+        (self.bol, self.last_bol) = (false, bol);
+        self.skip_splices();
+
+        // literals and identifiers have arbitrary length. punctuators are at
+        // most three ASCII characters and the longest one that matches wins
+        let (lo, line, col) = (self.pos, self.line, self.col);
+        let typ = match self.peek()? {
+            // numbers are scanned as pp-numbers (C89 6.1.8). the value, base
+            // and suffix are checked by the parser, so that 0x1F, 1.5e-3f and
+            // 1foo each stay one token
+            b'0'..=b'9' => self.pp_number(lo),
+            b'.' if self.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) => self.pp_number(lo),
+            q @ (b'\'' | b'"') => {
+                if let Err(e) = self.quoted(q) {
+                    return Some(Err(e));
+                }
+                if q == b'"' {
+                    TT::LiteralString
+                } else {
+                    TT::LiteralChar
+                }
             }
-            '&' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from("&"), typ: TT::Amp };
-                Ok(iter::once(t).chain(lex(r)?).collect())
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
+                self.bump_while(|c| c.is_ascii_alphanumeric() || c == b'_');
+                match keyword(&self.lexeme(lo)) {
+                    Some(TT::KeywordTrue | TT::KeywordFalse) if !self.opts.c0 => TT::Alias,
+                    k => k.unwrap_or(TT::Alias),
+                }
             }
-            '|' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from("|"), typ: TT::Bar };
-                Ok(iter::once(t).chain(lex(r)?).collect())
+            c => {
+                let Some((typ, len)) = punctuator(c, self.peek_nth(1), self.peek_nth(2)) else {
+                    let c = self.src[lo..].chars().next()?;
+                    for _ in 0..c.len_utf8() {
+                        self.bump();
+                    }
+                    return Some(Ok(Token {
+                        lexeme: c.to_string(),
+                        typ: TT::Other,
+                        span: Span::new(self.file, lo, self.pos, line, col),
+                        bol,
+                        space,
+                    }));
+                };
+
+                for _ in 0..len {
+                    self.bump();
+                }
+                typ
             }
-            '(' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from("("), typ: TT::PuncLeftParen };
-                Ok(iter::once(t).chain(lex(r)?).collect())
+        };
+
+        let lexeme = self.lexeme(lo);
+        let span = Span::new(self.file, lo, self.pos, line, col);
+        Some(Ok(Token {
+            lexeme,
+            typ,
+            span,
+            bol,
+            space,
+        }))
+    }
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(src: &'a str, file: usize, opts: LexOpts) -> Self {
+        Self {
+            src,
+            file,
+            opts,
+            pos: 0,
+            line: 1,
+            col: 1,
+            bol: true,
+            last_bol: true,
+        }
+    }
+
+    // whether the item next() returned last, token or error, began a line
+    pub fn last_bol(&self) -> bool {
+        self.last_bol
+    }
+
+    // the source text from lo to the cursor, with splices removed. only
+    // spliced tokens pay for the filtering
+    fn lexeme(&self, lo: usize) -> String {
+        let raw = &self.src[lo..self.pos];
+        if !raw.contains('\\') {
+            return raw.to_owned();
+        }
+
+        let (mut lexeme, mut i) = (String::new(), 0);
+        while i < raw.len() {
+            match splice_len(&raw.as_bytes()[i..]) {
+                0 => {
+                    let c = raw[i..].chars().next().unwrap(); // i < raw.len()
+                    lexeme.push(c);
+                    i += c.len_utf8();
+                }
+                n => i += n,
             }
-            ')' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from(")"), typ: TT::PuncRightParen };
-                Ok(iter::once(t).chain(lex(r)?).collect())
+        }
+
+        lexeme
+    }
+
+    // the nth character (0-based) ahead of the cursor, looking through splices
+    fn peek_nth(&self, n: usize) -> Option<u8> {
+        let bytes = self.src.as_bytes();
+        let mut i = self.pos;
+        for k in 0..=n {
+            while let l @ 1.. = splice_len(bytes.get(i..)?) {
+                i += l;
             }
-            '{' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from("{"), typ: TT::PuncLeftBrace };
-                Ok(iter::once(t).chain(lex(r)?).collect())
+            if k < n {
+                i += 1;
             }
-            '}' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from("}"), typ: TT::PuncRightBrace };
-                Ok(iter::once(t).chain(lex(r)?).collect())
+        }
+
+        bytes.get(i).copied()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.peek_nth(0)
+    }
+
+    fn skip_splices(&mut self) {
+        while let l @ 1.. = splice_len(&self.src.as_bytes()[self.pos..]) {
+            (self.pos, self.line, self.col) = (self.pos + l, self.line + 1, 1);
+        }
+    }
+
+    // consumes the next (non-splice) byte. columns count characters, so
+    // utf-8 continuation bytes do not advance them
+    fn bump(&mut self) -> Option<u8> {
+        self.skip_splices();
+        let c = *self.src.as_bytes().get(self.pos)?;
+        self.pos += 1;
+        match c {
+            b'\n' => (self.line, self.col) = (self.line + 1, 1),
+            c if c & 0xC0 != 0x80 => self.col += 1,
+            _ => (),
+        }
+
+        Some(c)
+    }
+
+    fn bump_while(&mut self, p: impl Fn(u8) -> bool) {
+        while self.peek().is_some_and(&p) {
+            self.bump();
+        }
+    }
+
+    fn skip_ws(&mut self) -> Result<(), Diagnostic> {
+        loop {
+            match (self.peek(), self.peek_nth(1)) {
+                (Some(c), _) if c.is_ascii_whitespace() || c == b'\x0b' => {
+                    if self.bump() == Some(b'\n') {
+                        self.bol = true;
+                    }
+                }
+                (Some(b'/'), Some(b'*')) => self.skip_block_comment()?,
+                (Some(b'/'), Some(b'/')) if self.opts.line_comments => {
+                    self.bump_while(|c| c != b'\n');
+                }
+                _ => return Ok(()),
             }
-            ';' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from(";"), typ: TT::PuncSemiColon };
-                Ok(iter::once(t).chain(lex(r)?).collect())
+        }
+    }
+
+    fn pp_number(&mut self, lo: usize) -> TT {
+        loop {
+            match (self.peek(), self.peek_nth(1)) {
+                (Some(b'e' | b'E'), Some(b'+' | b'-')) => {
+                    self.bump();
+                    self.bump();
+                }
+                (Some(c), _) if c.is_ascii_alphanumeric() || c == b'_' || c == b'.' => {
+                    self.bump();
+                }
+                _ => break,
             }
-            ',' => {
-                #[rustfmt::skip]
-                let t = Token { lexeme: String::from(","), typ: TT::PuncComma };
-                Ok(iter::once(t).chain(lex(r)?).collect())
+        }
+
+        let lexeme = self.lexeme(lo);
+        let hex = lexeme.starts_with("0x") || lexeme.starts_with("0X");
+        if !hex && lexeme.contains(['.', 'e', 'E']) {
+            TT::LiteralFloat
+        } else {
+            TT::LiteralInt
+        }
+    }
+
+    // scans a character constant or string literal up to its closing quote.
+    // escapes are only skipped here (the parser decodes them) and neither
+    // may span lines, so an unterminated one is cut off at the end of its line
+    fn quoted(&mut self, q: u8) -> Result<(), Diagnostic> {
+        self.skip_splices();
+        let (lo, line, col) = (self.pos, self.line, self.col);
+        self.bump();
+
+        loop {
+            match self.peek() {
+                None | Some(b'\n') => {
+                    let span = Span::new(self.file, lo, self.pos, line, col);
+                    let msg = format!("missing terminating {} character", q as char);
+                    return Err(Diagnostic::new(Code::Lex, span, msg));
+                }
+                Some(b'\\') => {
+                    self.bump();
+                    if self.peek().is_some_and(|c| c != b'\n') {
+                        self.bump();
+                    }
+                }
+                Some(c) => {
+                    self.bump();
+                    if c == q {
+                        return Ok(());
+                    }
+                }
             }
-            _ => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("unexpected token: {:?}", f),
-            )),
-        },
+        }
     }
-}
 
-fn scan_int(input: &[char]) -> Result<Vec<Token>, io::Error> {
-    // scan_int calls skip_whitespace too to remain idempotent
-    let cs = skip_ws(input);
-
-    match cs {
-        [] => Ok(vec![]),
-        [f, _r @ ..] => match f {
-            '0'..='9' => {
-                #[rustfmt::skip]
-                let i = _r
-                    .iter()
-                    .take_while(|&&c| c.is_numeric())
-                    .count();
-
-                let f = cs[..=i].iter().collect::<String>();
-                let r = &cs[i + 1..];
-
-                let t = Token {
-                    lexeme: f,
-                    typ: TT::LiteralInt,
-                };
+    // comments do not nest. an unterminated one is reported where it opened
+    fn skip_block_comment(&mut self) -> Result<(), Diagnostic> {
+        self.skip_splices();
+        let (lo, line, col) = (self.pos, self.line, self.col);
+        self.bump();
+        self.bump();
 
-                Ok(iter::once(t).chain(lex(r)?).collect())
+        loop {
+            match self.bump() {
+                None => {
+                    let span = Span::new(self.file, lo, lo + 2, line, col);
+                    return Err(Diagnostic::new(Code::Lex, span, "unterminated comment"));
+                }
+                Some(b'*') if self.peek() == Some(b'/') => {
+                    self.bump();
+                    return Ok(());
+                }
+                Some(_) => (),
             }
-            _ => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("unexpected token: {:?}", f),
-            )),
-        },
+        }
     }
 }
 
-// TODO: support identifiers with alpha*numeric* characters after first alphabetic
-fn scan_id(input: &[char]) -> Result<Vec<Token>, io::Error> {
-    // scan_id calls skip_whitespace too to remain idempotent
-    let cs = skip_ws(input);
-
-    match cs {
-        [] => Ok(vec![]),
-        [f, r @ ..] => match f {
-            'a'..='z' => {
-                // Find the index where the alphabetic characters end
-                #[rustfmt::skip]
-                let i = r
-                    .iter()
-                    .take_while(|&&c| c.is_alphabetic())
-                    .count();
-
-                let f = (cs[..=i].iter()).collect::<String>();
-                let new_r = &cs[i + 1..];
-
-                let keyword = match f.as_str() {
-                    "int" => Some(Token {
-                        lexeme: f.to_string(),
-                        typ: TT::KeywordInt,
-                    }),
-                    "if" => Some(Token {
-                        lexeme: f.to_string(),
-                        typ: TT::KeywordIf,
-                    }),
-                    "else" => Some(Token {
-                        lexeme: f.to_string(),
-                        typ: TT::KeywordEls,
-                    }),
-                    "for" => Some(Token {
-                        lexeme: f.to_string(),
-                        typ: TT::KeywordFor,
-                    }),
-                    "while" => Some(Token {
-                        lexeme: f.to_string(),
-                        typ: TT::KeywordWhile,
-                    }),
-                    "return" => Some(Token {
-                        lexeme: f.to_string(),
-                        typ: TT::KeywordRet,
-                    }),
-                    "true" => Some(Token {
-                        lexeme: f.to_string(),
-                        typ: TT::KeywordTrue,
-                    }),
-                    "false" => Some(Token {
-                        lexeme: f.to_string(),
-                        typ: TT::KeywordFalse,
-                    }),
-                    _ => None,
-                };
+fn keyword(id: &str) -> Option<TT> {
+    let typ = match id {
+        "auto" => TT::KeywordAuto,
+        "break" => TT::KeywordBreak,
+        "case" => TT::KeywordCase,
+        "char" => TT::KeywordChar,
+        "const" => TT::KeywordConst,
+        "continue" => TT::KeywordContinue,
+        "default" => TT::KeywordDefault,
+        "do" => TT::KeywordDo,
+        "double" => TT::KeywordDouble,
+        "else" => TT::KeywordEls,
+        "enum" => TT::KeywordEnum,
+        "extern" => TT::KeywordExtern,
+        "float" => TT::KeywordFloat,
+        "for" => TT::KeywordFor,
+        "goto" => TT::KeywordGoto,
+        "if" => TT::KeywordIf,
+        "int" => TT::KeywordInt,
+        "long" => TT::KeywordLong,
+        "register" => TT::KeywordRegister,
+        "return" => TT::KeywordRet,
+        "short" => TT::KeywordShort,
+        "signed" => TT::KeywordSigned,
+        "sizeof" => TT::KeywordSizeof,
+        "static" => TT::KeywordStatic,
+        "struct" => TT::KeywordStruct,
+        "switch" => TT::KeywordSwitch,
+        "typedef" => TT::KeywordTypedef,
+        "union" => TT::KeywordUnion,
+        "unsigned" => TT::KeywordUnsigned,
+        "void" => TT::KeywordVoid,
+        "volatile" => TT::KeywordVolatile,
+        "while" => TT::KeywordWhile,
+        "true" => TT::KeywordTrue,
+        "false" => TT::KeywordFalse,
+        _ => return None,
+    };
 
-                let t = match keyword {
-                    Some(k) => k,
-                    None => Token {
-                        lexeme: f,
-                        typ: TT::Alias,
-                    },
-                };
+    Some(typ)
+}
 
-                Ok(iter::once(t).chain(lex(new_r)?).collect())
-            }
-            _ => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("unexpected token: {:?}", f),
-            )),
-        },
-    }
+// the longest punctuator starting with c, given the two characters after it
+fn punctuator(c: u8, c1: Option<u8>, c2: Option<u8>) -> Option<(TT, usize)> {
+    let punc = match (c, c1, c2) {
+        (b'.', Some(b'.'), Some(b'.')) => (TT::PuncEllipsis, 3),
+        (b'<', Some(b'<'), Some(b'=')) => (TT::LeftShiftEquals, 3),
+        (b'>', Some(b'>'), Some(b'=')) => (TT::RightShiftEquals, 3),
+
+        (b'+', Some(b'+'), _) => (TT::PlusPlus, 2),
+        (b'-', Some(b'-'), _) => (TT::MinusMinus, 2),
+        (b'-', Some(b'>'), _) => (TT::Arrow, 2),
+        (b'<', Some(b'<'), _) => (TT::LeftShift, 2),
+        (b'>', Some(b'>'), _) => (TT::RightShift, 2),
+        (b'<', Some(b'='), _) => (TT::LeftAngleBracketEquals, 2),
+        (b'>', Some(b'='), _) => (TT::RightAngleBracketEquals, 2),
+        (b'=', Some(b'='), _) => (TT::EqualsEquals, 2),
+        (b'!', Some(b'='), _) => (TT::BangEquals, 2),
+        (b'&', Some(b'&'), _) => (TT::AmpAmp, 2),
+        (b'|', Some(b'|'), _) => (TT::BarBar, 2),
+        (b'+', Some(b'='), _) => (TT::PlusEquals, 2),
+        (b'-', Some(b'='), _) => (TT::MinusEquals, 2),
+        (b'*', Some(b'='), _) => (TT::StarEquals, 2),
+        (b'/', Some(b'='), _) => (TT::SlashEquals, 2),
+        (b'%', Some(b'='), _) => (TT::PercentEquals, 2),
+        (b'&', Some(b'='), _) => (TT::AmpEquals, 2),
+        (b'|', Some(b'='), _) => (TT::BarEquals, 2),
+        (b'^', Some(b'='), _) => (TT::CaretEquals, 2),
+        (b'#', Some(b'#'), _) => (TT::PuncHashHash, 2),
+
+        (b'+', ..) => (TT::Plus, 1),
+        (b'-', ..) => (TT::Minus, 1),
+        (b'*', ..) => (TT::Star, 1),
+        (b'/', ..) => (TT::Slash, 1),
+        (b'%', ..) => (TT::Percent, 1),
+        (b'<', ..) => (TT::LeftAngleBracket, 1),
+        (b'>', ..) => (TT::RightAngleBracket, 1),
+        (b'=', ..) => (TT::Equals, 1),
+        (b'!', ..) => (TT::Bang, 1),
+        (b'&', ..) => (TT::Amp, 1),
+        (b'|', ..) => (TT::Bar, 1),
+        (b'^', ..) => (TT::Caret, 1),
+        (b'~', ..) => (TT::Tilde, 1),
+        (b'?', ..) => (TT::Question, 1),
+        (b':', ..) => (TT::Colon, 1),
+        (b'.', ..) => (TT::Dot, 1),
+        (b'(', ..) => (TT::PuncLeftParen, 1),
+        (b')', ..) => (TT::PuncRightParen, 1),
+        (b'{', ..) => (TT::PuncLeftBrace, 1),
+        (b'}', ..) => (TT::PuncRightBrace, 1),
+        (b'[', ..) => (TT::PuncLeftBracket, 1),
+        (b']', ..) => (TT::PuncRightBracket, 1),
+        (b';', ..) => (TT::PuncSemiColon, 1),
+        (b',', ..) => (TT::PuncComma, 1),
+        (b'#', ..) => (TT::PuncHash, 1),
+        _ => return None,
+    };
+
+    Some(punc)
 }
 
-fn skip_ws(input: &[char]) -> &[char] {
-    match input {
-        [] => input,
-        [f, r @ ..] => {
-            if f.is_whitespace() {
-                skip_ws(r)
-            } else {
-                input
-            }
-        }
+// length of the backslash-newline splice at the start of bytes, if any
+fn splice_len(bytes: &[u8]) -> usize {
+    match bytes {
+        [b'\\', b'\n', ..] => 2,
+        [b'\\', b'\r', b'\n', ..] => 3,
+        _ => 0,
     }
 }
 
@@ -248,13 +450,9 @@ mod test_arith {
     #[test]
     fn lit() {
         #[rustfmt::skip]
-        let input = fs::read(format!("{TEST_DIR}/lit.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let input = fs::read_to_string(format!("{TEST_DIR}/lit.c")).expect("file dne");
 
-        let output = super::lex(input.as_slice()).unwrap();
+        let output = super::lex(&input).unwrap();
         insta::assert_yaml_snapshot!(output, @r###"
         ---
         - lexeme: int
@@ -281,13 +479,9 @@ mod test_arith {
     #[test]
     fn add() {
         #[rustfmt::skip]
-        let input = fs::read(format!("{TEST_DIR}/add.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let input = fs::read_to_string(format!("{TEST_DIR}/add.c")).expect("file dne");
 
-        let output = super::lex(input.as_slice()).unwrap();
+        let output = super::lex(&input).unwrap();
         insta::assert_yaml_snapshot!(output, @r###"
         ---
         - lexeme: int
@@ -318,13 +512,9 @@ mod test_arith {
     #[test]
     fn add_multi() {
         #[rustfmt::skip]
-        let input = fs::read(format!("{TEST_DIR}/add_multi.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let input = fs::read_to_string(format!("{TEST_DIR}/add_multi.c")).expect("file dne");
 
-        let output = super::lex(input.as_slice()).unwrap();
+        let output = super::lex(&input).unwrap();
         insta::assert_yaml_snapshot!(output, @r###"
         ---
         - lexeme: int
@@ -359,13 +549,9 @@ mod test_arith {
     #[test]
     fn sub() {
         #[rustfmt::skip]
-        let input = fs::read(format!("{}/sub.c", TEST_DIR))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let input = fs::read_to_string(format!("{}/sub.c", TEST_DIR)).expect("file dne");
 
-        let output = super::lex(input.as_slice()).unwrap();
+        let output = super::lex(&input).unwrap();
         insta::assert_yaml_snapshot!(output, @r###"
         ---
         - lexeme: int
@@ -396,13 +582,9 @@ mod test_arith {
     #[test]
     fn mult() {
         #[rustfmt::skip]
-        let input = fs::read(format!("{TEST_DIR}/mult.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let input = fs::read_to_string(format!("{TEST_DIR}/mult.c")).expect("file dne");
 
-        let output = super::lex(input.as_slice()).unwrap();
+        let output = super::lex(&input).unwrap();
         insta::assert_yaml_snapshot!(output, @r###"
         ---
         - lexeme: int
@@ -433,13 +615,9 @@ mod test_arith {
     #[test]
     fn div() {
         #[rustfmt::skip]
-        let input = fs::read(format!("{TEST_DIR}/div.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let input = fs::read_to_string(format!("{TEST_DIR}/div.c")).expect("file dne");
 
-        let output = super::lex(input.as_slice()).unwrap();
+        let output = super::lex(&input).unwrap();
         insta::assert_yaml_snapshot!(output, @r###"
         ---
         - lexeme: int
@@ -476,13 +654,9 @@ mod test_bindings {
     #[test]
     fn asnmt() {
         #[rustfmt::skip]
-        let input = fs::read(format!("{TEST_DIR}/asnmt.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let input = fs::read_to_string(format!("{TEST_DIR}/asnmt.c")).expect("file dne");
 
-        let output = super::lex(input.as_slice()).unwrap();
+        let output = super::lex(&input).unwrap();
         insta::assert_yaml_snapshot!(output, @r###"
         ---
         - lexeme: int
@@ -519,13 +693,9 @@ mod test_bindings {
     #[test]
     fn composition() {
         #[rustfmt::skip]
-        let input = fs::read(format!("{TEST_DIR}/composition.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let input = fs::read_to_string(format!("{TEST_DIR}/composition.c")).expect("file dne");
 
-        let output = super::lex(input.as_slice()).unwrap();
+        let output = super::lex(&input).unwrap();
         insta::assert_yaml_snapshot!(output, @r###"
         ---
         - lexeme: int
@@ -632,13 +802,9 @@ mod test_control {
     #[test]
     fn lit() {
         #[rustfmt::skip]
-        let input = fs::read(format!("{TEST_DIR}/ifels_then.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let input = fs::read_to_string(format!("{TEST_DIR}/ifels_then.c")).expect("file dne");
 
-        let output = super::lex(input.as_slice()).unwrap();
+        let output = super::lex(&input).unwrap();
         insta::assert_yaml_snapshot!(output, @r###"
         ---
         - lexeme: int
@@ -686,3 +852,406 @@ mod test_control {
         "###);
     }
 }
+
+#[cfg(test)]
+mod test_tokens {
+    use super::TT;
+    use std::fs;
+    const TEST_DIR: &str = "tests/fixtures/snap/lexical";
+
+    fn typs(name: &str) -> Vec<TT> {
+        let input = fs::read_to_string(format!("{TEST_DIR}/{name}")).expect("file dne");
+        let output = super::lex(&input).unwrap();
+        output.into_iter().map(|t| t.typ).collect()
+    }
+
+    #[test]
+    fn punctuators() {
+        insta::assert_yaml_snapshot!(typs("punctuators.c"), @r###"
+        ---
+        - PuncLeftBracket
+        - PuncRightBracket
+        - PuncLeftParen
+        - PuncRightParen
+        - PuncLeftBrace
+        - PuncRightBrace
+        - Dot
+        - Arrow
+        - PlusPlus
+        - MinusMinus
+        - Amp
+        - Star
+        - Plus
+        - Minus
+        - Tilde
+        - Bang
+        - Slash
+        - Percent
+        - LeftShift
+        - RightShift
+        - LeftAngleBracket
+        - RightAngleBracket
+        - LeftAngleBracketEquals
+        - RightAngleBracketEquals
+        - EqualsEquals
+        - BangEquals
+        - Caret
+        - Bar
+        - AmpAmp
+        - BarBar
+        - Question
+        - Colon
+        - PuncSemiColon
+        - PuncEllipsis
+        - Equals
+        - StarEquals
+        - SlashEquals
+        - PercentEquals
+        - PlusEquals
+        - MinusEquals
+        - LeftShiftEquals
+        - RightShiftEquals
+        - AmpEquals
+        - CaretEquals
+        - BarEquals
+        - PuncComma
+        - PuncHash
+        - PuncHashHash
+        "###);
+    }
+
+    #[test]
+    fn floats() {
+        let input = fs::read_to_string(format!("{TEST_DIR}/floats.c")).expect("file dne");
+        let output = super::lex(&input).unwrap();
+        let toks = output
+            .iter()
+            .map(|t| format!("{} {:?}", t.lexeme, t.typ))
+            .collect::<Vec<_>>();
+        insta::assert_yaml_snapshot!(toks, @r###"
+        ---
+        - 1.0 LiteralFloat
+        - ".5 LiteralFloat"
+        - 1e5 LiteralFloat
+        - 1.5e-3f LiteralFloat
+        - 2.L LiteralFloat
+        - "0x1e+1 LiteralInt"
+        - 1..2 LiteralFloat
+        - x Alias
+        - ".5 LiteralFloat"
+        "###);
+    }
+
+    #[test]
+    fn keywords() {
+        insta::assert_yaml_snapshot!(typs("keywords.c"), @r###"
+        ---
+        - KeywordAuto
+        - KeywordBreak
+        - KeywordCase
+        - KeywordChar
+        - KeywordConst
+        - KeywordContinue
+        - KeywordDefault
+        - KeywordDo
+        - KeywordDouble
+        - KeywordEls
+        - KeywordEnum
+        - KeywordExtern
+        - KeywordFloat
+        - KeywordFor
+        - KeywordGoto
+        - KeywordIf
+        - KeywordInt
+        - KeywordLong
+        - KeywordRegister
+        - KeywordRet
+        - KeywordShort
+        - KeywordSigned
+        - KeywordSizeof
+        - KeywordStatic
+        - KeywordStruct
+        - KeywordSwitch
+        - KeywordTypedef
+        - KeywordUnion
+        - KeywordUnsigned
+        - KeywordVoid
+        - KeywordVolatile
+        - KeywordWhile
+        "###);
+    }
+
+    #[test]
+    fn identifiers() {
+        let input = fs::read_to_string(format!("{TEST_DIR}/identifiers.c")).expect("file dne");
+        let output = super::lex(&input).unwrap();
+        let ids = output
+            .iter()
+            .filter(|t| t.typ == TT::Alias)
+            .map(|t| t.lexeme.as_str())
+            .collect::<Vec<_>>();
+        insta::assert_yaml_snapshot!(ids, @r###"
+        ---
+        - "false"
+        - "true"
+        - bool
+        - main
+        - x1
+        - my_var
+        - _Tmp
+        - x1
+        - my_var
+        - Returns
+        - _Tmp
+        - bool
+        - t
+        - "true"
+        - Returns
+        - t
+        "###);
+    }
+
+    #[test]
+    fn maximal_munch() {
+        let input = fs::read_to_string(format!("{TEST_DIR}/munch.c")).expect("file dne");
+        let output = super::lex(&input).unwrap();
+        let lexemes = output.iter().map(|t| t.lexeme.as_str()).collect::<Vec<_>>();
+        insta::assert_yaml_snapshot!(lexemes, @r###"
+        ---
+        - x
+        - ++
+        - ++
+        - +
+        - y
+        - a
+        - "<<="
+        - b
+        - ">>="
+        - c
+        - a
+        - "-"
+        - "-"
+        - b
+        - x
+        - "."
+        - "."
+        - y
+        - p
+        - "->"
+        - q
+        "###);
+    }
+
+    #[test]
+    fn unterminated() {
+        let input = fs::read_to_string(format!("{TEST_DIR}/unterminated.c")).expect("file dne");
+        let errs = super::Lexer::new(&input, 0, super::LexOpts::default())
+            .filter_map(Result::err)
+            .map(|e| format!("{}:{}: {}", e.span.line, e.span.col, e.msg))
+            .collect::<Vec<_>>();
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "2:12: missing terminating \" character"
+        - "3:12: missing terminating ' character"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_spans {
+    use std::fs;
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/bindings";
+
+    #[test]
+    fn asnmt() {
+        #[rustfmt::skip]
+        let input = fs::read_to_string(format!("{TEST_DIR}/asnmt.c")).expect("file dne");
+
+        let output = super::lex_file(&input, 3, super::LexOpts::default()).unwrap();
+        let spans = output
+            .iter()
+            .map(|t| {
+                let s = t.span;
+                format!(
+                    "{} {}:{}:{} [{}, {})",
+                    t.lexeme, s.file, s.line, s.col, s.lo, s.hi
+                )
+            })
+            .collect::<Vec<_>>();
+
+        insta::assert_yaml_snapshot!(spans, @r###"
+        ---
+        - "int 3:1:1 [0, 3)"
+        - "main 3:1:5 [4, 8)"
+        - "( 3:1:9 [8, 9)"
+        - ") 3:1:10 [9, 10)"
+        - "{ 3:1:12 [11, 12)"
+        - "int 3:2:5 [17, 20)"
+        - "x 3:2:9 [21, 22)"
+        - "= 3:2:11 [23, 24)"
+        - "8 3:2:13 [25, 26)"
+        - "; 3:2:14 [26, 27)"
+        - "return 3:3:5 [32, 38)"
+        - "x 3:3:12 [39, 40)"
+        - "; 3:3:13 [40, 41)"
+        - "} 3:4:1 [42, 43)"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_comments {
+    use super::{LexOpts, TT};
+    use std::fs;
+    const TEST_DIR: &str = "tests/fixtures/snap/whitespace";
+
+    fn read(name: &str) -> String {
+        fs::read_to_string(format!("{TEST_DIR}/{name}")).expect("file dne")
+    }
+
+    #[test]
+    fn block_comment() {
+        let input = read("block_comment.c");
+        let output = super::lex(&input).unwrap();
+        insta::assert_yaml_snapshot!(output, @r###"
+        ---
+        - lexeme: int
+          typ: KeywordInt
+        - lexeme: main
+          typ: Alias
+        - lexeme: (
+          typ: PuncLeftParen
+        - lexeme: )
+          typ: PuncRightParen
+        - lexeme: "{"
+          typ: PuncLeftBrace
+        - lexeme: return
+          typ: KeywordRet
+        - lexeme: "9"
+          typ: LiteralInt
+        - lexeme: ;
+          typ: PuncSemiColon
+        - lexeme: "}"
+          typ: PuncRightBrace
+        "###);
+    }
+
+    #[test]
+    fn line_comment() {
+        let input = read("line_comment.c");
+        let opts = LexOpts {
+            line_comments: true,
+            ..LexOpts::default()
+        };
+
+        let output = super::lex_file(&input, 0, opts).unwrap();
+        insta::assert_yaml_snapshot!(output, @r###"
+        ---
+        - lexeme: int
+          typ: KeywordInt
+        - lexeme: main
+          typ: Alias
+        - lexeme: (
+          typ: PuncLeftParen
+        - lexeme: )
+          typ: PuncRightParen
+        - lexeme: "{"
+          typ: PuncLeftBrace
+        - lexeme: return
+          typ: KeywordRet
+        - lexeme: "9"
+          typ: LiteralInt
+        - lexeme: ;
+          typ: PuncSemiColon
+        - lexeme: "}"
+          typ: PuncRightBrace
+        "###);
+    }
+
+    #[test]
+    fn line_comment_c89() {
+        let input = read("line_comment.c");
+        let output = super::lex(&input).unwrap();
+        assert_eq!((output[0].typ, output[1].typ), (TT::Slash, TT::Slash));
+    }
+
+    #[test]
+    fn splice() {
+        let input = read("splice.c");
+        let output = super::lex(&input).unwrap();
+        let spans = output
+            .iter()
+            .map(|t| {
+                let s = t.span;
+                format!("{} {}:{} [{}, {})", t.lexeme, s.line, s.col, s.lo, s.hi)
+            })
+            .collect::<Vec<_>>();
+
+        insta::assert_yaml_snapshot!(spans, @r###"
+        ---
+        - "int 1:1 [0, 3)"
+        - "main 1:5 [4, 10)"
+        - "( 2:3 [10, 11)"
+        - ") 2:4 [11, 12)"
+        - "{ 2:6 [13, 14)"
+        - "return 3:5 [19, 28)"
+        - "10 4:5 [29, 33)"
+        - "; 5:2 [33, 34)"
+        - "} 6:1 [35, 36)"
+        "###);
+    }
+
+    #[test]
+    fn unterminated_comment() {
+        let input = read("unterminated_comment.c");
+        let mut sm = crate::source::SourceMap::new();
+        let file = sm.add("unterminated_comment.c", &input);
+
+        let err = super::lex_file(&input, file, LexOpts::default()).unwrap_err();
+        insta::assert_snapshot!(crate::diagnostic::render(&err, &sm), @r###"
+        error[E0001]: unterminated comment
+         --> unterminated_comment.c:2:15
+          |
+        2 |     return 9; /* this comment
+          |               ^^
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_scale {
+    use super::{LexOpts, Lexer, TT};
+
+    #[test]
+    fn megabyte() {
+        // the recursive lexer overflowed the stack long before this
+        let input = "int main() { return 9 + 10; }\n".repeat(1 << 16);
+        assert!(input.len() > 1 << 20);
+
+        let output = super::lex(&input).unwrap();
+        assert_eq!(output.len(), 11 << 16);
+        assert_eq!(output.last().unwrap().span.line, 1 << 16);
+    }
+
+    #[test]
+    fn recovers() {
+        let input = "int main() {\n    return 0@1 `;\n    return '1;\n}";
+        let output = Lexer::new(input, 0, LexOpts::default()).collect::<Vec<_>>();
+
+        // stray characters are pp-tokens of their own, left for the parser
+        let others = output
+            .iter()
+            .filter_map(|t| t.as_ref().ok().filter(|t| t.typ == TT::Other))
+            .map(|t| (t.lexeme.as_str(), t.span.line, t.span.col))
+            .collect::<Vec<_>>();
+        assert_eq!(others, [("@", 2, 13), ("`", 2, 16)]);
+
+        let errors = output
+            .iter()
+            .filter_map(|t| t.as_ref().err())
+            .map(|e| (e.msg.as_str(), e.span.line, e.span.col))
+            .collect::<Vec<_>>();
+        assert_eq!(errors, [("missing terminating ' character", 3, 12)]);
+        assert!(matches!(&output[output.len() - 1], Ok(t) if t.typ == TT::PuncRightBrace));
+    }
+}
@@ -1,14 +1,19 @@
 use serde::{Deserialize, Serialize};
+use source::Span;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::fmt::{self, Debug};
 use std::rc::Rc;
 
 // pub mod evaluator;
 pub mod allocator;
+pub mod diagnostic;
 pub mod lexer;
 pub mod parser;
 pub mod parser_ast;
+pub mod preprocessor;
 pub mod selector;
+pub mod source;
 pub mod translator;
 pub mod typer;
 pub mod visualizer;
@@ -31,6 +36,11 @@ macro_rules! common_enum {
 }
 
 common_enum! { pub enum OptLevel { O0, O1, O2 } }
+
+// RV32I has no floating point, so float arithmetic is lowered to calls into
+// libgcc's soft-fp routines. Hard emits RV32F/D instructions instead, but
+// values still cross function calls in integer registers (ilp32)
+common_enum! { pub enum FloatMode { Soft, Hard } }
 impl TryFrom<u32> for OptLevel {
     type Error = &'static str;
 
@@ -49,9 +59,112 @@ impl TryFrom<u32> for OptLevel {
 // *********************************************************************************************************************
 
 // ***** static tnv: Map<Alias, Type> *****
-common_struct! { pub struct Tnv { fnv: HashMap<String, LambdaType>, vnv: HashMap<String, Type> }}
-common_struct! { pub struct LambdaType { fp: Vec<Type>, body: Type } }
-common_enum! { pub enum Type { Int, Bool, Void } } // Cond(Type::Bool, Box<Type>, Box<Type>),
+common_struct! { pub struct Tnv { fnv: HashMap<String, LambdaType>, #[serde(skip)] fdecls: HashMap<String, Span>, vnv: HashMap<String, Type>, #[serde(skip)] warnings: RefCell<Vec<diagnostic::Diagnostic>>, #[serde(skip)] records: Records }} // fdecls: where each function is declared. records: the program's struct and union layouts
+common_struct! { pub struct LambdaType { fp: Vec<Type>, body: Type, variadic: bool, proto: bool } } // f() declares no prototype, f(void) does
+common_enum! {
+    #[rustfmt::skip]
+    pub enum Type {
+        Char, SChar, UChar, Short, UShort, Int, UInt, Long, ULong, Float, Double, LongDouble, Bool, Void, // long double is represented as double
+        LongLong, ULongLong, // not C89 but accepted as gcc does. they have a size but no arithmetic yet
+        Pointer(Box<Type>), Array(Box<Type>, Option<usize>), Func(Box<LambdaType>), // derived (declarators). an array of unknown size is incomplete
+        Struct(Option<String>, usize), Union(Option<String>, usize), // tag (None when anonymous) and record id. enums are int
+        Const(Box<Type>), Volatile(Box<Type>), // qualified. const wraps volatile when both are given
+    }
+}
+
+impl Type {
+    // the type without its top-level qualifiers
+    pub fn unqual(&self) -> &Type {
+        match self {
+            Type::Const(t) | Type::Volatile(t) => t.unqual(),
+            t => t,
+        }
+    }
+
+    // size and alignment in bytes on ILP32. None for incomplete and function types
+    pub fn size(&self, rs: &Records) -> Option<usize> {
+        match self.unqual() {
+            Type::Char | Type::SChar | Type::UChar => Some(1),
+            Type::Short | Type::UShort => Some(2),
+            Type::Int | Type::UInt | Type::Long | Type::ULong | Type::Float | Type::Bool => Some(4),
+            Type::Pointer(_) => Some(4),
+            Type::Double | Type::LongDouble | Type::LongLong | Type::ULongLong => Some(8),
+            Type::Array(t, Some(n)) => t.size(rs).map(|s| s * n),
+            Type::Struct(_, id) | Type::Union(_, id) => rs.get(*id).complete().map(|r| r.size),
+            _ => None,
+        }
+    }
+
+    pub fn align(&self, rs: &Records) -> Option<usize> {
+        match self.unqual() {
+            Type::Array(t, _) => t.align(rs),
+            Type::Struct(_, id) | Type::Union(_, id) => rs.get(*id).complete().map(|r| r.align),
+            t => t.size(rs),
+        }
+    }
+
+    // structs and unions are aggregates too, but unlike arrays they are values
+    pub fn is_record(&self) -> bool {
+        matches!(self.unqual(), Type::Struct(..) | Type::Union(..))
+    }
+}
+
+// struct and union types refer to their members through an id into the
+// records of their translation unit, so a tag can be used before its members
+// are known (struct node *next) and every use of it sees them once they are.
+// the parser fills them in, and they travel with the program to the typer
+// and the translator
+common_struct! { pub struct Record { pub members: Option<Vec<Member>>, pub size: usize, pub align: usize } } // members are None while incomplete
+common_struct! { pub struct Member { pub alias: String, pub typ: Type, pub offset: usize } }
+common_struct! { #[derive(Default)] pub struct Records { rs: Vec<Record> } }
+
+impl Record {
+    pub fn complete(&self) -> Option<&Record> {
+        self.members.is_some().then_some(self)
+    }
+
+    pub fn member(&self, alias: &str) -> Option<&Member> {
+        self.members.as_ref()?.iter().find(|m| m.alias == alias)
+    }
+}
+
+impl Records {
+    pub fn fresh(&mut self) -> usize {
+        self.rs.push(Record {
+            members: None,
+            size: 0,
+            align: 1,
+        });
+        self.rs.len() - 1
+    }
+
+    // ILP32: every member sits at the next multiple of its alignment (all of a
+    // union's at 0), and the size is padded to a multiple of the strictest one
+    // so that the members of an array of records stay aligned too. members
+    // must be complete and not functions
+    pub fn define(&mut self, id: usize, union: bool, members: Vec<(String, Type)>) {
+        let (mut size, mut align) = (0usize, 1);
+        let members = members
+            .into_iter()
+            .map(|(alias, typ)| {
+                let (s, a) = (typ.size(self).unwrap(), typ.align(self).unwrap());
+                let offset = if union { 0 } else { size.next_multiple_of(a) };
+                (size, align) = (size.max(offset + s), align.max(a));
+                Member { alias, typ, offset }
+            })
+            .collect();
+
+        self.rs[id] = Record {
+            members: Some(members),
+            size: size.next_multiple_of(align),
+            align,
+        };
+    }
+
+    pub fn get(&self, id: usize) -> &Record {
+        &self.rs[id]
+    }
+}
 
 // ***** dynamic vnv: Map<Alias, Val> *****
 common_struct! { pub struct Vnv { fnv: HashMap<String, LambdaVal>, vnv: HashMap<String, i32> }} // todo, -> Val
@@ -144,6 +257,12 @@ impl Node for StartNode {
     }
 }
 
+impl Default for StartNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl StartNode {
     pub fn new() -> Self {
         Self {
@@ -218,6 +337,33 @@ impl ConstantNode {
     }
 }
 
+pub struct AddNode {
+    _id: i128,
+    use_def: Vec<Rc<dyn Node>>,
+    _def_use: Vec<Rc<dyn Node>>,
+}
+
+impl Node for AddNode {
+    fn use_defs(&self) -> &[Rc<dyn Node>] {
+        &self.use_def
+    }
+
+    fn print(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.use_def.iter().for_each(|n| n.print(f).unwrap()); // FIXME; no unwrap
+        writeln!(f, "AddNode")
+    }
+}
+
+impl AddNode {
+    pub fn new(l: Rc<dyn Node>, r: Rc<dyn Node>) -> Self {
+        Self {
+            _id: fresh_id(),
+            use_def: vec![l, r],
+            _def_use: vec![],
+        }
+    }
+}
+
 // TODO: for loops, etc.
 type _SugaredPrg = Vec<()>;
 
@@ -227,36 +373,109 @@ type _SugaredPrg = Vec<()>;
 // picoc's source representation is a forest of ASTS
 // since variable and function are not values
 
-type SPrg = Vec<SDef>;
+// every node carries the span of the source it was parsed from. spans are
+// skipped by serde so snapshots only capture the shape of the tree.
+
+// a translation unit: its definitions, and the records their types refer to
+// (serialized as just the definitions)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SPrg {
+    pub defs: Vec<SDef>,
+    #[serde(skip)]
+    pub records: Records,
+}
 common_enum! { pub enum SDef { FuncDef(SFuncDef), VarDef(SVarDef) } }
-common_struct! { pub struct SFuncDef {pub alias: String,  pub typ: Type, pub fps: Vec<(String, Type)>, pub body: Vec<SStmt> } } // fp needs Type for statics, and String for dynamics
-common_struct! { pub struct SVarDef { pub alias: String, pub typ: Type, pub expr: Box<SExpr> }} // UpdateBind { alias: String, op: BinOp, expr: Box<Expr> }
+common_struct! { pub struct SFuncDef {pub alias: String, pub sc: Option<StorageClass>, pub typ: Type, pub fps: Vec<SParam>, pub variadic: bool, pub proto: bool, pub body: Vec<SStmt>, #[serde(skip)] pub span: Span } } // fp needs Type for statics, and String for dynamics. typ is the return type. f() and f(a, b) are not prototypes
+common_struct! { pub struct SParam { pub alias: String, pub typ: Type, #[serde(skip)] pub span: Span } }
+common_struct! { pub struct SVarDef { pub alias: String, pub sc: Option<StorageClass>, pub typ: Type, pub expr: Option<Box<SExpr>>, #[serde(skip)] pub span: Span }}
+common_enum! { pub enum StorageClass { Auto, Register, Static, Extern, Typedef } } // typedef is one syntactically (C89 3.5.1)
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SStmt {
+    pub kind: SStmtKind,
+    #[serde(skip)]
+    pub span: Span,
+}
+
+// the typer annotates every expression with its type. an lvalue's is the
+// type of the object it designates
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SExpr {
+    #[serde(flatten)]
+    pub kind: SExprKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<Type>,
+    #[serde(skip)]
+    pub span: Span,
+}
+
+impl SStmt {
+    pub fn new(kind: SStmtKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+impl SExpr {
+    pub fn new(kind: SExprKind, span: Span) -> Self {
+        Self {
+            kind,
+            typ: None,
+            span,
+        }
+    }
+
+    pub fn typed(kind: SExprKind, typ: Type, span: Span) -> Self {
+        Self {
+            kind,
+            typ: Some(typ),
+            span,
+        }
+    }
+}
 
 common_enum! {
-    pub enum SStmt {
-        IfEls { cond: Box<SExpr>, then: Box<SStmt>, els: Option<Box<SStmt>> }, While { cond: Box<SExpr>, body: Box<SStmt> }, // control
-        Asnmt(SVarDef), Return(SExpr), // bindings (intros in C)
+    #[rustfmt::skip]
+    pub enum SStmtKind {
+        IfEls { cond: Box<SExpr>, then: Box<SStmt>, els: Option<Box<SStmt>> }, Switch { cond: Box<SExpr>, body: Box<SStmt> }, // control
+        While { cond: Box<SExpr>, body: Box<SStmt> }, DoWhile { body: Box<SStmt>, cond: Box<SExpr> },
+        For { init: Option<Box<SExpr>>, cond: Option<Box<SExpr>>, update: Option<Box<SExpr>>, body: Box<SStmt> }, // a missing condition is true
+        Case(i32, Box<SStmt>), Default(Box<SStmt>), Labeled(String, Box<SStmt>), // labels
+        Break, Continue, Goto(String), Return(Option<SExpr>), // jumps
+        Block(Vec<SStmt>), Expr(SExpr), Empty, // a block is a scope
+        Decl(Vec<SVarDef>), // bindings (intros in C). one declaration can introduce several
     }
 }
 
 common_enum! {
     #[rustfmt::skip]
-    pub enum SExpr {
+    pub enum SExprKind {
         // intros
-        Int(i32), Bool(bool),
+        Int(i32), UInt(u32), Long(i32), ULong(u32), Float(f32), Double(f64), LongDouble(f64), Bool(bool),
+        Str(Vec<u8>), // strings are unterminated: the backend appends the NUL
 
         // elims
         UnaryE { op: SUnaryOp, l: Box<SExpr> }, BinE { op: SBinOp, l: Box<SExpr>, r: Box<SExpr> }, LogE { op: SLogOp, l: Box<SExpr>, r: Box<SExpr> },
         BitE { op: SBitOp, l: Box<SExpr>, r: Box<SExpr> }, RelE { op: SRelOp, l: Box<SExpr>, r: Box<SExpr> },
-        VarApp(String), FuncApp{ alias: String, aps: Vec<SExpr> }
+        Ternary { cond: Box<SExpr>, then: Box<SExpr>, els: Box<SExpr> }, Comma { l: Box<SExpr>, r: Box<SExpr> }, // sequencing
+        Asnmt { op: Option<SAsnmtOp>, l: Box<SExpr>, r: Box<SExpr> }, Update { op: SUpdateOp, l: Box<SExpr> }, // side effects. a plain `=` has no op
+        VarApp(String), FuncApp{ f: Box<SExpr>, aps: Vec<SExpr> }, // f designates the function called
+        Deref(Box<SExpr>), AddrOf(Box<SExpr>), Index { l: Box<SExpr>, r: Box<SExpr> }, // pointers
+        Dot { l: Box<SExpr>, field: String }, Arrow { l: Box<SExpr>, field: String }, // members of a record and of a pointed-to one
+        Cast { typ: Type, l: Box<SExpr> }, SizeOfT(Type), SizeOfE(Box<SExpr>), // type names (abstract declarators)
+        InitList(Vec<SExpr>), // a braced initializer, which is only ever one
+        Load(Box<SExpr>), // the value of an lvalue, made explicit by the typer. an array's is a pointer to its first element
     }
 }
 
 common_enum! { pub enum SLogOp { And, Or } }
-common_enum! { pub enum SBitOp { And, Or, Xor } }
-common_enum! { pub enum SRelOp { Eq, Neq, And, Or, LtEq, Lt, GtEq, Gt } }
+common_enum! { pub enum SBitOp { And, Or, Xor, Shl, Shr } }
+common_enum! { pub enum SRelOp { Eq, Neq, LtEq, Lt, GtEq, Gt } }
 common_enum! { pub enum SBinOp { Add, Sub, Mult, Div, Mod } }
-common_enum! { pub enum SUnaryOp { Add, Sub } }
+common_enum! { pub enum SUnaryOp { Add, Sub, Not, BitNot } }
+common_enum! { pub enum SAsnmtOp { Add, Sub, Mult, Div, Mod, Shl, Shr, And, Xor, Or } } // compound assignments
+common_enum! { pub enum SUpdateOp { PreInc, PreDec, PostInc, PostDec } }
 
 //
 //
@@ -273,29 +492,38 @@ common_enum! { pub enum SUnaryOp { Add, Sub } }
 // - control/functions: -> jump w/ labels
 // - bindings: -> loads/stores w/ unlimited temps
 
+// objects live in memory at -O0: locals at fixed offsets from fp, and a
+// record's value is its address, so copying one is a block Copy
+
 type IPrg = Vec<IStmt>;
 common_enum! {
     pub enum IStmt {
-        Jump(Label), CJump(SExpr, Label, Label), // control
-        Compute(Temp, IExpr), Load(Temp, RiscvPointerReg), Store(RiscvPointerReg, Temp), // bindings
-        Seq(Label, Vec<Box<IStmt>>), Return(IExpr), // functions
-        // todo: maybe rename seq to func if not used for conditionals
+        Label(Label), Jump(Label), CJump(IExpr, Label, Label), // control. a cjump goes to the first label when the value is nonzero
+        Exp(IExpr), Compute(Temp, IExpr), Store(Width, IExpr, IExpr), Copy(Width, usize, IExpr, IExpr), // bindings. an exp is evaluated for its effects, stores and copies go to the first address
+        Func(Label, bool, usize, usize, Vec<Box<IStmt>>), Return(IExpr), // functions. a function is exported unless it has internal linkage, and its frame holds the given bytes of locals, and a variadic one's the bytes of argument registers it saves above ra and fp
+        Data(Global), // objects with static storage duration
     }
 }
 
-// do i need
-// - compute?
-// - seq if i have return?
-
 common_enum! {
     pub enum IExpr {
         Const(i32), BinOp(IBinOp, Box<IExpr>, Box<IExpr>), // arithmetic``
-        TempUse(Temp), // bindings
-        Call(Label, Vec<IExpr>), // functions
+        FConst(INum, f64), FBinOp(INum, IBinOp, Box<IExpr>, Box<IExpr>), FNeg(INum, Box<IExpr>), Cvt(INum, INum, Box<IExpr>), // floating point
+        FRel(INum, IRelOp, Box<IExpr>, Box<IExpr>), // a comparison of floats, which is 1 or 0. only lt, lteq and eq
+        TempUse(Temp), Mem(Width, Box<IExpr>), ESeq(Box<IStmt>, Box<IExpr>), Name(Label), // bindings. a name is the address of a label
+        Call(Label, Vec<IExpr>, usize), CallPtr(Box<IExpr>, Vec<IExpr>, usize), // functions. the callee returns the given number of words. a pointer is evaluated before the arguments
     }
 }
 
-common_enum! { pub enum IBinOp { Add, Sub, Mult, Div, Mod } }
+common_enum! { pub enum IBinOp { Add, Sub, Mult, Div, DivU, Mod, ModU, And, Or, Xor, Shl, Shr, Sar, Lt, LtU } } // shr shifts in zeros, sar copies of the sign bit. lt is 1 or 0
+common_enum! { pub enum INum { I32, U32, F32, F64 } } // machine representations of arithmetic types
+
+// a global is exported unless it has internal linkage (static). its contents
+// are values of a width, strings, runs of zero bytes and addresses (a label
+// plus an offset, which the linker relocates), and all zeros in .bss
+common_struct! { pub struct Global { pub label: Label, pub section: Section, pub align: usize, pub exported: bool, pub data: Vec<IData> } }
+common_enum! { pub enum Section { Data, Bss, RoData } }
+common_enum! { pub enum IData { Value(Width, i64), Ascii(Vec<u8>), Zero(usize), Addr(Label, i64) } }
 common_enum! { pub enum IBitOp { And, Or, Xor } }
 common_enum! { pub enum IRelOp { Eq, Neq, And, Or, LtEq, Lt, GtEq, Gt } }
 
@@ -318,36 +546,73 @@ common_enum! { pub enum IRelOp { Eq, Neq, And, Or, LtEq, Lt, GtEq, Gt } }
 type Imm = i32;
 common_enum! { pub enum Temp { UserTemp(String), MachineTemp(usize), PointerReg(RiscvPointerReg) } } // only util regs in abstract assembly
 common_enum! { pub enum Label { UserLabel(String), MachineLabel(usize) } }
-impl ToString for Label {
-    fn to_string(&self) -> String {
-        match self {
-            Label::UserLabel(l) => l.clone(),
-            Label::MachineLabel(_l) => todo!(),
-        }
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Label::UserLabel(l) => l.as_str(),
+            Label::MachineLabel(l) => return write!(f, ".L{l}"), // local to the assembly file
+        };
+        write!(f, "{s}")
     }
 }
 
-// FIXME: no static mut
-static mut TEMP_COUNTER: usize = 0;
-static mut LABEL_COUNTER: usize = 0;
+// counters are per thread so that compilations (and tests) running
+// side by side each number their temps and labels from zero
+thread_local! {
+    static TEMP_COUNTER: Cell<usize> = const { Cell::new(0) };
+    static LABEL_COUNTER: Cell<usize> = const { Cell::new(0) };
+}
 
 pub fn fresh_temp() -> Temp {
-    unsafe {
-        let temp = TEMP_COUNTER;
-        TEMP_COUNTER += 1;
-        Temp::MachineTemp(temp)
-    }
+    Temp::MachineTemp(TEMP_COUNTER.replace(TEMP_COUNTER.get() + 1))
 }
 
 pub fn fresh_label() -> Label {
-    unsafe {
-        let label = LABEL_COUNTER;
-        LABEL_COUNTER += 1;
-        Label::MachineLabel(label)
+    Label::MachineLabel(LABEL_COUNTER.replace(LABEL_COUNTER.get() + 1))
+}
+
+// the width of a load or store. narrow loads sign-extend, or zero-extend (U).
+// a double moves both of its words at once
+common_enum! { pub enum Width { B, BU, H, HU, W, D } }
+
+impl Width {
+    pub fn bytes(&self) -> usize {
+        match self {
+            Width::B | Width::BU => 1,
+            Width::H | Width::HU => 2,
+            Width::W => 4,
+            Width::D => 8,
+        }
+    }
+
+    pub fn load(&self) -> &'static str {
+        match self {
+            Width::B => "lb",
+            Width::BU => "lbu",
+            Width::H => "lh",
+            Width::HU => "lhu",
+            Width::W | Width::D => "lw",
+        }
+    }
+
+    pub fn store(&self) -> &'static str {
+        match self {
+            Width::B | Width::BU => "sb",
+            Width::H | Width::HU => "sh",
+            Width::W | Width::D => "sw",
+        }
     }
 }
 
 common_enum! { pub enum RiscvPointerReg { Z, Ra, Sp, Gp, Tp, Fp, A0, A1, A2, A3, A4, A5, A6, A7, Pc } }
+impl RiscvPointerReg {
+    // the i-th argument register
+    pub fn arg(i: usize) -> Option<Self> {
+        use RiscvPointerReg::*;
+        [A0, A1, A2, A3, A4, A5, A6, A7].get(i).cloned()
+    }
+}
+
 impl From<RiscvPointerReg> for RscvReg {
     fn from(ptr: RiscvPointerReg) -> Self {
         match ptr {
@@ -370,25 +635,26 @@ impl From<RiscvPointerReg> for RscvReg {
     }
 }
 
-impl ToString for RiscvPointerReg {
-    fn to_string(&self) -> String {
-        match self {
-            RiscvPointerReg::Z => "zero".to_string(),
-            RiscvPointerReg::Ra => "ra".to_string(),
-            RiscvPointerReg::Sp => "sp".to_string(),
-            RiscvPointerReg::Gp => "gp".to_string(),
-            RiscvPointerReg::Tp => "tp".to_string(),
-            RiscvPointerReg::Fp => "fp".to_string(),
-            RiscvPointerReg::A0 => "a0".to_string(),
-            RiscvPointerReg::A1 => "a1".to_string(),
-            RiscvPointerReg::A2 => "a2".to_string(),
-            RiscvPointerReg::A3 => "a3".to_string(),
-            RiscvPointerReg::A4 => "a4".to_string(),
-            RiscvPointerReg::A5 => "a5".to_string(),
-            RiscvPointerReg::A6 => "a6".to_string(),
-            RiscvPointerReg::A7 => "a7".to_string(),
-            RiscvPointerReg::Pc => "pc".to_string(),
-        }
+impl fmt::Display for RiscvPointerReg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            RiscvPointerReg::Z => "zero",
+            RiscvPointerReg::Ra => "ra",
+            RiscvPointerReg::Sp => "sp",
+            RiscvPointerReg::Gp => "gp",
+            RiscvPointerReg::Tp => "tp",
+            RiscvPointerReg::Fp => "fp",
+            RiscvPointerReg::A0 => "a0",
+            RiscvPointerReg::A1 => "a1",
+            RiscvPointerReg::A2 => "a2",
+            RiscvPointerReg::A3 => "a3",
+            RiscvPointerReg::A4 => "a4",
+            RiscvPointerReg::A5 => "a5",
+            RiscvPointerReg::A6 => "a6",
+            RiscvPointerReg::A7 => "a7",
+            RiscvPointerReg::Pc => "pc",
+        };
+        write!(f, "{s}")
     }
 }
 
@@ -404,60 +670,144 @@ common_enum! {
     pub enum TQuad {
         Reg(TRegOp, Temp, Temp, Temp),
         Imm(TImmOp, Temp, Temp, Imm),
-        Mem(TMemOp, Temp, usize, RiscvPointerReg),
+        Mem(TMemOp, Width, Temp, usize, Temp), Copy(Width, usize, Temp, Temp), // copies the given number of units from the second address to the first
+        FImm(TFmt, Temp, u64), FReg(TFRegOp, TFmt, Temp, Temp, Temp), FNeg(TFmt, Temp, Temp), FCvt(TFCvtOp, Temp, Temp), // RV32F/D
+        Call(Label, Vec<usize>, usize), CallPtr(Vec<usize>, usize), RetVal(usize), // calls taking and returning the given number of words, and the value returned. a pointer's callee is under its arguments
+        Jump(Label), CJump(Temp, Label, Label), Pop(Temp), // control, and dropping a value nothing uses
         Pseudo(PseudoOp),
-        Label(Label),
+        Label(Label), Globl(Label), Data(Global), // a globl label is visible to the linker
     }
 }
 
-common_enum! { pub enum TRegOp { Add, Sub, And, Or, Xor, Beq, Bneq, Bge, Blt, Jal } }
+common_enum! { pub enum TRegOp { Add, Sub, Mul, Div, Divu, Rem, Remu, And, Or, Xor, Sll, Srl, Sra, Slt, Sltu, Beq, Bneq, Bge, Blt, Jal } }
 common_enum! { pub enum TImmOp { AddI, SubI, AndI, OrI, XorI } }
 common_enum! { pub enum TMemOp { Load, Store } }
-common_enum! { pub enum PseudoOp { Call(Label), Ret } }
+common_enum! { pub enum PseudoOp { Call(Label), Ret, Li(Temp, Imm), La(Temp, Label) } } // la is lui and addi with %hi and %lo
+common_enum! { pub enum TFmt { S, D } }
+common_enum! { pub enum TFRegOp { FAdd, FSub, FMul, FDiv, FLt, FLe, FEq } } // the comparisons write an integer register
+common_enum! { pub enum TFCvtOp { WToS, WuToS, WToD, WuToD, SToW, SToWu, DToW, DToWu, SToD, DToS } }
 
-impl ToString for TRegOp {
-    fn to_string(&self) -> String {
-        match self {
-            TRegOp::Add => "add".to_string(),
-            TRegOp::Sub => "sub".to_string(),
-            TRegOp::And => "and".to_string(),
-            TRegOp::Or => "or".to_string(),
-            TRegOp::Xor => "xor".to_string(),
-            TRegOp::Beq => "beq".to_string(),
-            TRegOp::Bneq => "bne".to_string(),
-            TRegOp::Bge => "bge".to_string(),
-            TRegOp::Blt => "blt".to_string(),
-            TRegOp::Jal => "jal".to_string(),
-        }
+impl fmt::Display for TRegOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TRegOp::Add => "add",
+            TRegOp::Sub => "sub",
+            TRegOp::Mul => "mul",
+            TRegOp::Div => "div",
+            TRegOp::Divu => "divu",
+            TRegOp::Rem => "rem",
+            TRegOp::Remu => "remu",
+            TRegOp::And => "and",
+            TRegOp::Or => "or",
+            TRegOp::Xor => "xor",
+            TRegOp::Sll => "sll",
+            TRegOp::Srl => "srl",
+            TRegOp::Sra => "sra",
+            TRegOp::Slt => "slt",
+            TRegOp::Sltu => "sltu",
+            TRegOp::Beq => "beq",
+            TRegOp::Bneq => "bne",
+            TRegOp::Bge => "bge",
+            TRegOp::Blt => "blt",
+            TRegOp::Jal => "jal",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl fmt::Display for TImmOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TImmOp::AddI => "addi",
+            TImmOp::SubI => "subi",
+            TImmOp::AndI => "andi",
+            TImmOp::OrI => "ori",
+            TImmOp::XorI => "xori",
+        };
+        write!(f, "{s}")
     }
 }
 
-impl ToString for TImmOp {
-    fn to_string(&self) -> String {
+impl TFmt {
+    pub fn words(&self) -> usize {
         match self {
-            TImmOp::AddI => "addi".to_string(),
-            TImmOp::SubI => "subi".to_string(),
-            TImmOp::AndI => "andi".to_string(),
-            TImmOp::OrI => "ori".to_string(),
-            TImmOp::XorI => "xori".to_string(),
+            TFmt::S => 1,
+            TFmt::D => 2,
         }
     }
 }
 
-impl ToString for TMemOp {
-    fn to_string(&self) -> String {
+impl fmt::Display for TFmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TFmt::S => "s",
+            TFmt::D => "d",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl TFRegOp {
+    pub fn is_cmp(&self) -> bool {
+        matches!(self, TFRegOp::FLt | TFRegOp::FLe | TFRegOp::FEq)
+    }
+}
+
+impl fmt::Display for TFRegOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TFRegOp::FAdd => "fadd",
+            TFRegOp::FSub => "fsub",
+            TFRegOp::FMul => "fmul",
+            TFRegOp::FDiv => "fdiv",
+            TFRegOp::FLt => "flt",
+            TFRegOp::FLe => "fle",
+            TFRegOp::FEq => "feq",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl TFCvtOp {
+    // the formats converted from and to. None is an integer register
+    pub fn fmts(&self) -> (Option<TFmt>, Option<TFmt>) {
         match self {
-            TMemOp::Load => "lw".to_string(),
-            TMemOp::Store => "sw".to_string(),
+            TFCvtOp::WToS | TFCvtOp::WuToS => (None, Some(TFmt::S)),
+            TFCvtOp::WToD | TFCvtOp::WuToD => (None, Some(TFmt::D)),
+            TFCvtOp::SToW | TFCvtOp::SToWu => (Some(TFmt::S), None),
+            TFCvtOp::DToW | TFCvtOp::DToWu => (Some(TFmt::D), None),
+            TFCvtOp::SToD => (Some(TFmt::S), Some(TFmt::D)),
+            TFCvtOp::DToS => (Some(TFmt::D), Some(TFmt::S)),
         }
     }
 }
 
-impl ToString for PseudoOp {
-    fn to_string(&self) -> String {
+// conversions to integers truncate toward zero (rtz) like C casts do
+impl fmt::Display for TFCvtOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TFCvtOp::WToS => "fcvt.s.w",
+            TFCvtOp::WuToS => "fcvt.s.wu",
+            TFCvtOp::WToD => "fcvt.d.w",
+            TFCvtOp::WuToD => "fcvt.d.wu",
+            TFCvtOp::SToW => "fcvt.w.s",
+            TFCvtOp::SToWu => "fcvt.wu.s",
+            TFCvtOp::DToW => "fcvt.w.d",
+            TFCvtOp::DToWu => "fcvt.wu.d",
+            TFCvtOp::SToD => "fcvt.d.s",
+            TFCvtOp::DToS => "fcvt.s.d",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl fmt::Display for PseudoOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            PseudoOp::Call(l) => format!("call {}", l.to_string()),
-            PseudoOp::Ret => "ret".to_string(),
+            PseudoOp::Call(l) => write!(f, "call {l}"),
+            PseudoOp::Ret => write!(f, "ret"),
+            PseudoOp::Li(t, imm) => write!(f, "li {t:?}, {imm}"),
+            PseudoOp::La(t, l) => write!(f, "la {t:?}, {l}"),
         }
     }
 }
@@ -1,7 +1,13 @@
-use picoc089::{allocator, lexer, parser, parser_ast, selector, translator, typer};
+use picoc089::diagnostic::{DiagOpts, Report};
+use picoc089::lexer::{LexOpts, TT};
+use picoc089::preprocessor::{self, PpOpts};
+use picoc089::source::SourceMap;
+use picoc089::FloatMode;
+use picoc089::{allocator, parser, parser_ast, selector, translator, typer};
 use std::env;
 use std::fs;
 use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 fn main() {
     println!(
@@ -36,21 +42,112 @@ fn main() {
         .expect("picoc-error: invalid optimization level given (invalid level)");
     println!("picoc-info: received optimization level: {:?}", opt);
 
-    let chars = fs::read(src)
-        .expect("picoc-error: file dne`")
-        .iter()
-        .map(|b| *b as char)
-        .collect::<Vec<_>>();
-    let tokens = lexer::lex(&chars).unwrap();
-    println!("picoc-info: lexed");
-    let src_tree = parser_ast::parse_prg(&tokens).unwrap(); // recursive descent -> pratt parsing
+    // -std=gnu89 opts into the extensions gcc accepts in its default dialect
+    // (and drops trigraphs, like gcc). -march picks soft-float calls (rv32i)
+    // or F/D instructions (rv32ifd, rv32g). SOURCE_DATE_EPOCH pins __DATE__
+    // and __TIME__ for reproducible builds
+    let epoch = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|e| e.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs())
+        });
+    let mut opts = PpOpts {
+        trigraphs: true,
+        epoch,
+        ..PpOpts::default()
+    };
+    let (mut fm, mut emit_pp, mut object) = (FloatMode::Soft, false, false);
+    let mut dopts = DiagOpts::default();
+    let mut args = env::args().skip(4);
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "-E" => emit_pp = true,
+            // a file without main, to be linked into a program
+            "-c" => object = true,
+            // -Werror, -Wno-<warning> and -fdiagnostics-format=json
+            f if dopts.flag(f) => (),
+            // -DNAME=VAL and -D NAME=VAL alike. a bare -DNAME defines it as 1
+            f if ["-D", "-U", "-I"].iter().any(|o| f.starts_with(o)) => {
+                let val = match &f[2..] {
+                    "" => args.next().unwrap_or_else(|| {
+                        println!("picoc-error: missing argument to {f}");
+                        std::process::exit(1);
+                    }),
+                    val => val.to_owned(),
+                };
+                match &f[..2] {
+                    "-D" => {
+                        let (name, val) = val.split_once('=').unwrap_or((&val, "1"));
+                        opts.defines.push((name.to_owned(), Some(val.to_owned())));
+                    }
+                    "-U" => opts.defines.push((val, None)),
+                    _ => opts.include_dirs.push(val.into()),
+                }
+            }
+            "-std=c89" => (opts.lex, opts.trigraphs) = (LexOpts::default(), true),
+            "-std=gnu89" => (opts.lex.line_comments, opts.trigraphs) = (true, false),
+            march if march.starts_with("-march=rv32") => {
+                let exts = &march["-march=rv32".len()..];
+                fm = match (
+                    exts.starts_with('g'),
+                    exts.contains('f'),
+                    exts.contains('d'),
+                ) {
+                    (true, _, _) | (_, true, true) => FloatMode::Hard,
+                    (_, false, false) if exts.starts_with('i') => FloatMode::Soft,
+                    _ => {
+                        println!("picoc-error: unsupported -march (f and d go together): {flag}");
+                        std::process::exit(1);
+                    }
+                };
+            }
+            _ => {
+                println!("picoc-error: unknown flag: {flag}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // every stage's diagnostics are collected, and printed together when
+    // compilation stops
+    let mut report = Report::new(dopts);
+    let mut sm = SourceMap::new();
+    let tokens = preprocessor::preprocess(&mut sm, &src, &opts);
+    let Some(mut tokens) = report.stage(tokens) else {
+        abort(&report, &sm)
+    };
+    println!("picoc-info: preprocessed");
+
+    // -E stops here and leaves the translation unit in tmp.i
+    if emit_pp {
+        fs::write("./tmp.i", preprocessor::print(&tokens, &sm))
+            .expect("picoc-error: unable to write data");
+        eprint!("{}", report.render(&sm));
+        return;
+    }
+
+    // no pragma is recognized yet, and unrecognized ones are ignored (C89 3.8.6)
+    tokens.retain(|t| t.typ != TT::Pragma);
+    let Some(src_tree) = report.stage(parser_ast::parse_prg(&tokens)) else {
+        abort(&report, &sm) // recursive descent -> pratt parsing
+    };
     println!("picoc-info: parsed");
 
-    let src_graph = parser::parse_prg(&tokens).unwrap();
-    println!("picoc-info: parsed son");
-    println!("son graph: {:?}", src_graph);
+    // the son parser only covers a subset of the language so far
+    match parser::parse_prg(&tokens) {
+        Ok(src_graph) => {
+            println!("picoc-info: parsed son");
+            println!("son graph: {:?}", src_graph);
+        }
+        Err(e) => println!("picoc-info: skipped son: {e}"),
+    }
 
-    let _typ = typer::type_prg(&src_tree).unwrap();
+    let Some(typed_tree) = report.warned(typer::type_unit(&src_tree, !object)) else {
+        abort(&report, &sm)
+    };
     println!("picoc-info: typed");
 
     match strat.as_str() {
@@ -59,10 +156,12 @@ fn main() {
         //     println!("picoc-info: evaluated: {val}");
         // }
         "compilec89" => {
-            let trgt_tree = translator::translate(&src_tree);
+            let Some(trgt_tree) = report.stage(translator::translate(&typed_tree)) else {
+                abort(&report, &sm)
+            };
             println!("picoc-info: translated",);
 
-            let abs_as = selector::select(&trgt_tree);
+            let abs_as = selector::select(&trgt_tree, fm);
             println!("picoc-info: selected");
             let assembly = allocator::allocate(&abs_as, opt);
             println!("picoc-info: emitted");
@@ -70,6 +169,7 @@ fn main() {
             let mut f = fs::File::create("./tmp.s").expect("picoc-error: unable to create file");
             f.write_all(assembly.join("\n").as_bytes())
                 .expect("picoc-error: unable to write data");
+            eprint!("{}", report.render(&sm));
         }
         _ => {
             println!("picoc-error: unknown strategy: {:?}", strat);
@@ -77,3 +177,8 @@ fn main() {
         }
     }
 }
+
+fn abort(report: &Report, sm: &SourceMap) -> ! {
+    eprint!("{}", report.render(sm));
+    std::process::exit(1);
+}
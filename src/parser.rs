@@ -1,24 +1,23 @@
 use crate::{
+    diagnostic::{Code, Diagnostic},
     lexer::{Token, TT},
-    ConstantNode, Node, ReturnNode, StartNode,
+    parser_ast, AddNode, ConstantNode, Node, ReturnNode, SExprKind, StartNode,
 };
 use std::io;
 use std::rc::Rc;
 
 fn mtch(tokens: &[Token], tt: TT) -> Result<(&Token, &[Token]), io::Error> {
     match tokens {
-        [] => Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("expected: {:?} got: {:?}", tt, tokens),
-        )),
+        [] => Err(io::Error::other(format!(
+            "expected: {:?} got: end of input",
+            tt
+        ))),
         [f, r @ ..] => {
             if f.typ == tt {
                 Ok((f, r))
             } else {
-                Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("expected: {:?} got: {:?}", tt, f),
-                ))
+                let msg = format!("expected: {:?} got: {:?} `{}`", tt, f.typ, f.lexeme);
+                Err(Diagnostic::new(Code::Syntax, f.span, msg).into())
             }
         }
     }
@@ -39,10 +38,10 @@ pub fn parse_prg(tokens: &[Token]) -> Result<Rc<dyn Node>, io::Error> {
     if r.is_empty() {
         Ok(stmt.clone())
     } else {
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("expected empty token stream, got {:?}", r),
-        ))
+        Err(io::Error::other(format!(
+            "expected empty token stream, got {:?}",
+            r
+        )))
     }
 }
 
@@ -51,20 +50,18 @@ fn parse_stmt(
     tokens: &[Token],
 ) -> Result<(Rc<dyn Node>, &[Token]), io::Error> {
     match tokens {
-        [] => Err(io::Error::new(
-            io::ErrorKind::Other,
-            "expected: {:?} got an empty token stream",
-        )),
+        [] => Err(io::Error::other("expected: {:?} got an empty token stream")),
         [f, r @ ..] => match f.typ {
             TT::KeywordRet => {
                 let (expr, r) = parse_expr(start.clone(), r)?;
                 let (_, r) = mtch(r, TT::PuncSemiColon)?;
                 Ok((Rc::new(ReturnNode::new(start, expr)), r))
             }
-            t => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("expected: {:?} got: {:?}", TT::KeywordRet, t),
-            )),
+            t => Err(io::Error::other(format!(
+                "expected: {:?} got: {:?}",
+                TT::KeywordRet,
+                t
+            ))),
         },
     }
 }
@@ -72,21 +69,41 @@ fn parse_stmt(
 fn parse_expr(
     start: Rc<dyn Node>,
     tokens: &[Token],
+) -> Result<(Rc<dyn Node>, &[Token]), io::Error> {
+    let (mut l, mut r) = parse_atom(start.clone(), tokens)?;
+    while let [f, _r @ ..] = r {
+        if f.typ != TT::Plus {
+            break;
+        }
+
+        let (right, _r) = parse_atom(start.clone(), _r)?;
+        l = Rc::new(AddNode::new(l, right));
+        r = _r;
+    }
+
+    Ok((l, r))
+}
+
+fn parse_atom(
+    start: Rc<dyn Node>,
+    tokens: &[Token],
 ) -> Result<(Rc<dyn Node>, &[Token]), io::Error> {
     match tokens {
-        [] => Err(io::Error::new(
-            io::ErrorKind::Other,
-            "expected: {:?} got an empty token stream",
-        )),
+        [] => Err(io::Error::other("expected: {:?} got an empty token stream")),
         [f, r @ ..] => match f.typ {
-            TT::LiteralInt => Ok((
-                Rc::new(ConstantNode::new(start, f.lexeme.parse().unwrap())),
-                r,
-            )),
-            t => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("expected: {:?} got: {:?}", TT::LiteralInt, t),
-            )),
+            TT::LiteralInt => {
+                let value = match parser_ast::int_lit(f)? {
+                    SExprKind::Int(n) | SExprKind::Long(n) => n,
+                    SExprKind::UInt(n) | SExprKind::ULong(n) => n as i32,
+                    _ => unreachable!(),
+                };
+                Ok((Rc::new(ConstantNode::new(start, value)), r))
+            }
+            t => Err(io::Error::other(format!(
+                "expected: {:?} got: {:?}",
+                TT::LiteralInt,
+                t
+            ))),
         },
     }
 }
@@ -100,11 +117,7 @@ mod test_arith {
 
     #[test]
     fn lit() {
-        let chars = fs::read(format!("{TEST_DIR}/lit.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/lit.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -117,14 +130,16 @@ mod test_arith {
 
     #[test]
     fn add() {
-        let chars = fs::read(format!("{TEST_DIR}/add.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/add.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
-        insta::assert_debug_snapshot!(tree, @"");
+        insta::assert_debug_snapshot!(tree, @r###"
+        StartNode
+        ConstantNode(9)
+        ConstantNode(10)
+        AddNode
+        ReturnNode
+        "###);
     }
 }
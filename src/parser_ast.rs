@@ -1,494 +1,2216 @@
 use crate::{
+    diagnostic::{Code, Diagnostic, Diagnostics},
     lexer::{Token, TT},
-    SBinOp, SDef, SExpr, SFuncDef, SPrg, SRelOp, SStmt, SVarDef, Type,
+    source::Span,
+    LambdaType, Records, SAsnmtOp, SBinOp, SBitOp, SDef, SExpr, SExprKind, SFuncDef, SLogOp,
+    SParam, SPrg, SRelOp, SStmt, SStmtKind, SUnaryOp, SUpdateOp, SVarDef, StorageClass, Type,
 };
+use std::collections::{HashMap, HashSet};
 use std::io;
-use std::num::ParseIntError;
 
 fn eat(tokens: &[Token], tt: TT) -> Result<(&Token, &[Token]), io::Error> {
     match tokens {
-        [] => Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("expected: {:?} got: {:?}", tt, tokens),
-        )),
+        [] => Err(io::Error::other(format!(
+            "expected: {:?} got: end of input",
+            tt
+        ))),
         [f, r @ ..] => {
             if f.typ == tt {
                 Ok((f, r))
             } else {
-                Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("expected: {:?} got: {:?}", tt, f),
-                ))
+                Err(unexpected(f, format!("expected: {:?}", tt)))
+            }
+        }
+    }
+}
+
+fn unexpected(t: &Token, expected: String) -> io::Error {
+    let msg = format!("{expected} got: {:?} `{}`", t.typ, t.lexeme);
+    Diagnostic::new(Code::Syntax, t.span, msg).into()
+}
+
+// the span covering every token consumed between tokens and r
+fn consumed(tokens: &[Token], r: &[Token]) -> Span {
+    match &tokens[..tokens.len() - r.len()] {
+        [] => Span::default(),
+        [f, .., l] => f.span.to(l.span),
+        [f] => f.span,
+    }
+}
+
+// what the parser has to know about the identifiers in scope: tags, since
+// later declarations refer to their types, and ordinary identifiers, since
+// an enumerator is a constant and a typedef name a type specifier until a
+// declaration in an inner scope hides it. `T * x;` declares x only if T is one
+#[derive(Default)]
+struct Scope {
+    tags: HashMap<String, Tag>,
+    names: HashMap<String, Ordinary>,
+}
+
+#[derive(Clone, PartialEq)]
+enum Tag {
+    Record(bool, usize), // whether it is a union, and its id
+    Enum,
+}
+
+#[derive(Clone, PartialEq)]
+enum Ordinary {
+    Var, // objects and functions
+    Const(i32),
+    Typedef(Type),
+}
+
+// the statements that break, continue and case refer to, innermost last, and
+// the labels of the function being parsed, which have function scope so a
+// goto may come before its label (C89 3.1.2.1)
+#[derive(Default)]
+struct Jumps {
+    enclosing: Vec<Enclosing>,
+    labels: HashSet<String>,
+    gotos: Vec<(String, Span)>,
+}
+
+enum Enclosing {
+    Loop,
+    Switch(HashSet<i32>, bool), // its case values, and whether it has a default
+}
+
+// the state threaded through the parse of one translation unit
+struct Parser {
+    // innermost last. the file scope is never left
+    scopes: Vec<Scope>,
+    jumps: Jumps,
+    // the syntax errors recovered from so far
+    errors: Vec<Diagnostic>,
+    // how many statements, operands and declarators enclose what is being parsed
+    depth: usize,
+    // the layouts of the struct and union types declared so far
+    records: Records,
+}
+
+impl Parser {
+    fn new() -> Self {
+        Parser {
+            scopes: vec![Scope::default()],
+            jumps: Jumps::default(),
+            errors: vec![],
+            depth: 0,
+            records: Records::default(),
+        }
+    }
+}
+
+// every stage after the parser recurses on the tree, so nesting is bounded
+// here instead of by their stacks. C89 2.2.4.1 only asks for 15 levels of
+// blocks and 32 of parentheses
+const MAX_DEPTH: usize = 128;
+
+fn too_deep(span: Span) -> io::Error {
+    let msg = format!("nesting exceeds {MAX_DEPTH} levels");
+    Diagnostic::new(Code::Depth, span, msg).into()
+}
+
+// f parsed one level deeper than what encloses it
+fn nested<T>(
+    cx: &mut Parser,
+    tokens: &[Token],
+    f: impl FnOnce(&mut Parser) -> Result<T, io::Error>,
+) -> Result<T, io::Error> {
+    if cx.depth == MAX_DEPTH {
+        return Err(too_deep(tokens.first().map_or(Span::default(), |t| t.span)));
+    }
+    cx.depth += 1;
+    let t = f(cx);
+    cx.depth -= 1;
+    t
+}
+
+// the first node of a definition deeper than MAX_DEPTH. the parser's own
+// nesting is bounded as it goes, but a chain of left-associative operators
+// (1 + 1 + ... + 1) is folded in a loop, so its depth is only known here.
+// the walk keeps its own stack, for the same reason
+fn deepest(def: &SDef) -> Option<Span> {
+    enum Node<'a> {
+        S(&'a SStmt),
+        E(&'a SExpr),
+    }
+    let mut todo = match def {
+        SDef::FuncDef(fd) => fd.body.iter().map(|s| (Node::S(s), 1)).collect(),
+        SDef::VarDef(vd) => vd.expr.iter().map(|e| (Node::E(e), 1)).collect::<Vec<_>>(),
+    };
+    while let Some((node, depth)) = todo.pop() {
+        let (span, stmts, exprs): (_, Vec<&SStmt>, Vec<&SExpr>) = match node {
+            Node::S(s) => match &s.kind {
+                SStmtKind::IfEls { cond, then, els } => (
+                    s.span,
+                    [then].into_iter().chain(els).map(|s| &**s).collect(),
+                    vec![cond],
+                ),
+                SStmtKind::Switch { cond, body }
+                | SStmtKind::While { cond, body }
+                | SStmtKind::DoWhile { body, cond } => (s.span, vec![body], vec![cond]),
+                SStmtKind::For {
+                    init,
+                    cond,
+                    update,
+                    body,
+                } => {
+                    let es = [init, cond, update].into_iter().flatten().map(|e| &**e);
+                    (s.span, vec![body], es.collect())
+                }
+                SStmtKind::Case(_, body)
+                | SStmtKind::Default(body)
+                | SStmtKind::Labeled(_, body) => (s.span, vec![body], vec![]),
+                SStmtKind::Return(e) => (s.span, vec![], e.iter().collect()),
+                SStmtKind::Expr(e) => (s.span, vec![], vec![e]),
+                SStmtKind::Block(ss) => (s.span, ss.iter().collect(), vec![]),
+                SStmtKind::Decl(vds) => (
+                    s.span,
+                    vec![],
+                    vds.iter().filter_map(|vd| vd.expr.as_deref()).collect(),
+                ),
+                SStmtKind::Break | SStmtKind::Continue | SStmtKind::Goto(_) | SStmtKind::Empty => {
+                    (s.span, vec![], vec![])
+                }
+            },
+            Node::E(e) => match &e.kind {
+                SExprKind::UnaryE { l, .. }
+                | SExprKind::Update { l, .. }
+                | SExprKind::Deref(l)
+                | SExprKind::AddrOf(l)
+                | SExprKind::Dot { l, .. }
+                | SExprKind::Arrow { l, .. }
+                | SExprKind::Cast { l, .. }
+                | SExprKind::SizeOfE(l)
+                | SExprKind::Load(l) => (e.span, vec![], vec![l]),
+                SExprKind::BinE { l, r, .. }
+                | SExprKind::LogE { l, r, .. }
+                | SExprKind::BitE { l, r, .. }
+                | SExprKind::RelE { l, r, .. }
+                | SExprKind::Comma { l, r }
+                | SExprKind::Asnmt { l, r, .. }
+                | SExprKind::Index { l, r } => (e.span, vec![], vec![l, r]),
+                SExprKind::Ternary { cond, then, els } => (e.span, vec![], vec![cond, then, els]),
+                SExprKind::FuncApp { f, aps } => {
+                    (e.span, vec![], [&**f].into_iter().chain(aps).collect())
+                }
+                SExprKind::InitList(es) => (e.span, vec![], es.iter().collect()),
+                _ => (e.span, vec![], vec![]),
+            },
+        };
+        if depth > MAX_DEPTH {
+            return Some(span);
+        }
+        todo.extend(stmts.into_iter().map(|s| (Node::S(s), depth + 1)));
+        todo.extend(exprs.into_iter().map(|e| (Node::E(e), depth + 1)));
+    }
+    None
+}
+
+// errors at the end of input have no token to point at
+fn report(cx: &mut Parser, e: io::Error) {
+    let d = match e.get_ref().and_then(|e| e.downcast_ref::<Diagnostic>()) {
+        Some(d) => d.clone(),
+        None => Diagnostic::new(Code::Syntax, Span::default(), e.to_string()),
+    };
+    cx.errors.push(d);
+}
+
+// panic mode: skips the rest of a declaration or statement that failed to
+// parse, through the ; that ends it (not one inside parentheses, as in a
+// for) or the } that ends its block. a } that closes the enclosing block is left to
+// it, unless it is the first token
+fn recover(tokens: &[Token]) -> &[Token] {
+    let (mut depth, mut parens) = (0, 0usize);
+    for (i, t) in tokens.iter().enumerate() {
+        match t.typ {
+            TT::PuncLeftParen => parens += 1,
+            TT::PuncRightParen => parens = parens.saturating_sub(1),
+            TT::PuncSemiColon if depth == 0 && parens == 0 => return &tokens[i + 1..],
+            TT::PuncLeftBrace => (depth, parens) = (depth + 1, 0),
+            TT::PuncRightBrace if depth == 0 => return &tokens[i.max(1)..],
+            TT::PuncRightBrace if depth == 1 => return &tokens[i + 1..],
+            TT::PuncRightBrace => (depth, parens) = (depth - 1, 0),
+            _ => (),
+        }
+    }
+    &[]
+}
+
+fn enter_scope(cx: &mut Parser) {
+    cx.scopes.push(Scope::default());
+}
+
+fn leave_scope(cx: &mut Parser) {
+    cx.scopes.pop();
+}
+
+// f parsed in a scope of its own, which is left even when f fails
+fn scoped<T>(cx: &mut Parser, f: impl FnOnce(&mut Parser) -> T) -> T {
+    enter_scope(cx);
+    let t = f(cx);
+    leave_scope(cx);
+    t
+}
+
+// f parsed as the body of a loop or switch
+fn within<T>(cx: &mut Parser, e: Enclosing, f: impl FnOnce(&mut Parser) -> T) -> T {
+    cx.jumps.enclosing.push(e);
+    let t = f(cx);
+    cx.jumps.enclosing.pop();
+    t
+}
+
+// the innermost declaration of the tag, or only the current scope's
+fn lookup_tag(cx: &Parser, tag: &str, local: bool) -> Option<Tag> {
+    let n = if local { 1 } else { cx.scopes.len() };
+    cx.scopes
+        .iter()
+        .rev()
+        .take(n)
+        .find_map(|s| s.tags.get(tag).cloned())
+}
+
+fn declare_tag(cx: &mut Parser, tag: &str, t: Tag) {
+    cx.scopes.last_mut().unwrap().tags.insert(tag.to_owned(), t);
+}
+
+fn lookup(cx: &Parser, name: &str) -> Option<Ordinary> {
+    cx.scopes
+        .iter()
+        .rev()
+        .find_map(|s| s.names.get(name).cloned())
+}
+
+// the type t names if it is a typedef name in scope
+fn typedef_name(cx: &Parser, t: &Token) -> Option<Type> {
+    match (t.typ, lookup(cx, &t.lexeme)) {
+        (TT::Alias, Some(Ordinary::Typedef(typ))) => Some(typ),
+        _ => None,
+    }
+}
+
+// objects and functions may be redeclared (the typer checks that they agree),
+// but an enumerator or typedef name is the only declaration of its name in
+// its scope
+fn declare(cx: &mut Parser, t: &Token, o: Ordinary) -> Result<(), io::Error> {
+    {
+        let names = &mut cx.scopes.last_mut().unwrap().names;
+        let err = |msg: String| Err(Diagnostic::new(Code::Redeclared, t.span, msg).into());
+        match (names.get(&t.lexeme), &o) {
+            (Some(Ordinary::Typedef(_)), Ordinary::Typedef(_)) => {
+                err(format!("redefinition of typedef '{}'", t.lexeme))
+            }
+            (Some(Ordinary::Typedef(_)), _) | (Some(_), Ordinary::Typedef(_)) => err(format!(
+                "'{}' redeclared as different kind of symbol",
+                t.lexeme
+            )),
+            (Some(Ordinary::Const(_)), _) | (Some(_), Ordinary::Const(_)) => {
+                err(format!("redeclaration of '{}'", t.lexeme))
+            }
+            _ => {
+                names.insert(t.lexeme.clone(), o);
+                Ok(())
             }
         }
     }
 }
 
+// every token is parsed, and every syntax error reported: a declaration
+// that fails to parse is skipped, as is a statement in a block
 pub fn parse_prg(tokens: &[Token]) -> Result<SPrg, io::Error> {
-    let (mut fds, mut r) = (vec![], tokens);
-    while let Ok((fd, _r)) = parse_funcdef(r) {
-        fds.push(fd);
-        r = _r;
+    let cx = &mut Parser::new();
+
+    // a character that is no token of C survives preprocessing as a pp-token
+    // of its own. each is reported here, and left out of the parse
+    let strays = tokens.iter().filter(|t| t.typ == TT::Other);
+    for t in strays.clone() {
+        let msg = format!("stray '{}' in program", t.lexeme);
+        report(cx, Diagnostic::new(Code::Lex, t.span, msg).into());
+    }
+    let kept: Vec<Token>;
+    let tokens = match strays.count() {
+        0 => tokens,
+        _ => {
+            kept = tokens
+                .iter()
+                .filter(|t| t.typ != TT::Other)
+                .cloned()
+                .collect();
+            &kept
+        }
+    };
+
+    let (mut defs, mut r) = (vec![], tokens);
+    while !r.is_empty() {
+        match parse_external(cx, r) {
+            Ok((ds, _r)) => {
+                for span in ds.iter().filter_map(deepest) {
+                    report(cx, too_deep(span));
+                }
+                defs.extend(ds);
+                r = _r;
+            }
+            Err(e) => {
+                report(cx, e);
+                r = recover(r);
+            }
+        }
+    }
+
+    match std::mem::take(&mut cx.errors) {
+        es if es.is_empty() => Ok(SPrg {
+            defs,
+            records: std::mem::take(&mut cx.records),
+        }),
+        es => Err(Diagnostics(es).into()),
     }
+}
+
+// what the declaration specifiers say about every declarator that follows them
+struct Specs {
+    sc: Option<StorageClass>,
+    typ: Type,
+    span: Span,
+}
 
-    Ok(fds.into_iter().map(SDef::FuncDef).collect())
+// a declarator applied to its specifiers. the name is absent in abstract
+// declarators, and params holds the parameters when the name is declared
+// directly as a function, which is what a function definition needs
+struct Declarator<'a> {
+    alias: Option<&'a Token>,
+    typ: Type,
+    params: Option<Params<'a>>,
 }
 
-fn parse_funcdef(tokens: &[Token]) -> Result<(SFuncDef, &[Token]), io::Error> {
-    let (_, r) = eat(tokens, TT::KeywordInt)?;
-    let (alias, r) = eat(r, TT::Alias)?;
-    let (_, r) = eat(r, TT::PuncLeftParen)?;
+struct Params<'a> {
+    fps: Vec<(Option<&'a Token>, Type, Span)>,
+    variadic: bool,
+    proto: bool, // false for f() and identifier lists f(a, b) (C89 3.5.4.3)
+}
 
-    let (mut fps, mut r) = (vec![], r);
-    while let Ok((_fp_type, _r)) = eat(r, TT::KeywordInt) {
-        let (alias, _r) = eat(_r, TT::Alias)?;
-        fps.push((alias.lexeme.to_owned(), Type::Int));
+#[derive(Clone, Copy, PartialEq)]
+enum Name {
+    Required,
+    Optional,  // parameters
+    Forbidden, // type names
+}
 
-        if let TT::PuncComma = _r[0].typ {
-            r = &_r[1..];
-        } else {
-            r = _r;
+// an external declaration without specifiers declares an int (implicit int),
+// which is how C89 reads main() { ... }
+fn parse_external<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(Vec<SDef>, &'a [Token]), io::Error> {
+    let (specs, r) = match tokens {
+        [f, ..] if f.typ == TT::Alias && typedef_name(cx, f).is_none() => {
+            let specs = Specs {
+                sc: None,
+                typ: Type::Int,
+                span: f.span,
+            };
+            (specs, tokens)
         }
+        _ => parse_specs(cx, tokens)?,
+    };
+    if let Some(sc @ (StorageClass::Auto | StorageClass::Register)) = &specs.sc {
+        let msg = format!("file-scope declaration specifies '{}'", sc_name(sc));
+        return Err(Diagnostic::new(Code::Syntax, specs.span, msg).into());
     }
-    let (_, r) = eat(r, TT::PuncRightParen)?;
-    let (_, r) = eat(r, TT::PuncLeftBrace)?;
+    if let [f, r @ ..] = r {
+        if f.typ == TT::PuncSemiColon {
+            return Ok((vec![], r));
+        }
+    }
+
+    let (d, _r) = parse_declarator(cx, r, specs.typ.clone(), Name::Required)?;
+    match (&d.typ, &d.params, _r) {
+        (Type::Func(_), Some(ps), [f, ..])
+            if f.typ == TT::PuncLeftBrace
+                || !ps.proto && !ps.fps.is_empty() && starts_specs(cx, f) =>
+        {
+            if specs.sc == Some(StorageClass::Typedef) {
+                let msg = "function definition declared 'typedef'";
+                return Err(Diagnostic::new(Code::Syntax, specs.span, msg).into());
+            }
+            declare(cx, d.alias.unwrap(), Ordinary::Var)?;
+            let (fd, r) = parse_funcdef(cx, tokens, specs, d, _r)?;
+            Ok((vec![SDef::FuncDef(fd)], r))
+        }
+        _ => {
+            let (vds, r) = parse_vardefs(cx, r, &specs, d, _r)?;
+            Ok((vds.into_iter().map(SDef::VarDef).collect(), r))
+        }
+    }
+}
+
+// the declarator has been parsed, r starts at the declarations of an
+// identifier list's parameters or at the body
+fn parse_funcdef<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+    specs: Specs,
+    d: Declarator<'a>,
+    r: &'a [Token],
+) -> Result<(SFuncDef, &'a [Token]), io::Error> {
+    let (Type::Func(ft), Some(mut ps)) = (d.typ, d.params) else {
+        unreachable!("function definitions are declared as functions");
+    };
 
-    let (mut stmts, mut r) = (vec![], r);
-    while let Ok((s, _r)) = parse_stmt(r) {
-        stmts.push(s);
+    // int f(a, b) char *b; { ... } types its parameters in declarations
+    // between the declarator and the body. the undeclared ones are int
+    let mut r = r;
+    while let [f, ..] = r {
+        if !starts_specs(cx, f) {
+            break;
+        }
+        let (pspecs, _r) = parse_specs(cx, r)?;
+        let (vds, _r) = parse_declarators(cx, _r, &pspecs, Name::Required)?;
+        for (alias, typ, span) in vds {
+            let alias = alias.unwrap();
+            let fp = ps
+                .fps
+                .iter_mut()
+                .find(|(a, ..)| a.unwrap().lexeme == alias.lexeme);
+            let Some((_, fpt, fps)) = fp else {
+                let msg = format!(
+                    "declaration for parameter '{}' but no such parameter",
+                    alias.lexeme
+                );
+                return Err(Diagnostic::new(Code::Syntax, alias.span, msg).into());
+            };
+            (*fpt, *fps) = (adjust(typ), span);
+        }
         r = _r;
     }
-    let (_, r) = eat(r, TT::PuncRightBrace)?;
+
+    let fps = ps
+        .fps
+        .into_iter()
+        .map(|(alias, typ, span)| match alias {
+            Some(alias) => Ok((alias, typ, span)),
+            None => Err(io::Error::from(Diagnostic::new(
+                Code::Syntax,
+                span,
+                "parameter name omitted",
+            ))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // the parameters are in the scope of the body
+    let (_, r) = eat(r, TT::PuncLeftBrace)?;
+    cx.jumps = Jumps::default();
+    let (stmts, r) = scoped(cx, |cx| {
+        for (alias, ..) in &fps {
+            declare(cx, alias, Ordinary::Var)?;
+        }
+        parse_block(cx, r)
+    })?;
+    let fps = fps
+        .into_iter()
+        .map(|(alias, typ, span)| SParam {
+            alias: alias.lexeme.to_owned(),
+            typ,
+            span,
+        })
+        .collect();
+    let j = &cx.jumps;
+    let undefined = j.gotos.iter().find(|(l, _)| !j.labels.contains(l)).cloned();
+    if let Some((l, span)) = undefined {
+        return Err(Diagnostic::new(
+            Code::Syntax,
+            span,
+            format!("label '{l}' used but not defined"),
+        )
+        .into());
+    }
 
     Ok((
         SFuncDef {
-            alias: alias.lexeme.to_string(),
-            typ: Type::Int,
+            alias: d.alias.unwrap().lexeme.to_owned(),
+            sc: specs.sc,
+            typ: ft.body,
             fps,
+            variadic: ps.variadic,
+            proto: ps.proto,
             body: stmts,
+            span: consumed(tokens, r),
         },
         r,
     ))
 }
 
-fn parse_vardef(tokens: &[Token]) -> Result<(SVarDef, &[Token]), io::Error> {
-    match tokens {
-        [] => todo!(),
-        [f, r @ ..] => match f.typ {
-            // for now int is parsed with vardef
-            TT::KeywordInt => {
-                let (alias, r) = eat(r, TT::Alias)?;
-                let (_, r) = eat(r, TT::Equals)?;
-                let (expr, r) = parse_expr(r)?;
-
-                Ok((
-                    SVarDef {
-                        alias: alias.lexeme.to_owned(),
-                        typ: Type::Int,
-                        expr: Box::new(expr),
-                    },
-                    r,
-                ))
-            }
-            TT::Alias => match r {
-                [] => todo!(),
-                [s, t, _r @ ..] => match (s.typ, t.typ) {
-                    // (TT::Plus, TT::Equals) => {
-                    //     let (expr, r) = parse_rel_expr(r)?;
-                    //     Ok((
-                    //         VarDef::UpdateBind {
-                    //             alias: f.lexeme.parse().unwrap(),
-                    //             op: BinOp::Add,
-                    //             expr: Box::new(expr),
-                    //         },
-                    //         r,
-                    //     ))
-                    // }
-                    // (TT::Plus, TT::Plus) => Ok((
-                    //     VarDef::UpdateBind {
-                    //         alias: f.lexeme.parse().unwrap(),
-                    //         op: BinOp::Add,
-                    //         expr: Box::new(Expr::Int(1)),
-                    //     },
-                    //     r,
-                    // )),
-                    // (TT::Minus, TT::Equals) => {
-                    //     let (expr, r) = parse_rel_expr(r)?;
-
-                    //     Ok((
-                    //         VarDef::UpdateBind {
-                    //             alias: f.lexeme.parse().unwrap(),
-                    //             op: BinOp::Sub,
-                    //             expr: Box::new(expr),
-                    //         },
-                    //         r,
-                    //     ))
-                    // }
-                    // (TT::Minus, TT::Minus) => Ok((
-                    //     VarDef::UpdateBind {
-                    //         alias: f.lexeme.parse().unwrap(),
-                    //         op: BinOp::Sub,
-                    //         expr: Box::new(Expr::Int(1)),
-                    //     },
-                    //     r,
-                    // )),
-                    // (TT::Star, TT::Equals) => {
-                    //     let (expr, r) = parse_rel_expr(r)?;
-
-                    //     Ok((
-                    //         VarDef::UpdateBind {
-                    //             alias: f.lexeme.parse().unwrap(),
-                    //             op: BinOp::Mult,
-                    //             expr: Box::new(expr),
-                    //         },
-                    //         r,
-                    //     ))
-                    // }
-                    // (TT::Slash, TT::Equals) => {
-                    //     let (expr, r) = parse_rel_expr(r)?;
-
-                    //     Ok((
-                    //         VarDef::UpdateBind {
-                    //             alias: f.lexeme.parse().unwrap(),
-                    //             op: BinOp::Div,
-                    //             expr: Box::new(expr),
-                    //         },
-                    //         r,
-                    //     ))
-                    // }
-                    _t => {
-                        todo!()
-                    }
-                },
-                _t => todo!(),
-            },
-            t => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("token not recognizable {:?}", t),
-            )),
-        },
+// a declaration in a block: specifiers, then declarators with optional
+// initializers separated by commas
+fn parse_decl<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(Vec<SVarDef>, &'a [Token]), io::Error> {
+    let (specs, r) = parse_specs(cx, tokens)?;
+    if let [f, r @ ..] = r {
+        if f.typ == TT::PuncSemiColon {
+            return Ok((vec![], r));
+        }
     }
+
+    let (d, _r) = parse_declarator(cx, r, specs.typ.clone(), Name::Required)?;
+    parse_vardefs(cx, r, &specs, d, _r)
 }
 
-fn parse_stmt(tokens: &[Token]) -> Result<(SStmt, &[Token]), io::Error> {
-    match tokens {
-        [] => todo!(),
-        [f, r @ ..] => match f.typ {
-            TT::KeywordInt => {
-                // todo: | TT:KeywordAlias{++, --, -=}, etc.
-                let (a, r) = parse_vardef(tokens)?;
-                let (_, r) = eat(r, TT::PuncSemiColon)?;
+// the init-declarator list from its first declarator d (parsed from tokens
+// up to r) through the closing semicolon. a typedef defines no objects, it
+// names the type of each declarator instead
+fn parse_vardefs<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+    specs: &Specs,
+    d: Declarator<'a>,
+    r: &'a [Token],
+) -> Result<(Vec<SVarDef>, &'a [Token]), io::Error> {
+    let (mut vds, mut d, mut start, mut r) = (vec![], d, tokens, r);
+    loop {
+        let alias = d.alias.unwrap();
+        let typedef = specs.sc == Some(StorageClass::Typedef);
+        match typedef {
+            true => declare(cx, alias, Ordinary::Typedef(d.typ.clone()))?,
+            false => declare(cx, alias, Ordinary::Var)?, // in scope from its declarator on, initializer included
+        }
+        let (expr, _r) = match r {
+            [f, _r @ ..] if f.typ == TT::Equals => {
+                if typedef {
+                    let msg = format!("typedef '{}' is initialized", alias.lexeme);
+                    return Err(Diagnostic::new(Code::Syntax, alias.span, msg).into());
+                }
+                if let Type::Func(_) = d.typ {
+                    let msg = format!("function '{}' is initialized like a variable", alias.lexeme);
+                    return Err(Diagnostic::new(Code::Syntax, alias.span, msg).into());
+                }
+                let (e, _r) = parse_init(cx, _r)?;
+                (Some(Box::new(e)), _r)
+            }
+            _ => (None, r),
+        };
+        if !typedef {
+            vds.push(SVarDef {
+                alias: alias.lexeme.to_owned(),
+                sc: specs.sc.clone(),
+                typ: d.typ,
+                expr,
+                span: consumed(start, _r),
+            });
+        }
 
-                Ok((SStmt::Asnmt(a), r))
+        match _r {
+            [f, _r @ ..] if f.typ == TT::PuncComma => {
+                let (_d, __r) = parse_declarator(cx, _r, specs.typ.clone(), Name::Required)?;
+                (d, start, r) = (_d, _r, __r);
             }
-            TT::KeywordRet => {
-                let (expr, r) = parse_rel(r)?;
-                let (_, r) = eat(r, TT::PuncSemiColon)?;
-                Ok((SStmt::Return(expr), r))
+            _ => {
+                let (_, r) = eat(_r, TT::PuncSemiColon)?;
+                return Ok((vds, r));
             }
-            TT::KeywordIf => {
-                let (_, r) = eat(r, TT::PuncLeftParen)?;
-                let (cond, r) = parse_rel(r)?;
-                let (_, r) = eat(r, TT::PuncRightParen)?;
-                let (_, r) = eat(r, TT::PuncLeftBrace)?;
-                let (then, r) = parse_stmt(r)?;
-                let (_, r) = eat(r, TT::PuncRightBrace)?;
-                let (els, r) = if let TT::KeywordEls = r[0].typ {
-                    let (_, r) = eat(r, TT::KeywordEls)?;
-                    let (_, r) = eat(r, TT::PuncLeftBrace)?;
-                    let (els, r) = parse_stmt(r)?;
-                    let (_, r) = eat(r, TT::PuncRightBrace)?;
-                    (Some(Box::new(els)), r)
-                } else {
-                    (None, r)
-                };
+        }
+    }
+}
 
-                Ok((
-                    SStmt::IfEls {
-                        cond: Box::new(cond),
-                        then: Box::new(then),
-                        els,
-                    },
-                    r,
-                ))
+// an initializer is an assignment expression, or a braced list of them that
+// may end in a comma (C89 3.5.7)
+fn parse_init<'a>(cx: &mut Parser, tokens: &'a [Token]) -> Result<(SExpr, &'a [Token]), io::Error> {
+    let r = match tokens {
+        [f, r @ ..] if f.typ == TT::PuncLeftBrace => r,
+        _ => return parse_asnmt(cx, tokens),
+    };
+
+    let (mut items, mut r) = (vec![], r);
+    loop {
+        let (item, _r) = nested(cx, r, |cx| parse_init(cx, r))?;
+        items.push(item);
+        match _r {
+            [f, s, _r @ ..] if f.typ == TT::PuncComma && s.typ == TT::PuncRightBrace => {
+                r = _r;
+                break;
             }
-            TT::KeywordWhile => {
-                let (_, r) = eat(r, TT::PuncLeftParen)?;
-                let (cond, r) = parse_rel(r)?;
-                let (_, r) = eat(r, TT::PuncRightParen)?;
-                let (_, r) = eat(r, TT::PuncLeftBrace)?;
-                let (body, r) = parse_stmt(r)?;
-                let (_, r) = eat(r, TT::PuncRightBrace)?;
-
-                Ok((
-                    SStmt::While {
-                        cond: Box::new(cond),
-                        body: Box::new(body),
-                    },
-                    r,
-                ))
+            [f, _r @ ..] if f.typ == TT::PuncComma => r = _r,
+            _ => {
+                (_, r) = eat(_r, TT::PuncRightBrace)?;
+                break;
             }
-            // TT::KeywordFor => {
-            //     let (_, r) = mtch(r, TT::PuncLeftParen)?;
-            //     let (asnmt, r) = parse_asmt(r)?;
-            //     let (_, r) = mtch(r, TT::PuncSemiColon)?;
-            //     let (cond, r) = parse_rel_expr(r)?;
-            //     let (_, r) = mtch(r, TT::PuncSemiColon)?;
-            //     let (update, r) = parse_asmt(r)?;
-            //     let (_, r) = mtch(r, TT::PuncRightParen)?;
-            //     let (_, r) = mtch(r, TT::PuncLeftBrace)?;
-
-            //     let mut body = vec![];
-            //     let mut r0 = r;
-            //     while let Ok((s, r1)) = parse_stmt(r0) {
-            //         body.push(s);
-            //         r0 = r1;
-            //     }
-            //     let (_, r) = mtch(r0, TT::PuncRightBrace)?;
-
-            //     Ok((
-            //         Stmt::For {
-            //             asnmt: Box::new(asnmt),
-            //             cond: Box::new(cond),
-            //             update: Box::new(update),
-            //             body,
-            //         },
-            //         r,
-            //     ))
-            // }
-            t => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("token not recognizable {:?}", t),
-            )),
-        },
+        }
     }
+    Ok((
+        SExpr::new(SExprKind::InitList(items), consumed(tokens, r)),
+        r,
+    ))
 }
 
-fn parse_expr(tokens: &[Token]) -> Result<(SExpr, &[Token]), io::Error> {
-    parse_rel(tokens)
+// declarators without initializers through the closing semicolon, for the
+// parameter declarations of a function definition with an identifier list
+#[allow(clippy::type_complexity)]
+fn parse_declarators<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+    specs: &Specs,
+    name: Name,
+) -> Result<(Vec<(Option<&'a Token>, Type, Span)>, &'a [Token]), io::Error> {
+    let (mut ds, mut r) = (vec![], tokens);
+    loop {
+        let (d, _r) = parse_declarator(cx, r, specs.typ.clone(), name)?;
+        ds.push((d.alias, d.typ, consumed(r, _r)));
+        match _r {
+            [f, _r @ ..] if f.typ == TT::PuncComma => r = _r,
+            _ => {
+                let (_, r) = eat(_r, TT::PuncSemiColon)?;
+                return Ok((ds, r));
+            }
+        }
+    }
 }
 
-fn parse_rel(tokens: &[Token]) -> Result<(SExpr, &[Token]), io::Error> {
-    let (left, r) = parse_term(tokens)?;
+// a type name is a declaration of a nameless object (C89 3.5.5), as in casts
+// and sizeof
+fn parse_typename<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(Type, &'a [Token]), io::Error> {
+    let (specs, r) = parse_specs(cx, tokens)?;
+    if let Some(sc) = &specs.sc {
+        let msg = format!("storage class '{}' in type name", sc_name(sc));
+        return Err(Diagnostic::new(Code::Syntax, specs.span, msg).into());
+    }
 
-    match r {
-        [] => Ok((left, r)),
-        r => {
-            let mut cur_node = left;
-            let mut r = r;
+    let (d, r) = parse_declarator(cx, r, specs.typ, Name::Forbidden)?;
+    Ok((d.typ, r))
+}
 
-            while let Ok((op, _r)) = parse_rel_op(r) {
-                let (right, _r) = parse_term(_r)?;
+fn starts_specs(cx: &Parser, t: &Token) -> bool {
+    starts_typename(cx, t)
+        || matches!(
+            t.typ,
+            TT::KeywordAuto
+                | TT::KeywordRegister
+                | TT::KeywordStatic
+                | TT::KeywordExtern
+                | TT::KeywordTypedef
+        )
+}
 
-                cur_node = SExpr::RelE {
-                    op,
-                    l: Box::new(cur_node),
-                    r: Box::new(right),
-                };
+fn starts_typename(cx: &Parser, t: &Token) -> bool {
+    typedef_name(cx, t).is_some()
+        || matches!(
+            t.typ,
+            TT::KeywordVoid
+                | TT::KeywordChar
+                | TT::KeywordShort
+                | TT::KeywordInt
+                | TT::KeywordLong
+                | TT::KeywordFloat
+                | TT::KeywordDouble
+                | TT::KeywordSigned
+                | TT::KeywordUnsigned
+                | TT::KeywordConst
+                | TT::KeywordVolatile
+                | TT::KeywordStruct
+                | TT::KeywordUnion
+                | TT::KeywordEnum
+        )
+}
 
-                r = _r;
+// storage class, qualifiers and type specifiers, in any order (C89 3.5).
+// without a type specifier the type is int
+fn parse_specs<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(Specs, &'a [Token]), io::Error> {
+    let (mut sc, mut konst, mut volatile, mut tspecs, mut r) = (None, false, false, vec![], tokens);
+    let mut tagged = vec![]; // struct, union and enum specifiers, which are whole types
+    while let [f, _r @ ..] = r {
+        let err = |msg: &str| io::Error::from(Diagnostic::new(Code::Syntax, f.span, msg));
+        match f.typ {
+            TT::KeywordAuto
+            | TT::KeywordRegister
+            | TT::KeywordStatic
+            | TT::KeywordExtern
+            | TT::KeywordTypedef => {
+                if sc.is_some() {
+                    return Err(err("multiple storage classes in declaration specifiers"));
+                }
+                sc = Some(match f.typ {
+                    TT::KeywordAuto => StorageClass::Auto,
+                    TT::KeywordRegister => StorageClass::Register,
+                    TT::KeywordStatic => StorageClass::Static,
+                    TT::KeywordExtern => StorageClass::Extern,
+                    _ => StorageClass::Typedef,
+                });
+            }
+            // a qualifier shall not appear more than once (C89 3.5.3)
+            TT::KeywordConst if konst => return Err(err("duplicate 'const'")),
+            TT::KeywordVolatile if volatile => return Err(err("duplicate 'volatile'")),
+            TT::KeywordConst => konst = true,
+            TT::KeywordVolatile => volatile = true,
+            TT::KeywordStruct | TT::KeywordUnion | TT::KeywordEnum => {
+                let (t, __r) = match f.typ {
+                    TT::KeywordEnum => parse_enum(cx, _r)?,
+                    _ => parse_record(cx, f, _r)?,
+                };
+                tagged.push(t);
+                r = __r;
+                continue;
             }
+            // a typedef name is a type specifier only where no other one has
+            // been seen, so in `unsigned T;` or `T T;` the last T is declared
+            TT::Alias => match typedef_name(cx, f) {
+                Some(t) if tagged.is_empty() && tspecs.is_empty() => tagged.push(t),
+                _ => break,
+            },
+            _ if starts_typename(cx, f) => tspecs.push(f),
+            _ => break,
+        }
+        r = _r;
+    }
 
-            Ok((cur_node, r))
+    let span = match consumed(tokens, r) {
+        _ if r.len() == tokens.len() => {
+            return Err(match tokens {
+                [] => io::Error::other("expected: declaration specifiers got: end of input"),
+                [f, ..] => unexpected(f, "expected: declaration specifiers".to_string()),
+            })
         }
+        span => span,
+    };
+
+    let msg = "two or more data types in declaration specifiers";
+    let typ = match (tagged.as_slice(), tspecs.as_slice()) {
+        ([], []) => Type::Int,
+        ([], [f, .., l]) => base_type(&tspecs).ok_or_else(|| {
+            io::Error::from(Diagnostic::new(Code::Syntax, f.span.to(l.span), msg))
+        })?,
+        ([], [_]) => base_type(&tspecs).unwrap(),
+        ([t], []) => t.clone(),
+        _ => return Err(Diagnostic::new(Code::Syntax, span, msg).into()),
+    };
+    // also through a typedef (C89 3.5.3)
+    let inner = match &typ {
+        Type::Const(t) => t.as_ref(),
+        t => t,
+    };
+    if konst && matches!(typ, Type::Const(_)) {
+        return Err(Diagnostic::new(Code::Syntax, span, "duplicate 'const'").into());
     }
-}
-fn parse_rel_op(tokens: &[Token]) -> Result<(SRelOp, &[Token]), io::Error> {
-    match tokens {
-        [] => todo!(),
-        [f, r @ ..] => match f.typ {
-            TT::LeftAngleBracket => match r {
-                [] => todo!(),
-                [s, r @ ..] => match s.typ {
-                    TT::Equals => Ok((SRelOp::LtEq, r)),
-                    _ => Ok((SRelOp::Lt, &tokens[1..])), // include s
-                },
-            },
-            TT::RightAngleBracket => match r {
-                [] => todo!(),
-                [s, r @ ..] => match s.typ {
-                    TT::Equals => Ok((SRelOp::GtEq, r)),
-                    _ => Ok((SRelOp::Gt, &tokens[1..])), // include s
-                },
-            },
-            TT::Equals => match r {
-                [] => todo!(),
-                [s, r @ ..] => match s.typ {
-                    TT::Equals => Ok((SRelOp::Eq, r)),
-                    t => Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("token not recognizable {:?}", t),
-                    )),
-                },
-            },
-            TT::Bang => match r {
-                [] => todo!(),
-                [s, r @ ..] => match s.typ {
-                    TT::Equals => Ok((SRelOp::Neq, r)),
-                    t => Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("token not recognizable {:?}", t),
-                    )),
-                },
-            },
-            TT::Amp => match r {
-                [] => todo!(),
-                [s, r @ ..] => match s.typ {
-                    TT::Amp => Ok((SRelOp::And, r)),
-                    t => Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("token not recognizable {:?}", t),
-                    )),
-                },
-            },
-            TT::Bar => match r {
-                [] => todo!(),
-                [s, r @ ..] => match s.typ {
-                    TT::Bar => Ok((SRelOp::Or, r)),
-                    t => Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("token not recognizable {:?}", t),
-                    )),
-                },
-            },
-            t => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("token not recognizable {:?}", t),
-            )),
-        },
+    if volatile && matches!(inner, Type::Volatile(_)) {
+        return Err(Diagnostic::new(Code::Syntax, span, "duplicate 'volatile'").into());
     }
+
+    Ok((
+        Specs {
+            sc,
+            typ: qualify(typ, konst, volatile),
+            span,
+        },
+        r,
+    ))
 }
 
-fn parse_term(tokens: &[Token]) -> Result<(SExpr, &[Token]), io::Error> {
-    let (left, r) = parse_factor(tokens)?;
+// the type named by a multiset of type specifiers (C89 3.5.2)
+fn base_type(tspecs: &[&Token]) -> Option<Type> {
+    use TT::*;
+    // sorted, every combination has one spelling: unsigned long int
+    let order = [
+        KeywordSigned,
+        KeywordUnsigned,
+        KeywordVoid,
+        KeywordChar,
+        KeywordShort,
+        KeywordLong,
+        KeywordInt,
+        KeywordFloat,
+        KeywordDouble,
+    ];
+    let mut tts = tspecs.iter().map(|t| t.typ).collect::<Vec<_>>();
+    tts.sort_by_key(|tt| order.iter().position(|o| o == tt));
+
+    let typ = match tts.as_slice() {
+        [KeywordVoid] => Type::Void,
+        [KeywordChar] => Type::Char,
+        [KeywordSigned, KeywordChar] => Type::SChar,
+        [KeywordUnsigned, KeywordChar] => Type::UChar,
+        [KeywordShort] | [KeywordSigned, KeywordShort] => Type::Short,
+        [KeywordShort, KeywordInt] | [KeywordSigned, KeywordShort, KeywordInt] => Type::Short,
+        [KeywordUnsigned, KeywordShort] | [KeywordUnsigned, KeywordShort, KeywordInt] => {
+            Type::UShort
+        }
+        [KeywordInt] | [KeywordSigned] | [KeywordSigned, KeywordInt] => Type::Int,
+        [KeywordUnsigned] | [KeywordUnsigned, KeywordInt] => Type::UInt,
+        [KeywordLong] | [KeywordSigned, KeywordLong] => Type::Long,
+        [KeywordLong, KeywordInt] | [KeywordSigned, KeywordLong, KeywordInt] => Type::Long,
+        [KeywordUnsigned, KeywordLong] | [KeywordUnsigned, KeywordLong, KeywordInt] => Type::ULong,
+        [KeywordLong, KeywordLong] | [KeywordSigned, KeywordLong, KeywordLong] => Type::LongLong,
+        [KeywordLong, KeywordLong, KeywordInt]
+        | [KeywordSigned, KeywordLong, KeywordLong, KeywordInt] => Type::LongLong,
+        [KeywordUnsigned, KeywordLong, KeywordLong]
+        | [KeywordUnsigned, KeywordLong, KeywordLong, KeywordInt] => Type::ULongLong,
+        [KeywordFloat] => Type::Float,
+        [KeywordDouble] => Type::Double,
+        [KeywordLong, KeywordDouble] => Type::LongDouble,
+        _ => return None,
+    };
+    Some(typ)
+}
 
-    match r {
-        [] => Ok((left, r)),
-        r => {
-            let mut cur_node = left;
-            let mut r = r;
+// struct-or-union specifier after its keyword (C89 3.5.2.1). a tag names one
+// type throughout its scope: a list of members in a scope where the tag is
+// incomplete completes it, and `struct s;` declares a new one that hides any
+// outer s. a tag used before any declaration is declared in the current scope
+fn parse_record<'a>(
+    cx: &mut Parser,
+    kw: &Token,
+    tokens: &'a [Token],
+) -> Result<(Type, &'a [Token]), io::Error> {
+    let union = kw.typ == TT::KeywordUnion;
+    let typ = |tag: Option<&Token>, id| match union {
+        true => Type::Union(tag.map(|t| t.lexeme.to_owned()), id),
+        false => Type::Struct(tag.map(|t| t.lexeme.to_owned()), id),
+    };
+    let kind = match union {
+        true => "union",
+        false => "struct",
+    };
+    let wrong_kind = |t: &Token| {
+        let msg = format!("'{}' defined as wrong kind of tag", t.lexeme);
+        io::Error::from(Diagnostic::new(Code::Syntax, t.span, msg))
+    };
+    let declared = |cx: &Parser, t: &Token, local| match lookup_tag(cx, &t.lexeme, local) {
+        Some(Tag::Record(u, id)) if u == union => Ok(Some(id)),
+        Some(_) => Err(wrong_kind(t)),
+        None => Ok(None),
+    };
+    let fresh = |cx: &mut Parser, t: &Token| {
+        let id = cx.records.fresh();
+        declare_tag(cx, &t.lexeme, Tag::Record(union, id));
+        id
+    };
 
-            while let Ok((op, _r)) = parse_term_op(r) {
-                let (right, _r) = parse_factor(_r)?;
+    match tokens {
+        [t, b, r @ ..] if t.typ == TT::Alias && b.typ == TT::PuncLeftBrace => {
+            let id = match declared(cx, t, true)? {
+                Some(id) if cx.records.get(id).members.is_some() => {
+                    let msg = format!("redefinition of '{kind} {}'", t.lexeme);
+                    return Err(Diagnostic::new(Code::Syntax, t.span, msg).into());
+                }
+                Some(id) => id,
+                None => fresh(cx, t),
+            };
+            let r = nested(cx, r, |cx| parse_members(cx, id, union, b, r))?;
+            Ok((typ(Some(t), id), r))
+        }
+        [b, r @ ..] if b.typ == TT::PuncLeftBrace => {
+            let id = cx.records.fresh();
+            let r = nested(cx, r, |cx| parse_members(cx, id, union, b, r))?;
+            Ok((typ(None, id), r))
+        }
+        [t, s, ..] if t.typ == TT::Alias && s.typ == TT::PuncSemiColon => {
+            let id = match declared(cx, t, true)? {
+                Some(id) => id,
+                None => fresh(cx, t),
+            };
+            Ok((typ(Some(t), id), &tokens[1..]))
+        }
+        [t, r @ ..] if t.typ == TT::Alias => {
+            let id = match declared(cx, t, false)? {
+                Some(id) => id,
+                None => fresh(cx, t),
+            };
+            Ok((typ(Some(t), id), r))
+        }
+        [f, ..] => Err(unexpected(f, format!("expected: {:?}", TT::Alias))),
+        [] => Err(io::Error::other(format!(
+            "expected: {:?} got: end of input",
+            TT::Alias
+        ))),
+    }
+}
 
-                cur_node = SExpr::BinE {
-                    op,
-                    l: Box::new(cur_node),
-                    r: Box::new(right),
-                };
+// struct-declarations through the closing brace, which lay out the record.
+// members need complete types, since each offset depends on the sizes before
+fn parse_members<'a>(
+    cx: &mut Parser,
+    id: usize,
+    union: bool,
+    brace: &Token,
+    tokens: &'a [Token],
+) -> Result<&'a [Token], io::Error> {
+    let (mut members, mut r) = (vec![], tokens);
+    while let [f, ..] = r {
+        if f.typ == TT::PuncRightBrace {
+            break;
+        }
+        let (specs, _r) = parse_specs(cx, r)?;
+        if let Some(sc) = &specs.sc {
+            let msg = format!("storage class '{}' specified for member", sc_name(sc));
+            return Err(Diagnostic::new(Code::Syntax, specs.span, msg).into());
+        }
 
-                r = _r;
+        let mut _r = _r;
+        loop {
+            let (d, __r) = parse_declarator(cx, _r, specs.typ.clone(), Name::Required)?;
+            let alias = d.alias.unwrap();
+            let err = |msg: String| io::Error::from(Diagnostic::new(Code::Syntax, alias.span, msg));
+            match (d.typ.unqual(), __r) {
+                (_, [f, ..]) if f.typ == TT::Colon => {
+                    return Err(Diagnostic::new(
+                        Code::Syntax,
+                        f.span,
+                        "bit-fields are not supported",
+                    )
+                    .into())
+                }
+                (Type::Func(_), _) => {
+                    return Err(err(format!(
+                        "field '{}' declared as a function",
+                        alias.lexeme
+                    )))
+                }
+                (t, _) if t.size(&cx.records).is_none() => {
+                    return Err(err(format!("field '{}' has incomplete type", alias.lexeme)))
+                }
+                _ if members.iter().any(|(a, _)| *a == alias.lexeme) => {
+                    return Err(err(format!("duplicate member '{}'", alias.lexeme)))
+                }
+                _ => members.push((alias.lexeme.to_owned(), d.typ)),
             }
 
-            Ok((cur_node, r))
+            match __r {
+                [f, ___r @ ..] if f.typ == TT::PuncComma => _r = ___r,
+                _ => {
+                    (_, r) = eat(__r, TT::PuncSemiColon)?;
+                    break;
+                }
+            }
         }
     }
-}
-fn parse_term_op(tokens: &[Token]) -> Result<(SBinOp, &[Token]), io::Error> {
-    match tokens {
-        [] => todo!(),
-        [f, r @ ..] => match f.typ {
-            TT::Plus => Ok((SBinOp::Add, r)),
-            TT::Minus => Ok((SBinOp::Sub, r)),
-            t => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("token not recognizable {:?}", t),
-            )),
-        },
+    let (_, r) = eat(r, TT::PuncRightBrace)?;
+
+    if members.is_empty() {
+        let msg = format!("{} has no members", if union { "union" } else { "struct" });
+        return Err(Diagnostic::new(Code::Syntax, brace.span, msg).into());
     }
+    cx.records.define(id, union, members);
+    Ok(r)
 }
 
-fn parse_factor(tokens: &[Token]) -> Result<(SExpr, &[Token]), io::Error> {
-    let (left, r) = parse_funcapp(tokens)?;
-
-    match r {
-        [] => Ok((left, r)),
-        r => {
-            let mut cur_node = left;
-            let mut r = r;
-            while let Ok((op, _r)) = parse_factor_op(r) {
-                let (right, _r) = parse_atom(_r)?;
+// enum specifier after its keyword (C89 3.5.2.2). the type is int, and each
+// enumerator an int constant: its value if given, or one more than the last
+fn parse_enum<'a>(cx: &mut Parser, tokens: &'a [Token]) -> Result<(Type, &'a [Token]), io::Error> {
+    let (tag, r) = match tokens {
+        [t, r @ ..] if t.typ == TT::Alias => (Some(t), r),
+        _ => (None, tokens),
+    };
+    let wrong_kind = |t: &Token| {
+        let msg = format!("'{}' defined as wrong kind of tag", t.lexeme);
+        io::Error::from(Diagnostic::new(Code::Syntax, t.span, msg))
+    };
 
-                cur_node = SExpr::BinE {
-                    op,
-                    l: Box::new(cur_node),
-                    r: Box::new(right),
-                };
+    let mut r = match (tag, r) {
+        (_, [b, r @ ..]) if b.typ == TT::PuncLeftBrace => r,
+        (Some(t), r) => {
+            return match lookup_tag(cx, &t.lexeme, false) {
+                Some(Tag::Enum) => Ok((Type::Int, r)),
+                Some(_) => Err(wrong_kind(t)),
+                None => {
+                    let msg = format!("use of enum '{}' without previous declaration", t.lexeme);
+                    Err(Diagnostic::new(Code::Syntax, t.span, msg).into())
+                }
+            }
+        }
+        (None, [f, ..]) => return Err(unexpected(f, format!("expected: {:?}", TT::Alias))),
+        (None, []) => {
+            return Err(io::Error::other(format!(
+                "expected: {:?} got: end of input",
+                TT::Alias
+            )))
+        }
+    };
+    if let Some(t) = tag {
+        match lookup_tag(cx, &t.lexeme, true) {
+            Some(Tag::Enum) => {
+                let msg = format!("redefinition of 'enum {}'", t.lexeme);
+                return Err(Diagnostic::new(Code::Syntax, t.span, msg).into());
+            }
+            Some(_) => return Err(wrong_kind(t)),
+            None => declare_tag(cx, &t.lexeme, Tag::Enum),
+        }
+    }
 
-                r = _r;
+    let mut next = Some(0i64);
+    loop {
+        let (alias, _r) = eat(r, TT::Alias)?;
+        let (n, _r) = match _r {
+            [f, _r @ ..] if f.typ == TT::Equals => {
+                let (e, _r) = parse_const(cx, _r)?;
+                match const_int(&cx.records, &e) {
+                    Some(n) if i32::try_from(n).is_ok() => (n, _r),
+                    Some(_) => {
+                        let msg =
+                            format!("enumerator value for '{}' is out of range", alias.lexeme);
+                        return Err(Diagnostic::new(Code::Syntax, e.span, msg).into());
+                    }
+                    None => {
+                        let msg = format!(
+                            "enumerator value for '{}' is not an integer constant",
+                            alias.lexeme
+                        );
+                        return Err(Diagnostic::new(Code::Syntax, e.span, msg).into());
+                    }
+                }
             }
+            _ => match next {
+                Some(n) if i32::try_from(n).is_ok() => (n, _r),
+                _ => {
+                    return Err(Diagnostic::new(
+                        Code::Syntax,
+                        alias.span,
+                        "overflow in enumeration values",
+                    )
+                    .into())
+                }
+            },
+        };
+        declare(cx, alias, Ordinary::Const(n as i32))?;
+        next = n.checked_add(1);
 
-            Ok((cur_node, r))
+        // a trailing comma is accepted, as most compilers do
+        match _r {
+            [f, s, _r @ ..] if f.typ == TT::PuncComma && s.typ == TT::PuncRightBrace => {
+                return Ok((Type::Int, _r))
+            }
+            [f, _r @ ..] if f.typ == TT::PuncComma => r = _r,
+            _ => {
+                let (_, r) = eat(_r, TT::PuncRightBrace)?;
+                return Ok((Type::Int, r));
+            }
         }
     }
 }
 
-fn parse_factor_op(tokens: &[Token]) -> Result<(SBinOp, &[Token]), io::Error> {
-    match tokens {
-        [] => todo!(),
-        [f, r @ ..] => match f.typ {
-            TT::Star => Ok((SBinOp::Mult, r)),
-            TT::Slash => Ok((SBinOp::Div, r)),
-            t => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("token not recognizable {:?}", t),
-            )),
-        },
+// a typedef name's type may be qualified already, and const stays outermost
+fn qualify(typ: Type, konst: bool, volatile: bool) -> Type {
+    let (konst, typ) = match typ {
+        Type::Const(t) => (true, *t),
+        t => (konst, t),
+    };
+    let typ = match volatile {
+        true => Type::Volatile(Box::new(typ)),
+        false => typ,
+    };
+    match konst {
+        true => Type::Const(Box::new(typ)),
+        false => typ,
     }
 }
 
-fn parse_funcapp(tokens: &[Token]) -> Result<(SExpr, &[Token]), io::Error> {
-    let (left, r0) = parse_atom(tokens)?;
+fn sc_name(sc: &StorageClass) -> &'static str {
+    match sc {
+        StorageClass::Auto => "auto",
+        StorageClass::Register => "register",
+        StorageClass::Static => "static",
+        StorageClass::Extern => "extern",
+        StorageClass::Typedef => "typedef",
+    }
+}
 
-    match r0 {
-        [] => Ok((left, r0)),
-        [f, r @ ..] => {
-            if let TT::PuncLeftParen = f.typ {
-                let (mut aps, mut r) = (vec![], r);
-                while let Ok((ap, _r)) = parse_expr(r) {
-                    aps.push(ap);
-
-                    if let TT::PuncComma = _r[0].typ {
-                        r = &_r[1..];
-                    } else {
-                        r = _r;
-                    }
+// declarator: ('*' qualifier*)* direct-declarator (C89 3.5.4). every pointer
+// derives from the type to its left
+fn parse_declarator<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+    base: Type,
+    name: Name,
+) -> Result<(Declarator<'a>, &'a [Token]), io::Error> {
+    let (mut typ, mut r) = (base, tokens);
+    while let [f, _r @ ..] = r {
+        if f.typ != TT::Star {
+            break;
+        }
+        let (mut konst, mut volatile, mut _r) = (false, false, _r);
+        while let [q, __r @ ..] = _r {
+            match q.typ {
+                TT::KeywordConst if konst => {
+                    return Err(Diagnostic::new(Code::Syntax, q.span, "duplicate 'const'").into())
                 }
-                let (_, r) = eat(r, TT::PuncRightParen)?;
-
-                match left {
-                    SExpr::VarApp(alias) => Ok((SExpr::FuncApp { alias, aps }, r)),
-                    _ => Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        "expected alias".to_string(),
-                    )),
+                TT::KeywordVolatile if volatile => {
+                    return Err(
+                        Diagnostic::new(Code::Syntax, q.span, "duplicate 'volatile'").into(),
+                    )
                 }
-            } else {
-                Ok((left, r0))
+                TT::KeywordConst => konst = true,
+                TT::KeywordVolatile => volatile = true,
+                _ => break,
             }
+            _r = __r;
         }
+        typ = qualify(Type::Pointer(Box::new(typ)), konst, volatile);
+        r = _r;
     }
+
+    parse_direct_declarator(cx, r, typ, name)
 }
 
-fn parse_atom(tokens: &[Token]) -> Result<(SExpr, &[Token]), io::Error> {
+// the suffixes after a parenthesized declarator apply before it does, so
+// int (*fp)(int) is a pointer to a function. the inner declarator ends at
+// the matching parenthesis, and is parsed on the type the suffixes derive
+fn parse_direct_declarator<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+    base: Type,
+    name: Name,
+) -> Result<(Declarator<'a>, &'a [Token]), io::Error> {
     match tokens {
-        [] => todo!(),
-        [f, r @ ..] => match f.typ {
-            TT::Alias => Ok((SExpr::VarApp(f.lexeme.to_owned()), r)),
-            TT::LiteralInt => Ok((
-                SExpr::Int(f.lexeme.parse().map_err(|e: ParseIntError| {
-                    io::Error::new(io::ErrorKind::Other, e.to_string())
-                })?),
+        [f, r @ ..] if f.typ == TT::Alias && name != Name::Forbidden => {
+            let (typ, params, r) = parse_suffixes(cx, r, base)?;
+            Ok((
+                Declarator {
+                    alias: Some(f),
+                    typ,
+                    params,
+                },
                 r,
-            )),
-            TT::KeywordTrue => Ok((SExpr::Bool(true), r)),
-            TT::KeywordFalse => Ok((SExpr::Bool(false), r)),
-            t => Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("token not recognizable {:?}", t),
-            )),
-        },
-    }
+            ))
+        }
+        [f, s, ..]
+            if f.typ == TT::PuncLeftParen
+                && (matches!(s.typ, TT::Star | TT::PuncLeftParen | TT::PuncLeftBracket)
+                    || s.typ == TT::Alias
+                        && name != Name::Forbidden
+                        && typedef_name(cx, s).is_none()) =>
+        {
+            let close = closing(tokens).ok_or_else(|| {
+                io::Error::other(format!(
+                    "expected: {:?} got: end of input",
+                    TT::PuncRightParen
+                ))
+            })?;
+            let (typ, _, r) = parse_suffixes(cx, &tokens[close + 1..], base)?;
+            let inner = &tokens[1..close];
+            let (d, _r) = nested(cx, inner, |cx| parse_declarator(cx, inner, typ, name))?;
+            match _r {
+                [] => Ok((d, r)),
+                [f, ..] => Err(unexpected(f, format!("expected: {:?}", TT::PuncRightParen))),
+            }
+        }
+        [f, ..] if name == Name::Required => {
+            Err(unexpected(f, format!("expected: {:?}", TT::Alias)))
+        }
+        [] if name == Name::Required => Err(io::Error::other(format!(
+            "expected: {:?} got: end of input",
+            TT::Alias
+        ))),
+        _ => {
+            let (typ, params, r) = parse_suffixes(cx, tokens, base)?;
+            Ok((
+                Declarator {
+                    alias: None,
+                    typ,
+                    params,
+                },
+                r,
+            ))
+        }
+    }
+}
+
+// the index of the parenthesis that closes the one tokens start with
+fn closing(tokens: &[Token]) -> Option<usize> {
+    let mut parens = 0usize;
+    for (i, t) in tokens.iter().enumerate() {
+        match t.typ {
+            TT::PuncLeftParen => parens += 1,
+            TT::PuncRightParen if parens == 1 => return Some(i),
+            TT::PuncRightParen => parens -= 1,
+            _ => (),
+        }
+    }
+    None
+}
+
+// array [n] and function (params) suffixes. a[2][3] is an array of 2 arrays
+// of 3, so the rest of the suffixes derive the element (or return) type
+fn parse_suffixes<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+    base: Type,
+) -> Result<(Type, Option<Params<'a>>, &'a [Token]), io::Error> {
+    match tokens {
+        [f, r @ ..] if f.typ == TT::PuncLeftBracket => {
+            let (n, r) = match r {
+                [s, ..] if s.typ == TT::PuncRightBracket => (None, r),
+                _ => {
+                    let (e, r) = parse_const(cx, r)?;
+                    (Some(array_len(&cx.records, &e)?), r)
+                }
+            };
+            let (_, r) = eat(r, TT::PuncRightBracket)?;
+            let (elem, _, r) = nested(cx, r, |cx| parse_suffixes(cx, r, base))?;
+
+            let err = |msg: &str| io::Error::from(Diagnostic::new(Code::Syntax, f.span, msg));
+            match elem.unqual() {
+                Type::Func(_) => Err(err("array of functions is not allowed")),
+                t if t.size(&cx.records).is_none() => {
+                    Err(err("array type has incomplete element type"))
+                }
+                _ => Ok((Type::Array(Box::new(elem), n), None, r)),
+            }
+        }
+        [f, r @ ..] if f.typ == TT::PuncLeftParen => {
+            let (ps, r) = parse_params(cx, r)?;
+            let (body, _, r) = nested(cx, r, |cx| parse_suffixes(cx, r, base))?;
+
+            let err = |msg: &str| io::Error::from(Diagnostic::new(Code::Syntax, f.span, msg));
+            match body.unqual() {
+                Type::Array(..) => Err(err("function cannot return an array")),
+                Type::Func(_) => Err(err("function cannot return a function")),
+                _ => {
+                    let fp = match ps.proto {
+                        true => ps.fps.iter().map(|(_, t, _)| t.clone()).collect(),
+                        false => vec![],
+                    };
+                    let ft = LambdaType {
+                        fp,
+                        body,
+                        variadic: ps.variadic,
+                        proto: ps.proto,
+                    };
+                    Ok((Type::Func(Box::new(ft)), Some(ps), r))
+                }
+            }
+        }
+        _ => Ok((base, None, tokens)),
+    }
+}
+
+// parameters through the closing parenthesis: a prototype's declarations, an
+// identifier list (whose types come later, int by default) or nothing
+fn parse_params<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(Params<'a>, &'a [Token]), io::Error> {
+    let mut ps = Params {
+        fps: vec![],
+        variadic: false,
+        proto: true,
+    };
+    match tokens {
+        [f, r @ ..] if f.typ == TT::PuncRightParen => {
+            ps.proto = false;
+            return Ok((ps, r));
+        }
+        [f, s, r @ ..] if f.typ == TT::KeywordVoid && s.typ == TT::PuncRightParen => {
+            return Ok((ps, r));
+        }
+        [f, ..] if f.typ == TT::Alias && typedef_name(cx, f).is_none() => {
+            ps.proto = false;
+            let mut r = tokens;
+            loop {
+                let (alias, _r) = eat(r, TT::Alias)?;
+                ps.fps.push((Some(alias), Type::Int, alias.span));
+                match _r {
+                    [f, _r @ ..] if f.typ == TT::PuncComma => r = _r,
+                    _ => {
+                        let (_, r) = eat(_r, TT::PuncRightParen)?;
+                        return Ok((ps, r));
+                    }
+                }
+            }
+        }
+        _ => (),
+    }
+
+    let mut r = tokens;
+    loop {
+        let (specs, _r) = parse_specs(cx, r)?;
+        if let Some(
+            sc @ (StorageClass::Auto
+            | StorageClass::Static
+            | StorageClass::Extern
+            | StorageClass::Typedef),
+        ) = &specs.sc
+        {
+            let msg = format!("storage class '{}' specified for parameter", sc_name(sc));
+            return Err(Diagnostic::new(Code::Syntax, specs.span, msg).into());
+        }
+        let (d, _r) = nested(cx, _r, |cx| {
+            parse_declarator(cx, _r, specs.typ, Name::Optional)
+        })?;
+        let span = consumed(r, _r);
+        if *d.typ.unqual() == Type::Void {
+            return Err(
+                Diagnostic::new(Code::Syntax, span, "'void' must be the only parameter").into(),
+            );
+        }
+        ps.fps.push((d.alias, adjust(d.typ), span));
+
+        match _r {
+            [f, s, _r @ ..] if f.typ == TT::PuncComma && s.typ == TT::PuncEllipsis => {
+                ps.variadic = true;
+                let (_, r) = eat(_r, TT::PuncRightParen)?;
+                return Ok((ps, r));
+            }
+            [f, _r @ ..] if f.typ == TT::PuncComma => r = _r,
+            _ => {
+                let (_, r) = eat(_r, TT::PuncRightParen)?;
+                return Ok((ps, r));
+            }
+        }
+    }
+}
+
+// a parameter declared as an array or function is a pointer (C89 3.7.1)
+fn adjust(typ: Type) -> Type {
+    match typ {
+        Type::Array(elem, _) => Type::Pointer(elem),
+        Type::Func(_) => Type::Pointer(Box::new(typ)),
+        typ => typ,
+    }
+}
+
+// an array's size is a positive integral constant expression
+fn array_len(rs: &Records, e: &SExpr) -> Result<usize, io::Error> {
+    match const_int(rs, e) {
+        Some(n) if n > 0 => Ok(n as usize),
+        Some(_) => {
+            Err(Diagnostic::new(Code::Syntax, e.span, "size of array is not positive").into())
+        }
+        None => Err(Diagnostic::new(
+            Code::Syntax,
+            e.span,
+            "size of array is not an integer constant",
+        )
+        .into()),
+    }
+}
+
+// the value of an integral constant expression (C89 3.4)
+fn const_int(rs: &Records, e: &SExpr) -> Option<i64> {
+    match &e.kind {
+        SExprKind::Int(n) | SExprKind::Long(n) => Some(*n as i64),
+        SExprKind::UInt(n) | SExprKind::ULong(n) => Some(*n as i64),
+        SExprKind::UnaryE { op, l } => {
+            let n = const_int(rs, l)?;
+            match op {
+                SUnaryOp::Add => Some(n),
+                SUnaryOp::Sub => n.checked_neg(),
+                SUnaryOp::Not => Some((n == 0) as i64),
+                SUnaryOp::BitNot => Some(!n),
+            }
+        }
+        SExprKind::BinE { op, l, r } => {
+            let (l, r) = (const_int(rs, l)?, const_int(rs, r)?);
+            match op {
+                SBinOp::Add => l.checked_add(r),
+                SBinOp::Sub => l.checked_sub(r),
+                SBinOp::Mult => l.checked_mul(r),
+                SBinOp::Div => l.checked_div(r),
+                SBinOp::Mod => l.checked_rem(r),
+            }
+        }
+        SExprKind::BitE { op, l, r } => {
+            let (l, r) = (const_int(rs, l)?, const_int(rs, r)?);
+            match op {
+                SBitOp::And => Some(l & r),
+                SBitOp::Or => Some(l | r),
+                SBitOp::Xor => Some(l ^ r),
+                SBitOp::Shl => l.checked_shl(u32::try_from(r).ok()?),
+                SBitOp::Shr => l.checked_shr(u32::try_from(r).ok()?),
+            }
+        }
+        SExprKind::RelE { op, l, r } => {
+            let (l, r) = (const_int(rs, l)?, const_int(rs, r)?);
+            let b = match op {
+                SRelOp::Eq => l == r,
+                SRelOp::Neq => l != r,
+                SRelOp::LtEq => l <= r,
+                SRelOp::Lt => l < r,
+                SRelOp::GtEq => l >= r,
+                SRelOp::Gt => l > r,
+            };
+            Some(b as i64)
+        }
+        SExprKind::LogE { op, l, r } => {
+            let (l, r) = (const_int(rs, l)? != 0, const_int(rs, r)? != 0);
+            match op {
+                SLogOp::And => Some((l && r) as i64),
+                SLogOp::Or => Some((l || r) as i64),
+            }
+        }
+        SExprKind::Ternary { cond, then, els } => match const_int(rs, cond)? {
+            0 => const_int(rs, els),
+            _ => const_int(rs, then),
+        },
+        SExprKind::SizeOfT(t) => t.size(rs).map(|n| n as i64),
+        SExprKind::Cast { typ, l } if typ.size(rs).is_some() => const_int(rs, l),
+        _ => None,
+    }
+}
+
+// statements through the closing brace of a block, which is consumed
+fn parse_block<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(Vec<SStmt>, &'a [Token]), io::Error> {
+    let (mut stmts, mut r) = (vec![], tokens);
+    loop {
+        match r {
+            [f, r @ ..] if f.typ == TT::PuncRightBrace => return Ok((stmts, r)),
+            [] => return Err(io::Error::other("expected: statement got: end of input")),
+            _ => match parse_stmt(cx, r) {
+                Ok((s, _r)) => {
+                    stmts.push(s);
+                    r = _r;
+                }
+                Err(e) => {
+                    report(cx, e);
+                    r = recover(r);
+                }
+            },
+        }
+    }
+}
+
+// statements (C89 3.6). a body is any statement, a block only if braced
+fn parse_stmt<'a>(cx: &mut Parser, tokens: &'a [Token]) -> Result<(SStmt, &'a [Token]), io::Error> {
+    nested(cx, tokens, |cx| parse_unnested_stmt(cx, tokens))
+}
+
+fn parse_unnested_stmt<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(SStmt, &'a [Token]), io::Error> {
+    let stmt = |kind, r| Ok((SStmt::new(kind, consumed(tokens, r)), r));
+    let err = |t: &Token, msg: String| Err(Diagnostic::new(Code::Syntax, t.span, msg).into());
+    match tokens {
+        [] => Err(io::Error::other("expected: statement got: end of input")),
+        // labels are in a name space of their own, so even a typedef name can be one
+        [f, s, r @ ..] if f.typ == TT::Alias && s.typ == TT::Colon => {
+            if !cx.jumps.labels.insert(f.lexeme.to_owned()) {
+                return err(f, format!("duplicate label '{}'", f.lexeme));
+            }
+            let (s, r) = parse_stmt(cx, r)?;
+            stmt(SStmtKind::Labeled(f.lexeme.to_owned(), Box::new(s)), r)
+        }
+        [f, r @ ..] => match f.typ {
+            _ if starts_specs(cx, f) => {
+                let (vds, r) = parse_decl(cx, tokens)?;
+                stmt(SStmtKind::Decl(vds), r)
+            }
+            TT::PuncLeftBrace => {
+                let (stmts, r) = scoped(cx, |cx| parse_block(cx, r))?;
+                stmt(SStmtKind::Block(stmts), r)
+            }
+            TT::PuncSemiColon => stmt(SStmtKind::Empty, r),
+            // an else belongs to the nearest if, which parsing the then branch
+            // first gives it
+            TT::KeywordIf => {
+                let (cond, r) = parse_cond(cx, r)?;
+                let (then, r) = parse_stmt(cx, r)?;
+                let (els, r) = match r {
+                    [s, r @ ..] if s.typ == TT::KeywordEls => {
+                        let (els, r) = parse_stmt(cx, r)?;
+                        (Some(Box::new(els)), r)
+                    }
+                    _ => (None, r),
+                };
+
+                let kind = SStmtKind::IfEls {
+                    cond: Box::new(cond),
+                    then: Box::new(then),
+                    els,
+                };
+                stmt(kind, r)
+            }
+            TT::KeywordSwitch => {
+                let (cond, r) = parse_cond(cx, r)?;
+                let (body, r) = within(cx, Enclosing::Switch(HashSet::new(), false), |cx| {
+                    parse_stmt(cx, r)
+                })?;
+
+                let kind = SStmtKind::Switch {
+                    cond: Box::new(cond),
+                    body: Box::new(body),
+                };
+                stmt(kind, r)
+            }
+            TT::KeywordWhile => {
+                let (cond, r) = parse_cond(cx, r)?;
+                let (body, r) = within(cx, Enclosing::Loop, |cx| parse_stmt(cx, r))?;
+
+                let kind = SStmtKind::While {
+                    cond: Box::new(cond),
+                    body: Box::new(body),
+                };
+                stmt(kind, r)
+            }
+            TT::KeywordDo => {
+                let (body, r) = within(cx, Enclosing::Loop, |cx| parse_stmt(cx, r))?;
+                let (_, r) = eat(r, TT::KeywordWhile)?;
+                let (cond, r) = parse_cond(cx, r)?;
+                let (_, r) = eat(r, TT::PuncSemiColon)?;
+
+                let kind = SStmtKind::DoWhile {
+                    body: Box::new(body),
+                    cond: Box::new(cond),
+                };
+                stmt(kind, r)
+            }
+            // each clause is an optional expression
+            TT::KeywordFor => {
+                let (_, r) = eat(r, TT::PuncLeftParen)?;
+                if let [s, ..] = r {
+                    if starts_specs(cx, s) {
+                        let msg = "'for' loop initial declarations are only allowed in C99 mode";
+                        return err(s, msg.to_string());
+                    }
+                }
+                let (init, r) = parse_clause(cx, r, TT::PuncSemiColon)?;
+                let (cond, r) = parse_clause(cx, r, TT::PuncSemiColon)?;
+                let (update, r) = parse_clause(cx, r, TT::PuncRightParen)?;
+                let (body, r) = within(cx, Enclosing::Loop, |cx| parse_stmt(cx, r))?;
+
+                let kind = SStmtKind::For {
+                    init,
+                    cond,
+                    update,
+                    body: Box::new(body),
+                };
+                stmt(kind, r)
+            }
+            TT::KeywordCase => {
+                let (e, r) = parse_const(cx, r)?;
+                let Some(n) = const_int(&cx.records, &e) else {
+                    let msg = "case label does not reduce to an integer constant";
+                    return Err(Diagnostic::new(Code::Syntax, e.span, msg).into());
+                };
+                let n = n as i32;
+                let (_, r) = eat(r, TT::Colon)?;
+                let case = cx.jumps.enclosing.iter_mut().rev().find_map(|e| match e {
+                    Enclosing::Switch(cases, _) => Some(cases.insert(n)),
+                    Enclosing::Loop => None,
+                });
+                match case {
+                    None => return err(f, "case label not within a switch statement".to_string()),
+                    Some(false) => return err(f, "duplicate case value".to_string()),
+                    Some(true) => (),
+                }
+
+                let (s, r) = parse_stmt(cx, r)?;
+                stmt(SStmtKind::Case(n, Box::new(s)), r)
+            }
+            TT::KeywordDefault => {
+                let (_, r) = eat(r, TT::Colon)?;
+                let default = cx.jumps.enclosing.iter_mut().rev().find_map(|e| match e {
+                    Enclosing::Switch(_, default) => Some(!std::mem::replace(default, true)),
+                    Enclosing::Loop => None,
+                });
+                match default {
+                    None => {
+                        let msg = "'default' label not within a switch statement";
+                        return err(f, msg.to_string());
+                    }
+                    Some(false) => {
+                        return err(f, "multiple default labels in one switch".to_string())
+                    }
+                    Some(true) => (),
+                }
+
+                let (s, r) = parse_stmt(cx, r)?;
+                stmt(SStmtKind::Default(Box::new(s)), r)
+            }
+            TT::KeywordBreak => {
+                if cx.jumps.enclosing.is_empty() {
+                    return err(f, "break statement not within loop or switch".to_string());
+                }
+                let (_, r) = eat(r, TT::PuncSemiColon)?;
+                stmt(SStmtKind::Break, r)
+            }
+            TT::KeywordContinue => {
+                let enclosing = &cx.jumps.enclosing;
+                let looping = enclosing.iter().any(|e| matches!(e, Enclosing::Loop));
+                if !looping {
+                    return err(f, "continue statement not within a loop".to_string());
+                }
+                let (_, r) = eat(r, TT::PuncSemiColon)?;
+                stmt(SStmtKind::Continue, r)
+            }
+            TT::KeywordGoto => {
+                let (l, r) = eat(r, TT::Alias)?;
+                cx.jumps.gotos.push((l.lexeme.to_owned(), l.span));
+                let (_, r) = eat(r, TT::PuncSemiColon)?;
+                stmt(SStmtKind::Goto(l.lexeme.to_owned()), r)
+            }
+            TT::KeywordRet => match r {
+                [s, r @ ..] if s.typ == TT::PuncSemiColon => stmt(SStmtKind::Return(None), r),
+                _ => {
+                    let (expr, r) = parse_expr(cx, r)?;
+                    let (_, r) = eat(r, TT::PuncSemiColon)?;
+                    stmt(SStmtKind::Return(Some(expr)), r)
+                }
+            },
+            _ => {
+                let (expr, r) = parse_expr(cx, tokens)?;
+                let (_, r) = eat(r, TT::PuncSemiColon)?;
+                stmt(SStmtKind::Expr(expr), r)
+            }
+        },
+    }
+}
+
+// the parenthesized controlling expression of an if, switch or loop
+fn parse_cond<'a>(cx: &mut Parser, tokens: &'a [Token]) -> Result<(SExpr, &'a [Token]), io::Error> {
+    let (_, r) = eat(tokens, TT::PuncLeftParen)?;
+    let (cond, r) = parse_expr(cx, r)?;
+    let (_, r) = eat(r, TT::PuncRightParen)?;
+    Ok((cond, r))
+}
+
+// a for clause through the token that ends it
+fn parse_clause<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+    end: TT,
+) -> Result<(Option<Box<SExpr>>, &'a [Token]), io::Error> {
+    match tokens {
+        [f, r @ ..] if f.typ == end => Ok((None, r)),
+        _ => {
+            let (e, r) = parse_expr(cx, tokens)?;
+            let (_, r) = eat(r, end)?;
+            Ok((Some(Box::new(e)), r))
+        }
+    }
+}
+
+// the 15 levels of C89 3.3, loosest first. every binary level associates
+// left but the conditional and assignment ones, which associate right
+#[rustfmt::skip]
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+enum Prec {
+    Comma, Asnmt, Cond, LogOr, LogAnd, BitOr, BitXor, BitAnd, Eq, Rel, Shift, Add, Mult, Unary, Postfix,
+}
+
+impl Prec {
+    // the level of the right operand of a left-associative operator
+    fn tighter(self) -> Prec {
+        use Prec::*;
+        let levels = [
+            Comma, Asnmt, Cond, LogOr, LogAnd, BitOr, BitXor, BitAnd, Eq, Rel, Shift, Add, Mult,
+            Unary, Postfix,
+        ];
+        levels[self as usize + 1]
+    }
+}
+
+enum Infix {
+    Comma,
+    Asnmt(Option<SAsnmtOp>),
+    Cond,
+    Log(SLogOp),
+    Bit(SBitOp),
+    Rel(SRelOp),
+    Bin(SBinOp),
+}
+
+#[rustfmt::skip]
+fn infix(tt: &TT) -> Option<(Prec, Infix)> {
+    let op = match tt {
+        TT::PuncComma => (Prec::Comma, Infix::Comma),
+        TT::Equals => (Prec::Asnmt, Infix::Asnmt(None)),
+        TT::PlusEquals => (Prec::Asnmt, Infix::Asnmt(Some(SAsnmtOp::Add))),
+        TT::MinusEquals => (Prec::Asnmt, Infix::Asnmt(Some(SAsnmtOp::Sub))),
+        TT::StarEquals => (Prec::Asnmt, Infix::Asnmt(Some(SAsnmtOp::Mult))),
+        TT::SlashEquals => (Prec::Asnmt, Infix::Asnmt(Some(SAsnmtOp::Div))),
+        TT::PercentEquals => (Prec::Asnmt, Infix::Asnmt(Some(SAsnmtOp::Mod))),
+        TT::LeftShiftEquals => (Prec::Asnmt, Infix::Asnmt(Some(SAsnmtOp::Shl))),
+        TT::RightShiftEquals => (Prec::Asnmt, Infix::Asnmt(Some(SAsnmtOp::Shr))),
+        TT::AmpEquals => (Prec::Asnmt, Infix::Asnmt(Some(SAsnmtOp::And))),
+        TT::CaretEquals => (Prec::Asnmt, Infix::Asnmt(Some(SAsnmtOp::Xor))),
+        TT::BarEquals => (Prec::Asnmt, Infix::Asnmt(Some(SAsnmtOp::Or))),
+        TT::Question => (Prec::Cond, Infix::Cond),
+        TT::BarBar => (Prec::LogOr, Infix::Log(SLogOp::Or)),
+        TT::AmpAmp => (Prec::LogAnd, Infix::Log(SLogOp::And)),
+        TT::Bar => (Prec::BitOr, Infix::Bit(SBitOp::Or)),
+        TT::Caret => (Prec::BitXor, Infix::Bit(SBitOp::Xor)),
+        TT::Amp => (Prec::BitAnd, Infix::Bit(SBitOp::And)),
+        TT::EqualsEquals => (Prec::Eq, Infix::Rel(SRelOp::Eq)),
+        TT::BangEquals => (Prec::Eq, Infix::Rel(SRelOp::Neq)),
+        TT::LeftAngleBracket => (Prec::Rel, Infix::Rel(SRelOp::Lt)),
+        TT::LeftAngleBracketEquals => (Prec::Rel, Infix::Rel(SRelOp::LtEq)),
+        TT::RightAngleBracket => (Prec::Rel, Infix::Rel(SRelOp::Gt)),
+        TT::RightAngleBracketEquals => (Prec::Rel, Infix::Rel(SRelOp::GtEq)),
+        TT::LeftShift => (Prec::Shift, Infix::Bit(SBitOp::Shl)),
+        TT::RightShift => (Prec::Shift, Infix::Bit(SBitOp::Shr)),
+        TT::Plus => (Prec::Add, Infix::Bin(SBinOp::Add)),
+        TT::Minus => (Prec::Add, Infix::Bin(SBinOp::Sub)),
+        TT::Star => (Prec::Mult, Infix::Bin(SBinOp::Mult)),
+        TT::Slash => (Prec::Mult, Infix::Bin(SBinOp::Div)),
+        TT::Percent => (Prec::Mult, Infix::Bin(SBinOp::Mod)),
+        _ => return None,
+    };
+    Some(op)
+}
+
+// expression (C89 3.3.17), comma operators included
+fn parse_expr<'a>(cx: &mut Parser, tokens: &'a [Token]) -> Result<(SExpr, &'a [Token]), io::Error> {
+    parse_prec(cx, tokens, Prec::Comma)
+}
+
+// an assignment expression: an initializer or an argument, where a comma
+// separates rather than sequences
+fn parse_asnmt<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(SExpr, &'a [Token]), io::Error> {
+    parse_prec(cx, tokens, Prec::Asnmt)
+}
+
+// a constant expression is a conditional one (C89 3.4)
+fn parse_const<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(SExpr, &'a [Token]), io::Error> {
+    parse_prec(cx, tokens, Prec::Cond)
+}
+
+// pratt parsing: operators are folded in while they bind at least as tight
+// as min. the right operand then takes the operators tighter than its own
+fn parse_prec<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+    min: Prec,
+) -> Result<(SExpr, &'a [Token]), io::Error> {
+    let (mut l, mut r) = parse_cast(cx, tokens)?;
+
+    while let [f, _r @ ..] = r {
+        let Some((prec, op)) = infix(&f.typ).filter(|(prec, _)| *prec >= min) else {
+            break;
+        };
+        let (kind, _r) = match op {
+            Infix::Cond => {
+                let (then, _r) = nested(cx, _r, |cx| parse_expr(cx, _r))?;
+                let (_, _r) = eat(_r, TT::Colon)?;
+                let (els, _r) = nested(cx, _r, |cx| parse_prec(cx, _r, Prec::Cond))?;
+                let (then, els) = (Box::new(then), Box::new(els));
+                let kind = SExprKind::Ternary {
+                    cond: Box::new(l),
+                    then,
+                    els,
+                };
+                (kind, _r)
+            }
+            Infix::Asnmt(op) => {
+                let (e, _r) = nested(cx, _r, |cx| parse_prec(cx, _r, Prec::Asnmt))?;
+                (
+                    SExprKind::Asnmt {
+                        op,
+                        l: Box::new(l),
+                        r: Box::new(e),
+                    },
+                    _r,
+                )
+            }
+            op => {
+                let (e, _r) = nested(cx, _r, |cx| parse_prec(cx, _r, prec.tighter()))?;
+                let (l, e) = (Box::new(l), Box::new(e));
+                let kind = match op {
+                    Infix::Comma => SExprKind::Comma { l, r: e },
+                    Infix::Log(op) => SExprKind::LogE { op, l, r: e },
+                    Infix::Bit(op) => SExprKind::BitE { op, l, r: e },
+                    Infix::Rel(op) => SExprKind::RelE { op, l, r: e },
+                    Infix::Bin(op) => SExprKind::BinE { op, l, r: e },
+                    Infix::Cond | Infix::Asnmt(_) => unreachable!(),
+                };
+                (kind, _r)
+            }
+        };
+        l = SExpr::new(kind, consumed(tokens, _r));
+        r = _r;
+    }
+
+    Ok((l, r))
+}
+
+// a cast is a parenthesized type name before its operand (C89 3.3.4)
+// every operand is one, so this is where an expression nests
+fn parse_cast<'a>(cx: &mut Parser, tokens: &'a [Token]) -> Result<(SExpr, &'a [Token]), io::Error> {
+    nested(cx, tokens, |cx| parse_unnested_cast(cx, tokens))
+}
+
+fn parse_unnested_cast<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(SExpr, &'a [Token]), io::Error> {
+    match tokens {
+        [f, s, ..] if f.typ == TT::PuncLeftParen && starts_typename(cx, s) => {
+            let (typ, r) = parse_typename(cx, &tokens[1..])?;
+            let (_, r) = eat(r, TT::PuncRightParen)?;
+            let (l, r) = parse_cast(cx, r)?;
+            let kind = SExprKind::Cast {
+                typ,
+                l: Box::new(l),
+            };
+            Ok((SExpr::new(kind, consumed(tokens, r)), r))
+        }
+        _ => parse_unary(cx, tokens),
+    }
+}
+
+// prefix operators (C89 3.3.3), which bind tighter than any binary one
+fn parse_unary<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(SExpr, &'a [Token]), io::Error> {
+    let node = |kind, r| Ok((SExpr::new(kind, consumed(tokens, r)), r));
+    match tokens {
+        [f, r @ ..] if matches!(f.typ, TT::PlusPlus | TT::MinusMinus) => {
+            let (l, r) = nested(cx, r, |cx| parse_unary(cx, r))?;
+            let op = match f.typ {
+                TT::PlusPlus => SUpdateOp::PreInc,
+                _ => SUpdateOp::PreDec,
+            };
+            node(SExprKind::Update { op, l: Box::new(l) }, r)
+        }
+        [f, r @ ..]
+            if matches!(
+                f.typ,
+                TT::Amp | TT::Star | TT::Plus | TT::Minus | TT::Tilde | TT::Bang
+            ) =>
+        {
+            let (l, r) = parse_cast(cx, r)?;
+            let l = Box::new(l);
+            let kind = match f.typ {
+                TT::Amp => SExprKind::AddrOf(l),
+                TT::Star => SExprKind::Deref(l),
+                TT::Plus => SExprKind::UnaryE {
+                    op: SUnaryOp::Add,
+                    l,
+                },
+                TT::Minus => SExprKind::UnaryE {
+                    op: SUnaryOp::Sub,
+                    l,
+                },
+                TT::Tilde => SExprKind::UnaryE {
+                    op: SUnaryOp::BitNot,
+                    l,
+                },
+                _ => SExprKind::UnaryE {
+                    op: SUnaryOp::Not,
+                    l,
+                },
+            };
+            node(kind, r)
+        }
+        [f, r @ ..] if f.typ == TT::KeywordSizeof => match r {
+            [s, t, ..] if s.typ == TT::PuncLeftParen && starts_typename(cx, t) => {
+                let (typ, r) = parse_typename(cx, &r[1..])?;
+                let (_, r) = eat(r, TT::PuncRightParen)?;
+                node(SExprKind::SizeOfT(typ), r)
+            }
+            _ => {
+                let (l, r) = nested(cx, r, |cx| parse_unary(cx, r))?;
+                node(SExprKind::SizeOfE(Box::new(l)), r)
+            }
+        },
+        _ => parse_postfix(cx, tokens),
+    }
+}
+
+// postfix operators: subscripts, calls, member accesses and increments,
+// applied left to right
+fn parse_postfix<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(SExpr, &'a [Token]), io::Error> {
+    let (mut l, mut r) = parse_atom(cx, tokens)?;
+
+    loop {
+        let (kind, _r) = match r {
+            [f, _r @ ..] if f.typ == TT::PuncLeftBracket => {
+                let (i, _r) = parse_expr(cx, _r)?;
+                let (_, _r) = eat(_r, TT::PuncRightBracket)?;
+                let kind = SExprKind::Index {
+                    l: Box::new(l),
+                    r: Box::new(i),
+                };
+                (kind, _r)
+            }
+            [f, _r @ ..] if f.typ == TT::PuncLeftParen => {
+                let (aps, _r) = parse_args(cx, _r)?;
+                (
+                    SExprKind::FuncApp {
+                        f: Box::new(l),
+                        aps,
+                    },
+                    _r,
+                )
+            }
+            [f, _r @ ..] if matches!(f.typ, TT::Dot | TT::Arrow) => {
+                let (field, _r) = eat(_r, TT::Alias)?;
+                let (l, field) = (Box::new(l), field.lexeme.to_owned());
+                let kind = match f.typ {
+                    TT::Dot => SExprKind::Dot { l, field },
+                    _ => SExprKind::Arrow { l, field },
+                };
+                (kind, _r)
+            }
+            [f, _r @ ..] if matches!(f.typ, TT::PlusPlus | TT::MinusMinus) => {
+                let op = match f.typ {
+                    TT::PlusPlus => SUpdateOp::PostInc,
+                    _ => SUpdateOp::PostDec,
+                };
+                (SExprKind::Update { op, l: Box::new(l) }, _r)
+            }
+            _ => return Ok((l, r)),
+        };
+        l = SExpr::new(kind, consumed(tokens, _r));
+        r = _r;
+    }
+}
+
+// a call's arguments through the closing parenthesis
+fn parse_args<'a>(
+    cx: &mut Parser,
+    tokens: &'a [Token],
+) -> Result<(Vec<SExpr>, &'a [Token]), io::Error> {
+    let (mut aps, mut r) = (vec![], tokens);
+    if let [f, r @ ..] = tokens {
+        if f.typ == TT::PuncRightParen {
+            return Ok((aps, r));
+        }
+    }
+
+    loop {
+        let (ap, _r) = parse_asnmt(cx, r)?;
+        aps.push(ap);
+        match _r {
+            [f, _r @ ..] if f.typ == TT::PuncComma => r = _r,
+            _ => {
+                let (_, r) = eat(_r, TT::PuncRightParen)?;
+                return Ok((aps, r));
+            }
+        }
+    }
+}
+
+fn parse_atom<'a>(cx: &mut Parser, tokens: &'a [Token]) -> Result<(SExpr, &'a [Token]), io::Error> {
+    match tokens {
+        [] => Err(io::Error::other("expected: expression got: end of input")),
+        [f, r @ ..] => match f.typ {
+            TT::Alias => match lookup(cx, &f.lexeme) {
+                Some(Ordinary::Const(n)) => Ok((SExpr::new(SExprKind::Int(n), f.span), r)),
+                Some(Ordinary::Typedef(_)) => {
+                    Err(unexpected(f, "expected: expression".to_string()))
+                }
+                _ => Ok((
+                    SExpr::new(SExprKind::VarApp(f.lexeme.to_owned()), f.span),
+                    r,
+                )),
+            },
+            TT::LiteralInt => Ok((SExpr::new(int_lit(f)?, f.span), r)),
+            TT::LiteralFloat => Ok((SExpr::new(float_lit(f)?, f.span), r)),
+            TT::LiteralChar => Ok((SExpr::new(char_lit(f)?, f.span), r)),
+            TT::LiteralString => {
+                // adjacent string literals are concatenated (translation phase 6)
+                let (mut bytes, mut r) = (unescape(f)?, r);
+                while let [s, _r @ ..] = r {
+                    if s.typ != TT::LiteralString {
+                        break;
+                    }
+                    bytes.extend(unescape(s)?);
+                    r = _r;
+                }
+
+                let span = consumed(tokens, r);
+                Ok((SExpr::new(SExprKind::Str(bytes), span), r))
+            }
+            TT::PuncLeftParen => {
+                let (e, r) = parse_expr(cx, r)?;
+                let (_, r) = eat(r, TT::PuncRightParen)?;
+                Ok((SExpr::new(e.kind, consumed(tokens, r)), r))
+            }
+            TT::KeywordTrue => Ok((SExpr::new(SExprKind::Bool(true), f.span), r)),
+            TT::KeywordFalse => Ok((SExpr::new(SExprKind::Bool(false), f.span), r)),
+            _ => Err(unexpected(f, "expected: expression".to_string())),
+        },
+    }
+}
+
+// an integer constant has the first type in its list that can represent its
+// value (C89 6.1.3.2). int and long are both 32 bits wide on RV32 (ILP32)
+pub(crate) fn int_lit(t: &Token) -> Result<SExprKind, io::Error> {
+    let lexeme = t.lexeme.as_str();
+    let err = |msg: String| io::Error::from(Diagnostic::new(Code::Syntax, t.span, msg));
+
+    let hex = lexeme.len() > 2
+        && lexeme[..2].eq_ignore_ascii_case("0x")
+        && lexeme.as_bytes()[2].is_ascii_hexdigit();
+    let (radix, digits) = if hex {
+        let n = lexeme[2..]
+            .bytes()
+            .take_while(u8::is_ascii_hexdigit)
+            .count();
+        (16, &lexeme[2..2 + n])
+    } else {
+        let n = lexeme.bytes().take_while(u8::is_ascii_digit).count();
+        let radix = if lexeme.starts_with('0') { 8 } else { 10 };
+        (radix, &lexeme[..n])
+    };
+
+    if let Some(d) = digits.chars().find(|d| !d.is_digit(radix)) {
+        return Err(err(format!("invalid digit \"{d}\" in octal constant")));
+    }
+
+    let suffix = &lexeme[digits.len() + if hex { 2 } else { 0 }..];
+    let candidates: &[Type] = match suffix.to_ascii_lowercase().as_str() {
+        "" if radix == 10 => &[Type::Int, Type::Long, Type::ULong],
+        "" => &[Type::Int, Type::UInt, Type::Long, Type::ULong],
+        "u" => &[Type::UInt, Type::ULong],
+        "l" => &[Type::Long, Type::ULong],
+        "ul" | "lu" => &[Type::ULong],
+        _ => {
+            let msg = format!("invalid suffix \"{suffix}\" on integer constant");
+            return Err(err(msg));
+        }
+    };
+
+    let too_large = || err("integer constant is too large for its type".to_string());
+    let n = u64::from_str_radix(digits, radix).map_err(|_| too_large())?;
+    candidates
+        .iter()
+        .find_map(|typ| match typ {
+            Type::Int if n <= i32::MAX as u64 => Some(SExprKind::Int(n as i32)),
+            Type::Long if n <= i32::MAX as u64 => Some(SExprKind::Long(n as i32)),
+            Type::UInt if n <= u32::MAX as u64 => Some(SExprKind::UInt(n as u32)),
+            Type::ULong if n <= u32::MAX as u64 => Some(SExprKind::ULong(n as u32)),
+            _ => None,
+        })
+        .ok_or_else(too_large)
+}
+
+// a floating constant is a double unless suffixed with f (float) or l (long
+// double). its value is rounded to the nearest representable one
+fn float_lit(t: &Token) -> Result<SExprKind, io::Error> {
+    let lexeme = t.lexeme.as_str();
+    let err = |msg: String| io::Error::from(Diagnostic::new(Code::Syntax, t.span, msg));
+    let digits = |i: usize| lexeme[i..].bytes().take_while(u8::is_ascii_digit).count();
+
+    // digits? (. digits?)? (e [+-]? digits)?
+    let mut n = digits(0);
+    if lexeme[n..].starts_with('.') {
+        n += 1 + digits(n + 1);
+    }
+    if lexeme[n..].starts_with(['e', 'E']) {
+        let sign = lexeme[n + 1..].starts_with(['+', '-']) as usize;
+        match digits(n + 1 + sign) {
+            0 => return Err(err("exponent has no digits".to_string())),
+            d => n += 1 + sign + d,
+        }
+    }
+
+    let (value, suffix) = lexeme.split_at(n);
+    let kind = match suffix {
+        "f" | "F" => value.parse().ok().map(SExprKind::Float),
+        "" => value.parse().ok().map(SExprKind::Double),
+        "l" | "L" => value.parse().ok().map(SExprKind::LongDouble),
+        _ => {
+            let msg = format!("invalid suffix \"{suffix}\" on floating constant");
+            return Err(err(msg));
+        }
+    };
+
+    match kind {
+        Some(SExprKind::Float(f)) if f.is_infinite() => {
+            Err(err("floating constant exceeds range of float".to_string()))
+        }
+        Some(SExprKind::Double(f) | SExprKind::LongDouble(f)) if f.is_infinite() => {
+            Err(err("floating constant exceeds range of double".to_string()))
+        }
+        Some(kind) => Ok(kind),
+        None => Err(err(format!("invalid floating constant `{lexeme}`"))),
+    }
+}
+
+// a character constant has type int. plain char is unsigned on RV32, so
+// '\xff' is 255 rather than -1
+pub(crate) fn char_lit(t: &Token) -> Result<SExprKind, io::Error> {
+    match unescape(t)?.as_slice() {
+        [c] => Ok(SExprKind::Int(*c as i32)),
+        [] => Err(Diagnostic::new(Code::Syntax, t.span, "empty character constant").into()),
+        _ => {
+            Err(Diagnostic::new(Code::Syntax, t.span, "multi-character character constant").into())
+        }
+    }
+}
+
+// the bytes between the quotes of a character constant or string literal,
+// with escape sequences (C89 6.1.3.4) replaced by the bytes they denote
+fn unescape(t: &Token) -> Result<Vec<u8>, io::Error> {
+    let body = &t.lexeme.as_bytes()[1..t.lexeme.len() - 1];
+    let err = |msg: String| io::Error::from(Diagnostic::new(Code::Syntax, t.span, msg));
+
+    let (mut bytes, mut i) = (vec![], 0);
+    while i < body.len() {
+        if body[i] != b'\\' {
+            bytes.push(body[i]);
+            i += 1;
+            continue;
+        }
+
+        // the lexer never ends a literal on a backslash, but # can, as in a
+        // stringified lone \
+        let Some(&c) = body.get(i + 1) else {
+            return Err(err("invalid string literal: it ends in a backslash".into()));
+        };
+        i += 2;
+        let b = match c {
+            b'n' => b'\n',
+            b't' => b'\t',
+            b'v' => 0x0b,
+            b'b' => 0x08,
+            b'r' => b'\r',
+            b'f' => 0x0c,
+            b'a' => 0x07,
+            b'\\' | b'\'' | b'"' | b'?' => c,
+            // up to three octal digits, or any number of hex digits
+            b'0'..=b'7' => {
+                let (mut v, end) = ((c - b'0') as u32, (i + 2).min(body.len()));
+                while let Some(d @ b'0'..=b'7') = body[..end].get(i) {
+                    v = v * 8 + (d - b'0') as u32;
+                    i += 1;
+                }
+                u8::try_from(v).map_err(|_| err("octal escape sequence out of range".into()))?
+            }
+            b'x' => {
+                let n = body[i..]
+                    .iter()
+                    .take_while(|d| d.is_ascii_hexdigit())
+                    .count();
+                if n == 0 {
+                    return Err(err("\\x used with no following hex digits".into()));
+                }
+                let v = body[i..i + n].iter().fold(0u32, |v, d| {
+                    v.saturating_mul(16) + (*d as char).to_digit(16).unwrap()
+                });
+                i += n;
+                u8::try_from(v).map_err(|_| err("hex escape sequence out of range".into()))?
+            }
+            _ => {
+                let c = String::from_utf8_lossy(&body[i - 1..])
+                    .chars()
+                    .next()
+                    .unwrap();
+                return Err(err(format!("unknown escape sequence: '\\{c}'")));
+            }
+        };
+        bytes.push(b);
+    }
+
+    Ok(bytes)
 }
 
 #[cfg(test)]
@@ -500,11 +2222,454 @@ mod test_arith {
 
     #[test]
     fn lit() {
-        let chars = fs::read(format!("{TEST_DIR}/lit.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/lit.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  Int: 8
+        "###);
+    }
+
+    #[test]
+    fn add() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/add.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  BinE:
+                    op: Add
+                    l:
+                      Int: 9
+                    r:
+                      Int: 10
+        "###);
+    }
+
+    #[test]
+    fn add_multi() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/add_multi.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  BinE:
+                    op: Add
+                    l:
+                      BinE:
+                        op: Add
+                        l:
+                          Int: 9
+                        r:
+                          Int: 10
+                    r:
+                      Int: 11
+        "###);
+    }
+
+    #[test]
+    fn sub() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/sub.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  BinE:
+                    op: Sub
+                    l:
+                      Int: 88
+                    r:
+                      Int: 32
+        "###);
+    }
+
+    #[test]
+    fn mult() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/mult.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  BinE:
+                    op: Mult
+                    l:
+                      Int: 9
+                    r:
+                      Int: 10
+        "###);
+    }
+
+    #[test]
+    fn div() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/div.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  BinE:
+                    op: Div
+                    l:
+                      Int: 100
+                    r:
+                      Int: 9
+        "###);
+    }
+
+    #[test]
+    fn add_associative() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/add_associative.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  BinE:
+                    op: Add
+                    l:
+                      BinE:
+                        op: Add
+                        l:
+                          Int: 9
+                        r:
+                          Int: 10
+                    r:
+                      Int: 11
+        "###);
+    }
+
+    #[test]
+    fn sub_associative() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/sub_associative.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  BinE:
+                    op: Sub
+                    l:
+                      BinE:
+                        op: Sub
+                        l:
+                          Int: 30
+                        r:
+                          Int: 9
+                    r:
+                      Int: 10
+        "###);
+    }
+
+    #[test]
+    fn mult_add_precedence() {
+        let chars =
+            fs::read_to_string(format!("{TEST_DIR}/mult_add_precedence.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  BinE:
+                    op: Add
+                    l:
+                      BinE:
+                        op: Mult
+                        l:
+                          Int: 9
+                        r:
+                          Int: 10
+                    r:
+                      Int: 11
+        "###);
+    }
+
+    #[test]
+    fn mult_add_precedence_multi() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/mult_add_precedence_multi.c"))
+            .expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  BinE:
+                    op: Add
+                    l:
+                      BinE:
+                        op: Mult
+                        l:
+                          Int: 9
+                        r:
+                          Int: 10
+                    r:
+                      BinE:
+                        op: Mult
+                        l:
+                          Int: 11
+                        r:
+                          Int: 12
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_control_c0 {
+    use crate::lexer;
+    use std::fs;
+    const TEST_DIR: &str = "tests/fixtures/snap/statics-c0/control";
+
+    #[test]
+    fn ifels() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/if.c0")).expect("file dne");
+
+        let tokens = lexer::lex_c0(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - IfEls:
+                  cond:
+                    Bool: true
+                  then:
+                    Block:
+                      - Return:
+                          Int: 9
+                  els:
+                    Block:
+                      - Return:
+                          Int: 10
+        "###);
+    }
+
+    #[test]
+    fn ifnoels() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/if4.c0")).expect("file dne");
+
+        let tokens = lexer::lex_c0(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - IfEls:
+                  cond:
+                    Bool: true
+                  then:
+                    Block:
+                      - Decl:
+                          - alias: x
+                            sc: ~
+                            typ: Int
+                            expr:
+                              Int: 8
+                  els: ~
+              - IfEls:
+                  cond:
+                    Bool: false
+                  then:
+                    Block:
+                      - Return:
+                          Int: 9
+                  els: ~
+              - IfEls:
+                  cond:
+                    Bool: false
+                  then:
+                    Block:
+                      - Return:
+                          Int: 10
+                  els: ~
+              - IfEls:
+                  cond:
+                    Bool: false
+                  then:
+                    Block:
+                      - Return:
+                          Int: 11
+                  els: ~
+              - IfEls:
+                  cond:
+                    Bool: true
+                  then:
+                    Block:
+                      - Return:
+                          Int: 12
+                  els: ~
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_control_c89 {
+    use crate::diagnostic;
+    use crate::lexer;
+    use std::fs;
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/control";
+
+    #[test]
+    fn eq() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/eq_true.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  RelE:
+                    op: Eq
+                    l:
+                      Int: 9
+                    r:
+                      Int: 9
+        "###);
+    }
+
+    #[test]
+    fn neq() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/neq_true.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  RelE:
+                    op: Neq
+                    l:
+                      Int: 9
+                    r:
+                      Int: 10
+        "###);
+    }
+
+    #[test]
+    fn and() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/and_true.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -512,21 +2677,25 @@ mod test_arith {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
               - Return:
-                  Int: 8
+                  LogE:
+                    op: And
+                    l:
+                      Int: 1
+                    r:
+                      Int: 1
         "###);
     }
 
     #[test]
-    fn add() {
-        let chars = fs::read(format!("{TEST_DIR}/add.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn or() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/or_true.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -534,26 +2703,25 @@ mod test_arith {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
               - Return:
-                  BinE:
-                    op: Add
+                  LogE:
+                    op: Or
                     l:
-                      Int: 9
+                      Int: 1
                     r:
-                      Int: 10
+                      Int: 1
         "###);
     }
 
     #[test]
-    fn add_multi() {
-        let chars = fs::read(format!("{TEST_DIR}/add_multi.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn lt() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/lt_true.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -561,31 +2729,25 @@ mod test_arith {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
               - Return:
-                  BinE:
-                    op: Add
+                  RelE:
+                    op: Lt
                     l:
-                      BinE:
-                        op: Add
-                        l:
-                          Int: 9
-                        r:
-                          Int: 10
+                      Int: 9
                     r:
-                      Int: 11
+                      Int: 10
         "###);
     }
 
     #[test]
-    fn sub() {
-        let chars = fs::read(format!("{TEST_DIR}/sub.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn gt() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/gt_true.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -593,26 +2755,25 @@ mod test_arith {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
               - Return:
-                  BinE:
-                    op: Sub
+                  RelE:
+                    op: Gt
                     l:
-                      Int: 88
+                      Int: 10
                     r:
-                      Int: 32
+                      Int: 9
         "###);
     }
 
     #[test]
-    fn mult() {
-        let chars = fs::read(format!("{TEST_DIR}/mult.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn ifels_then() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/ifels_then.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -620,26 +2781,85 @@ mod test_arith {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
-              - Return:
-                  BinE:
-                    op: Mult
-                    l:
-                      Int: 9
-                    r:
-                      Int: 10
+              - IfEls:
+                  cond:
+                    Int: 1
+                  then:
+                    Block:
+                      - Return:
+                          Int: 9
+                  els:
+                    Block:
+                      - Return:
+                          Int: 10
         "###);
     }
 
+    // #[test]
+    // fn for_loop() {
+    //     let chars = fs::read(format!("{TEST_DIR}/for.c"))
+    //         .expect("file dne")
+    //         .iter()
+    //         .map(|b| *b as char)
+    //         .collect::<Vec<_>>();
+
+    //     let tokens = lexer::lex(&chars).unwrap();
+    //     let tree = super::parse_prg(&tokens).unwrap();
+    //     insta::assert_yaml_snapshot!(tree, @r###"
+    //     ---
+    //     main_function:
+    //       stmts:
+    //         - Asnmt:
+    //             CreateBind:
+    //               id: n
+    //               expr:
+    //                 Int: 0
+    //         - For:
+    //             asnmt:
+    //               CreateBind:
+    //                 id: i
+    //                 expr:
+    //                   Int: 0
+    //             cond:
+    //               RelE:
+    //                 op: Lt
+    //                 l:
+    //                   Var: i
+    //                 r:
+    //                   Int: 10
+    //             update:
+    //               UpdateBind:
+    //                 id: i
+    //                 op: Add
+    //                 expr:
+    //                   Int: 1
+    //             body:
+    //               - Asnmt:
+    //                   UpdateBind:
+    //                     id: n
+    //                     op: Add
+    //                     expr:
+    //                       Int: 1
+    //               - Asnmt:
+    //                   UpdateBind:
+    //                     id: n
+    //                     op: Add
+    //                     expr:
+    //                       Int: 1
+    //         - Return:
+    //             Var: n
+    //     "###);
+    // }
+
     #[test]
-    fn div() {
-        let chars = fs::read(format!("{TEST_DIR}/div.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn block() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/block.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -647,26 +2867,55 @@ mod test_arith {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
+              - Decl:
+                  - alias: x
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 1
+              - Block:
+                  - Decl:
+                      - alias: x
+                        sc: ~
+                        typ: Int
+                        expr:
+                          Int: 2
+                  - Empty
+                  - Block:
+                      - Decl:
+                          - alias: y
+                            sc: ~
+                            typ: Int
+                            expr:
+                              Int: 3
+                  - IfEls:
+                      cond:
+                        VarApp: x
+                      then:
+                        IfEls:
+                          cond:
+                            Int: 0
+                          then:
+                            Return:
+                              Int: 4
+                          els:
+                            Return:
+                              VarApp: x
+                      els: ~
               - Return:
-                  BinE:
-                    op: Div
-                    l:
-                      Int: 100
-                    r:
-                      Int: 9
+                  VarApp: x
         "###);
     }
 
     #[test]
-    fn add_associative() {
-        let chars = fs::read(format!("{TEST_DIR}/add_associative.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn dowhile() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/dowhile.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -674,233 +2923,377 @@ mod test_arith {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
+              - DoWhile:
+                  body:
+                    Block:
+                      - Continue
+                      - Return:
+                          Int: 1
+                  cond:
+                    Int: 0
+              - For:
+                  init: ~
+                  cond: ~
+                  update: ~
+                  body: Break
               - Return:
-                  BinE:
-                    op: Add
-                    l:
-                      BinE:
-                        op: Add
-                        l:
-                          Int: 9
-                        r:
-                          Int: 10
-                    r:
-                      Int: 11
+                  Int: 7
         "###);
     }
 
     #[test]
-    fn sub_associative() {
-        let chars = fs::read(format!("{TEST_DIR}/sub_associative.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn switch() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/switch.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
         insta::assert_yaml_snapshot!(tree, @r###"
         ---
+        - FuncDef:
+            alias: pick
+            sc: ~
+            typ: Int
+            fps:
+              - alias: n
+                typ: Int
+            variadic: false
+            proto: true
+            body:
+              - Switch:
+                  cond:
+                    VarApp: n
+                  body:
+                    Block:
+                      - Case:
+                          - 0
+                          - Return:
+                              Int: 10
+                      - Case:
+                          - 1
+                          - Case:
+                              - 2
+                              - Switch:
+                                  cond:
+                                    VarApp: n
+                                  body:
+                                    Block:
+                                      - Case:
+                                          - 1
+                                          - Break
+                                      - Default:
+                                          Return:
+                                            Int: 20
+                      - Return:
+                          Int: 30
+                      - Default: Break
+              - Return:
+                  Int: 40
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
               - Return:
                   BinE:
-                    op: Sub
+                    op: Add
                     l:
                       BinE:
-                        op: Sub
+                        op: Add
                         l:
-                          Int: 30
+                          BinE:
+                            op: Add
+                            l:
+                              FuncApp:
+                                f:
+                                  VarApp: pick
+                                aps:
+                                  - Int: 0
+                            r:
+                              FuncApp:
+                                f:
+                                  VarApp: pick
+                                aps:
+                                  - Int: 1
                         r:
-                          Int: 9
+                          FuncApp:
+                            f:
+                              VarApp: pick
+                            aps:
+                              - Int: 2
                     r:
-                      Int: 10
+                      FuncApp:
+                        f:
+                          VarApp: pick
+                        aps:
+                          - Int: 5
         "###);
     }
 
     #[test]
-    fn mult_add_precedence() {
-        let chars = fs::read(format!("{TEST_DIR}/mult_add_precedence.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn goto() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/goto.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
         insta::assert_yaml_snapshot!(tree, @r###"
         ---
+        - FuncDef:
+            alias: one
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  Int: 1
+        - FuncDef:
+            alias: skip
+            sc: ~
+            typ: Void
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Goto: out
+              - Expr:
+                  FuncApp:
+                    f:
+                      VarApp: one
+                    aps: []
+              - Labeled:
+                  - out
+                  - Return: ~
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
+              - Expr:
+                  FuncApp:
+                    f:
+                      VarApp: skip
+                    aps: []
+              - Goto: end
               - Return:
-                  BinE:
-                    op: Add
-                    l:
-                      BinE:
-                        op: Mult
-                        l:
-                          Int: 9
-                        r:
-                          Int: 10
-                    r:
-                      Int: 11
+                  FuncApp:
+                    f:
+                      VarApp: one
+                    aps: []
+              - Labeled:
+                  - end
+                  - Block:
+                      - Labeled:
+                          - inner
+                          - Return:
+                              Int: 5
         "###);
     }
 
     #[test]
-    fn mult_add_precedence_multi() {
-        let chars = fs::read(format!("{TEST_DIR}/mult_add_precedence_multi.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn jump_errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/jump_errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| {
+            let tokens = lexer::lex(l)?;
+            super::parse_prg(&tokens)
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "int f() { a: a: return 0; }: E0003 1:14 duplicate label 'a'"
+        - "int f() { goto b; }: E0003 1:16 label 'b' used but not defined"
+        - "int f() { for (int i = 0; i; ) ; }: E0003 1:16 'for' loop initial declarations are only allowed in C99 mode"
+        - "int f() { int x; switch (x) { case x: ; } }: E0003 1:36 case label does not reduce to an integer constant"
+        - "int f() { case 1: ; }: E0003 1:11 case label not within a switch statement"
+        - "int f() { switch (1) { case 1: case 1: ; } }: E0003 1:32 duplicate case value"
+        - "int f() { default: ; }: E0003 1:11 'default' label not within a switch statement"
+        - "int f() { switch (1) { default: default: ; } }: E0003 1:33 multiple default labels in one switch"
+        - "int f() { break; }: E0003 1:11 break statement not within loop or switch"
+        - "int f() { switch (1) { continue; } }: E0003 1:24 continue statement not within a loop"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_bindings {
+    use crate::lexer;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/bindings";
+
+    #[test]
+    fn composition() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/composition.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
         insta::assert_yaml_snapshot!(tree, @r###"
         ---
         - FuncDef:
-            alias: main
+            alias: h
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  Int: 11
+        - FuncDef:
+            alias: g
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
               - Return:
                   BinE:
                     op: Add
                     l:
-                      BinE:
-                        op: Mult
-                        l:
-                          Int: 9
-                        r:
-                          Int: 10
+                      Int: 10
                     r:
-                      BinE:
-                        op: Mult
-                        l:
-                          Int: 11
-                        r:
-                          Int: 12
+                      FuncApp:
+                        f:
+                          VarApp: h
+                        aps: []
+        - FuncDef:
+            alias: f
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  BinE:
+                    op: Add
+                    l:
+                      Int: 9
+                    r:
+                      FuncApp:
+                        f:
+                          VarApp: g
+                        aps: []
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  FuncApp:
+                    f:
+                      VarApp: f
+                    aps: []
         "###);
-    }
-}
-
-#[cfg(test)]
-mod test_control_c0 {
-    use crate::lexer;
-    use std::fs;
-    const TEST_DIR: &str = "tests/fixtures/snap/statics-c0/control";
+    }
 
     #[test]
-    fn ifels() {
-        let chars = fs::read(format!("{TEST_DIR}/if.c0"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn formal_param() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/formal_param.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
         insta::assert_yaml_snapshot!(tree, @r###"
         ---
+        - FuncDef:
+            alias: f
+            sc: ~
+            typ: Int
+            fps:
+              - alias: x
+                typ: Int
+            variadic: false
+            proto: true
+            body:
+              - Return:
+                  BinE:
+                    op: Add
+                    l:
+                      VarApp: x
+                    r:
+                      Int: 10
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
-              - IfEls:
-                  cond:
-                    Bool: true
-                  then:
-                    Return:
-                      Int: 9
-                  els:
-                    Return:
-                      Int: 10
+              - Return:
+                  FuncApp:
+                    f:
+                      VarApp: f
+                    aps:
+                      - Int: 9
         "###);
     }
 
     #[test]
-    fn ifnoels() {
-        let chars = fs::read(format!("{TEST_DIR}/if4.c0"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn formal_param_multi() {
+        let chars =
+            fs::read_to_string(format!("{TEST_DIR}/formal_param_multi.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
         insta::assert_yaml_snapshot!(tree, @r###"
         ---
+        - FuncDef:
+            alias: f
+            sc: ~
+            typ: Int
+            fps:
+              - alias: x
+                typ: Int
+              - alias: y
+                typ: Int
+            variadic: false
+            proto: true
+            body:
+              - Return:
+                  BinE:
+                    op: Add
+                    l:
+                      VarApp: x
+                    r:
+                      VarApp: y
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
-              - IfEls:
-                  cond:
-                    Bool: true
-                  then:
-                    Asnmt:
-                      alias: x
-                      typ: Int
-                      expr:
-                        Int: 8
-                  els: ~
-              - IfEls:
-                  cond:
-                    Bool: false
-                  then:
-                    Return:
-                      Int: 9
-                  els: ~
-              - IfEls:
-                  cond:
-                    Bool: false
-                  then:
-                    Return:
-                      Int: 10
-                  els: ~
-              - IfEls:
-                  cond:
-                    Bool: false
-                  then:
-                    Return:
-                      Int: 11
-                  els: ~
-              - IfEls:
-                  cond:
-                    Bool: true
-                  then:
-                    Return:
-                      Int: 12
-                  els: ~
+              - Return:
+                  FuncApp:
+                    f:
+                      VarApp: f
+                    aps:
+                      - Int: 9
+                      - Int: 10
         "###);
     }
-}
-
-#[cfg(test)]
-mod test_control_c89 {
-    use crate::lexer;
-    use std::fs;
-    const TEST_DIR: &str = "tests/fixtures/snap/shared/control";
 
     #[test]
-    fn eq() {
-        let chars = fs::read(format!("{TEST_DIR}/eq_true.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn asnmt() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/assignment.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -908,26 +3301,26 @@ mod test_control_c89 {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
-              - Return:
-                  RelE:
-                    op: Eq
-                    l:
-                      Int: 9
-                    r:
+              - Decl:
+                  - alias: x
+                    sc: ~
+                    typ: Int
+                    expr:
                       Int: 9
+              - Return:
+                  VarApp: x
         "###);
     }
 
     #[test]
-    fn neq() {
-        let chars = fs::read(format!("{TEST_DIR}/neq_true.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn asnmt_update() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/asnmt_update.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -935,26 +3328,33 @@ mod test_control_c89 {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
-              - Return:
-                  RelE:
-                    op: Neq
+              - Decl:
+                  - alias: n
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 0
+              - Expr:
+                  Asnmt:
+                    op: Add
                     l:
-                      Int: 9
+                      VarApp: n
                     r:
                       Int: 10
+              - Return:
+                  VarApp: n
         "###);
     }
 
     #[test]
-    fn and() {
-        let chars = fs::read(format!("{TEST_DIR}/and_true.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn asnmt_update_inc() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/asnmt_update_inc.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -962,26 +3362,109 @@ mod test_control_c89 {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
-              - Return:
-                  RelE:
-                    op: And
+              - Decl:
+                  - alias: n
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 0
+              - Expr:
+                  Update:
+                    op: PostInc
                     l:
-                      Int: 1
-                    r:
-                      Int: 1
+                      VarApp: n
+              - Expr:
+                  Update:
+                    op: PostInc
+                    l:
+                      VarApp: n
+              - Expr:
+                  Update:
+                    op: PostInc
+                    l:
+                      VarApp: n
+              - Return:
+                  Int: 3
         "###);
     }
+}
+
+#[cfg(test)]
+mod test_errors {
+    use crate::diagnostic;
+    use crate::lexer::{self, LexOpts};
+    use crate::source::SourceMap;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/lexical";
 
     #[test]
-    fn or() {
-        let chars = fs::read(format!("{TEST_DIR}/or_true.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn id() {
+        let src = fs::read_to_string(format!("{TEST_DIR}/id.c")).expect("file dne");
+        let mut sm = SourceMap::new();
+        let file = sm.add("id.c", &src);
+
+        let tokens = lexer::lex_file(&src, file, LexOpts::default()).unwrap();
+        let err = super::parse_stmt(&mut super::Parser::new(), &tokens[5..]).unwrap_err();
+        insta::assert_snapshot!(diagnostic::render(&err, &sm), @r###"
+        error[E0003]: invalid suffix "foo" on integer constant
+         --> id.c:2:12
+          |
+        2 |     return 1foo;
+          |            ^^^^
+        "###);
+    }
+
+    #[test]
+    fn recovery() {
+        let src = fs::read_to_string(format!("{TEST_DIR}/recovery.c")).expect("file dne");
+        let mut sm = SourceMap::new();
+        let file = sm.add("recovery.c", &src);
+
+        let tokens = lexer::lex_file(&src, file, LexOpts::default()).unwrap();
+        let err = super::parse_prg(&tokens).unwrap_err();
+        insta::assert_snapshot!(diagnostic::render(&err, &sm), @r###"
+        error[E0003]: expected: expression got: PuncSemiColon `;`
+         --> recovery.c:2:16
+          |
+        2 |     int x = 1 +;
+          |                ^
+        error[E0003]: expected: PuncRightParen got: PuncLeftBrace `{`
+         --> recovery.c:7:11
+          |
+        7 |     if (x {
+          |           ^
+        error[E0003]: expected: PuncSemiColon got: LiteralInt `4`
+         --> recovery.c:11:14
+           |
+        11 |     return 3 4;
+           |              ^
+        error[E0003]: expected: Alias got: Equals `=`
+         --> recovery.c:18:7
+           |
+        18 | float = 1;
+           |       ^
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_literals {
+    use crate::diagnostic;
+    use crate::lexer;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/literals";
+
+    #[test]
+    fn ints() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/ints.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -989,26 +3472,74 @@ mod test_control_c89 {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
+              - Decl:
+                  - alias: a
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 31
+              - Decl:
+                  - alias: b
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 15
+              - Decl:
+                  - alias: c
+                    sc: ~
+                    typ: Int
+                    expr:
+                      UInt: 10
+              - Decl:
+                  - alias: d
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Long: 10
+              - Decl:
+                  - alias: e
+                    sc: ~
+                    typ: Int
+                    expr:
+                      ULong: 10
+              - Decl:
+                  - alias: f
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 2147483647
+              - Decl:
+                  - alias: g
+                    sc: ~
+                    typ: Int
+                    expr:
+                      ULong: 2147483648
+              - Decl:
+                  - alias: h
+                    sc: ~
+                    typ: Int
+                    expr:
+                      UInt: 2147483648
+              - Decl:
+                  - alias: i
+                    sc: ~
+                    typ: Int
+                    expr:
+                      UInt: 4294967295
               - Return:
-                  RelE:
-                    op: Or
-                    l:
-                      Int: 1
-                    r:
-                      Int: 1
+                  Int: 0
         "###);
     }
 
     #[test]
-    fn lt() {
-        let chars = fs::read(format!("{TEST_DIR}/lt_true.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn chars() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/chars.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -1016,26 +3547,69 @@ mod test_control_c89 {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
-              - Return:
-                  RelE:
-                    op: Lt
-                    l:
-                      Int: 9
-                    r:
+              - Decl:
+                  - alias: a
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 97
+              - Decl:
+                  - alias: b
+                    sc: ~
+                    typ: Int
+                    expr:
                       Int: 10
+              - Decl:
+                  - alias: c
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 65
+              - Decl:
+                  - alias: d
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 65
+              - Decl:
+                  - alias: e
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 0
+              - Decl:
+                  - alias: f
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 39
+              - Decl:
+                  - alias: g
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 255
+              - Decl:
+                  - alias: h
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 34
+              - Return:
+                  VarApp: a
         "###);
     }
 
     #[test]
-    fn gt() {
-        let chars = fs::read(format!("{TEST_DIR}/gt_true.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn floats() {
+        const TEST_DIR: &str = "tests/fixtures/snap/shared/floats";
+        let chars = fs::read_to_string(format!("{TEST_DIR}/lit.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
@@ -1043,306 +3617,1516 @@ mod test_control_c89 {
         ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
+              - Decl:
+                  - alias: a
+                    sc: ~
+                    typ: Double
+                    expr:
+                      Double: 1
+              - Decl:
+                  - alias: b
+                    sc: ~
+                    typ: Double
+                    expr:
+                      Double: 0.5
+              - Decl:
+                  - alias: c
+                    sc: ~
+                    typ: Double
+                    expr:
+                      Double: 1000
+              - Decl:
+                  - alias: d
+                    sc: ~
+                    typ: Float
+                    expr:
+                      Float: 0.0015
+              - Decl:
+                  - alias: e
+                    sc: ~
+                    typ: LongDouble
+                    expr:
+                      LongDouble: 2
+              - Decl:
+                  - alias: f
+                    sc: ~
+                    typ: Double
+                    expr:
+                      Double: 0.1
               - Return:
-                  RelE:
-                    op: Gt
-                    l:
-                      Int: 10
-                    r:
-                      Int: 9
+                  Int: 0
         "###);
     }
 
     #[test]
-    fn ifels_then() {
-        let chars = fs::read(format!("{TEST_DIR}/ifels_then.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn strings() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/strings.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let (expr, _) = super::parse_expr(&mut super::Parser::new(), &tokens[8..]).unwrap();
+        let crate::SExprKind::Str(bytes) = expr.kind else {
+            panic!("expected a string literal, got {:?}", expr.kind);
+        };
+        insta::assert_snapshot!(bytes.escape_ascii().to_string(), @r###"
+        hello, world\ntab\there \"quoted\" AA\x00
+        "###);
+    }
+
+    #[test]
+    fn errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| {
+            let tokens = lexer::lex(l)?;
+            super::parse_expr(&mut super::Parser::new(), &tokens).map(|(e, _)| e)
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "1foo: E0003 1:1 invalid suffix \"foo\" on integer constant"
+        - "08: E0003 1:1 invalid digit \"8\" in octal constant"
+        - "0x: E0003 1:1 invalid suffix \"x\" on integer constant"
+        - "0xfu8: E0003 1:1 invalid suffix \"u8\" on integer constant"
+        - "4294967296: E0003 1:1 integer constant is too large for its type"
+        - "99999999999999999999999: E0003 1:1 integer constant is too large for its type"
+        - "'': E0003 1:1 empty character constant"
+        - "'ab': E0003 1:1 multi-character character constant"
+        - "'\\q': E0003 1:1 unknown escape sequence: '\\q'"
+        - "'\\x100': E0003 1:1 hex escape sequence out of range"
+        - "'\\777': E0003 1:1 octal escape sequence out of range"
+        - "\"\\x\": E0003 1:1 \\x used with no following hex digits"
+        - "1e: E0003 1:1 exponent has no digits"
+        - "1.5x: E0003 1:1 invalid suffix \"x\" on floating constant"
+        - "1e99999: E0003 1:1 floating constant exceeds range of double"
+        - "1e39f: E0003 1:1 floating constant exceeds range of float"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_decls {
+    use crate::diagnostic;
+    use crate::lexer;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/decls";
+
+    #[test]
+    fn specifiers() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/specifiers.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
         insta::assert_yaml_snapshot!(tree, @r###"
         ---
+        - VarDef:
+            alias: errno
+            sc: Extern
+            typ: Int
+            expr: ~
+        - VarDef:
+            alias: c
+            sc: Static
+            typ:
+              Const: Char
+            expr:
+              Int: 97
+        - VarDef:
+            alias: ul
+            sc: ~
+            typ: ULong
+            expr: ~
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
-              - IfEls:
-                  cond:
-                    Int: 1
-                  then:
-                    Return:
-                      Int: 9
-                  els:
-                    Return:
-                      Int: 10
+              - Decl:
+                  - alias: s
+                    sc: Register
+                    typ: Short
+                    expr: ~
+              - Decl:
+                  - alias: lu
+                    sc: Auto
+                    typ: ULong
+                    expr: ~
+              - Decl:
+                  - alias: sc
+                    sc: ~
+                    typ: SChar
+                    expr: ~
+              - Decl:
+                  - alias: u
+                    sc: ~
+                    typ:
+                      Const:
+                        Volatile: UInt
+                    expr:
+                      Int: 1
+              - Decl:
+                  - alias: ld
+                    sc: ~
+                    typ: LongDouble
+                    expr: ~
+              - Decl:
+                  - alias: x
+                    sc: Static
+                    typ: Int
+                    expr: ~
+              - Decl:
+                  - alias: i
+                    sc: ~
+                    typ: Int
+                    expr: ~
+                  - alias: j
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 2
+              - Return:
+                  Int: 0
         "###);
     }
 
-    // #[test]
-    // fn for_loop() {
-    //     let chars = fs::read(format!("{TEST_DIR}/for.c"))
-    //         .expect("file dne")
-    //         .iter()
-    //         .map(|b| *b as char)
-    //         .collect::<Vec<_>>();
-
-    //     let tokens = lexer::lex(&chars).unwrap();
-    //     let tree = super::parse_prg(&tokens).unwrap();
-    //     insta::assert_yaml_snapshot!(tree, @r###"
-    //     ---
-    //     main_function:
-    //       stmts:
-    //         - Asnmt:
-    //             CreateBind:
-    //               id: n
-    //               expr:
-    //                 Int: 0
-    //         - For:
-    //             asnmt:
-    //               CreateBind:
-    //                 id: i
-    //                 expr:
-    //                   Int: 0
-    //             cond:
-    //               RelE:
-    //                 op: Lt
-    //                 l:
-    //                   Var: i
-    //                 r:
-    //                   Int: 10
-    //             update:
-    //               UpdateBind:
-    //                 id: i
-    //                 op: Add
-    //                 expr:
-    //                   Int: 1
-    //             body:
-    //               - Asnmt:
-    //                   UpdateBind:
-    //                     id: n
-    //                     op: Add
-    //                     expr:
-    //                       Int: 1
-    //               - Asnmt:
-    //                   UpdateBind:
-    //                     id: n
-    //                     op: Add
-    //                     expr:
-    //                       Int: 1
-    //         - Return:
-    //             Var: n
-    //     "###);
-    // }
-}
-
-#[cfg(test)]
-mod test_bindings {
-    use crate::lexer;
-    use std::fs;
+    #[test]
+    fn declarators() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/declarators.c")).expect("file dne");
 
-    const TEST_DIR: &str = "tests/fixtures/snap/shared/bindings";
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
+        - VarDef:
+            alias: p
+            sc: ~
+            typ:
+              Pointer: Int
+            expr: ~
+        - VarDef:
+            alias: pp
+            sc: ~
+            typ:
+              Pointer:
+                Pointer: Int
+            expr: ~
+        - VarDef:
+            alias: a
+            sc: ~
+            typ:
+              Array:
+                - Int
+                - 3
+            expr: ~
+        - VarDef:
+            alias: pa
+            sc: ~
+            typ:
+              Pointer:
+                Array:
+                  - Int
+                  - 5
+            expr: ~
+        - VarDef:
+            alias: m
+            sc: ~
+            typ:
+              Array:
+                - Array:
+                    - Int
+                    - 3
+                - 2
+            expr: ~
+        - VarDef:
+            alias: s
+            sc: ~
+            typ:
+              Volatile:
+                Pointer:
+                  Const: Char
+            expr: ~
+        - VarDef:
+            alias: cs
+            sc: ~
+            typ:
+              Pointer:
+                Const:
+                  Pointer:
+                    Const: Char
+            expr: ~
+        - VarDef:
+            alias: fp
+            sc: ~
+            typ:
+              Pointer:
+                Func:
+                  fp:
+                    - Int
+                    - Pointer: Char
+                  body: Int
+                  variadic: false
+                  proto: true
+            expr: ~
+        - VarDef:
+            alias: signal
+            sc: ~
+            typ:
+              Func:
+                fp:
+                  - Int
+                  - Pointer:
+                      Func:
+                        fp:
+                          - Int
+                        body: Void
+                        variadic: false
+                        proto: true
+                body:
+                  Pointer:
+                    Func:
+                      fp:
+                        - Int
+                      body: Void
+                      variadic: false
+                      proto: true
+                variadic: false
+                proto: true
+            expr: ~
+        - VarDef:
+            alias: f
+            sc: ~
+            typ:
+              Func:
+                fp: []
+                body: Int
+                variadic: false
+                proto: true
+            expr: ~
+        - VarDef:
+            alias: g
+            sc: ~
+            typ:
+              Func:
+                fp: []
+                body: Int
+                variadic: false
+                proto: false
+            expr: ~
+        - VarDef:
+            alias: h
+            sc: ~
+            typ:
+              Func:
+                fp:
+                  - Pointer: Int
+                  - Pointer:
+                      Array:
+                        - Int
+                        - 3
+                  - Pointer:
+                      Func:
+                        fp: []
+                        body: Int
+                        variadic: false
+                        proto: true
+                body: Int
+                variadic: false
+                proto: true
+            expr: ~
+        - VarDef:
+            alias: printf
+            sc: ~
+            typ:
+              Func:
+                fp:
+                  - Pointer:
+                      Const: Char
+                body: Int
+                variadic: true
+                proto: true
+            expr: ~
+        "###);
+    }
 
     #[test]
-    fn composition() {
-        let chars = fs::read(format!("{TEST_DIR}/composition.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn knr() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/knr.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
         insta::assert_yaml_snapshot!(tree, @r###"
         ---
         - FuncDef:
-            alias: h
-            typ: Int
-            fps: []
-            body:
-              - Return:
-                  Int: 11
-        - FuncDef:
-            alias: g
+            alias: add
+            sc: ~
             typ: Int
-            fps: []
+            fps:
+              - alias: a
+                typ: Int
+              - alias: b
+                typ:
+                  Pointer: Char
+              - alias: c
+                typ:
+                  Pointer: Char
+            variadic: false
+            proto: false
             body:
               - Return:
-                  BinE:
-                    op: Add
-                    l:
-                      Int: 10
-                    r:
-                      FuncApp:
-                        alias: h
-                        aps: []
+                  VarApp: a
         - FuncDef:
-            alias: f
+            alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
               - Return:
-                  BinE:
-                    op: Add
-                    l:
-                      Int: 9
-                    r:
-                      FuncApp:
-                        alias: g
-                        aps: []
+                  Int: 0
+        "###);
+    }
+
+    #[test]
+    fn typenames() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/typenames.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        insta::assert_yaml_snapshot!(tree, @r###"
+        ---
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
+              - Decl:
+                  - alias: n
+                    sc: ~
+                    typ: Int
+                    expr:
+                      SizeOfT: Int
+              - Decl:
+                  - alias: m
+                    sc: ~
+                    typ: ULong
+                    expr:
+                      SizeOfT:
+                        Array:
+                          - Pointer: Char
+                          - 4
+              - Decl:
+                  - alias: d
+                    sc: ~
+                    typ: Double
+                    expr:
+                      Cast:
+                        typ: Double
+                        l:
+                          VarApp: n
+              - Decl:
+                  - alias: k
+                    sc: ~
+                    typ: Int
+                    expr:
+                      BinE:
+                        op: Add
+                        l:
+                          SizeOfE:
+                            VarApp: n
+                        r:
+                          SizeOfT:
+                            Pointer:
+                              Func:
+                                fp: []
+                                body: Int
+                                variadic: false
+                                proto: true
               - Return:
-                  FuncApp:
-                    alias: f
-                    aps: []
+                  Cast:
+                    typ: Int
+                    l:
+                      BinE:
+                        op: Add
+                        l:
+                          VarApp: d
+                        r:
+                          Double: 1.5
         "###);
     }
 
     #[test]
-    fn formal_param() {
-        let chars = fs::read(format!("{TEST_DIR}/formal_param.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| {
+            let tokens = lexer::lex(l)?;
+            super::parse_external(&mut super::Parser::new(), &tokens).map(|(ds, _)| ds)
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "int char x;: E0003 1:1 two or more data types in declaration specifiers"
+        - "unsigned float x;: E0003 1:1 two or more data types in declaration specifiers"
+        - "static extern int x;: E0003 1:8 multiple storage classes in declaration specifiers"
+        - "const const int x;: E0003 1:7 duplicate 'const'"
+        - "int *const const p;: E0003 1:12 duplicate 'const'"
+        - "auto int x;: E0003 1:1 file-scope declaration specifies 'auto'"
+        - "int f()[3];: E0003 1:6 function cannot return an array"
+        - "int f()();: E0003 1:6 function cannot return a function"
+        - "int a[2]();: E0003 1:6 array of functions is not allowed"
+        - "void v[3];: E0003 1:7 array type has incomplete element type"
+        - "int m[3][];: E0003 1:6 array type has incomplete element type"
+        - "int a[0];: E0003 1:7 size of array is not positive"
+        - "int a[4 - 5];: E0003 1:7 size of array is not positive"
+        - "int a[n];: E0003 1:7 size of array is not an integer constant"
+        - "int f(void, int);: E0003 1:7 'void' must be the only parameter"
+        - "int f(static int x);: E0003 1:7 storage class 'static' specified for parameter"
+        - "int f(int) { return 0; }: E0003 1:7 parameter name omitted"
+        - "int f(a) int b; { return 0; }: E0003 1:14 declaration for parameter 'b' but no such parameter"
+        - "int f(void) = 0;: E0003 1:5 function 'f' is initialized like a variable"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_records {
+    use crate::diagnostic;
+    use crate::lexer;
+    use std::fs;
+    use std::io;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/structs";
+
+    #[test]
+    fn layout() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/layout.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = super::parse_prg(&tokens).unwrap();
+        let records = (0..7).map(|id| tree.records.get(id)).collect::<Vec<_>>();
+        insta::assert_yaml_snapshot!((&tree, records), @r###"
+        ---
+        - - VarDef:
+              alias: origin
+              sc: ~
+              typ:
+                Struct:
+                  - ~
+                  - 6
+              expr: ~
+          - VarDef:
+              alias: squares
+              sc: ~
+              typ:
+                Array:
+                  - Int
+                  - 22
+              expr: ~
+        - - members:
+              - alias: c
+                typ: Char
+                offset: 0
+              - alias: i
+                typ: Int
+                offset: 4
+            size: 8
+            align: 4
+          - members:
+              - alias: a
+                typ: Char
+                offset: 0
+              - alias: d
+                typ: Double
+                offset: 8
+              - alias: b
+                typ: Char
+                offset: 16
+            size: 24
+            align: 8
+          - members:
+              - alias: a
+                typ: Char
+                offset: 0
+              - alias: s
+                typ: Short
+                offset: 2
+              - alias: b
+                typ: Char
+                offset: 4
+            size: 6
+            align: 2
+          - members:
+              - alias: bytes
+                typ:
+                  Array:
+                    - Char
+                    - 5
+                offset: 0
+              - alias: i
+                typ: Int
+                offset: 0
+            size: 8
+            align: 4
+          - members:
+              - alias: h
+                typ:
+                  Struct:
+                    - halves
+                    - 2
+                offset: 0
+              - alias: c
+                typ: Char
+                offset: 6
+              - alias: w
+                typ:
+                  Union:
+                    - word
+                    - 3
+                offset: 8
+            size: 16
+            align: 4
+          - members:
+              - alias: v
+                typ: Int
+                offset: 0
+              - alias: next
+                typ:
+                  Pointer:
+                    Struct:
+                      - node
+                      - 5
+                offset: 4
+            size: 8
+            align: 4
+          - members:
+              - alias: x
+                typ: Int
+                offset: 0
+              - alias: y
+                typ: Int
+                offset: 4
+            size: 8
+            align: 4
+        "###);
+    }
+
+    #[test]
+    fn tags() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/tags.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
         insta::assert_yaml_snapshot!(tree, @r###"
         ---
+        - VarDef:
+            alias: head
+            sc: ~
+            typ:
+              Pointer:
+                Struct:
+                  - list
+                  - 0
+            expr: ~
         - FuncDef:
-            alias: f
+            alias: main
+            sc: ~
             typ: Int
-            fps:
-              - - x
-                - Int
+            fps: []
+            variadic: false
+            proto: false
             body:
+              - Decl: []
+              - Decl: []
+              - Decl:
+                  - alias: l
+                    sc: ~
+                    typ:
+                      Struct:
+                        - list
+                        - 2
+                    expr: ~
+              - Decl:
+                  - alias: a
+                    sc: ~
+                    typ:
+                      Array:
+                        - Int
+                        - 7
+                    expr: ~
               - Return:
                   BinE:
                     op: Add
                     l:
-                      VarApp: x
+                      BinE:
+                        op: Add
+                        l:
+                          SizeOfE:
+                            VarApp: l
+                        r:
+                          SizeOfE:
+                            VarApp: a
                     r:
-                      Int: 10
+                      Int: 180
         - FuncDef:
-            alias: main
+            alias: f
+            sc: ~
             typ: Int
-            fps: []
+            fps:
+              - alias: SOUTH
+                typ: Int
+            variadic: false
+            proto: true
             body:
               - Return:
-                  FuncApp:
-                    alias: f
-                    aps:
-                      - Int: 9
+                  VarApp: SOUTH
         "###);
     }
 
     #[test]
-    fn formal_param_multi() {
-        let chars = fs::read(format!("{TEST_DIR}/formal_param_multi.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+    fn errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| -> Result<(), io::Error> {
+            let tokens = lexer::lex(l)?;
+            let (cx, mut r) = (&mut super::Parser::new(), &tokens[..]);
+            loop {
+                (_, r) = super::parse_external(cx, r)?;
+            }
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "struct s { int x; int x; };: E0003 1:23 duplicate member 'x'"
+        - "struct s { int a : 3; };: E0003 1:18 bit-fields are not supported"
+        - "struct s { void v; };: E0003 1:17 field 'v' has incomplete type"
+        - "struct s { int f(void); };: E0003 1:16 field 'f' declared as a function"
+        - "struct s { struct s inner; };: E0003 1:21 field 'inner' has incomplete type"
+        - "struct s { static int x; };: E0003 1:12 storage class 'static' specified for member"
+        - "struct s { };: E0003 1:10 struct has no members"
+        - "struct s { int x; }; struct s { int y; };: E0003 1:29 redefinition of 'struct s'"
+        - "struct s { int x; }; union s u;: E0003 1:28 's' defined as wrong kind of tag"
+        - "enum e { A, B }; enum e { C };: E0003 1:23 redefinition of 'enum e'"
+        - "enum e x;: E0003 1:6 use of enum 'e' without previous declaration"
+        - "enum { A, A };: E0007 1:11 redeclaration of 'A'"
+        - "enum { A = 1.5 };: E0003 1:12 enumerator value for 'A' is not an integer constant"
+        - "enum { A = 2147483647, B };: E0003 1:24 overflow in enumeration values"
+        - "int A; enum { A };: E0007 1:15 redeclaration of 'A'"
+        - "struct s; union s *p;: E0003 1:17 's' defined as wrong kind of tag"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_typedefs {
+    use crate::diagnostic;
+    use crate::lexer;
+    use crate::preprocessor::{self, PpOpts};
+    use crate::source::SourceMap;
+    use std::fs;
+    use std::io;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/typedefs";
+
+    #[test]
+    fn typedefs() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/typedefs.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = super::parse_prg(&tokens).unwrap();
         insta::assert_yaml_snapshot!(tree, @r###"
         ---
+        - VarDef:
+            alias: add
+            sc: ~
+            typ:
+              Func:
+                fp:
+                  - Int
+                  - Int
+                body: Int
+                variadic: false
+                proto: true
+            expr: ~
         - FuncDef:
-            alias: f
+            alias: apply
+            sc: ~
             typ: Int
             fps:
-              - - x
-                - Int
-              - - y
-                - Int
+              - alias: f
+                typ:
+                  Pointer:
+                    Func:
+                      fp:
+                        - Int
+                        - Int
+                      body: Int
+                      variadic: false
+                      proto: true
+              - alias: p
+                typ:
+                  Struct:
+                    - point
+                    - 0
+            variadic: false
+            proto: true
             body:
               - Return:
                   BinE:
                     op: Add
                     l:
-                      VarApp: x
+                      SizeOfE:
+                        VarApp: f
                     r:
-                      VarApp: y
+                      Dot:
+                        l:
+                          VarApp: p
+                        field: x
+        - FuncDef:
+            alias: shadow
+            sc: ~
+            typ: Int
+            fps:
+              - alias: T
+                typ: Int
+            variadic: false
+            proto: true
+            body:
+              - Return:
+                  BinE:
+                    op: Mult
+                    l:
+                      VarApp: T
+                    r:
+                      Int: 2
         - FuncDef:
             alias: main
+            sc: ~
             typ: Int
             fps: []
+            variadic: false
+            proto: false
             body:
+              - Decl:
+                  - alias: p
+                    sc: ~
+                    typ:
+                      Pointer: Int
+                    expr: ~
+              - Decl:
+                  - alias: q
+                    sc: ~
+                    typ:
+                      Pointer: Int
+                    expr: ~
+              - Decl:
+                  - alias: a
+                    sc: ~
+                    typ:
+                      Array:
+                        - Int
+                        - 3
+                    expr: ~
+              - Decl:
+                  - alias: pt
+                    sc: ~
+                    typ:
+                      Struct:
+                        - point
+                        - 0
+                    expr: ~
+              - Decl:
+                  - alias: n
+                    sc: ~
+                    typ:
+                      Array:
+                        - Char
+                        - 16
+                    expr: ~
+              - Decl:
+                  - alias: c
+                    sc: ~
+                    typ:
+                      Const:
+                        Volatile: Int
+                    expr:
+                      Int: 3
+              - Decl:
+                  - alias: big
+                    sc: ~
+                    typ: ULongLong
+                    expr: ~
+              - Decl:
+                  - alias: T
+                    sc: ~
+                    typ: Int
+                    expr:
+                      BinE:
+                        op: Add
+                        l:
+                          BinE:
+                            op: Add
+                            l:
+                              SizeOfE:
+                                VarApp: a
+                            r:
+                              SizeOfE:
+                                VarApp: n
+                        r:
+                          SizeOfE:
+                            VarApp: big
               - Return:
-                  FuncApp:
-                    alias: f
-                    aps:
-                      - Int: 9
-                      - Int: 10
+                  BinE:
+                    op: Mult
+                    l:
+                      VarApp: T
+                    r:
+                      Cast:
+                        typ: Int
+                        l:
+                          SizeOfT:
+                            Struct:
+                              - point
+                              - 0
         "###);
     }
 
     #[test]
-    fn asnmt() {
-        let chars = fs::read(format!("{TEST_DIR}/assignment.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
+    fn egos() {
+        let mut sm = SourceMap::new();
+        let path = "tests/fixtures/smoke/egos2k/library/egos.h";
+        let tokens = preprocessor::preprocess(&mut sm, path, &PpOpts::default()).unwrap();
+
+        let (mut tree, mut r) = (vec![], &tokens[..]);
+        let cx = &mut super::Parser::new();
+        while !r.is_empty() {
+            let (ds, _r) = super::parse_external(cx, r).unwrap();
+            tree.extend(ds);
+            r = _r;
+        }
+        let earth = cx.records.get(0);
+        insta::assert_yaml_snapshot!((tree, earth), @r###"
+        ---
+        - - VarDef:
+              alias: earth
+              sc: Extern
+              typ:
+                Pointer:
+                  Struct:
+                    - earth
+                    - 0
+              expr: ~
+          - VarDef:
+              alias: grass
+              sc: Extern
+              typ:
+                Pointer:
+                  Struct:
+                    - grass
+                    - 1
+              expr: ~
+          - VarDef:
+              alias: my_printf
+              sc: ~
+              typ:
+                Func:
+                  fp:
+                    - Pointer:
+                        Const: Char
+                  body: Int
+                  variadic: true
+                  proto: true
+              expr: ~
+          - VarDef:
+              alias: INFO
+              sc: ~
+              typ:
+                Func:
+                  fp:
+                    - Pointer:
+                        Const: Char
+                  body: Int
+                  variadic: true
+                  proto: true
+              expr: ~
+          - VarDef:
+              alias: FATAL
+              sc: ~
+              typ:
+                Func:
+                  fp:
+                    - Pointer:
+                        Const: Char
+                  body: Int
+                  variadic: true
+                  proto: true
+              expr: ~
+          - VarDef:
+              alias: SUCCESS
+              sc: ~
+              typ:
+                Func:
+                  fp:
+                    - Pointer:
+                        Const: Char
+                  body: Int
+                  variadic: true
+                  proto: true
+              expr: ~
+          - VarDef:
+              alias: CRITICAL
+              sc: ~
+              typ:
+                Func:
+                  fp:
+                    - Pointer:
+                        Const: Char
+                  body: Int
+                  variadic: true
+                  proto: true
+              expr: ~
+        - members:
+            - alias: timer_reset
+              typ:
+                Pointer:
+                  Func:
+                    fp:
+                      - UInt
+                    body: Void
+                    variadic: false
+                    proto: true
+              offset: 0
+            - alias: mmu_flush_cache
+              typ:
+                Pointer:
+                  Func:
+                    fp: []
+                    body: Void
+                    variadic: false
+                    proto: false
+              offset: 4
+            - alias: mmu_alloc
+              typ:
+                Pointer:
+                  Func:
+                    fp:
+                      - Pointer: UInt
+                      - Pointer:
+                          Pointer: Void
+                    body: Void
+                    variadic: false
+                    proto: true
+              offset: 8
+            - alias: mmu_free
+              typ:
+                Pointer:
+                  Func:
+                    fp:
+                      - Int
+                    body: Void
+                    variadic: false
+                    proto: true
+              offset: 12
+            - alias: mmu_map
+              typ:
+                Pointer:
+                  Func:
+                    fp:
+                      - Int
+                      - UInt
+                      - UInt
+                    body: Void
+                    variadic: false
+                    proto: true
+              offset: 16
+            - alias: mmu_switch
+              typ:
+                Pointer:
+                  Func:
+                    fp:
+                      - Int
+                    body: Void
+                    variadic: false
+                    proto: true
+              offset: 20
+            - alias: tty_read
+              typ:
+                Pointer:
+                  Func:
+                    fp:
+                      - Pointer: Char
+                    body: Void
+                    variadic: false
+                    proto: true
+              offset: 24
+            - alias: tty_write
+              typ:
+                Pointer:
+                  Func:
+                    fp:
+                      - Char
+                    body: Void
+                    variadic: false
+                    proto: true
+              offset: 28
+            - alias: disk_read
+              typ:
+                Pointer:
+                  Func:
+                    fp:
+                      - UInt
+                      - UInt
+                      - Pointer: Char
+                    body: Void
+                    variadic: false
+                    proto: true
+              offset: 32
+            - alias: disk_write
+              typ:
+                Pointer:
+                  Func:
+                    fp:
+                      - UInt
+                      - UInt
+                      - Pointer: Char
+                    body: Void
+                    variadic: false
+                    proto: true
+              offset: 36
+            - alias: platform
+              typ: Int
+              offset: 40
+            - alias: translation
+              typ: Int
+              offset: 44
+          size: 48
+          align: 4
+        "###);
+    }
+
+    #[test]
+    fn errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| -> Result<(), io::Error> {
+            let tokens = lexer::lex(l)?;
+            let (cx, mut r) = (&mut super::Parser::new(), &tokens[..]);
+            loop {
+                (_, r) = super::parse_external(cx, r)?;
+            }
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "typedef int T; typedef int T;: E0007 1:28 redefinition of typedef 'T'"
+        - "typedef int T; int T;: E0007 1:20 'T' redeclared as different kind of symbol"
+        - "int T; typedef int T;: E0007 1:20 'T' redeclared as different kind of symbol"
+        - "typedef int T = 1;: E0003 1:13 typedef 'T' is initialized"
+        - "typedef int f(void) { return 0; }: E0003 1:1 function definition declared 'typedef'"
+        - "typedef static int T;: E0003 1:9 multiple storage classes in declaration specifiers"
+        - "int f(typedef int x);: E0003 1:7 storage class 'typedef' specified for parameter"
+        - "struct s { typedef int T; };: E0003 1:12 storage class 'typedef' specified for member"
+        - "typedef const int C; const C x;: E0003 1:22 duplicate 'const'"
+        - "typedef volatile int V; const volatile V x;: E0003 1:25 duplicate 'volatile'"
+        - "typedef int T; unsigned T x;: E0007 1:25 'T' redeclared as different kind of symbol"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_exprs {
+    use crate::diagnostic;
+    use crate::lexer;
+    use crate::SExpr;
+    use crate::SExprKind::*;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/exprs";
+
+    // fully parenthesized, so each line shows how its operators grouped
+    fn paren(e: &SExpr) -> String {
+        match &e.kind {
+            Int(n) => n.to_string(),
+            VarApp(x) => x.clone(),
+            UnaryE { op, l } => format!("({op:?} {})", paren(l)),
+            BinE { op, l, r } => format!("({op:?} {} {})", paren(l), paren(r)),
+            LogE { op, l, r } => format!("(Log{op:?} {} {})", paren(l), paren(r)),
+            BitE { op, l, r } => format!("(Bit{op:?} {} {})", paren(l), paren(r)),
+            RelE { op, l, r } => format!("({op:?} {} {})", paren(l), paren(r)),
+            Ternary { cond, then, els } => {
+                format!("(? {} {} {})", paren(cond), paren(then), paren(els))
+            }
+            Comma { l, r } => format!("(, {} {})", paren(l), paren(r)),
+            Asnmt { op: None, l, r } => format!("(= {} {})", paren(l), paren(r)),
+            Asnmt { op: Some(op), l, r } => format!("({op:?}= {} {})", paren(l), paren(r)),
+            Update { op, l } => format!("({op:?} {})", paren(l)),
+            FuncApp { f, aps } => {
+                let aps = aps.iter().map(paren).collect::<Vec<_>>();
+                format!("({} {})", paren(f), aps.join(" "))
+            }
+            Deref(l) => format!("(* {})", paren(l)),
+            AddrOf(l) => format!("(& {})", paren(l)),
+            Index { l, r } => format!("([] {} {})", paren(l), paren(r)),
+            Dot { l, field } => format!("(. {} {field})", paren(l)),
+            Arrow { l, field } => format!("(-> {} {field})", paren(l)),
+            Cast { typ, l } => format!("(({typ:?}) {})", paren(l)),
+            SizeOfT(typ) => format!("(sizeof {typ:?})"),
+            SizeOfE(l) => format!("(sizeof {})", paren(l)),
+            InitList(items) => {
+                let items = items.iter().map(paren).collect::<Vec<_>>();
+                format!("{{{}}}", items.join(" "))
+            }
+            k => format!("{k:?}"),
+        }
+    }
+
+    #[test]
+    fn precedence() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/precedence.c")).expect("file dne");
+
+        let exprs = chars
+            .lines()
+            .map(|l| {
+                let tokens = lexer::lex(l).unwrap();
+                let (e, r) = super::parse_expr(&mut super::Parser::new(), &tokens).unwrap();
+                assert!(r.is_empty(), "{l}: trailing tokens");
+                format!("{l}: {}", paren(&e))
+            })
             .collect::<Vec<_>>();
+        insta::assert_yaml_snapshot!(exprs, @r###"
+        ---
+        - "a, b = c, d: (, (, a (= b c)) d)"
+        - "a = b = c += d: (= a (= b (Add= c d)))"
+        - "a ? b : c ? d : e: (? a b (? c d e))"
+        - "a ? b, c : d: (? a (, b c) d)"
+        - "a || b && c | d ^ e & f: (LogOr a (LogAnd b (BitOr c (BitXor d (BitAnd e f)))))"
+        - "a == b != c < d: (Neq (Eq a b) (Lt c d))"
+        - "a < b << c + d: (Lt a (BitShl b (Add c d)))"
+        - "a - b - c: (Sub (Sub a b) c)"
+        - "a * b / c % d + e: (Add (Mod (Div (Mult a b) c) d) e)"
+        - "a + b * c: (Add a (Mult b c))"
+        - "-a * b: (Mult (Sub a) b)"
+        - "!a && ~b: (LogAnd (Not a) (BitNot b))"
+        - "(a + b) * c: (Mult (Add a b) c)"
+        - "*p++: (* (PostInc p))"
+        - "&a[1]: (& ([] a 1))"
+        - "++*p: (PreInc (* p))"
+        - "--p->x: (PreDec (-> p x))"
+        - "a.b.c[i][j]: ([] ([] (. (. a b) c) i) j)"
+        - "(int)a + b: (Add ((Int) a) b)"
+        - "(unsigned long)-a: ((ULong) (Sub a))"
+        - "sizeof a + 1: (Add (sizeof a) 1)"
+        - "sizeof (int) * 2: (Mult (sizeof Int) 2)"
+        - "sizeof -a: (sizeof (Sub a))"
+        - "f(a, (b, c), d = e): (f a (, b c) (= d e))"
+        - "a ? b : c = d: (= (? a b c) d)"
+        - "x = a < b ? a : b: (= x (? (Lt a b) a b))"
+        - "a & b == c: (BitAnd a (Eq b c))"
+        - "(*g)(1, 1): ((* g) 1 1)"
+        - "s->fn(x)(y): (((-> s fn) x) y)"
+        - "fs[0](): (([] fs 0) )"
+        "###);
+    }
 
-        let tokens = lexer::lex(&chars).unwrap();
-        let tree = super::parse_prg(&tokens).unwrap();
-        insta::assert_yaml_snapshot!(tree, @r###"
+    #[test]
+    fn errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| {
+            let tokens = lexer::lex(l)?;
+            super::parse_expr(&mut super::Parser::new(), &tokens).map(|(e, _)| e)
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
         ---
-        - FuncDef:
-            alias: main
-            typ: Int
-            fps: []
-            body:
-              - Asnmt:
-                  alias: x
-                  typ: Int
-                  expr:
-                    Int: 9
-              - Return:
-                  VarApp: x
+        - "a +: E0000 0:0 expected: expression got: end of input"
+        - "(a + b: E0000 0:0 expected: PuncRightParen got: end of input"
+        - "a ? b: E0000 0:0 expected: Colon got: end of input"
+        - "f(a,: E0000 0:0 expected: expression got: end of input"
+        - "a[1: E0000 0:0 expected: PuncRightBracket got: end of input"
+        - "x.: E0000 0:0 expected: Alias got: end of input"
+        - "+: E0000 0:0 expected: expression got: end of input"
+        - "sizeof: E0000 0:0 expected: expression got: end of input"
         "###);
     }
 
-    // #[test]
-    // fn asnmt_update() {
-    //     let chars = fs::read(format!("{TEST_DIR}/asnmt_update.c"))
-    //         .expect("file dne")
-    //         .iter()
-    //         .map(|b| *b as char)
-    //         .collect::<Vec<_>>();
+    #[test]
+    fn inits() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/inits.c")).expect("file dne");
 
-    //     let tokens = lexer::lex(&chars).unwrap();
-    //     let tree = super::parse_prg(&tokens).unwrap();
-    //     insta::assert_yaml_snapshot!(tree, @r###"
-    //     ---
-    //     main_function:
-    //       stmts:
-    //         - Asnmt:
-    //             CreateBind:
-    //               id: n
-    //               expr:
-    //                 Int: 0
-    //         - Asnmt:
-    //             UpdateBind:
-    //               id: n
-    //               op: Add
-    //               expr:
-    //                 Int: 10
-    //         - Return:
-    //             Var: n
-    //     "###);
-    // }
+        let inits = chars
+            .lines()
+            .map(|l| {
+                let tokens = lexer::lex(l).unwrap();
+                let (e, r) = super::parse_init(&mut super::Parser::new(), &tokens).unwrap();
+                assert!(r.is_empty(), "{l}: trailing tokens");
+                format!("{l}: {}", paren(&e))
+            })
+            .collect::<Vec<_>>();
+        insta::assert_yaml_snapshot!(inits, @r###"
+        ---
+        - "1: 1"
+        - "{1}: {1}"
+        - "{1, 2,}: {1 2}"
+        - "{{1, 2}, 3, {4}}: {{1 2} 3 {4}}"
+        - "{a = 1, b ? c : d}: {(= a 1) (? b c d)}"
+        - "{\"ab\", {x}}: {Str([97, 98]) {x}}"
+        "###);
+    }
+
+    #[test]
+    fn init_errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/init_errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| {
+            let tokens = lexer::lex(l)?;
+            super::parse_init(&mut super::Parser::new(), &tokens).map(|(e, _)| e)
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "{: E0000 0:0 expected: expression got: end of input"
+        - "{1,: E0000 0:0 expected: expression got: end of input"
+        - "{1 2}: E0003 1:4 expected: PuncRightBrace got: LiteralInt `2`"
+        - "{,}: E0003 1:2 expected: expression got: PuncComma `,`"
+        - "{1,,}: E0003 1:4 expected: expression got: PuncComma `,`"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_fuzz {
+    use crate::diagnostic;
+    use crate::lexer::{self, LexOpts};
+    use crate::source::SourceMap;
+    use crate::typer;
+
+    // spellings of every kind of token, and a few runs of them that get a
+    // random stream past the first declarator more often
+    #[rustfmt::skip]
+    const VOCAB: &[&str] = &[
+        "1", "0x1Fu", "1.5e3f", "'a'", "\"s\"", "a", "b", "f", "T", "auto", "break", "case",
+        "char", "const", "continue", "default", "do", "double", "else", "enum", "extern",
+        "float", "for", "goto", "if", "int", "long", "register", "return", "short", "signed",
+        "sizeof", "static", "struct", "switch", "typedef", "union", "unsigned", "void",
+        "volatile", "while", "+", "-", "*", "/", "%", "<", ">", "=", "!", "&", "|", "^", "~",
+        "?", ":", ".", "++", "--", "->", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "+=",
+        "-=", "*=", "/=", "%=", "<<=", ">>=", "&=", "|=", "^=", "(", ")", "{", "}", "[", "]",
+        ";", ",", "...", "int main(void) {", "struct s {", "typedef int T;", "enum { A = 1 }",
+        "switch (a) {", "case 1:", "int f(a, b) int a;", "} ;", "return a;", "{ int x = {1, 2};",
+    ];
+
+    // xorshift, so that a failure can be replayed from its seed
+    fn next(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    #[test]
+    fn random_streams() {
+        for seed in 1..=5000u64 {
+            let mut state = seed;
+            let len = next(&mut state) % 64;
+            let src = (0..len)
+                .map(|_| VOCAB[(next(&mut state) % VOCAB.len() as u64) as usize])
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let tokens = lexer::lex(&src).unwrap();
+            match super::parse_prg(&tokens) {
+                Ok(prg) => {
+                    if let Err(e) = typer::type_prg(&prg) {
+                        assert!(!e.to_string().is_empty(), "seed {seed}: {src}");
+                    }
+                }
+                Err(e) => assert!(!e.to_string().is_empty(), "seed {seed}: {src}"),
+            }
+        }
+    }
+
+    // random programs that parse, so that the typer sees them too. a budget
+    // bounds their size, and the odd long run of one construct nests them
+    // past the parser's limit
+    fn expr(state: &mut u64, budget: &mut i32) -> String {
+        *budget -= 1;
+        if *budget <= 0 {
+            return ["a", "1", "x", "p[0]"][(next(state) % 4) as usize].to_string();
+        }
+        match next(state) % 12 {
+            0 => format!("({})", expr(state, budget)),
+            1 => format!("- {}", expr(state, budget)),
+            2 => format!("!{}", expr(state, budget)),
+            3 => format!("{} + {}", expr(state, budget), expr(state, budget)),
+            4 => format!("{} < {}", expr(state, budget), expr(state, budget)),
+            5 => format!("{} && {}", expr(state, budget), expr(state, budget)),
+            6 => format!("(a = {})", expr(state, budget)),
+            7 => format!(
+                "{} ? {} : {}",
+                expr(state, budget),
+                expr(state, budget),
+                expr(state, budget)
+            ),
+            8 => format!("f({})", expr(state, budget)),
+            9 => format!("sizeof ({})", expr(state, budget)),
+            10 => format!("p[{}]", expr(state, budget)),
+            _ => format!("(char){}", expr(state, budget)),
+        }
+    }
+
+    fn stmt(state: &mut u64, budget: &mut i32) -> String {
+        *budget -= 1;
+        if *budget <= 0 {
+            return "a++;".to_string();
+        }
+        match next(state) % 8 {
+            0 => format!("{{ {} {} }}", stmt(state, budget), stmt(state, budget)),
+            1 => format!(
+                "if ({}) {} else {}",
+                expr(state, budget),
+                stmt(state, budget),
+                stmt(state, budget)
+            ),
+            2 => format!("while ({}) {}", expr(state, budget), stmt(state, budget)),
+            3 => format!(
+                "for (x = 0; x < {}; x++) {}",
+                expr(state, budget),
+                stmt(state, budget)
+            ),
+            4 => format!(
+                "{{ int x = {}; {} }}",
+                expr(state, budget),
+                stmt(state, budget)
+            ),
+            5 => format!("return {};", expr(state, budget)),
+            6 => format!(
+                "switch (a) {{ case 1: {} default: break; }}",
+                stmt(state, budget)
+            ),
+            _ => format!("{};", expr(state, budget)),
+        }
+    }
+
+    // on a thread with the stack the driver's main thread gets (8 MiB on
+    // linux), rather than the test harness's 2 MiB
+    fn with_main_stack(f: impl FnOnce() + Send + 'static) {
+        let t = std::thread::Builder::new()
+            .stack_size(8 << 20)
+            .spawn(f)
+            .unwrap();
+        if let Err(e) = t.join() {
+            std::panic::resume_unwind(e);
+        }
+    }
+
+    // a construct repeated a line at a time, far deeper than the parser allows
+    fn deep(head: &str, open: &str, mid: &str, close: &str) -> String {
+        let sm = &mut SourceMap::new();
+        let src = format!(
+            "int a;\nint main() {{\n{head}\n{}{mid}\n{};\n}}\n",
+            format!("{open}\n").repeat(20000),
+            format!("{close}\n").repeat(20000)
+        );
+        let file = sm.add("deep.c", &src);
+        let tokens = lexer::lex_file(&src, file, LexOpts::default()).unwrap();
+        diagnostic::render(&super::parse_prg(&tokens).unwrap_err(), sm)
+    }
+
+    #[test]
+    fn deep_nesting() {
+        with_main_stack(|| {
+            let errs = [
+                deep("", "{", "a = 1;", "}"),
+                deep("", "if (a)", "a = 1;", ""),
+                deep("", "(", "a", ")"),
+                deep("", "a = -(", "1", ")"),
+                deep("a =", "a =", "1", ""),
+                deep("a = 0", "", "", "+ 1"),
+                deep("int", "(", "b", ")"),
+            ];
+            insta::assert_snapshot!(errs.concat(), @r###"
+            error[E0012]: nesting exceeds 128 levels
+             --> deep.c:132:1
+                |
+            132 | {
+                | ^
+            error[E0012]: nesting exceeds 128 levels
+             --> deep.c:131:5
+                |
+            131 | if (a)
+                |     ^
+            error[E0012]: nesting exceeds 128 levels
+             --> deep.c:131:1
+                |
+            131 | (
+                | ^
+            error[E0012]: nesting exceeds 128 levels
+             --> deep.c:46:5
+               |
+            46 | a = -(
+               |     ^
+            error[E0012]: nesting exceeds 128 levels
+             --> deep.c:130:1
+                |
+            130 | a =
+                | ^
+            error[E0012]: nesting exceeds 128 levels
+             --> deep.c:39879:3
+                  |
+            39879 | + 1
+                  |   ^
+            error[E0012]: nesting exceeds 128 levels
+             --> deep.c:132:1
+                |
+            132 | (
+                | ^
+            "###);
+        })
+    }
+
+    #[test]
+    fn random_programs() {
+        with_main_stack(random_programs_)
+    }
+
+    fn random_programs_() {
+        for seed in 1..=500u64 {
+            let mut state = seed;
+            let mut budget = (next(&mut state) % 200) as i32;
+            let mut body = stmt(&mut state, &mut budget);
+            if next(&mut state).is_multiple_of(8) {
+                let n = (next(&mut state) % 300) as usize;
+                body = match next(&mut state) % 3 {
+                    0 => format!("{}{body}{}", "{".repeat(n), "}".repeat(n)),
+                    1 => format!("{}{body}", "if (a) ".repeat(n)),
+                    _ => format!("{body} a = {}a{};", "-(".repeat(n), ")".repeat(n)),
+                };
+            }
+            let src = format!(
+                "int a, *p; int f(int x) {{ return x; }} int main() {{ int x; {body} return 0; }}"
+            );
+
+            let tokens = lexer::lex(&src).unwrap();
+            let prg = match super::parse_prg(&tokens) {
+                Ok(prg) => prg,
+                Err(e) => {
+                    assert!(
+                        e.to_string().contains("nesting exceeds"),
+                        "seed {seed}: {e}\n{src}"
+                    );
+                    continue;
+                }
+            };
+            if let Err(e) = typer::type_prg(&prg) {
+                panic!("seed {seed}: {e}\n{src}");
+            }
+        }
+    }
 }
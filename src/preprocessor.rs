@@ -0,0 +1,1542 @@
+use crate::diagnostic::{Code, Diagnostic};
+use crate::lexer::{LexOpts, Lexer, Token, TT};
+use crate::parser_ast;
+use crate::source::{Expansion, LineMark, SourceMap, Span};
+use crate::SExprKind;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+//  1. translation phases 1-4 (C89 5.1.1.2). trigraphs are replaced before
+//     lexing (phase 1), the lexer takes care of splices and comments (2, 3)
+//     and everything else here is phase 4: directives and macro expansion
+//  2. a file is lexed in one go. a directive is a `#` that begins a logical
+//     line, and lexer errors are only reported once they reach a group that
+//     is not skipped, so `#if 0` can hold any text
+//  3. macro expansion follows Prosser's algorithm: every token carries the
+//     set of macro names it came out of (its hide set) and is never expanded
+//     by a macro in it. that is what stops recursion, even for tokens that
+//     are rescanned long after the expansion that produced them
+//  4. tokens of a replacement list take the span of the macro name they
+//     replace, so diagnostics point at the invocation, and the id of the
+//     expansion, which leads back to the #define. argument tokens keep their
+//     own spans
+
+const MAX_DEPTH: usize = 200;
+const BUILTINS: &[&str] = &["__FILE__", "__LINE__", "__DATE__", "__TIME__", "__STDC__"];
+// the headers picoc ships with (include/), found after every include dir so
+// that no external toolchain is needed. they are named <picoc>/stdio.h etc.
+const HEADERS: &[(&str, &str)] = &[
+    ("stddef.h", include_str!("../include/stddef.h")),
+    ("limits.h", include_str!("../include/limits.h")),
+    ("stdarg.h", include_str!("../include/stdarg.h")),
+    ("float.h", include_str!("../include/float.h")),
+    ("stdio.h", include_str!("../include/stdio.h")),
+    ("stdlib.h", include_str!("../include/stdlib.h")),
+    ("string.h", include_str!("../include/string.h")),
+    ("ctype.h", include_str!("../include/ctype.h")),
+];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+#[derive(Clone, Default, Debug)]
+pub struct PpOpts {
+    pub lex: LexOpts,
+    pub trigraphs: bool,                        // ??= ??/ ... (C89, off in gnu89)
+    pub include_dirs: Vec<PathBuf>, // searched for <...>, and for "..." after the includer's directory
+    pub epoch: u64,                 // seconds since 1970 that __DATE__ and __TIME__ spell
+    pub defines: Vec<(String, Option<String>)>, // -D NAME=VAL and -U NAME (None), in order
+}
+
+// preprocesses the file at path (and everything it includes) into the
+// token stream the parser sees. every file read is added to sm
+pub fn preprocess(sm: &mut SourceMap, path: &str, opts: &PpOpts) -> Result<Vec<Token>, io::Error> {
+    let mut pp = Pp {
+        sm,
+        opts,
+        macros: HashMap::new(),
+        once: HashSet::new(),
+        conds: vec![],
+        out: vec![],
+        depth: 0,
+    };
+
+    // the command line acts like a file of #defines and #undefs read first
+    let cmdline = opts
+        .defines
+        .iter()
+        .map(|(name, val)| match val {
+            Some(val) => format!("#define {name} {val}\n"),
+            None => format!("#undef {name}\n"),
+        })
+        .collect::<String>();
+    pp.text("<command-line>", &cmdline, Span::default())?;
+
+    pp.file(Path::new(path), Span::default())?;
+    Ok(pp.out)
+}
+
+// the preprocessed translation unit as text (-E). tokens stay on the line
+// and column they were written at, short gaps become blank lines and
+// anything else gets a line marker `# line "file" flags` as gcc writes them:
+// 1 after entering an include and 2 after returning to an includer
+pub fn print(toks: &[Token], sm: &SourceMap) -> String {
+    let includers = |f: usize| std::iter::successors(Some(f), |f| sm.get(*f)?.includer);
+    let Some(root) = toks.first().and_then(|t| includers(t.span.file).last()) else {
+        return String::new();
+    };
+
+    let mut out = format!("# 1 {}\n", quote(&sm.get(root).unwrap().name)); // from a token
+    let (mut file, mut line) = (root, 1);
+    let mut prev: Option<&Token> = None;
+
+    for t in toks {
+        let Some(f) = sm.get(t.span.file) else {
+            continue;
+        };
+        let (name, at) = f.presumed(t.span.line);
+
+        if t.bol || prev.is_none() {
+            if t.span.file == file && (line..line + 8).contains(&at) {
+                out.push_str(&"\n".repeat(at - line));
+            } else {
+                let flag = match t.span.file {
+                    f if f == file => "",
+                    f if includers(file).any(|i| i == f) => " 2",
+                    _ => " 1",
+                };
+                if prev.is_some() {
+                    out.push('\n');
+                }
+                out.push_str(&format!("# {at} {}{flag}\n", quote(name)));
+            }
+            (file, line) = (t.span.file, at);
+            out.push_str(&" ".repeat(t.span.col - 1));
+        } else if t.space || prev.is_some_and(|p| pastes(p, t)) {
+            out.push(' ');
+        }
+
+        out.push_str(&t.lexeme);
+        prev = Some(t);
+    }
+
+    out.push('\n');
+    out
+}
+
+// whether two tokens written back to back would lex differently, as `-` `-`
+// out of `-NEG` would
+fn pastes(l: &Token, r: &Token) -> bool {
+    let text = format!("{}{}", l.lexeme, r.lexeme);
+    let opts = LexOpts {
+        line_comments: true,
+        ..LexOpts::default()
+    };
+
+    let mut toks = Lexer::new(&text, 0, opts);
+    !matches!(toks.next(), Some(Ok(t)) if t.lexeme == l.lexeme)
+}
+
+type HideSet = Rc<HashSet<String>>;
+
+#[derive(Clone, Debug)]
+struct PTok {
+    tok: Token,
+    hide: HideSet,
+}
+
+impl PTok {
+    fn new(tok: Token) -> Self {
+        Self {
+            tok,
+            hide: HideSet::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Macro {
+    params: Option<Vec<String>>, // None for object-like macros
+    body: Vec<Token>,
+    span: Span, // the name in the #define
+}
+
+impl Macro {
+    // redefinitions must be identical: same parameters, same spelling and
+    // same whitespace between tokens (C89 3.8.3)
+    fn same(&self, other: &Macro) -> bool {
+        self.params == other.params
+            && self.body.len() == other.body.len()
+            && self
+                .body
+                .iter()
+                .zip(&other.body)
+                .all(|(a, b)| a.typ == b.typ && a.lexeme == b.lexeme && a.space == b.space)
+    }
+}
+
+// one #if ... #endif. a group is active if the enclosing one is and its
+// condition held. taken records that some group of the chain already ran
+struct Cond {
+    span: Span,
+    parent: bool,
+    active: bool,
+    taken: bool,
+    els: bool,
+}
+
+// the tokens of one file, behind whatever macro expansion pushed back in
+// front of them for rescanning
+struct Input {
+    toks: Vec<Result<Token, Diagnostic>>,
+    bols: Vec<bool>, // errors have no bol of their own
+    pos: usize,
+    pending: Vec<PTok>, // reversed: the next token is last
+}
+
+impl Input {
+    fn new(mut lexer: Lexer) -> Self {
+        let (mut toks, mut bols) = (vec![], vec![]);
+        while let Some(t) = lexer.next() {
+            toks.push(t);
+            bols.push(lexer.last_bol());
+        }
+
+        Self {
+            toks,
+            bols,
+            pos: 0,
+            pending: vec![],
+        }
+    }
+
+    fn rescan(arg: &[PTok]) -> Self {
+        Self {
+            toks: vec![],
+            bols: vec![],
+            pos: 0,
+            pending: arg.iter().rev().cloned().collect(),
+        }
+    }
+
+    fn next(&mut self) -> Option<Result<PTok, Diagnostic>> {
+        if let Some(t) = self.pending.pop() {
+            return Some(Ok(t));
+        }
+
+        let t = self.toks.get(self.pos)?.clone();
+        self.pos += 1;
+        Some(t.map(PTok::new))
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        match self.pending.last() {
+            Some(t) => Some(&t.tok),
+            None => self.toks.get(self.pos)?.as_ref().ok(),
+        }
+    }
+
+    // expansions never produce directives, only the file itself does
+    fn at_directive(&self) -> bool {
+        self.pending.is_empty()
+            && matches!(self.toks.get(self.pos), Some(Ok(t)) if t.bol && t.typ == TT::PuncHash)
+    }
+
+    fn done(&self) -> bool {
+        self.pending.is_empty() && self.pos >= self.toks.len()
+    }
+
+    // the rest of the logical line
+    fn line(&mut self) -> Vec<Result<Token, Diagnostic>> {
+        let n = self.bols[self.pos..]
+            .iter()
+            .take_while(|bol| !**bol)
+            .count();
+        self.pos += n;
+        self.toks[self.pos - n..self.pos].to_vec()
+    }
+}
+
+struct Pp<'a> {
+    sm: &'a mut SourceMap,
+    opts: &'a PpOpts,
+    macros: HashMap<String, Macro>,
+    once: HashSet<PathBuf>, // files that said #pragma once
+    conds: Vec<Cond>,
+    out: Vec<Token>,
+    depth: usize,
+}
+
+impl Pp<'_> {
+    fn file(&mut self, path: &Path, at: Span) -> Result<(), io::Error> {
+        if self.depth > MAX_DEPTH {
+            return Err(Diagnostic::new(Code::Preprocess, at, "#include nested too deeply").into());
+        }
+        if fs::canonicalize(path).is_ok_and(|p| self.once.contains(&p)) {
+            return Ok(());
+        }
+
+        let bytes = fs::read(path).map_err(|e| {
+            Diagnostic::new(Code::Preprocess, at, format!("{}: {e}", path.display()))
+        })?;
+        let text = String::from_utf8_lossy(&bytes);
+        self.depth += 1;
+        self.text(&path.to_string_lossy(), &text, at)?;
+        self.depth -= 1;
+        Ok(())
+    }
+
+    // at is the #include that led here, dummy for the main file
+    fn text(&mut self, name: &str, text: &str, at: Span) -> Result<(), io::Error> {
+        let text = match self.opts.trigraphs {
+            true => trigraphs(text),
+            false => text.to_owned(),
+        };
+
+        let file = self.sm.add(name, &text);
+        if !at.is_dummy() {
+            self.sm.include(file, at.file);
+        }
+        let mut inp = Input::new(Lexer::new(&text, file, self.opts.lex));
+        let conds = self.conds.len();
+        loop {
+            if inp.at_directive() {
+                self.directive(&mut inp, file)?;
+            } else if inp.done() {
+                break;
+            } else if !self.active() {
+                inp.pos += 1;
+                inp.line();
+            } else if let Some(t) = self.expanded(&mut inp)? {
+                self.out.push(t.tok);
+            }
+        }
+
+        match self.conds.get(conds) {
+            Some(c) => Err(Diagnostic::new(
+                Code::Preprocess,
+                c.span,
+                "unterminated conditional directive",
+            )
+            .into()),
+            None => Ok(()),
+        }
+    }
+
+    fn active(&self) -> bool {
+        self.conds.last().is_none_or(|c| c.active)
+    }
+
+    fn defined(&self, name: &str) -> bool {
+        self.macros.contains_key(name) || BUILTINS.contains(&name)
+    }
+
+    // the next token with macros expanded. None at the end of the input or,
+    // once nothing is left to rescan, in front of a directive
+    fn expanded(&mut self, inp: &mut Input) -> Result<Option<PTok>, io::Error> {
+        loop {
+            if inp.at_directive() {
+                return Ok(None);
+            }
+            let Some(t) = inp.next() else {
+                return Ok(None);
+            };
+
+            let t = t?;
+            if !self.expand(&t, inp)? {
+                return Ok(Some(t));
+            }
+        }
+    }
+
+    // pushes the expansion of t back onto the input if t invokes a macro
+    fn expand(&mut self, t: &PTok, inp: &mut Input) -> Result<bool, io::Error> {
+        let name = t.tok.lexeme.as_str();
+        if !ident(&t.tok) || t.hide.contains(name) {
+            return Ok(false);
+        }
+        if let Some(tok) = self.builtin(&t.tok) {
+            inp.pending.push(PTok::new(tok));
+            return Ok(true);
+        }
+        let Some(m) = self.macros.get(name).cloned() else {
+            return Ok(false);
+        };
+
+        let (args, hide) = match &m.params {
+            None => (vec![], with(&t.hide, name)),
+            Some(params) => {
+                // a function-like macro name without arguments is left alone
+                if inp.peek().is_none_or(|p| p.typ != TT::PuncLeftParen) {
+                    return Ok(false);
+                }
+                inp.next();
+
+                let (args, rparen) = self.args(&t.tok, params.len(), inp)?;
+                (args, with(&intersect(&t.hide, &rparen.hide), name))
+            }
+        };
+
+        let expn = self.sm.expand(Expansion {
+            name: name.to_owned(),
+            call: t.tok.span,
+            def: m.span,
+        });
+        let mut body = self.subst(&m, &args, Span { expn, ..t.tok.span })?;
+        for b in &mut body {
+            b.hide = union(&b.hide, &hide);
+        }
+        if let Some(first) = body.first_mut() {
+            (first.tok.bol, first.tok.space) = (t.tok.bol, t.tok.space);
+        }
+
+        inp.pending.extend(body.into_iter().rev());
+        Ok(true)
+    }
+
+    // the arguments of an invocation, read after its `(`. commas only split
+    // arguments outside of nested parentheses
+    fn args(
+        &mut self,
+        name: &Token,
+        n: usize,
+        inp: &mut Input,
+    ) -> Result<(Vec<Vec<PTok>>, PTok), io::Error> {
+        let (mut args, mut depth) = (vec![vec![]], 0);
+        let rparen = loop {
+            let Some(t) = inp.next() else {
+                let msg = format!(
+                    "unterminated argument list invoking macro \"{}\"",
+                    name.lexeme
+                );
+                return Err(Diagnostic::new(Code::Preprocess, name.span, msg).into());
+            };
+
+            let t = t?;
+            match t.tok.typ {
+                TT::PuncRightParen if depth == 0 => break t,
+                TT::PuncComma if depth == 0 => {
+                    args.push(vec![]);
+                    continue;
+                }
+                TT::PuncLeftParen => depth += 1,
+                TT::PuncRightParen => depth -= 1,
+                _ => (),
+            }
+            args.last_mut().unwrap().push(t); // args starts non-empty
+        };
+
+        // f() passes no arguments to a macro without parameters
+        if n == 0 && args.len() == 1 && args[0].is_empty() {
+            args.clear();
+        }
+
+        let (k, f) = (args.len(), &name.lexeme);
+        let msg = match k.cmp(&n) {
+            std::cmp::Ordering::Less => {
+                format!("macro \"{f}\" requires {n} arguments, but only {k} given")
+            }
+            std::cmp::Ordering::Greater => {
+                format!("macro \"{f}\" passed {k} arguments, but takes just {n}")
+            }
+            std::cmp::Ordering::Equal => return Ok((args, rparen)),
+        };
+        Err(Diagnostic::new(Code::Preprocess, name.span, msg).into())
+    }
+
+    // the replacement list of m with its parameters substituted (C89 3.8.3).
+    // an argument is macro-expanded first unless it is an operand of # or ##.
+    // an empty argument next to ## is a placemarker that pastes to nothing
+    fn subst(&mut self, m: &Macro, args: &[Vec<PTok>], at: Span) -> Result<Vec<PTok>, io::Error> {
+        let params = m.params.as_deref().unwrap_or_default();
+        let param = |t: &Token| params.iter().position(|p| ident(t) && *p == t.lexeme);
+        let own = |t: &Token| {
+            PTok::new(Token {
+                span: at,
+                bol: false,
+                ..t.clone()
+            })
+        };
+
+        let (mut out, mut placemarker, mut i) = (vec![], false, 0);
+        while i < m.body.len() {
+            let (t, next) = (&m.body[i], m.body.get(i + 1));
+            match (t.typ, next.and_then(param)) {
+                (TT::PuncHash, Some(p)) if m.params.is_some() => {
+                    let mut s = stringify(&args[p]);
+                    (s.span, s.space) = (at, t.space);
+                    out.push(PTok::new(s));
+                    (placemarker, i) = (false, i + 2);
+                }
+                (TT::PuncHashHash, p) => {
+                    // ## never ends a replacement list, so next exists
+                    let rhs = match p {
+                        Some(p) => args[p].clone(),
+                        None => vec![own(next.unwrap())],
+                    };
+                    i += 2;
+
+                    match (rhs.split_first(), placemarker) {
+                        (None, _) => (),
+                        (Some(_), true) => {
+                            out.extend(rhs);
+                            placemarker = false;
+                        }
+                        (Some((r, rest)), false) => {
+                            let l = out.pop().unwrap(); // ## never starts one either
+                            out.push(self.paste(l, r)?);
+                            out.extend(rest.iter().cloned());
+                        }
+                    }
+                }
+                _ => {
+                    match param(t) {
+                        Some(p) => {
+                            // the argument takes the whitespace before the parameter
+                            let n = out.len();
+                            if next.is_some_and(|n| n.typ == TT::PuncHashHash) {
+                                out.extend(args[p].iter().cloned());
+                            } else {
+                                out.extend(self.expand_arg(&args[p])?);
+                            }
+                            if let Some(first) = out.get_mut(n) {
+                                first.tok.space = t.space;
+                            }
+                            placemarker = out.len() == n;
+                        }
+                        None => {
+                            out.push(own(t));
+                            placemarker = false;
+                        }
+                    }
+                    i += 1;
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
+    // an argument is expanded on its own, as if it were the rest of the file
+    fn expand_arg(&mut self, arg: &[PTok]) -> Result<Vec<PTok>, io::Error> {
+        let mut inp = Input::rescan(arg);
+        let mut out = vec![];
+        while let Some(t) = self.expanded(&mut inp)? {
+            out.push(t);
+        }
+
+        Ok(out)
+    }
+
+    // the operand of #include, #line and #if when it is not spelled out
+    fn expand_line(&mut self, toks: &[Token]) -> Result<Vec<Token>, io::Error> {
+        let arg = toks.iter().cloned().map(PTok::new).collect::<Vec<_>>();
+        Ok(self.expand_arg(&arg)?.into_iter().map(|t| t.tok).collect())
+    }
+
+    // l ## r must spell exactly one token
+    fn paste(&self, l: PTok, r: &PTok) -> Result<PTok, io::Error> {
+        let text = format!("{}{}", l.tok.lexeme, r.tok.lexeme);
+        let mut toks = Lexer::new(&text, l.tok.span.file, self.opts.lex);
+        match (toks.next(), toks.next()) {
+            (Some(Ok(t)), None) if t.lexeme == text => Ok(PTok {
+                tok: Token {
+                    lexeme: t.lexeme,
+                    typ: t.typ,
+                    ..l.tok
+                },
+                hide: l.hide,
+            }),
+            _ => {
+                let msg = format!(
+                    "pasting \"{}\" and \"{}\" does not give a valid preprocessing token",
+                    l.tok.lexeme, r.tok.lexeme
+                );
+                Err(Diagnostic::new(Code::Preprocess, l.tok.span, msg).into())
+            }
+        }
+    }
+
+    // __FILE__ and __LINE__ are where the name appears, as adjusted by #line
+    fn builtin(&self, t: &Token) -> Option<Token> {
+        let (name, line) = match self.sm.get(t.span.file) {
+            Some(f) => f.presumed(t.span.line),
+            None => ("", t.span.line),
+        };
+
+        let (date, time) = date_time(self.opts.epoch);
+        let (typ, lexeme) = match t.lexeme.as_str() {
+            "__FILE__" => (TT::LiteralString, quote(name)),
+            "__LINE__" => (TT::LiteralInt, line.to_string()),
+            "__DATE__" => (TT::LiteralString, date),
+            "__TIME__" => (TT::LiteralString, time),
+            "__STDC__" => (TT::LiteralInt, "1".to_string()),
+            _ => return None,
+        };
+
+        Some(Token {
+            lexeme,
+            typ,
+            ..t.clone()
+        })
+    }
+
+    fn directive(&mut self, inp: &mut Input, file: usize) -> Result<(), io::Error> {
+        let hash = inp.next().unwrap()?.tok; // at_directive
+        let line = inp.line();
+        let name = match line.first() {
+            Some(Ok(name)) => name.clone(),
+            Some(Err(e)) if self.active() => return Err(e.clone().into()),
+            _ => return Ok(()), // the null directive
+        };
+
+        // conditionals nest even inside skipped groups, nothing else is looked at
+        let rest = &line[1..];
+        match name.lexeme.as_str() {
+            "if" | "ifdef" | "ifndef" | "elif" | "else" | "endif" => {
+                return self.conditional(&name, rest)
+            }
+            _ if !self.active() => return Ok(()),
+            _ => (),
+        }
+
+        let rest = rest.iter().cloned().collect::<Result<Vec<_>, _>>()?;
+        match name.lexeme.as_str() {
+            "define" => self.define(&name, &rest),
+            "undef" => {
+                let m = macro_name(&name, rest.first())?;
+                self.macros.remove(&m.lexeme);
+                Ok(())
+            }
+            "include" => self.include(&name, &rest),
+            "line" => self.line(&name, &rest, file),
+            "error" => Err(Diagnostic::new(
+                Code::Preprocess,
+                name.span,
+                format!("#error {}", join(&rest)),
+            )
+            .into()),
+            "pragma" => {
+                self.pragma(&hash, &rest, file);
+                Ok(())
+            }
+            _ => {
+                let msg = format!("invalid preprocessing directive #{}", name.lexeme);
+                Err(Diagnostic::new(Code::Preprocess, name.span, msg).into())
+            }
+        }
+    }
+
+    fn conditional(
+        &mut self,
+        d: &Token,
+        line: &[Result<Token, Diagnostic>],
+    ) -> Result<(), io::Error> {
+        let err = |msg: &str| Err(Diagnostic::new(Code::Preprocess, d.span, msg).into());
+        match d.lexeme.as_str() {
+            "if" | "ifdef" | "ifndef" => {
+                let parent = self.active();
+                let active = parent && self.condition(d, line)?;
+                self.conds.push(Cond {
+                    span: d.span,
+                    parent,
+                    active,
+                    taken: active,
+                    els: false,
+                });
+            }
+            "elif" => {
+                let (parent, taken) = match self.conds.last() {
+                    None => return err("#elif without #if"),
+                    Some(c) if c.els => return err("#elif after #else"),
+                    Some(c) => (c.parent, c.taken),
+                };
+
+                let active = parent && !taken && self.condition(d, line)?;
+                let c = self.conds.last_mut().unwrap();
+                (c.active, c.taken) = (active, taken || active);
+            }
+            "else" => match self.conds.last_mut() {
+                None => return err("#else without #if"),
+                Some(c) if c.els => return err("#else after #else"),
+                Some(c) => (c.active, c.taken, c.els) = (c.parent && !c.taken, true, true),
+            },
+            _ => {
+                if self.conds.pop().is_none() {
+                    return err("#endif without #if");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn condition(
+        &mut self,
+        d: &Token,
+        line: &[Result<Token, Diagnostic>],
+    ) -> Result<bool, io::Error> {
+        let toks = line.iter().cloned().collect::<Result<Vec<_>, _>>()?;
+        match d.lexeme.as_str() {
+            "ifdef" => Ok(self.defined(&macro_name(d, toks.first())?.lexeme)),
+            "ifndef" => Ok(!self.defined(&macro_name(d, toks.first())?.lexeme)),
+            _ => self.eval(d, &toks),
+        }
+    }
+
+    // `defined X` and `defined(X)` are resolved before macro expansion.
+    // identifiers still left after it are 0 (C89 3.8.1)
+    fn eval(&mut self, d: &Token, toks: &[Token]) -> Result<bool, io::Error> {
+        let (mut resolved, mut i) = (vec![], 0);
+        while i < toks.len() {
+            if toks[i].lexeme != "defined" {
+                resolved.push(toks[i].clone());
+                i += 1;
+                continue;
+            }
+
+            let (name, n) = match &toks[i + 1..] {
+                [l, n, r, ..]
+                    if l.typ == TT::PuncLeftParen && ident(n) && r.typ == TT::PuncRightParen =>
+                {
+                    (n, 4)
+                }
+                [n, ..] if ident(n) => (n, 2),
+                _ => {
+                    let msg = "operator \"defined\" requires an identifier";
+                    return Err(Diagnostic::new(Code::Preprocess, toks[i].span, msg).into());
+                }
+            };
+            resolved.push(Token {
+                lexeme: (self.defined(&name.lexeme) as u8).to_string(),
+                typ: TT::LiteralInt,
+                ..toks[i].clone()
+            });
+            i += n;
+        }
+
+        let toks = self.expand_line(&resolved)?;
+        if toks.is_empty() {
+            let msg = format!("#{} with no expression", d.lexeme);
+            return Err(Diagnostic::new(Code::Preprocess, d.span, msg).into());
+        }
+
+        let mut e = Expr {
+            toks: &toks,
+            pos: 0,
+            at: d.span,
+        };
+        let v = e.cond(true)?;
+        match toks.get(e.pos) {
+            Some(t) => {
+                let msg = format!("missing binary operator before token \"{}\"", t.lexeme);
+                Err(Diagnostic::new(Code::Preprocess, t.span, msg).into())
+            }
+            None => Ok(v.n != 0),
+        }
+    }
+
+    fn define(&mut self, d: &Token, toks: &[Token]) -> Result<(), io::Error> {
+        let name = reserved(macro_name(d, toks.first())?)?;
+
+        // a ( right after the name (no whitespace) makes it function-like
+        let (params, body) = match toks.get(1) {
+            Some(l) if l.typ == TT::PuncLeftParen && !l.space => {
+                let (params, n) = params(l, &toks[2..])?;
+                (Some(params), &toks[2 + n..])
+            }
+            _ => (None, &toks[1..]),
+        };
+
+        let mut body = body.to_vec();
+        if let Some(t) = [body.first(), body.last()]
+            .into_iter()
+            .flatten()
+            .find(|t| t.typ == TT::PuncHashHash)
+        {
+            let msg = "'##' cannot appear at either end of a macro expansion";
+            return Err(Diagnostic::new(Code::Preprocess, t.span, msg).into());
+        }
+        if let Some(ps) = &params {
+            let is_param = |t: Option<&Token>| t.is_some_and(|t| ps.contains(&t.lexeme));
+            if let Some(i) =
+                (0..body.len()).find(|&i| body[i].typ == TT::PuncHash && !is_param(body.get(i + 1)))
+            {
+                let msg = "'#' is not followed by a macro parameter";
+                return Err(Diagnostic::new(Code::Preprocess, body[i].span, msg).into());
+            }
+        }
+        if let Some(first) = body.first_mut() {
+            first.space = false;
+        }
+
+        let m = Macro {
+            params,
+            body,
+            span: name.span,
+        };
+        match self.macros.get(&name.lexeme) {
+            Some(old) if !old.same(&m) => {
+                let msg = format!("\"{}\" redefined", name.lexeme);
+                Err(Diagnostic::new(Code::Preprocess, name.span, msg).into())
+            }
+            _ => {
+                self.macros.insert(name.lexeme.clone(), m);
+                Ok(())
+            }
+        }
+    }
+
+    // "..." is looked up next to the includer first. the name is taken
+    // verbatim: no escapes in either form (C89 3.8.2)
+    fn include(&mut self, d: &Token, toks: &[Token]) -> Result<(), io::Error> {
+        let toks = match toks.first() {
+            Some(t) if matches!(t.typ, TT::LiteralString | TT::LeftAngleBracket) => toks.to_vec(),
+            _ => self.expand_line(toks)?,
+        };
+
+        let (name, angled) = match toks.as_slice() {
+            [t] if t.typ == TT::LiteralString => {
+                (t.lexeme[1..t.lexeme.len() - 1].to_owned(), false)
+            }
+            [l, inner @ .., r]
+                if l.typ == TT::LeftAngleBracket && r.typ == TT::RightAngleBracket =>
+            {
+                (join(inner), true)
+            }
+            _ => {
+                let msg = "#include expects \"FILENAME\" or <FILENAME>";
+                return Err(Diagnostic::new(Code::Preprocess, d.span, msg).into());
+            }
+        };
+
+        let includer = self.sm.get(d.span.file).map(|f| PathBuf::from(&f.name));
+        let dir = includer
+            .as_deref()
+            .and_then(Path::parent)
+            .filter(|_| !angled);
+        let path = dir
+            .into_iter()
+            .chain(self.opts.include_dirs.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(&name))
+            .find(|p| p.is_file());
+
+        let header = HEADERS.iter().find(|(h, _)| *h == name);
+        match (path, header) {
+            (Some(path), _) => self.file(&path, d.span),
+            (None, Some((h, text))) => {
+                self.depth += 1;
+                self.text(&format!("<picoc>/{h}"), text, d.span)?;
+                self.depth -= 1;
+                Ok(())
+            }
+            (None, None) => {
+                Err(
+                    Diagnostic::new(Code::Preprocess, d.span, format!("'{name}' file not found"))
+                        .into(),
+                )
+            }
+        }
+    }
+
+    // #line n "name" renumbers the lines after it
+    fn line(&mut self, d: &Token, toks: &[Token], file: usize) -> Result<(), io::Error> {
+        let toks = self.expand_line(toks)?;
+        let line = match toks.first() {
+            Some(t) if t.lexeme.bytes().all(|c| c.is_ascii_digit()) => t.lexeme.parse().ok(),
+            _ => None,
+        };
+        let Some(line @ 1..=2147483647) = line else {
+            let (span, lexeme) = toks.first().map_or((d.span, ""), |t| (t.span, &t.lexeme));
+            let msg = format!("\"{lexeme}\" after #line is not a positive integer");
+            return Err(Diagnostic::new(Code::Preprocess, span, msg).into());
+        };
+
+        let f = self.sm.get(file).unwrap(); // the file being preprocessed
+        let last = toks.last().map_or(d.span, |t| t.span).hi.max(d.span.hi);
+        let from = f.src[..last].matches('\n').count() + 2;
+        let name = match &toks[1..] {
+            [] => f.presumed(d.span.line).0.to_owned(),
+            [s] if s.typ == TT::LiteralString => s.lexeme[1..s.lexeme.len() - 1].to_owned(),
+            [t, ..] => {
+                let msg = format!("invalid filename \"{}\" in #line", t.lexeme);
+                return Err(Diagnostic::new(Code::Preprocess, t.span, msg).into());
+            }
+        };
+
+        self.sm.mark(file, LineMark { from, line, name });
+        Ok(())
+    }
+
+    // #pragma once is honored here. every other pragma reaches the compiler
+    // as a single token holding the directive
+    fn pragma(&mut self, hash: &Token, toks: &[Token], file: usize) {
+        if let [t] = toks {
+            if t.lexeme == "once" {
+                let name = &self.sm.get(file).unwrap().name; // the file being preprocessed
+                if let Ok(p) = fs::canonicalize(name) {
+                    self.once.insert(p);
+                }
+                return;
+            }
+        }
+
+        self.out.push(Token {
+            lexeme: format!("#pragma {}", join(toks)).trim_end().to_owned(),
+            typ: TT::Pragma,
+            span: toks.last().map_or(hash.span, |t| hash.span.to(t.span)),
+            bol: true,
+            space: false,
+        });
+    }
+}
+
+// `#define f(a, b)`: the parameter names up to and including the `)`
+fn params(l: &Token, toks: &[Token]) -> Result<(Vec<String>, usize), io::Error> {
+    let err = |t: Option<&Token>, msg: String| match t {
+        Some(t) => Err(Diagnostic::new(Code::Preprocess, t.span, msg).into()),
+        None => Err(Diagnostic::new(
+            Code::Preprocess,
+            l.span,
+            "missing ')' in macro parameter list",
+        )
+        .into()),
+    };
+
+    let mut params: Vec<String> = vec![];
+    if toks.first().is_some_and(|t| t.typ == TT::PuncRightParen) {
+        return Ok((params, 1));
+    }
+
+    let mut i = 0;
+    loop {
+        match toks.get(i) {
+            Some(t) if ident(t) && params.contains(&t.lexeme) => {
+                return err(
+                    Some(t),
+                    format!("duplicate macro parameter \"{}\"", t.lexeme),
+                );
+            }
+            Some(t) if ident(t) => params.push(t.lexeme.clone()),
+            t => {
+                let msg = format!("expected parameter name, found \"{}\"", lexeme(t));
+                return err(t, msg);
+            }
+        }
+
+        match toks.get(i + 1) {
+            Some(t) if t.typ == TT::PuncRightParen => return Ok((params, i + 2)),
+            Some(t) if t.typ == TT::PuncComma => i += 2,
+            t => return err(t, format!("expected ',' or ')', found \"{}\"", lexeme(t))),
+        }
+    }
+}
+
+// the identifier a directive like #define or #ifdef is about
+fn macro_name<'t>(d: &Token, t: Option<&'t Token>) -> Result<&'t Token, io::Error> {
+    match t {
+        Some(t) if ident(t) => Ok(t),
+        Some(t) => {
+            Err(Diagnostic::new(Code::Preprocess, t.span, "macro names must be identifiers").into())
+        }
+        None => {
+            let msg = format!("no macro name given in #{} directive", d.lexeme);
+            Err(Diagnostic::new(Code::Preprocess, d.span, msg).into())
+        }
+    }
+}
+
+fn reserved(t: &Token) -> Result<&Token, io::Error> {
+    if t.lexeme == "defined" || BUILTINS.contains(&t.lexeme.as_str()) {
+        let msg = format!("\"{}\" cannot be used as a macro name", t.lexeme);
+        return Err(Diagnostic::new(Code::Preprocess, t.span, msg).into());
+    }
+
+    Ok(t)
+}
+
+#[rustfmt::skip]
+#[derive(Copy, Clone)]
+struct Val { n: u32, unsigned: bool }
+
+// #if expressions are integral constant expressions evaluated in long and
+// unsigned long (C89 3.8.1), which are 32 bits wide on RV32
+struct Expr<'t> {
+    toks: &'t [Token],
+    pos: usize,
+    at: Span,
+}
+
+impl Expr<'_> {
+    fn err<T>(&self, span: Span, msg: String) -> Result<T, io::Error> {
+        Err(Diagnostic::new(Code::Preprocess, span, msg).into())
+    }
+
+    // live is false for an operand that is not evaluated (the other side of
+    // &&, || and ?:), where dividing by zero is no error
+    fn cond(&mut self, live: bool) -> Result<Val, io::Error> {
+        let c = self.binary(1, live)?;
+        if self
+            .toks
+            .get(self.pos)
+            .is_none_or(|t| t.typ != TT::Question)
+        {
+            return Ok(c);
+        }
+        self.pos += 1;
+
+        let t = self.cond(live && c.n != 0)?;
+        match self.toks.get(self.pos) {
+            Some(t) if t.typ == TT::Colon => self.pos += 1,
+            _ => return self.err(self.span(), "expected ':' in expression".to_string()),
+        }
+        let f = self.cond(live && c.n == 0)?;
+
+        let n = if c.n != 0 { t.n } else { f.n };
+        Ok(Val {
+            n,
+            unsigned: t.unsigned || f.unsigned,
+        })
+    }
+
+    fn binary(&mut self, min: u8, live: bool) -> Result<Val, io::Error> {
+        let mut l = self.unary(live)?;
+        while let Some(t) = self.toks.get(self.pos) {
+            let prec = match t.typ {
+                TT::BarBar => 1,
+                TT::AmpAmp => 2,
+                TT::Bar => 3,
+                TT::Caret => 4,
+                TT::Amp => 5,
+                TT::EqualsEquals | TT::BangEquals => 6,
+                TT::LeftAngleBracket
+                | TT::RightAngleBracket
+                | TT::LeftAngleBracketEquals
+                | TT::RightAngleBracketEquals => 7,
+                TT::LeftShift | TT::RightShift => 8,
+                TT::Plus | TT::Minus => 9,
+                TT::Star | TT::Slash | TT::Percent => 10,
+                _ => break,
+            };
+            if prec < min {
+                break;
+            }
+            self.pos += 1;
+
+            let rlive = match t.typ {
+                TT::AmpAmp => live && l.n != 0,
+                TT::BarBar => live && l.n == 0,
+                _ => live,
+            };
+            let r = self.binary(prec + 1, rlive)?;
+            l = self.apply(t, l, r, live)?;
+        }
+
+        Ok(l)
+    }
+
+    // the usual arithmetic conversions: unsigned if either side is
+    fn apply(&self, op: &Token, l: Val, r: Val, live: bool) -> Result<Val, io::Error> {
+        let unsigned = l.unsigned || r.unsigned;
+        let (a, b, sa, sb) = (l.n, r.n, l.n as i32, r.n as i32);
+        let cmp = |lt: bool| Val {
+            n: lt as u32,
+            unsigned: false,
+        };
+
+        let n = match op.typ {
+            TT::BarBar => return Ok(cmp(a != 0 || b != 0)),
+            TT::AmpAmp => return Ok(cmp(a != 0 && b != 0)),
+            TT::EqualsEquals => return Ok(cmp(a == b)),
+            TT::BangEquals => return Ok(cmp(a != b)),
+            TT::LeftAngleBracket => return Ok(cmp(if unsigned { a < b } else { sa < sb })),
+            TT::RightAngleBracket => return Ok(cmp(if unsigned { a > b } else { sa > sb })),
+            TT::LeftAngleBracketEquals => return Ok(cmp(if unsigned { a <= b } else { sa <= sb })),
+            TT::RightAngleBracketEquals => {
+                return Ok(cmp(if unsigned { a >= b } else { sa >= sb }))
+            }
+
+            // a shift has the type of its left operand
+            TT::LeftShift => {
+                return Ok(Val {
+                    n: a.wrapping_shl(b),
+                    ..l
+                })
+            }
+            TT::RightShift if l.unsigned => {
+                return Ok(Val {
+                    n: a.wrapping_shr(b),
+                    ..l
+                })
+            }
+            TT::RightShift => {
+                return Ok(Val {
+                    n: sa.wrapping_shr(b) as u32,
+                    ..l
+                })
+            }
+
+            TT::Slash | TT::Percent if b == 0 && live => {
+                return self.err(op.span, "division by zero in #if".to_string())
+            }
+            TT::Slash | TT::Percent if b == 0 => 0,
+            TT::Slash if unsigned => a / b,
+            TT::Slash => sa.wrapping_div(sb) as u32,
+            TT::Percent if unsigned => a % b,
+            TT::Percent => sa.wrapping_rem(sb) as u32,
+            TT::Plus => a.wrapping_add(b),
+            TT::Minus => a.wrapping_sub(b),
+            TT::Star => a.wrapping_mul(b),
+            TT::Amp => a & b,
+            TT::Bar => a | b,
+            _ => a ^ b, // Caret
+        };
+
+        Ok(Val { n, unsigned })
+    }
+
+    fn unary(&mut self, live: bool) -> Result<Val, io::Error> {
+        let Some(t) = self.toks.get(self.pos) else {
+            return self.err(self.span(), "expected value in expression".to_string());
+        };
+        self.pos += 1;
+
+        let signed = |n: i32| Val {
+            n: n as u32,
+            unsigned: false,
+        };
+        match t.typ {
+            TT::Plus => self.unary(live),
+            TT::Minus => self.unary(live).map(|v| Val {
+                n: v.n.wrapping_neg(),
+                ..v
+            }),
+            TT::Tilde => self.unary(live).map(|v| Val { n: !v.n, ..v }),
+            TT::Bang => self.unary(live).map(|v| signed((v.n == 0) as i32)),
+            TT::PuncLeftParen => {
+                let v = self.cond(live)?;
+                match self.toks.get(self.pos) {
+                    Some(t) if t.typ == TT::PuncRightParen => self.pos += 1,
+                    _ => return self.err(t.span, "missing ')' in expression".to_string()),
+                }
+                Ok(v)
+            }
+            TT::LiteralInt => match parser_ast::int_lit(t)? {
+                SExprKind::Int(n) | SExprKind::Long(n) => Ok(signed(n)),
+                SExprKind::UInt(n) | SExprKind::ULong(n) => Ok(Val { n, unsigned: true }),
+                _ => unreachable!("int_lit only makes integers"),
+            },
+            TT::LiteralChar => match parser_ast::char_lit(t)? {
+                SExprKind::Int(n) => Ok(signed(n)),
+                _ => unreachable!("char_lit only makes ints"),
+            },
+            TT::LiteralFloat => self.err(
+                t.span,
+                "floating constant in preprocessor expression".to_string(),
+            ),
+            _ if ident(t) => Ok(signed(0)),
+            _ => {
+                let msg = format!(
+                    "token \"{}\" is not valid in preprocessor expressions",
+                    t.lexeme
+                );
+                self.err(t.span, msg)
+            }
+        }
+    }
+
+    // where a missing token should have been
+    fn span(&self) -> Span {
+        self.toks
+            .get(self.pos)
+            .or(self.toks.last())
+            .map_or(self.at, |t| t.span)
+    }
+}
+
+// keywords are still plain identifiers while preprocessing
+fn ident(t: &Token) -> bool {
+    t.lexeme
+        .starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+}
+
+fn lexeme(t: Option<&Token>) -> &str {
+    t.map_or("", |t| &t.lexeme)
+}
+
+// tokens as written, one space wherever the source had any
+fn join(toks: &[Token]) -> String {
+    let mut s = String::new();
+    for (i, t) in toks.iter().enumerate() {
+        if i > 0 && t.space {
+            s.push(' ');
+        }
+        s.push_str(&t.lexeme);
+    }
+
+    s
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// #x: the argument spelled as a string literal, with the " and \ of string
+// and character constants escaped (C89 3.8.3.2)
+fn stringify(arg: &[PTok]) -> Token {
+    let mut s = String::new();
+    for (i, t) in arg.iter().enumerate() {
+        if i > 0 && t.tok.space {
+            s.push(' ');
+        }
+        for c in t.tok.lexeme.chars() {
+            if matches!(t.tok.typ, TT::LiteralString | TT::LiteralChar) && matches!(c, '"' | '\\') {
+                s.push('\\');
+            }
+            s.push(c);
+        }
+    }
+
+    Token {
+        lexeme: format!("\"{s}\""),
+        typ: TT::LiteralString,
+        span: Span::default(),
+        bol: false,
+        space: false,
+    }
+}
+
+fn with(hide: &HideSet, name: &str) -> HideSet {
+    let mut hide = (**hide).clone();
+    hide.insert(name.to_owned());
+    Rc::new(hide)
+}
+
+fn union(a: &HideSet, b: &HideSet) -> HideSet {
+    match (a.is_empty(), b.is_empty()) {
+        (_, true) => a.clone(),
+        (true, _) => b.clone(),
+        _ => Rc::new(a.union(b).cloned().collect()),
+    }
+}
+
+fn intersect(a: &HideSet, b: &HideSet) -> HideSet {
+    Rc::new(a.intersection(b).cloned().collect())
+}
+
+// phase 1: the nine trigraphs ??= ??( ??/ ??) ??' ??< ??! ??> ??- (C89 5.2.1.1)
+fn trigraphs(src: &str) -> String {
+    let (mut out, mut rest) = (String::new(), src);
+    while let Some(i) = rest.find("??") {
+        let c = match rest.as_bytes().get(i + 2) {
+            Some(b'=') => '#',
+            Some(b'(') => '[',
+            Some(b'/') => '\\',
+            Some(b')') => ']',
+            Some(b'\'') => '^',
+            Some(b'<') => '{',
+            Some(b'!') => '|',
+            Some(b'>') => '}',
+            Some(b'-') => '~',
+            _ => {
+                out.push_str(&rest[..i + 1]);
+                rest = &rest[i + 1..];
+                continue;
+            }
+        };
+
+        out.push_str(&rest[..i]);
+        out.push(c);
+        rest = &rest[i + 3..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+// "Mmm dd yyyy" and "hh:mm:ss" (C89 3.8.8) in UTC. days to a civil date
+// after Howard Hinnant's days_from_civil inverse
+fn date_time(epoch: u64) -> (String, String) {
+    let (days, secs) = ((epoch / 86400) as i64, epoch % 86400);
+    let z = days + 719468;
+    let (era, doe) = (z.div_euclid(146097), z.rem_euclid(146097));
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + (m <= 2) as i64;
+
+    let date = format!("\"{} {d:2} {y}\"", MONTHS[m as usize - 1]);
+    let time = format!(
+        "\"{:02}:{:02}:{:02}\"",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    );
+    (date, time)
+}
+
+#[cfg(test)]
+mod test_preprocess {
+    use super::PpOpts;
+    use crate::diagnostic;
+    use crate::source::SourceMap;
+    use std::fs;
+    use std::path::PathBuf;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/preprocessor";
+
+    fn opts() -> PpOpts {
+        PpOpts {
+            trigraphs: true,
+            include_dirs: vec![PathBuf::from(format!("{TEST_DIR}/sys"))],
+            epoch: 951782400, // 2000-02-29 00:00:00
+            ..PpOpts::default()
+        }
+    }
+
+    // the output spelled back out, one line per logical line of input
+    fn pp(name: &str) -> String {
+        let mut sm = SourceMap::new();
+        match super::preprocess(&mut sm, &format!("{TEST_DIR}/{name}"), &opts()) {
+            Ok(toks) => {
+                let mut out = String::new();
+                for (i, t) in toks.iter().enumerate() {
+                    match (t.bol, t.space) {
+                        (true, _) if i > 0 => out.push('\n'),
+                        (false, true) => out.push(' '),
+                        _ => (),
+                    }
+                    out.push_str(&t.lexeme);
+                }
+                out
+            }
+            Err(e) => diagnostic::render(&e, &sm),
+        }
+    }
+
+    #[test]
+    fn object() {
+        insta::assert_snapshot!(pp("object.c"), @r###"
+        int main() {
+        return 8 + 8;
+        }
+        foo a b
+        N
+        "###);
+    }
+
+    #[test]
+    fn function() {
+        insta::assert_snapshot!(pp("function.c"), @r###"
+        f(2 * (y+1)) + f(2 * (f(2 * (z[0])))) % f(2 * (0)) + t(1);
+        f(2 * (2+(3,4)-0,1)) | f(2 * (~ 5)) & f(2 * (0,1))^m(0,1);
+        ((f(2 * (1))) > ((2, 3)) ? (f(2 * (1))) : ((2, 3))) max 0 nil
+        "###);
+    }
+
+    #[test]
+    fn stringify() {
+        insta::assert_snapshot!(pp("stringify.c"), @r###"
+        printf("x" "1" "= %d, x" "2" "= %s", x1, x2);
+        fputs("strncmp(\"abc\\0d\", \"abc\", '\\4') == 0", s);
+        "vers2.h"
+        "hello";
+        "hello" ", world"
+        x x += 1.5e ""
+        ": @\n"
+        "###);
+    }
+
+    #[test]
+    fn conditionals() {
+        insta::assert_snapshot!(pp("conditionals.c"), @r###"
+        yes1
+        yes2
+        yes3
+        yes4
+        yes5
+        yes6
+        "###);
+    }
+
+    #[test]
+    fn include() {
+        insta::assert_snapshot!(pp("include.c"), @r###"
+        int b;
+        int once;
+        #pragma pack (1)
+        int main() { return 1 + 2 + 4 + 3; }
+        "###);
+    }
+
+    #[test]
+    fn builtins() {
+        insta::assert_snapshot!(pp("builtins.c"), @r###"
+        1 1
+        "Feb 29 2000" "00:00:00"
+        6
+        100 "tests/fixtures/snap/preprocessor/builtins.c"
+        "foo.c" 7
+        [ ] { } | ^ ~ ?#
+        "###);
+    }
+
+    #[test]
+    fn headers() {
+        let mut sm = SourceMap::new();
+        let path = format!("{TEST_DIR}/headers.c");
+        let toks = super::preprocess(&mut sm, &path, &PpOpts::default()).unwrap();
+
+        // what the headers declare is left out, only what headers.c expands to
+        let main = toks.last().unwrap().span.file;
+        let toks = toks
+            .into_iter()
+            .filter(|t| t.span.file == main)
+            .collect::<Vec<_>>();
+        insta::assert_snapshot!(super::print(&toks, &sm), @r###"
+        # 1 "tests/fixtures/snap/preprocessor/headers.c"
+        # 11 "tests/fixtures/snap/preprocessor/headers.c"
+        int ilp32;
+
+        ((void *)0) (-1) 1 ((size_t)&((struct s *)0)->m) 3.40282347E+38F 2.2204460492503131E-16
+        ((ap) = ((char *)(((unsigned long)(ap) + ((sizeof(double) + 3) & ~3) - 1) & ~(unsigned long)(((sizeof(double) + 3) & ~3) - 1))) + ((sizeof(double) + 3) & ~3), *(double *)((ap) - ((sizeof(double) + 3) & ~3)))
+        "###);
+    }
+
+    // the libc in runtime/ against the built-in headers, as test.sh builds it
+    #[test]
+    fn runtime() {
+        let mut paths = fs::read_dir("runtime/libc")
+            .expect("runtime dne")
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|e| e == "c"))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        let out = paths
+            .iter()
+            .map(|p| {
+                let mut sm = SourceMap::new();
+                let path = p.to_str().unwrap();
+                match super::preprocess(&mut sm, path, &PpOpts::default()) {
+                    Ok(toks) => format!("{path}: {} tokens\n", toks.len()),
+                    Err(e) => diagnostic::render(&e, &sm),
+                }
+            })
+            .collect::<String>();
+        insta::assert_snapshot!(out, @r###"
+        runtime/libc/ctype.c: 358 tokens
+        runtime/libc/stdio.c: 4079 tokens
+        runtime/libc/stdlib.c: 2034 tokens
+        runtime/libc/string.c: 1082 tokens
+        "###);
+    }
+
+    #[test]
+    fn cmdline() {
+        let opts = PpOpts {
+            defines: vec![
+                ("N".to_string(), Some("1".to_string())),
+                ("M".to_string(), Some("2".to_string())),
+                ("M".to_string(), None),
+                ("F(x)".to_string(), Some("x + x".to_string())),
+            ],
+            ..PpOpts::default()
+        };
+
+        let mut sm = SourceMap::new();
+        let toks = super::preprocess(&mut sm, &format!("{TEST_DIR}/cmdline.c"), &opts).unwrap();
+        insta::assert_snapshot!(super::print(&toks, &sm), @r###"
+        # 1 "tests/fixtures/snap/preprocessor/cmdline.c"
+        1 M 1 + 1
+        "###);
+    }
+
+    #[test]
+    fn line_markers() {
+        let opts = PpOpts {
+            include_dirs: vec![PathBuf::from(format!("{TEST_DIR}/sys"))],
+            ..PpOpts::default()
+        };
+
+        let mut sm = SourceMap::new();
+        let toks = super::preprocess(&mut sm, &format!("{TEST_DIR}/markers.c"), &opts).unwrap();
+        insta::assert_snapshot!(super::print(&toks, &sm), @r###"
+        # 1 "tests/fixtures/snap/preprocessor/markers.c"
+        # 2 "tests/fixtures/snap/preprocessor/inc/b.h" 1
+        int b;
+        # 3 "tests/fixtures/snap/preprocessor/markers.c" 2
+        int x = - -1;
+
+        int y;
+
+
+
+        int z;
+        # 40 "renamed.c"
+        int w;
+            #pragma weak w
+        "###);
+    }
+
+    // a redefinition that only differs in white space is no error
+    #[test]
+    fn redefined_same() {
+        insta::assert_snapshot!(pp("redefined_same.c"), @"1");
+    }
+
+    #[test]
+    fn errors() {
+        let mut names = fs::read_dir(format!("{TEST_DIR}/errors"))
+            .expect("dir dne")
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+
+        let errs = diagnostic::diags(names.iter().map(String::as_str), |n| {
+            let path = format!("{TEST_DIR}/errors/{n}");
+            super::preprocess(&mut SourceMap::new(), &path, &opts())
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "arg_count.c: E0002 2:1 macro \"f\" requires 2 arguments, but only 1 given"
+        - "arg_count_many.c: E0002 2:1 macro \"f\" passed 2 arguments, but takes just 1"
+        - "define_builtin.c: E0002 1:9 \"__LINE__\" cannot be used as a macro name"
+        - "define_empty.c: E0002 1:2 no macro name given in #define directive"
+        - "define_number.c: E0002 1:9 macro names must be identifiers"
+        - "div_zero.c: E0002 1:7 division by zero in #if"
+        - "dup_param.c: E0002 1:14 duplicate macro parameter \"a\""
+        - "elif.c: E0002 4:2 #elif without #if"
+        - "else_after_else.c: E0002 3:2 #else after #else"
+        - "else_else.c: E0002 1:2 #else without #if"
+        - "endif.c: E0002 1:2 #endif without #if"
+        - "error.c: E0002 1:2 #error stop right \"here\""
+        - "hash.c: E0002 1:14 '#' is not followed by a macro parameter"
+        - "hashhash.c: E0002 1:14 '##' cannot appear at either end of a macro expansion"
+        - "if_defined.c: E0002 1:5 operator \"defined\" requires an identifier"
+        - "if_empty.c: E0002 1:2 #if with no expression"
+        - "if_float.c: E0002 1:5 floating constant in preprocessor expression"
+        - "if_incomplete.c: E0002 1:7 expected value in expression"
+        - "if_paren.c: E0002 1:5 missing ')' in expression"
+        - "if_trailing.c: E0002 1:7 missing binary operator before token \"2\""
+        - "include_empty.c: E0002 1:2 #include expects \"FILENAME\" or <FILENAME>"
+        - "include_missing.c: E0002 1:2 'nope.h' file not found"
+        - "invalid.c: E0002 1:2 invalid preprocessing directive #foo"
+        - "lex.c: E0001 1:9 missing terminating ' character"
+        - "lex_active.c: E0001 3:1 missing terminating ' character"
+        - "line_zero.c: E0002 1:7 \"0\" after #line is not a positive integer"
+        - "param_name.c: E0002 1:11 expected parameter name, found \"1\""
+        - "param_paren.c: E0002 1:10 missing ')' in macro parameter list"
+        - "param_sep.c: E0002 1:13 expected ',' or ')', found \"b\""
+        - "paste.c: E0002 2:5 pasting \"+\" and \"/\" does not give a valid preprocessing token"
+        - "redefined.c: E0002 2:9 \"N\" redefined"
+        - "unterminated_args.c: E0002 2:1 unterminated argument list invoking macro \"f\""
+        - "unterminated_if.c: E0002 1:2 unterminated conditional directive"
+        "###);
+    }
+}
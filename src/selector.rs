@@ -1,181 +1,418 @@
 use crate::{
-    fresh_temp, IBinOp, IExpr, IPrg, IStmt, PseudoOp, RiscvPointerReg, TImmOp, TMemOp, TQuad,
-    TRegOp, Temp,
+    fresh_temp, FloatMode, IBinOp, IExpr, INum, IPrg, IRelOp, IStmt, Label, PseudoOp,
+    RiscvPointerReg, TFCvtOp, TFRegOp, TFmt, TImmOp, TMemOp, TQuad, TRegOp, Temp, Width,
 };
 
-pub fn select(prg: &IPrg) -> Vec<TQuad> {
-    let trgt_prg = prg.iter().flat_map(select_stmt).collect();
+pub fn select(prg: &IPrg, fm: FloatMode) -> Vec<TQuad> {
+    let trgt_prg = prg.iter().flat_map(|s| select_stmt(s, &fm)).collect();
     trgt_prg
 }
 
-fn select_stmt(s: &IStmt) -> Vec<TQuad> {
+fn select_stmt(s: &IStmt, fm: &FloatMode) -> Vec<TQuad> {
     match s {
-        IStmt::Jump(_) => todo!(),
-        IStmt::CJump(_sexpr, _, _) => todo!(),
+        IStmt::Label(l) => vec![TQuad::Label(l.clone())],
+        IStmt::Jump(l) => vec![TQuad::Jump(l.clone())],
+        IStmt::CJump(e, t, f) => {
+            let ctemp = fresh_temp();
+            let cq = select_expr(ctemp.clone(), e, fm);
+            let instr = vec![TQuad::CJump(ctemp, t.clone(), f.clone())];
+
+            cq.into_iter().chain(instr).collect()
+        }
+        // a call to a void function leaves nothing to drop
+        IStmt::Exp(e) => {
+            let etemp = fresh_temp();
+            let eq = select_expr(etemp.clone(), e, fm);
+            let instr = (expr_words(e) > 0).then_some(TQuad::Pop(etemp));
+
+            eq.into_iter().chain(instr).collect()
+        }
         IStmt::Compute(_temp, _iexpr) => todo!(),
-        IStmt::Load(_, _) => todo!(),
-        IStmt::Store(_, _) => todo!(),
-        IStmt::Seq(l, stmts) => {
+        IStmt::Store(w, addr, e) => {
+            let (vtemp, atemp) = (fresh_temp(), fresh_temp());
+            let (vq, aq) = (
+                select_expr(vtemp.clone(), e, fm),
+                select_expr(atemp.clone(), addr, fm),
+            );
+            let instr = vec![TQuad::Mem(TMemOp::Store, w.clone(), vtemp, 0, atemp)];
+
+            vq.into_iter().chain(aq).chain(instr).collect()
+        }
+        IStmt::Copy(w, n, dst, src) => {
+            let (dtemp, stemp) = (fresh_temp(), fresh_temp());
+            let (dq, sq) = (
+                select_expr(dtemp.clone(), dst, fm),
+                select_expr(stemp.clone(), src, fm),
+            );
+            let instr = vec![TQuad::Copy(w.clone(), *n, dtemp, stemp)];
+
+            dq.into_iter().chain(sq).chain(instr).collect()
+        }
+        IStmt::Func(l, exported, locals, regs, stmts) => {
+            // the saved argument registers, ra and fp, then the locals,
+            // keeping sp 16-byte aligned
+            let size = (regs + 8 + locals).next_multiple_of(16);
+            let sp = || Temp::PointerReg(RiscvPointerReg::Sp);
+            let export = exported.then(|| TQuad::Globl(l.clone()));
             let prologue = vec![
                 TQuad::Label(l.clone()),
-                // allocate 4 words
-                TQuad::Imm(
-                    TImmOp::AddI,
-                    Temp::PointerReg(RiscvPointerReg::Sp),
-                    Temp::PointerReg(RiscvPointerReg::Sp),
-                    -16,
-                ),
+                // allocate the frame
+                TQuad::Imm(TImmOp::AddI, sp(), sp(), -(size as i32)),
                 // save caller's ra
                 TQuad::Mem(
                     TMemOp::Store,
+                    Width::W,
                     Temp::PointerReg(RiscvPointerReg::Ra),
-                    12,
-                    RiscvPointerReg::Sp,
+                    size - regs - 4,
+                    sp(),
                 ),
                 // save caller's fp (s0)
                 TQuad::Mem(
                     TMemOp::Store,
+                    Width::W,
                     Temp::PointerReg(RiscvPointerReg::Fp),
-                    8,
-                    RiscvPointerReg::Sp,
+                    size - regs - 8,
+                    sp(),
                 ),
                 // setup callee's fp
                 TQuad::Imm(
                     TImmOp::AddI,
                     Temp::PointerReg(RiscvPointerReg::Fp),
-                    Temp::PointerReg(RiscvPointerReg::Sp),
-                    16,
+                    sp(),
+                    size as i32,
                 ),
             ];
 
-            let _fps: Vec<TQuad> = vec![];
-
             let body = stmts
                 .iter()
-                .flat_map(|stmt| select_stmt(stmt))
+                .flat_map(|stmt| select_stmt(stmt, fm))
                 .collect::<Vec<_>>();
 
             let epilogue = vec![
                 // restore ra
                 TQuad::Mem(
                     TMemOp::Load,
+                    Width::W,
                     Temp::PointerReg(RiscvPointerReg::Ra),
-                    12,
-                    RiscvPointerReg::Sp,
+                    size - regs - 4,
+                    sp(),
                 ),
                 // restore fp
                 TQuad::Mem(
                     TMemOp::Load,
+                    Width::W,
                     Temp::PointerReg(RiscvPointerReg::Fp),
-                    8,
-                    RiscvPointerReg::Sp,
-                ),
-                // deallocate 4 words
-                TQuad::Imm(
-                    TImmOp::AddI,
-                    Temp::PointerReg(RiscvPointerReg::Sp),
-                    Temp::PointerReg(RiscvPointerReg::Sp),
-                    16,
+                    size - regs - 8,
+                    sp(),
                 ),
+                // deallocate the frame
+                TQuad::Imm(TImmOp::AddI, sp(), sp(), size as i32),
                 // ret
                 TQuad::Pseudo(PseudoOp::Ret),
             ];
 
-            prologue.into_iter().chain(body).chain(epilogue).collect()
+            export
+                .into_iter()
+                .chain(prologue)
+                .chain(body)
+                .chain(epilogue)
+                .collect()
         }
+        IStmt::Data(g) => vec![TQuad::Data(g.clone())],
         IStmt::Return(iexpr) => {
             let t = fresh_temp();
-            let expr_instrs = select_expr(t.clone(), iexpr);
-            let ret_instr = vec![TQuad::Imm(
-                TImmOp::AddI,
-                Temp::PointerReg(RiscvPointerReg::A0),
-                t,
-                0,
-            )];
+            let expr_instrs = select_expr(t.clone(), iexpr, fm);
+            let ret_instr = vec![TQuad::RetVal(expr_words(iexpr))];
 
             expr_instrs.into_iter().chain(ret_instr).collect()
         }
     }
 }
 
-fn select_expr(d: Temp, e: &IExpr) -> Vec<TQuad> {
+fn select_expr(d: Temp, e: &IExpr, fm: &FloatMode) -> Vec<TQuad> {
     match e {
+        // addi only takes a 12-bit signed immediate
+        IExpr::Const(n) if !(-2048..2048).contains(n) => vec![TQuad::Pseudo(PseudoOp::Li(d, *n))],
         IExpr::Const(n) => vec![TQuad::Imm(
             TImmOp::AddI,
             d,
             Temp::PointerReg(RiscvPointerReg::Z),
             *n,
         )],
+        IExpr::FConst(INum::F32, f) => vec![TQuad::FImm(TFmt::S, d, (*f as f32).to_bits() as u64)],
+        IExpr::FConst(_, f) => vec![TQuad::FImm(TFmt::D, d, f.to_bits())],
+        IExpr::FBinOp(n, op, l, r) => {
+            let fmt = fmt(n);
+            let (ltemp, rtemp) = (fresh_temp(), fresh_temp());
+            let (lq, rq) = (
+                select_expr(ltemp.clone(), l, fm),
+                select_expr(rtemp.clone(), r, fm),
+            );
+
+            let instr = match fm {
+                FloatMode::Hard => {
+                    let op = match op {
+                        IBinOp::Add => TFRegOp::FAdd,
+                        IBinOp::Sub => TFRegOp::FSub,
+                        IBinOp::Mult => TFRegOp::FMul,
+                        IBinOp::Div => TFRegOp::FDiv,
+                        _ => unreachable!(), // rejected by the typer
+                    };
+                    TQuad::FReg(op, fmt, d, ltemp, rtemp)
+                }
+                FloatMode::Soft => {
+                    let op = match op {
+                        IBinOp::Add => "add",
+                        IBinOp::Sub => "sub",
+                        IBinOp::Mult => "mul",
+                        IBinOp::Div => "div",
+                        _ => unreachable!(), // rejected by the typer
+                    };
+                    let routine = format!("__{op}{fmt}f3");
+                    let w = fmt.words();
+                    TQuad::Call(Label::UserLabel(routine), vec![w, w], w)
+                }
+            };
+
+            lq.into_iter().chain(rq).chain(vec![instr]).collect()
+        }
+        // soft-fp comparisons return an int that is compared with 0: below
+        // it for lt, not above it for le, and 0 for eq. each is false when
+        // an operand is a nan, as flt, fle and feq are
+        IExpr::FRel(n, op, l, r) => {
+            let fmt = fmt(n);
+            let (ltemp, rtemp) = (fresh_temp(), fresh_temp());
+            let (lq, rq) = (
+                select_expr(ltemp.clone(), l, fm),
+                select_expr(rtemp.clone(), r, fm),
+            );
+
+            let instrs = match fm {
+                FloatMode::Hard => {
+                    let op = match op {
+                        IRelOp::Lt => TFRegOp::FLt,
+                        IRelOp::LtEq => TFRegOp::FLe,
+                        IRelOp::Eq => TFRegOp::FEq,
+                        _ => unreachable!(), // the translator swaps and negates the others
+                    };
+                    vec![TQuad::FReg(op, fmt, d, ltemp, rtemp)]
+                }
+                FloatMode::Soft => {
+                    let (op, cmp, k) = match op {
+                        IRelOp::Lt => ("lt", TRegOp::Slt, 0),
+                        IRelOp::LtEq => ("le", TRegOp::Slt, 1),
+                        IRelOp::Eq => ("eq", TRegOp::Sltu, 1),
+                        _ => unreachable!(), // the translator swaps and negates the others
+                    };
+                    let routine = format!("__{op}{fmt}f2");
+                    let w = fmt.words();
+                    let (ctemp, ktemp) = (fresh_temp(), fresh_temp());
+                    [TQuad::Call(Label::UserLabel(routine), vec![w, w], 1)]
+                        .into_iter()
+                        .chain(select_expr(ktemp.clone(), &IExpr::Const(k), fm))
+                        .chain([TQuad::Reg(cmp, d, ctemp, ktemp)])
+                        .collect()
+                }
+            };
+
+            lq.into_iter().chain(rq).chain(instrs).collect()
+        }
+        IExpr::FNeg(n, e) => {
+            let fmt = fmt(n);
+            let stemp = fresh_temp();
+            let sq = select_expr(stemp.clone(), e, fm);
+
+            let instr = match fm {
+                FloatMode::Hard => TQuad::FNeg(fmt, d, stemp),
+                FloatMode::Soft => {
+                    let w = fmt.words();
+                    TQuad::Call(Label::UserLabel(format!("__neg{fmt}f2")), vec![w], w)
+                }
+            };
+
+            sq.into_iter().chain(vec![instr]).collect()
+        }
+        IExpr::Cvt(from, to, e) => {
+            let stemp = fresh_temp();
+            let sq = select_expr(stemp.clone(), e, fm);
+
+            let instr = match fm {
+                FloatMode::Hard => {
+                    let op = match (from, to) {
+                        (INum::I32, INum::F32) => TFCvtOp::WToS,
+                        (INum::U32, INum::F32) => TFCvtOp::WuToS,
+                        (INum::I32, INum::F64) => TFCvtOp::WToD,
+                        (INum::U32, INum::F64) => TFCvtOp::WuToD,
+                        (INum::F32, INum::I32) => TFCvtOp::SToW,
+                        (INum::F32, INum::U32) => TFCvtOp::SToWu,
+                        (INum::F64, INum::I32) => TFCvtOp::DToW,
+                        (INum::F64, INum::U32) => TFCvtOp::DToWu,
+                        (INum::F32, INum::F64) => TFCvtOp::SToD,
+                        (INum::F64, INum::F32) => TFCvtOp::DToS,
+                        _ => unreachable!(), // integer conversions keep their bits
+                    };
+                    TQuad::FCvt(op, d, stemp)
+                }
+                FloatMode::Soft => {
+                    let routine = match (from, to) {
+                        (INum::I32, INum::F32) => "__floatsisf",
+                        (INum::U32, INum::F32) => "__floatunsisf",
+                        (INum::I32, INum::F64) => "__floatsidf",
+                        (INum::U32, INum::F64) => "__floatunsidf",
+                        (INum::F32, INum::I32) => "__fixsfsi",
+                        (INum::F32, INum::U32) => "__fixunssfsi",
+                        (INum::F64, INum::I32) => "__fixdfsi",
+                        (INum::F64, INum::U32) => "__fixunsdfsi",
+                        (INum::F32, INum::F64) => "__extendsfdf2",
+                        (INum::F64, INum::F32) => "__truncdfsf2",
+                        _ => unreachable!(), // integer conversions keep their bits
+                    };
+                    let (fw, tw) = (words(from), words(to));
+                    TQuad::Call(Label::UserLabel(routine.to_owned()), vec![fw], tw)
+                }
+            };
+
+            sq.into_iter().chain(vec![instr]).collect()
+        }
         IExpr::BinOp(op, l, r) => {
+            // an address at a small offset from a register is one addi
+            if let (IBinOp::Add, IExpr::TempUse(pr @ Temp::PointerReg(_)), IExpr::Const(n)) =
+                (op, &**l, &**r)
+            {
+                if (-2048..2048).contains(n) {
+                    return vec![TQuad::Imm(TImmOp::AddI, d, pr.clone(), *n)];
+                }
+            }
+
             let op = match op {
                 IBinOp::Add => TRegOp::Add,
                 IBinOp::Sub => TRegOp::Sub,
-                IBinOp::Mult => todo!(), // RV32M
-                IBinOp::Div => todo!(),  // RV32M
-                IBinOp::Mod => todo!(),  // RV32M
+                IBinOp::Mult => TRegOp::Mul, // RV32M
+                IBinOp::Div => TRegOp::Div,
+                IBinOp::DivU => TRegOp::Divu,
+                IBinOp::Mod => TRegOp::Rem,
+                IBinOp::ModU => TRegOp::Remu,
+                IBinOp::And => TRegOp::And,
+                IBinOp::Or => TRegOp::Or,
+                IBinOp::Xor => TRegOp::Xor,
+                IBinOp::Shl => TRegOp::Sll,
+                IBinOp::Shr => TRegOp::Srl,
+                IBinOp::Sar => TRegOp::Sra,
+                IBinOp::Lt => TRegOp::Slt,
+                IBinOp::LtU => TRegOp::Sltu,
             };
 
             let (ltemp, rtemp) = (fresh_temp(), fresh_temp());
-            let (lq, rq) = (select_expr(ltemp.clone(), l), select_expr(rtemp.clone(), r));
+            let (lq, rq) = (
+                select_expr(ltemp.clone(), l, fm),
+                select_expr(rtemp.clone(), r, fm),
+            );
             let instr = vec![TQuad::Reg(op, d, ltemp, rtemp)];
 
             lq.into_iter().chain(rq).chain(instr).collect()
         }
+        IExpr::TempUse(pr @ Temp::PointerReg(_)) => {
+            vec![TQuad::Imm(TImmOp::AddI, d, pr.clone(), 0)]
+        }
         IExpr::TempUse(_) => todo!(),
-        IExpr::Call(l, aps) => {
-            if aps.len() > 8 {
-                panic!("todo: more than 8 args not supported");
-            }
+        IExpr::Name(l) => vec![TQuad::Pseudo(PseudoOp::La(d, l.clone()))],
+        IExpr::Mem(w, addr) => {
+            let atemp = fresh_temp();
+            let aq = select_expr(atemp.clone(), addr, fm);
+            let instr = vec![TQuad::Mem(TMemOp::Load, w.clone(), d, 0, atemp)];
 
-            let aps = aps.iter().enumerate().map(|(i, a)| match i {
-                0 => select_expr(Temp::PointerReg(RiscvPointerReg::A0), a),
-                1 => select_expr(Temp::PointerReg(RiscvPointerReg::A1), a),
-                2 => select_expr(Temp::PointerReg(RiscvPointerReg::A2), a),
-                3 => select_expr(Temp::PointerReg(RiscvPointerReg::A3), a),
-                4 => select_expr(Temp::PointerReg(RiscvPointerReg::A4), a),
-                5 => select_expr(Temp::PointerReg(RiscvPointerReg::A5), a),
-                6 => select_expr(Temp::PointerReg(RiscvPointerReg::A6), a),
-                7 => select_expr(Temp::PointerReg(RiscvPointerReg::A7), a),
-                _ => unreachable!(),
-            });
+            aq.into_iter().chain(instr).collect()
+        }
+        IExpr::ESeq(s, e) => {
+            let sq = select_stmt(s, fm);
+            let eq = select_expr(d, e, fm);
+
+            sq.into_iter().chain(eq).collect()
+        }
+        IExpr::Call(l, aps, ret) => {
+            let ws = aps.iter().map(expr_words).collect::<Vec<_>>();
+
+            // arguments are evaluated before any of them is moved to its
+            // register, so that calls among them cannot clobber the others
+            let aps = aps
+                .iter()
+                .flat_map(|a| select_expr(fresh_temp(), a, fm))
+                .collect::<Vec<_>>();
 
             aps.into_iter()
-                .flatten()
-                .chain(vec![
-                    TQuad::Pseudo(PseudoOp::Call(l.clone())),
-                    TQuad::Imm(TImmOp::AddI, d, Temp::PointerReg(RiscvPointerReg::A0), 0),
-                ])
+                .chain(vec![TQuad::Call(l.clone(), ws, *ret)])
+                .collect()
+        }
+        IExpr::CallPtr(p, aps, ret) => {
+            let ws = aps.iter().map(expr_words).collect::<Vec<_>>();
+            let pq = select_expr(fresh_temp(), p, fm);
+            let aps = aps
+                .iter()
+                .flat_map(|a| select_expr(fresh_temp(), a, fm))
+                .collect::<Vec<_>>();
+
+            pq.into_iter()
+                .chain(aps)
+                .chain(vec![TQuad::CallPtr(ws, *ret)])
                 .collect()
         }
     }
 }
 
+fn fmt(n: &INum) -> TFmt {
+    match n {
+        INum::F32 => TFmt::S,
+        _ => TFmt::D,
+    }
+}
+
+fn words(n: &INum) -> usize {
+    match n {
+        INum::F64 => 2,
+        _ => 1,
+    }
+}
+
+// how many words of the stack machine e's value takes
+fn expr_words(e: &IExpr) -> usize {
+    match e {
+        IExpr::FConst(n, _) | IExpr::FBinOp(n, ..) | IExpr::FNeg(n, _) | IExpr::Cvt(_, n, _) => {
+            words(n)
+        }
+        IExpr::Mem(w, _) => w.bytes().div_ceil(4),
+        IExpr::ESeq(_, e) => expr_words(e),
+        IExpr::Call(_, _, ret) | IExpr::CallPtr(_, _, ret) => *ret,
+        _ => 1,
+    }
+}
+
 #[cfg(test)]
 mod test_arith {
     use crate::lexer;
     use crate::parser_ast;
     use crate::translator;
     use crate::typer;
+    use crate::FloatMode;
     use std::fs;
 
     const TEST_DIR: &str = "tests/fixtures/snap/shared/arith";
 
     #[test]
     fn add() {
-        let chars = fs::read(format!("{TEST_DIR}/add.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/add.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let src_tree = parser_ast::parse_prg(&tokens).unwrap();
-        let _ = typer::type_prg(&src_tree).unwrap();
-        let trgt_tree = translator::translate(&src_tree);
-        let abs_as = super::select(&trgt_tree);
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = translator::translate(&typed_tree).unwrap();
+        let abs_as = super::select(&trgt_tree, FloatMode::Soft);
 
         insta::assert_yaml_snapshot!(abs_as, @r###"
         ---
+        - Globl:
+            UserLabel: main
+        - Label:
+            UserLabel: main
         - Imm:
             - AddI
             - PointerReg: Sp
@@ -183,14 +420,16 @@ mod test_arith {
             - -16
         - Mem:
             - Store
+            - W
             - PointerReg: Ra
             - 12
-            - Sp
+            - PointerReg: Sp
         - Mem:
             - Store
+            - W
             - PointerReg: Fp
             - 8
-            - Sp
+            - PointerReg: Sp
         - Imm:
             - AddI
             - PointerReg: Fp
@@ -211,21 +450,19 @@ mod test_arith {
             - MachineTemp: 0
             - MachineTemp: 1
             - MachineTemp: 2
-        - Imm:
-            - AddI
-            - PointerReg: Ra
-            - MachineTemp: 0
-            - 0
+        - RetVal: 1
         - Mem:
             - Load
+            - W
             - PointerReg: Ra
             - 12
-            - Sp
+            - PointerReg: Sp
         - Mem:
             - Load
+            - W
             - PointerReg: Fp
             - 8
-            - Sp
+            - PointerReg: Sp
         - Imm:
             - AddI
             - PointerReg: Sp
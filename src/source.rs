@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+
+// spans are byte ranges into one file of the source map. line and col are
+// resolved once at lex time so later stages never have to rescan the source.
+// tokens produced by a macro expansion point at its invocation and carry the
+// id of the expansion (0 for none), which leads back to the #define
+#[rustfmt::skip]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Span { pub file: usize, pub lo: usize, pub hi: usize, pub line: usize, pub col: usize, pub expn: usize }
+
+impl Span {
+    pub fn new(file: usize, lo: usize, hi: usize, line: usize, col: usize) -> Self {
+        Self {
+            file,
+            lo,
+            hi,
+            line,
+            col,
+            expn: 0,
+        }
+    }
+
+    // smallest span covering both self and other. position is taken from self
+    pub fn to(&self, other: Span) -> Span {
+        if *self == Span::default() {
+            return other;
+        }
+        if other == Span::default() || other.file != self.file {
+            return *self;
+        }
+
+        Span {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
+            ..*self
+        }
+    }
+
+    pub fn is_dummy(&self) -> bool {
+        *self == Span::default()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SourceFile {
+    pub name: String,
+    pub src: String,
+    pub marks: Vec<LineMark>,
+    pub includer: Option<usize>,
+}
+
+// left by a #line directive: physical lines from `from` on are presumed to
+// be numbered from `line` and to come from `name`
+#[derive(Clone, PartialEq, Debug)]
+pub struct LineMark {
+    pub from: usize,
+    pub line: usize,
+    pub name: String,
+}
+
+impl SourceFile {
+    // where a physical line claims to be, which is what users are shown
+    pub fn presumed(&self, line: usize) -> (&str, usize) {
+        match self.marks.iter().rev().find(|m| m.from <= line) {
+            Some(m) => (&m.name, m.line + (line - m.from)),
+            None => (&self.name, line),
+        }
+    }
+
+    // the full text of the 1-based line, without its terminator
+    pub fn line(&self, line: usize) -> Option<&str> {
+        self.src
+            .split('\n')
+            .nth(line.checked_sub(1)?)
+            .map(|l| l.strip_suffix('\r').unwrap_or(l))
+    }
+}
+
+// one invocation of a macro: where it was invoked and where it was defined.
+// call has an expansion of its own when the invocation came out of another
+#[derive(Clone, PartialEq, Debug)]
+pub struct Expansion {
+    pub name: String,
+    pub call: Span,
+    pub def: Span,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+    expansions: Vec<Expansion>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, name: &str, src: &str) -> usize {
+        self.files.push(SourceFile {
+            name: name.to_owned(),
+            src: src.to_owned(),
+            marks: vec![],
+            includer: None,
+        });
+        self.files.len() - 1
+    }
+
+    pub fn get(&self, file: usize) -> Option<&SourceFile> {
+        self.files.get(file)
+    }
+
+    // the id spans of the expansion's tokens carry
+    pub fn expand(&mut self, e: Expansion) -> usize {
+        self.expansions.push(e);
+        self.expansions.len()
+    }
+
+    pub fn expansion(&self, expn: usize) -> Option<&Expansion> {
+        self.expansions.get(expn.checked_sub(1)?)
+    }
+
+    pub fn include(&mut self, file: usize, includer: usize) {
+        if let Some(f) = self.files.get_mut(file) {
+            f.includer = Some(includer);
+        }
+    }
+
+    pub fn mark(&mut self, file: usize, mark: LineMark) {
+        if let Some(f) = self.files.get_mut(file) {
+            f.marks.push(mark);
+        }
+    }
+}
@@ -1,89 +1,1293 @@
-use crate::{IBinOp, IExpr, IPrg, IStmt, Label, SBinOp, SDef, SExpr, SFuncDef, SPrg, SStmt, Temp};
+use crate::typer::{self, is_float, Constant};
+use crate::{
+    fresh_label, Global, IBinOp, IData, IExpr, INum, IPrg, IRelOp, IStmt, Label, Records,
+    RiscvPointerReg, SAsnmtOp, SBinOp, SBitOp, SDef, SExpr, SExprKind, SFuncDef, SLogOp, SParam,
+    SPrg, SRelOp, SStmt, SStmtKind, SUnaryOp, SUpdateOp, SVarDef, Section, StorageClass, Temp,
+    Type, Width,
+};
+use std::collections::{HashMap, HashSet};
+use std::io;
 
-pub fn translate(src_tree: &SPrg) -> IPrg {
-    let intrm_prg = src_tree
+// the tree is the typer's: its types pick between integer and floating
+// point operations, and its conversions are explicit
+pub fn translate(typed_tree: &SPrg) -> Result<IPrg, io::Error> {
+    let mut globals: Vec<Object> = vec![];
+    let mut file = Frame::new(&typed_tree.records); // where string literals in initializers go
+
+    // a function's first declaration gives its linkage, like an object's
+    let mut internal = HashMap::new();
+    let mut intrm_prg = vec![];
+    for def in &typed_tree.defs {
+        let (alias, sc) = match def {
+            SDef::FuncDef(fd) => (&fd.alias, &fd.sc),
+            SDef::VarDef(vd) if matches!(vd.typ, Type::Func(_)) => (&vd.alias, &vd.sc),
+            SDef::VarDef(_) => continue,
+        };
+        internal
+            .entry(alias)
+            .or_insert(*sc == Some(StorageClass::Static));
+    }
+    for def in &typed_tree.defs {
+        match def {
+            SDef::FuncDef(func_def) => {
+                let exported = !internal[&func_def.alias];
+                intrm_prg.extend(translate_func_def(&typed_tree.records, func_def, exported))
+            }
+            SDef::VarDef(var_def) if matches!(var_def.typ, Type::Func(_)) => (),
+            SDef::VarDef(var_def) => declare(&mut globals, var_def),
+        }
+    }
+
+    intrm_prg.extend(globals.into_iter().filter(|g| g.defined).map(|g| {
+        let label = Label::UserLabel(g.alias);
+        global(label, &g.typ, g.init, g.exported, &mut file)
+    }));
+    intrm_prg.extend(file.statics);
+    Ok(intrm_prg)
+}
+
+// a file-scope object, merged from its declarations: the first gives its
+// linkage, the one with an initializer its contents, and any that is not
+// extern makes it a definition (a tentative one without an initializer)
+struct Object<'a> {
+    alias: String,
+    typ: Type,
+    exported: bool,
+    defined: bool,
+    init: Option<&'a SExpr>,
+}
+
+fn declare<'a>(globals: &mut Vec<Object<'a>>, vd: &'a SVarDef) {
+    let t = vd.typ.clone(); // completed by its initializer
+    let i = match globals.iter().position(|g| g.alias == vd.alias) {
+        Some(i) => i,
+        None => {
+            globals.push(Object {
+                alias: vd.alias.clone(),
+                typ: t.clone(),
+                exported: vd.sc != Some(StorageClass::Static),
+                defined: false,
+                init: None,
+            });
+            globals.len() - 1
+        }
+    };
+    let g = &mut globals[i];
+    g.typ = typer::composite(&g.typ, &t).unwrap();
+    g.defined |= vd.sc != Some(StorageClass::Extern) || vd.expr.is_some();
+    g.init = g.init.or(vd.expr.as_deref());
+}
+
+// an object with static storage: in .rodata when it is const, .bss when it
+// is all zeros and .data otherwise. the string literals its initializer
+// takes the addresses of are the frame's statics
+fn global(
+    label: Label,
+    t: &Type,
+    init: Option<&SExpr>,
+    exported: bool,
+    frame: &mut Frame,
+) -> IStmt {
+    // a tentative definition of an array of unknown size has one element
+    let t = match t.unqual() {
+        Type::Array(et, None) => Type::Array(et.clone(), Some(1)),
+        _ => t.clone(),
+    };
+    let data = contents(&t, init, frame);
+    let zero = data
         .iter()
-        .map(|def| match def {
-            SDef::FuncDef(func_def) => translate_func_def(func_def),
-            SDef::VarDef(_var_def) => todo!(),
-        })
-        .collect::<Vec<_>>();
+        .all(|d| matches!(d, IData::Zero(_) | IData::Value(_, 0)));
+    let (section, data) = match init {
+        Some(_) if is_const(&t) => (Section::RoData, data),
+        Some(_) if !zero => (Section::Data, data),
+        _ => (Section::Bss, vec![IData::Zero(t.size(frame.rs).unwrap())]),
+    };
+    IStmt::Data(Global {
+        label,
+        section,
+        align: t.align(frame.rs).unwrap(),
+        exported,
+        data,
+    })
+}
+
+fn is_const(t: &Type) -> bool {
+    match t {
+        Type::Const(_) => true,
+        Type::Array(et, _) => is_const(et),
+        _ => false,
+    }
+}
+
+// the bytes of an object with static storage, from its constant
+// initializer. what it leaves out is zero
+fn contents(t: &Type, init: Option<&SExpr>, frame: &mut Frame) -> Vec<IData> {
+    let size = t.size(frame.rs).unwrap();
+    let Some(init) = init else {
+        return vec![IData::Zero(size)];
+    };
+    let (_, inits) = typer::flatten(frame.rs, t, init).unwrap();
+
+    let (mut data, mut end) = (vec![], 0);
+    for (off, et, e) in inits {
+        if off > end {
+            data.push(IData::Zero(off - end));
+        }
+        match (&e.kind, et.unqual()) {
+            (SExprKind::Str(s), Type::Array(_, Some(n))) => {
+                let s = &s[..s.len().min(*n)];
+                data.push(IData::Ascii(s.to_vec()));
+                data.extend((*n > s.len()).then(|| IData::Zero(n - s.len())));
+            }
+            _ => {
+                let c = typer::constant(frame.rs, e, &HashSet::new()).expect("a constant");
+                data.extend(value(&et, c, frame))
+            }
+        }
+        end = off + et.size(frame.rs).unwrap();
+    }
+    if size > end {
+        data.push(IData::Zero(size - end));
+    }
+    data
+}
+
+// a constant of type t, in the words it is stored as. an address is its
+// object's label, relocated
+fn value(t: &Type, c: Constant, frame: &mut Frame) -> Vec<IData> {
+    let words = |n: u64| {
+        let (lo, hi) = (n as u32 as i64, (n >> 32) as u32 as i64);
+        vec![IData::Value(Width::W, lo), IData::Value(Width::W, hi)]
+    };
+    match (t.unqual(), c) {
+        (_, Constant::Addr(e, off)) => match address(e, frame) {
+            IExpr::Name(l) => vec![IData::Addr(l, off)],
+            _ => unreachable!("the address of an object without static storage"),
+        },
+        (Type::Float, Constant::Float(f)) => {
+            vec![IData::Value(Width::W, (f as f32).to_bits() as i64)]
+        }
+        (_, Constant::Float(f)) => words(f.to_bits()),
+        (Type::LongLong | Type::ULongLong, Constant::Int(n)) => words(n as u64),
+        (t, Constant::Int(n)) => vec![IData::Value(width(t), n)],
+    }
+}
 
-    intrm_prg
+// a function's locals live below the saved ra and fp, each at a fixed offset
+// from fp. size is how many bytes of them the prologue reserves
+struct Frame<'a> {
+    rs: &'a Records,               // the layouts of the program's records
+    slots: HashMap<String, IExpr>, // a static local's is its label
+    size: usize,
+    regs: usize,       // the bytes of argument registers saved above ra and fp
+    sret: Option<i32>, // where the address a record is returned to was saved
+    statics: Vec<IStmt>,
 }
 
-fn translate_func_def(fd: &SFuncDef) -> IStmt {
+const SAVED: usize = 8;
+
+impl<'a> Frame<'a> {
+    fn new(rs: &'a Records) -> Self {
+        Frame {
+            rs,
+            slots: HashMap::new(),
+            size: 0,
+            regs: 0,
+            sret: None,
+            statics: vec![],
+        }
+    }
+
+    fn alloc(&mut self, size: usize, align: usize) -> i32 {
+        self.size = (self.size + size).next_multiple_of(align);
+        -((SAVED + self.regs + self.size) as i32)
+    }
+
+    // a name not in the frame is a global's
+    fn addr(&self, alias: &str) -> IExpr {
+        self.slots
+            .get(alias)
+            .cloned()
+            .unwrap_or_else(|| IExpr::Name(Label::UserLabel(alias.to_owned())))
+    }
+}
+
+// the function, followed by its static locals
+fn translate_func_def(rs: &Records, fd: &SFuncDef, exported: bool) -> Vec<IStmt> {
     let label = Label::UserLabel(fd.alias.clone());
+    let mut frame = Frame::new(rs);
+
+    // arguments arrive in a0..a7 (then on the stack) and are spilled to the
+    // frame, a record returned in memory taking the first for its address.
+    // a variadic function saves a0..a7 right below its stack arguments
+    // instead, so that its arguments are one run of words that va_arg walks,
+    // and its named parameters live where they arrived
+    let mut entry = vec![];
+    let mut next = 0;
+    if fd.variadic {
+        frame.regs = 32;
+        entry.extend((0..8).map(|i| IStmt::Store(Width::W, fp(arg_off(i)), arg_word(i))));
+    }
+    if by_ref(frame.rs, &fd.typ) {
+        let off = match fd.variadic {
+            true => arg_off(0),
+            false => {
+                let off = frame.alloc(4, 4);
+                entry.push(IStmt::Store(Width::W, fp(off), arg_word(0)));
+                off
+            }
+        };
+        (frame.sret, next) = (Some(off), 1);
+    }
+    for SParam { alias, typ: t, .. } in &fd.fps {
+        // a function without a prototype is called with unnamed arguments
+        next += (!fd.proto && paired(frame.rs, t) && next % 2 == 1) as usize;
+        if fd.variadic && !by_ref(frame.rs, t) {
+            frame.slots.insert(alias.clone(), fp(arg_off(next)));
+            next += words(frame.rs, t);
+            continue;
+        }
 
-    // todo: formal params
-    let body = fd
+        let (size, align) = (t.size(frame.rs).unwrap(), t.align(frame.rs).unwrap());
+        let off = match by_ref(frame.rs, t) {
+            true => frame.alloc(size, align),
+            false => frame.alloc(size.next_multiple_of(4), align.max(4)),
+        };
+        frame.slots.insert(alias.clone(), fp(off));
+
+        match (t.is_record(), words(frame.rs, t)) {
+            _ if by_ref(frame.rs, t) => entry.push(copy(frame.rs, t, fp(off), arg_word(next))),
+            (false, 1) => entry.push(IStmt::Store(width(t), fp(off), arg_word(next))),
+            (_, n) => entry.extend(
+                (0..n).map(|i| IStmt::Store(Width::W, fp(off + 4 * i as i32), arg_word(next + i))),
+            ),
+        }
+        next += words(frame.rs, t);
+    }
+
+    let exit = fresh_label();
+    let mut jumps = Jumps {
+        exit: exit.clone(),
+        breaks: vec![],
+        continues: vec![],
+        cases: vec![],
+        labels: HashMap::new(),
+    };
+    let mut body = fd
         .body
         .iter()
-        .map(|s_stmt| match s_stmt {
-            SStmt::Asnmt(vd) => {
-                let expr = translate_expr(&vd.expr);
-                let temp = Temp::UserTemp(vd.alias.clone());
-                IStmt::Compute(temp, expr)
-                // ************************************* ??????????????zsd
-            }
-            SStmt::IfEls {
-                cond: _,
-                then: _,
-                els: _,
-            } => todo!(),
-            SStmt::While { cond: _, body: _ } => todo!(),
-            SStmt::Return(expr) => IStmt::Return(translate_expr(expr)),
-        })
-        .map(Box::new)
+        .flat_map(|s| translate_stmt(s, &fd.typ, &mut frame, &mut jumps))
         .collect::<Vec<_>>();
 
-    IStmt::Seq(label, body)
+    // the epilogue follows the body, so a return at its end falls through
+    if body.last() == Some(&IStmt::Jump(exit.clone())) {
+        body.pop();
+    }
+    if body.iter().any(|s| *s == IStmt::Jump(exit.clone())) {
+        body.push(IStmt::Label(exit));
+    }
+
+    let stmts = entry.into_iter().chain(body).map(Box::new).collect();
+    [IStmt::Func(label, exported, frame.size, frame.regs, stmts)]
+        .into_iter()
+        .chain(frame.statics)
+        .collect()
 }
 
-fn translate_expr(e: &SExpr) -> IExpr {
-    match e {
-        SExpr::Int(n) => IExpr::Const(*n),
-        SExpr::Bool(b) => IExpr::Const(*b as i32),
-        SExpr::UnaryE { op: _, l: _ } => todo!(),
-        SExpr::BinE { op, l, r } => match op {
-            // C language designed as portable assembly makes tree rewrites straightforward
-            SBinOp::Add => IExpr::BinOp(
-                IBinOp::Add,
-                Box::new(translate_expr(l)),
-                Box::new(translate_expr(r)),
-            ),
-            SBinOp::Sub => IExpr::BinOp(
-                IBinOp::Sub,
-                Box::new(translate_expr(l)),
-                Box::new(translate_expr(r)),
-            ),
-            SBinOp::Mult => IExpr::BinOp(
-                IBinOp::Mult,
-                Box::new(translate_expr(l)),
-                Box::new(translate_expr(r)),
-            ),
-            SBinOp::Div => IExpr::BinOp(
-                IBinOp::Div,
-                Box::new(translate_expr(l)),
-                Box::new(translate_expr(r)),
-            ),
-            SBinOp::Mod => IExpr::BinOp(
-                IBinOp::Mod,
-                Box::new(translate_expr(l)),
-                Box::new(translate_expr(r)),
-            ),
+// where the statements of a function jump to: its epilogue, the ends of the
+// loops and switches and the continuations of the loops around them
+// (innermost last), the cases of the switches, and its labels
+struct Jumps {
+    exit: Label,
+    breaks: Vec<Label>,
+    continues: Vec<Label>,
+    cases: Vec<HashMap<Option<i32>, Label>>, // None for the default
+    labels: HashMap<String, Label>,
+}
+
+impl Jumps {
+    // a goto can come before its label
+    fn label(&mut self, l: &str) -> Label {
+        self.labels
+            .entry(l.to_owned())
+            .or_insert_with(fresh_label)
+            .clone()
+    }
+}
+
+// rt is the function's return type
+fn translate_stmt(s: &SStmt, rt: &Type, frame: &mut Frame, jumps: &mut Jumps) -> Vec<IStmt> {
+    match &s.kind {
+        // uninitialized variables, statics, externs and function declarations
+        // compute nothing
+        SStmtKind::Decl(vds) => vds
+            .iter()
+            .filter(|vd| !matches!(vd.typ, Type::Func(_)))
+            .flat_map(|vd| {
+                let t = vd.typ.clone();
+                match vd.sc {
+                    Some(StorageClass::Extern) => {
+                        frame.slots.remove(&vd.alias);
+                        return vec![];
+                    }
+                    Some(StorageClass::Static) => {
+                        // in scope in its own initializer
+                        let l = fresh_label();
+                        frame.slots.insert(vd.alias.clone(), IExpr::Name(l.clone()));
+                        let g = global(l, &t, vd.expr.as_deref(), false, frame);
+                        frame.statics.push(g);
+                        return vec![];
+                    }
+                    _ => (),
+                }
+                let off = frame.alloc(t.size(frame.rs).unwrap(), t.align(frame.rs).unwrap());
+                frame.slots.insert(vd.alias.clone(), fp(off));
+
+                match &vd.expr {
+                    Some(init) => initialize(off, &t, init, frame),
+                    None => vec![],
+                }
+            })
+            .collect(),
+        // the block's declarations go out of scope with it
+        SStmtKind::Block(stmts) => {
+            let slots = frame.slots.clone();
+            let is = stmts
+                .iter()
+                .flat_map(|s| translate_stmt(s, rt, frame, jumps))
+                .collect();
+            frame.slots = slots;
+            is
+        }
+        SStmtKind::Expr(e) => vec![IStmt::Exp(translate_expr(e, frame))],
+        SStmtKind::Empty => vec![],
+        SStmtKind::IfEls { cond, then, els } => {
+            let (t, f, end) = (fresh_label(), fresh_label(), fresh_label());
+            let c = cond_expr(cond, frame);
+            let then = translate_stmt(then, rt, frame, jumps);
+            let els = els
+                .iter()
+                .flat_map(|els| translate_stmt(els, rt, frame, jumps))
+                .collect::<Vec<_>>();
+
+            [IStmt::CJump(c, t.clone(), f.clone()), IStmt::Label(t)]
+                .into_iter()
+                .chain(then)
+                .chain([IStmt::Jump(end.clone()), IStmt::Label(f)])
+                .chain(els)
+                .chain([IStmt::Label(end)])
+                .collect()
+        }
+        // the controlling expression is evaluated once, then compared with
+        // each case in turn
+        SStmtKind::Switch { cond, body } => {
+            let off = frame.alloc(4, 4);
+            let store = IStmt::Store(Width::W, fp(off), translate_expr(cond, frame));
+
+            let mut cases = vec![];
+            collect_cases(body, &mut cases);
+            let cases = cases
+                .into_iter()
+                .map(|c| (c, fresh_label()))
+                .collect::<Vec<_>>();
+            let end = fresh_label();
+            let dispatch = cases.iter().flat_map(|(c, l)| {
+                let n = (*c)?;
+                let next = fresh_label();
+                let ne = IExpr::BinOp(
+                    IBinOp::Sub,
+                    Box::new(IExpr::Mem(Width::W, Box::new(fp(off)))),
+                    Box::new(IExpr::Const(n)),
+                );
+                Some([
+                    IStmt::CJump(ne, next.clone(), l.clone()),
+                    IStmt::Label(next),
+                ])
+            });
+            let default = cases
+                .iter()
+                .find(|(c, _)| c.is_none())
+                .map_or(end.clone(), |(_, l)| l.clone());
+            let dispatch = dispatch.flatten().collect::<Vec<_>>();
+
+            jumps.breaks.push(end.clone());
+            jumps.cases.push(cases.into_iter().collect());
+            let body = translate_stmt(body, rt, frame, jumps);
+            jumps.cases.pop();
+            jumps.breaks.pop();
+
+            [store]
+                .into_iter()
+                .chain(dispatch)
+                .chain([IStmt::Jump(default)])
+                .chain(body)
+                .chain([IStmt::Label(end)])
+                .collect()
+        }
+        SStmtKind::While { cond, body } => {
+            let (top, next, end) = (fresh_label(), fresh_label(), fresh_label());
+            let c = cond_expr(cond, frame);
+            let body = translate_loop(body, &end, &top, rt, frame, jumps);
+
+            [
+                IStmt::Label(top.clone()),
+                IStmt::CJump(c, next.clone(), end.clone()),
+                IStmt::Label(next),
+            ]
+            .into_iter()
+            .chain(body)
+            .chain([IStmt::Jump(top), IStmt::Label(end)])
+            .collect()
+        }
+        SStmtKind::DoWhile { body, cond } => {
+            let (top, cont, end) = (fresh_label(), fresh_label(), fresh_label());
+            let body = translate_loop(body, &end, &cont, rt, frame, jumps);
+            let c = cond_expr(cond, frame);
+
+            [IStmt::Label(top.clone())]
+                .into_iter()
+                .chain(body)
+                .chain([
+                    IStmt::Label(cont),
+                    IStmt::CJump(c, top, end.clone()),
+                    IStmt::Label(end),
+                ])
+                .collect()
+        }
+        SStmtKind::For {
+            init,
+            cond,
+            update,
+            body,
+        } => {
+            let (top, cont, end) = (fresh_label(), fresh_label(), fresh_label());
+            let init = init
+                .iter()
+                .map(|e| IStmt::Exp(translate_expr(e, frame)))
+                .collect::<Vec<_>>();
+            let test = cond
+                .iter()
+                .flat_map(|c| {
+                    let next = fresh_label();
+                    let c = cond_expr(c, frame);
+                    [
+                        IStmt::CJump(c, next.clone(), end.clone()),
+                        IStmt::Label(next),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            let body = translate_loop(body, &end, &cont, rt, frame, jumps);
+            let update = update
+                .iter()
+                .map(|e| IStmt::Exp(translate_expr(e, frame)))
+                .collect::<Vec<_>>();
+
+            init.into_iter()
+                .chain([IStmt::Label(top.clone())])
+                .chain(test)
+                .chain(body)
+                .chain([IStmt::Label(cont)])
+                .chain(update)
+                .chain([IStmt::Jump(top), IStmt::Label(end)])
+                .collect()
+        }
+        SStmtKind::Case(n, s) => labeled(
+            jumps.cases.last().unwrap()[&Some(*n)].clone(),
+            s,
+            rt,
+            frame,
+            jumps,
+        ),
+        SStmtKind::Default(s) => labeled(
+            jumps.cases.last().unwrap()[&None].clone(),
+            s,
+            rt,
+            frame,
+            jumps,
+        ),
+        SStmtKind::Labeled(l, s) => {
+            let l = jumps.label(l);
+            labeled(l, s, rt, frame, jumps)
+        }
+        SStmtKind::Break => vec![IStmt::Jump(jumps.breaks.last().unwrap().clone())],
+        SStmtKind::Continue => vec![IStmt::Jump(jumps.continues.last().unwrap().clone())],
+        SStmtKind::Goto(l) => vec![IStmt::Jump(jumps.label(l))],
+        SStmtKind::Return(Some(expr)) if rt.is_record() => {
+            let src = translate_expr(expr, frame);
+            let ret = match frame.sret {
+                Some(off) => vec![
+                    copy(frame.rs, rt, IExpr::Mem(Width::W, Box::new(fp(off))), src),
+                    IStmt::Return(IExpr::Mem(Width::W, Box::new(fp(off)))),
+                ],
+                None => vec![IStmt::Return(in_regs(frame.rs, rt, src))],
+            };
+            ret.into_iter()
+                .chain([IStmt::Jump(jumps.exit.clone())])
+                .collect()
+        }
+        SStmtKind::Return(Some(expr)) => {
+            let e = translate_expr(expr, frame);
+            vec![IStmt::Return(e), IStmt::Jump(jumps.exit.clone())]
+        }
+        SStmtKind::Return(None) => vec![IStmt::Jump(jumps.exit.clone())],
+    }
+}
+
+// a loop body, which break leaves for end and continue for cont
+#[allow(clippy::too_many_arguments)]
+fn translate_loop(
+    body: &SStmt,
+    end: &Label,
+    cont: &Label,
+    rt: &Type,
+    frame: &mut Frame,
+    jumps: &mut Jumps,
+) -> Vec<IStmt> {
+    jumps.breaks.push(end.clone());
+    jumps.continues.push(cont.clone());
+    let body = translate_stmt(body, rt, frame, jumps);
+    jumps.continues.pop();
+    jumps.breaks.pop();
+    body
+}
+
+#[allow(clippy::too_many_arguments)]
+fn labeled(l: Label, s: &SStmt, rt: &Type, frame: &mut Frame, jumps: &mut Jumps) -> Vec<IStmt> {
+    [IStmt::Label(l)]
+        .into_iter()
+        .chain(translate_stmt(s, rt, frame, jumps))
+        .collect()
+}
+
+// the case values of a switch (None for its default) in the order they
+// appear, leaving out those of the switches nested in it
+fn collect_cases(s: &SStmt, cases: &mut Vec<Option<i32>>) {
+    match &s.kind {
+        SStmtKind::Case(n, s) => {
+            cases.push(Some(*n));
+            collect_cases(s, cases)
+        }
+        SStmtKind::Default(s) => {
+            cases.push(None);
+            collect_cases(s, cases)
+        }
+        SStmtKind::Block(stmts) => stmts.iter().for_each(|s| collect_cases(s, cases)),
+        SStmtKind::IfEls { then, els, .. } => {
+            collect_cases(then, cases);
+            els.iter().for_each(|s| collect_cases(s, cases))
+        }
+        SStmtKind::While { body, .. }
+        | SStmtKind::DoWhile { body, .. }
+        | SStmtKind::For { body, .. }
+        | SStmtKind::Labeled(_, body) => collect_cases(body, cases),
+        _ => (),
+    }
+}
+
+// a controlling expression's value, which is nonzero when it holds
+fn cond_expr(e: &SExpr, frame: &mut Frame) -> IExpr {
+    match typ(e) {
+        // e != 0.0, which holds for a nan too
+        t if is_float(t) => {
+            let zero = IExpr::FConst(num(t), 0.0);
+            let eq = IExpr::FRel(
+                num(t),
+                IRelOp::Eq,
+                Box::new(translate_expr(e, frame)),
+                Box::new(zero),
+            );
+            IExpr::BinOp(IBinOp::Xor, Box::new(eq), Box::new(IExpr::Const(1)))
+        }
+        _ => translate_expr(e, frame),
+    }
+}
+
+fn translate_expr(e: &SExpr, frame: &mut Frame) -> IExpr {
+    match &e.kind {
+        SExprKind::Int(n) | SExprKind::Long(n) => IExpr::Const(*n),
+        SExprKind::UInt(n) | SExprKind::ULong(n) => IExpr::Const(*n as i32), // same bits
+        SExprKind::Float(f) => IExpr::FConst(INum::F32, *f as f64),          // exact
+        SExprKind::Double(f) | SExprKind::LongDouble(f) => IExpr::FConst(INum::F64, *f),
+        SExprKind::InitList(_) => unreachable!("braced initializers are not expressions"),
+        SExprKind::Bool(b) => IExpr::Const(*b as i32),
+        // !e is e == 0, and ~e is e ^ -1
+        SExprKind::UnaryE {
+            op: SUnaryOp::Not,
+            l,
+        } => {
+            let l = cond_expr(l, frame);
+            IExpr::BinOp(IBinOp::LtU, Box::new(l), Box::new(IExpr::Const(1)))
+        }
+        SExprKind::UnaryE { op, l } => {
+            let t = typ(e);
+            let l = translate_expr(l, frame);
+            match op {
+                SUnaryOp::Add => l,
+                SUnaryOp::Sub if !is_float(t) => {
+                    IExpr::BinOp(IBinOp::Sub, Box::new(IExpr::Const(0)), Box::new(l))
+                }
+                SUnaryOp::BitNot => {
+                    IExpr::BinOp(IBinOp::Xor, Box::new(l), Box::new(IExpr::Const(-1)))
+                }
+                SUnaryOp::Sub => IExpr::FNeg(num(t), Box::new(l)),
+                SUnaryOp::Not => unreachable!(),
+            }
+        }
+        // a pointer moves by the size of what it points to, and the
+        // difference of two is in those units
+        SExprKind::BinE { op, l, r } => match (op, typ(l), typ(r)) {
+            (SBinOp::Sub, Type::Pointer(et), Type::Pointer(_)) => {
+                let diff = IExpr::BinOp(
+                    IBinOp::Sub,
+                    Box::new(translate_expr(l, frame)),
+                    Box::new(translate_expr(r, frame)),
+                );
+                let n = et.size(frame.rs).unwrap();
+                match n.is_power_of_two() {
+                    true => IExpr::BinOp(
+                        IBinOp::Sar,
+                        Box::new(diff),
+                        Box::new(IExpr::Const(n.trailing_zeros() as i32)),
+                    ),
+                    false => IExpr::BinOp(
+                        IBinOp::Div,
+                        Box::new(diff),
+                        Box::new(IExpr::Const(n as i32)),
+                    ),
+                }
+            }
+            (SBinOp::Add | SBinOp::Sub, Type::Pointer(et), _) => {
+                let op = match op {
+                    SBinOp::Add => IBinOp::Add,
+                    _ => IBinOp::Sub,
+                };
+                let i = translate_expr(r, frame);
+                IExpr::BinOp(
+                    op,
+                    Box::new(translate_expr(l, frame)),
+                    Box::new(scale(i, et.size(frame.rs).unwrap())),
+                )
+            }
+            (SBinOp::Add, _, Type::Pointer(et)) => {
+                let i = translate_expr(l, frame);
+                IExpr::BinOp(
+                    IBinOp::Add,
+                    Box::new(scale(i, et.size(frame.rs).unwrap())),
+                    Box::new(translate_expr(r, frame)),
+                )
+            }
+            (op, _, _) => {
+                let t = typ(e);
+                // C language designed as portable assembly makes tree rewrites straightforward
+                let op = match op {
+                    SBinOp::Add => IBinOp::Add,
+                    SBinOp::Sub => IBinOp::Sub,
+                    SBinOp::Mult => IBinOp::Mult,
+                    SBinOp::Div if num(t) == INum::U32 => IBinOp::DivU,
+                    SBinOp::Div => IBinOp::Div,
+                    SBinOp::Mod if num(t) == INum::U32 => IBinOp::ModU,
+                    SBinOp::Mod => IBinOp::Mod,
+                };
+                let (l, r) = (
+                    Box::new(translate_expr(l, frame)),
+                    Box::new(translate_expr(r, frame)),
+                );
+
+                if is_float(t) {
+                    IExpr::FBinOp(num(t), op, l, r)
+                } else {
+                    IExpr::BinOp(op, l, r)
+                }
+            }
         },
-        SExpr::LogE { op: _, l: _, r: _ } => todo!(),
-        SExpr::BitE { op: _, l: _, r: _ } => todo!(),
-        SExpr::RelE { op: _, l: _, r: _ } => todo!(),
-        SExpr::VarApp(alias) => IExpr::TempUse(Temp::UserTemp(alias.clone())),
-        SExpr::FuncApp { alias, aps: ap } => {
-            let aps = ap.iter().map(translate_expr).collect::<Vec<_>>();
-            IExpr::Call(Label::UserLabel(alias.clone()), aps)
+        // the right operand is evaluated only when the left one does not
+        // decide the result, which is left in a slot of the frame
+        SExprKind::LogE { op, l, r } => {
+            let (next, decided, end) = (fresh_label(), fresh_label(), fresh_label());
+            let off = frame.alloc(4, 4);
+            let (l, r) = (cond_expr(l, frame), cond_expr(r, frame));
+            let (init, set, jumps) = match op {
+                SLogOp::And => (
+                    0,
+                    1,
+                    [(next.clone(), end.clone()), (decided.clone(), end.clone())],
+                ),
+                SLogOp::Or => (
+                    1,
+                    0,
+                    [(end.clone(), next.clone()), (end.clone(), decided.clone())],
+                ),
+            };
+            let [(lt, lf), (rt, rf)] = jumps;
+
+            let stmts = [
+                IStmt::Store(Width::W, fp(off), IExpr::Const(init)),
+                IStmt::CJump(l, lt, lf),
+                IStmt::Label(next),
+                IStmt::CJump(r, rt, rf),
+                IStmt::Label(decided),
+                IStmt::Store(Width::W, fp(off), IExpr::Const(set)),
+                IStmt::Label(end),
+            ];
+            let value = IExpr::Mem(Width::W, Box::new(fp(off)));
+            stmts
+                .into_iter()
+                .rev()
+                .fold(value, |e, s| IExpr::ESeq(Box::new(s), Box::new(e)))
+        }
+        SExprKind::BitE { op, l, r } => {
+            let t = typ(e);
+            let (l, r) = (translate_expr(l, frame), translate_expr(r, frame));
+            let op = match op {
+                SBitOp::And => IBinOp::And,
+                SBitOp::Or => IBinOp::Or,
+                SBitOp::Xor => IBinOp::Xor,
+                SBitOp::Shl => IBinOp::Shl,
+                SBitOp::Shr if num(t) == INum::I32 => IBinOp::Sar,
+                SBitOp::Shr => IBinOp::Shr,
+            };
+            IExpr::BinOp(op, Box::new(l), Box::new(r))
+        }
+        // every comparison is a less-than: a > b is b < a, a <= b is
+        // !(b < a), and a == b is (a - b) < 1 unsigned. pointers compare as
+        // unsigned addresses
+        SExprKind::RelE { op, l, r } => {
+            let t = match typ(l) {
+                Type::Pointer(_) => &Type::UInt,
+                t => t,
+            };
+            let (l, r) = (translate_expr(l, frame), translate_expr(r, frame));
+            // a nan compares unordered, so a float's a <= b is not !(b < a)
+            if is_float(t) {
+                let rel = |op, l, r| IExpr::FRel(num(t), op, Box::new(l), Box::new(r));
+                return match op {
+                    SRelOp::Lt => rel(IRelOp::Lt, l, r),
+                    SRelOp::Gt => rel(IRelOp::Lt, r, l),
+                    SRelOp::LtEq => rel(IRelOp::LtEq, l, r),
+                    SRelOp::GtEq => rel(IRelOp::LtEq, r, l),
+                    SRelOp::Eq => rel(IRelOp::Eq, l, r),
+                    SRelOp::Neq => IExpr::BinOp(
+                        IBinOp::Xor,
+                        Box::new(rel(IRelOp::Eq, l, r)),
+                        Box::new(IExpr::Const(1)),
+                    ),
+                };
+            }
+            let lt = match num(t) {
+                INum::U32 => IBinOp::LtU,
+                _ => IBinOp::Lt,
+            };
+            let bin = |op, l, r| IExpr::BinOp(op, Box::new(l), Box::new(r));
+            let not = |e| bin(IBinOp::Xor, e, IExpr::Const(1));
+            match op {
+                SRelOp::Lt => bin(lt, l, r),
+                SRelOp::Gt => bin(lt, r, l),
+                SRelOp::LtEq => not(bin(lt, r, l)),
+                SRelOp::GtEq => not(bin(lt, l, r)),
+                SRelOp::Eq => bin(IBinOp::LtU, bin(IBinOp::Sub, l, r), IExpr::Const(1)),
+                SRelOp::Neq => bin(IBinOp::LtU, IExpr::Const(0), bin(IBinOp::Sub, l, r)),
+            }
+        }
+        // the arm that runs leaves its value in a slot of the frame
+        SExprKind::Ternary { cond, then, els } => {
+            let t = typ(e);
+            let (tl, fl, end) = (fresh_label(), fresh_label(), fresh_label());
+            let c = cond_expr(cond, frame);
+            let (w, off) = match t.unqual() {
+                Type::Void => (Width::W, 0),
+                t if t.is_record() => (Width::W, frame.alloc(4, 4)),
+                t => (
+                    width(t),
+                    frame.alloc(t.size(frame.rs).unwrap(), t.align(frame.rs).unwrap()),
+                ),
+            };
+            let mut arm = |e: &SExpr| match t.unqual() {
+                Type::Void => IStmt::Exp(translate_expr(e, frame)),
+                _ => IStmt::Store(w.clone(), fp(off), translate_expr(e, frame)),
+            };
+            let (then, els) = (arm(then), arm(els));
+            let value = match t.unqual() {
+                Type::Void => IExpr::Const(0), // dropped
+                _ => IExpr::Mem(w, Box::new(fp(off))),
+            };
+
+            let stmts = [
+                IStmt::CJump(c, tl.clone(), fl.clone()),
+                IStmt::Label(tl),
+                then,
+                IStmt::Jump(end.clone()),
+                IStmt::Label(fl),
+                els,
+                IStmt::Label(end),
+            ];
+            stmts
+                .into_iter()
+                .rev()
+                .fold(value, |e, s| IExpr::ESeq(Box::new(s), Box::new(e)))
+        }
+        SExprKind::Comma { l, r } => IExpr::ESeq(
+            Box::new(IStmt::Exp(translate_expr(l, frame))),
+            Box::new(translate_expr(r, frame)),
+        ),
+        // the value of an assignment is the object's after the store
+        SExprKind::Asnmt { op, l, r } => {
+            let t = typ(l).clone();
+            let (addr, once) = address_once(l, frame);
+            let store = match op {
+                None => assign(addr.clone(), &t, r, frame),
+                // e1 op= e2 is e1 = e1 op e2, with e1 evaluated once (C89 3.3.16.2)
+                Some(op) => {
+                    let (op, ct) = match op {
+                        SAsnmtOp::Shl => (IBinOp::Shl, t.clone()),
+                        SAsnmtOp::Shr if num(&t) == INum::U32 => (IBinOp::Shr, t.clone()),
+                        SAsnmtOp::Shr => (IBinOp::Sar, t.clone()),
+                        op => {
+                            let op = match op {
+                                SAsnmtOp::Add => IBinOp::Add,
+                                SAsnmtOp::Sub => IBinOp::Sub,
+                                SAsnmtOp::Mult => IBinOp::Mult,
+                                SAsnmtOp::Div => IBinOp::Div,
+                                SAsnmtOp::Mod => IBinOp::Mod,
+                                SAsnmtOp::And => IBinOp::And,
+                                SAsnmtOp::Xor => IBinOp::Xor,
+                                _ => IBinOp::Or,
+                            };
+                            (op, typer::arith(&t, typ(r)))
+                        }
+                    };
+                    let op = match (op, num(&ct)) {
+                        (IBinOp::Div, INum::U32) => IBinOp::DivU,
+                        (IBinOp::Mod, INum::U32) => IBinOp::ModU,
+                        (op, _) => op,
+                    };
+                    let l = cvt(load(&t, addr.clone()), &t, &ct);
+                    let r = match &t {
+                        Type::Pointer(et) => {
+                            scale(translate_expr(r, frame), et.size(frame.rs).unwrap())
+                        }
+                        _ => translate_expr(r, frame),
+                    };
+                    let v = cvt(binop(op, &ct, l, r), &ct, &t);
+                    IStmt::Store(width(&t), addr.clone(), v)
+                }
+            };
+            sequence(once, IExpr::ESeq(Box::new(store), Box::new(load(&t, addr))))
+        }
+        // a postfix update yields the value from before it, kept in a slot
+        SExprKind::Update { op, l } => {
+            let t = typ(l).clone();
+            let (addr, once) = address_once(l, frame);
+            let one = match &t {
+                t if is_float(t) => IExpr::FConst(num(t), 1.0),
+                Type::Pointer(et) => IExpr::Const(et.size(frame.rs).unwrap() as i32),
+                _ => IExpr::Const(1),
+            };
+            let op = match op {
+                SUpdateOp::PreInc | SUpdateOp::PostInc => IBinOp::Add,
+                SUpdateOp::PreDec | SUpdateOp::PostDec => IBinOp::Sub,
+            };
+            let update = |v| IStmt::Store(width(&t), addr.clone(), binop(op, &t, v, one));
+
+            let e = match &e.kind {
+                SExprKind::Update {
+                    op: SUpdateOp::PreInc | SUpdateOp::PreDec,
+                    ..
+                } => IExpr::ESeq(
+                    Box::new(update(load(&t, addr.clone()))),
+                    Box::new(load(&t, addr.clone())),
+                ),
+                _ => {
+                    let old =
+                        fp(frame.alloc(t.size(frame.rs).unwrap(), t.align(frame.rs).unwrap()));
+                    let save = IStmt::Store(width(&t), old.clone(), load(&t, addr.clone()));
+                    let e = IExpr::ESeq(
+                        Box::new(update(load(&t, old.clone()))),
+                        Box::new(load(&t, old)),
+                    );
+                    IExpr::ESeq(Box::new(save), Box::new(e))
+                }
+            };
+            sequence(once, e)
+        }
+        // the value of the object an lvalue designates. that of a record or
+        // an array is its address
+        SExprKind::Load(l) => translate_expr(l, frame),
+        SExprKind::VarApp(_)
+        | SExprKind::Str(_)
+        | SExprKind::Dot { .. }
+        | SExprKind::Arrow { .. }
+        | SExprKind::Deref(_)
+        | SExprKind::Index { .. } => load(typ(e), address(e, frame)),
+        SExprKind::AddrOf(l) => address(l, frame),
+        SExprKind::Cast { typ: t, l } => cvt(translate_expr(l, frame), typ(l), t),
+        // the operand of sizeof is not evaluated (C89 3.3.3.4)
+        SExprKind::SizeOfT(t) => IExpr::Const(t.size(frame.rs).unwrap() as i32),
+        SExprKind::SizeOfE(l) => IExpr::Const(typ(l).size(frame.rs).unwrap() as i32),
+        // a function called by its name (or a pointer that is a function's
+        // address) is called by its label, and any other through the pointer
+        SExprKind::FuncApp { f, aps: ap } => {
+            let lt = match typ(f).unqual() {
+                Type::Func(lt) => lt,
+                Type::Pointer(t) => match t.unqual() {
+                    Type::Func(lt) => lt,
+                    _ => unreachable!("a call through a non-function pointer"),
+                },
+                _ => unreachable!("a call of a non-function"),
+            };
+            let f = match &f.kind {
+                SExprKind::VarApp(alias) => IExpr::Name(Label::UserLabel(alias.clone())),
+                _ => translate_expr(f, frame),
+            };
+
+            // arguments are passed as their type, which a prototype's
+            // parameters have converted them to
+            let fpts = ap.iter().map(typ).collect::<Vec<_>>();
+
+            // a record returned in memory goes to a temporary in the caller's frame
+            let rt = typ(e);
+            let sret = by_ref(frame.rs, rt)
+                .then(|| frame.alloc(rt.size(frame.rs).unwrap(), rt.align(frame.rs).unwrap()));
+            let aps = sret
+                .map(fp)
+                .into_iter()
+                .chain(ap.iter().zip(&fpts).map(|(ap, fpt)| match fpt {
+                    _ if by_ref(frame.rs, fpt) => {
+                        let src = translate_expr(ap, frame);
+                        let off =
+                            frame.alloc(fpt.size(frame.rs).unwrap(), fpt.align(frame.rs).unwrap());
+                        IExpr::ESeq(
+                            Box::new(copy(frame.rs, fpt, fp(off), src)),
+                            Box::new(fp(off)),
+                        )
+                    }
+                    _ if fpt.is_record() => in_regs(frame.rs, fpt, translate_expr(ap, frame)),
+                    _ => translate_expr(ap, frame),
+                }))
+                .collect::<Vec<_>>();
+
+            // an unnamed argument (past a prototype's parameters, or any
+            // without one) that is paired goes after a word of padding when
+            // it would start at an odd one
+            let named = sret.is_some() as usize + if lt.proto { lt.fp.len() } else { 0 };
+            let ws = sret.iter().map(|_| (1, false));
+            let ws = ws.chain(
+                fpts.iter()
+                    .map(|t| (words(frame.rs, t), paired(frame.rs, t))),
+            );
+            let mut next = 0;
+            let aps = aps
+                .into_iter()
+                .zip(ws)
+                .enumerate()
+                .flat_map(|(i, (ap, (w, paired)))| {
+                    let pad = i >= named && paired && next % 2 == 1;
+                    next += pad as usize + w;
+                    pad.then_some(IExpr::Const(0)).into_iter().chain([ap])
+                })
+                .collect::<Vec<_>>();
+
+            // one returned in registers is stored to one
+            let call = match f {
+                IExpr::Name(l) => IExpr::Call(l, aps, words(frame.rs, rt)),
+                p => IExpr::CallPtr(Box::new(p), aps, words(frame.rs, rt)),
+            };
+            match rt.size(frame.rs) {
+                Some(n) if rt.is_record() && !by_ref(frame.rs, rt) => {
+                    let off = frame.alloc(8, rt.align(frame.rs).unwrap().max(4));
+                    let w = if n > 4 { Width::D } else { Width::W };
+                    let store = IStmt::Store(w, fp(off), call);
+                    IExpr::ESeq(Box::new(store), Box::new(fp(off)))
+                }
+                _ => call,
+            }
         }
     }
 }
 
+fn cvt(e: IExpr, from: &Type, to: &Type) -> IExpr {
+    let e = match (num(from), num(to)) {
+        (from, to) if from == to => e,
+        (INum::I32 | INum::U32, INum::I32 | INum::U32) => e, // same bits
+        (from, to) => IExpr::Cvt(from, to, Box::new(e)),
+    };
+    if width(from) == width(to) {
+        return e;
+    }
+
+    // a narrower integer keeps its low bits, zero or sign extended
+    let bin = |op, l, r| IExpr::BinOp(op, Box::new(l), Box::new(r));
+    let sext = |e, n| {
+        bin(
+            IBinOp::Sar,
+            bin(IBinOp::Shl, e, IExpr::Const(n)),
+            IExpr::Const(n),
+        )
+    };
+    match (width(to), e) {
+        (Width::BU, IExpr::Const(n)) => IExpr::Const(n as u8 as i32),
+        (Width::HU, IExpr::Const(n)) => IExpr::Const(n as u16 as i32),
+        (Width::B, IExpr::Const(n)) => IExpr::Const(n as i8 as i32),
+        (Width::H, IExpr::Const(n)) => IExpr::Const(n as i16 as i32),
+        (Width::BU, e) => bin(IBinOp::And, e, IExpr::Const(0xff)),
+        (Width::HU, e) => bin(IBinOp::And, e, IExpr::Const(0xffff)),
+        (Width::B, e) => sext(e, 24),
+        (Width::H, e) => sext(e, 16),
+        (_, e) => e,
+    }
+}
+
+fn binop(op: IBinOp, t: &Type, l: IExpr, r: IExpr) -> IExpr {
+    match is_float(t) {
+        true => IExpr::FBinOp(num(t), op, Box::new(l), Box::new(r)),
+        false => IExpr::BinOp(op, Box::new(l), Box::new(r)),
+    }
+}
+
+// the address of the object an lvalue designates
+fn address(e: &SExpr, frame: &mut Frame) -> IExpr {
+    match &e.kind {
+        SExprKind::VarApp(alias) => frame.addr(alias),
+        // a record's value is its address already, and a pointer is one
+        SExprKind::Dot { l, field } | SExprKind::Arrow { l, field } => {
+            let t = match typ(l) {
+                Type::Pointer(t) => t,
+                t => t,
+            };
+            let (Type::Struct(_, id) | Type::Union(_, id)) = t.unqual() else {
+                unreachable!("members of a non-record");
+            };
+            let m = frame.rs.get(*id).member(field).cloned().unwrap();
+            offset(translate_expr(l, frame), m.offset as i32)
+        }
+        SExprKind::Deref(l) => translate_expr(l, frame),
+        // either operand of a subscript can be the pointer
+        SExprKind::Index { l, r } => {
+            let (p, i) = match typ(l) {
+                Type::Pointer(_) => (l, r),
+                _ => (r, l),
+            };
+            let Type::Pointer(et) = typ(p) else {
+                unreachable!("subscript of a non-pointer");
+            };
+            let i = translate_expr(i, frame);
+            IExpr::BinOp(
+                IBinOp::Add,
+                Box::new(translate_expr(p, frame)),
+                Box::new(scale(i, et.size(frame.rs).unwrap())),
+            )
+        }
+        // a string literal is a nameless array in .rodata
+        SExprKind::Str(s) => {
+            let l = fresh_label();
+            let t = Type::Array(
+                Box::new(Type::Const(Box::new(Type::Char))),
+                Some(s.len() + 1),
+            );
+            let g = global(l.clone(), &t, Some(e), false, frame);
+            frame.statics.push(g);
+            IExpr::Name(l)
+        }
+        _ => unreachable!("the address of a non-lvalue"),
+    }
+}
+
+// an index scaled to a byte offset, by a shift for a power of two
+fn scale(i: IExpr, n: usize) -> IExpr {
+    match (i, n) {
+        (IExpr::Const(i), n) => IExpr::Const(i * n as i32),
+        (i, 1) => i,
+        (i, n) if n.is_power_of_two() => IExpr::BinOp(
+            IBinOp::Shl,
+            Box::new(i),
+            Box::new(IExpr::Const(n.trailing_zeros() as i32)),
+        ),
+        (i, n) => IExpr::BinOp(IBinOp::Mult, Box::new(i), Box::new(IExpr::Const(n as i32))),
+    }
+}
+
+// an address used twice, as by a compound assignment, that is not at a fixed
+// offset from fp is computed once into a slot, so that its side effects
+// happen once
+fn address_once(e: &SExpr, frame: &mut Frame) -> (IExpr, Option<IStmt>) {
+    fn fixed(e: &IExpr) -> bool {
+        match e {
+            IExpr::Const(_) | IExpr::Name(_) | IExpr::TempUse(Temp::PointerReg(_)) => true,
+            IExpr::BinOp(IBinOp::Add, l, r) => fixed(l) && fixed(r),
+            _ => false,
+        }
+    }
+
+    match address(e, frame) {
+        addr if fixed(&addr) => (addr, None),
+        addr => {
+            let slot = fp(frame.alloc(4, 4));
+            let save = IStmt::Store(Width::W, slot.clone(), addr);
+            (IExpr::Mem(Width::W, Box::new(slot)), Some(save))
+        }
+    }
+}
+
+fn sequence(s: Option<IStmt>, e: IExpr) -> IExpr {
+    match s {
+        Some(s) => IExpr::ESeq(Box::new(s), Box::new(e)),
+        None => e,
+    }
+}
+
+// stores the initializer's elements into the automatic object of type t at
+// off, and zeros what a braced initializer leaves out
+fn initialize(off: i32, t: &Type, init: &SExpr, frame: &mut Frame) -> Vec<IStmt> {
+    if !matches!(init.kind, SExprKind::InitList(_) | SExprKind::Str(_)) {
+        return vec![assign(fp(off), t, init, frame)];
+    }
+    let (_, inits) = typer::flatten(frame.rs, t, init).unwrap();
+
+    let (mut is, mut end) = (vec![], 0);
+    for (at, et, e) in inits {
+        is.extend(zero(off, end, at));
+        match (&e.kind, et.unqual()) {
+            (SExprKind::Str(s), Type::Array(_, Some(n))) => {
+                let s = &s[..s.len().min(*n)];
+                is.extend(s.iter().enumerate().map(|(i, b)| {
+                    let addr = fp(off + (at + i) as i32);
+                    IStmt::Store(Width::B, addr, IExpr::Const(*b as i32))
+                }));
+                is.extend(zero(off, at + s.len(), at + n));
+            }
+            _ => is.push(assign(fp(off + at as i32), &et, e, frame)),
+        }
+        end = at + et.size(frame.rs).unwrap();
+    }
+    is.extend(zero(off, end, t.size(frame.rs).unwrap()));
+    is
+}
+
+// zeros bytes from..to of the object at off, a word at a time where aligned
+fn zero(off: i32, from: usize, to: usize) -> Vec<IStmt> {
+    let mut is = vec![];
+    let mut at = from;
+    while at < to {
+        let w = match at.is_multiple_of(4) && to - at >= 4 {
+            true => Width::W,
+            false => Width::B,
+        };
+        let n = w.bytes();
+        is.push(IStmt::Store(w, fp(off + at as i32), IExpr::Const(0)));
+        at += n;
+    }
+    is
+}
+
+// stores e's value, already of type t, at addr. a record is copied
+fn assign(addr: IExpr, t: &Type, e: &SExpr, frame: &mut Frame) -> IStmt {
+    match t.is_record() {
+        true => copy(frame.rs, t, addr, translate_expr(e, frame)),
+        false => IStmt::Store(width(t), addr, translate_expr(e, frame)),
+    }
+}
+
+// the value of an object of type t at addr. aggregates are their address
+fn load(t: &Type, addr: IExpr) -> IExpr {
+    match t.unqual() {
+        Type::Struct(..) | Type::Union(..) | Type::Array(..) | Type::Func(_) => addr,
+        _ => IExpr::Mem(width(t), Box::new(addr)),
+    }
+}
+
+// a record is copied in units of its alignment
+fn copy(rs: &Records, t: &Type, dst: IExpr, src: IExpr) -> IStmt {
+    let w = match t.align(rs).unwrap() {
+        1 => Width::BU,
+        2 => Width::HU,
+        _ => Width::W,
+    };
+    IStmt::Copy(w.clone(), t.size(rs).unwrap() / w.bytes(), dst, src)
+}
+
+// the words of a record small enough to be passed in registers. the last may
+// read up to three bytes past its end, which the callee never looks at
+fn in_regs(rs: &Records, t: &Type, addr: IExpr) -> IExpr {
+    let w = match t.size(rs).unwrap() {
+        1 => Width::BU,
+        2 => Width::HU,
+        3 | 4 => Width::W,
+        _ => Width::D,
+    };
+    IExpr::Mem(w, Box::new(addr))
+}
+
+// how many argument (or result) registers a value of type t takes under the
+// ilp32 calling convention. records larger than two words are passed by
+// reference to a copy, and returned through memory the caller provides
+fn words(rs: &Records, t: &Type) -> usize {
+    match t.unqual() {
+        Type::Void => 0,
+        Type::Double | Type::LongDouble | Type::LongLong | Type::ULongLong => 2,
+        t if by_ref(rs, t) => 1,
+        t if t.is_record() => t.size(rs).unwrap().div_ceil(4),
+        _ => 1,
+    }
+}
+
+fn by_ref(rs: &Records, t: &Type) -> bool {
+    t.is_record() && t.size(rs).unwrap() > 8
+}
+
+// the i-th argument word, past a7 on the caller's stack at the callee's fp
+fn arg_word(i: usize) -> IExpr {
+    match RiscvPointerReg::arg(i) {
+        Some(r) => IExpr::TempUse(Temp::PointerReg(r)),
+        None => IExpr::Mem(Width::W, Box::new(fp(arg_off(i)))),
+    }
+}
+
+// where the i-th argument word is in memory from fp: on the caller's stack,
+// or for a0..a7, where a variadic function saves them
+fn arg_off(i: usize) -> i32 {
+    4 * (i as i32 - 8)
+}
+
+// a double, passed as an unnamed argument, starts at an even word, in an
+// aligned register pair (or at an aligned stack slot)
+fn paired(rs: &Records, t: &Type) -> bool {
+    !by_ref(rs, t) && t.align(rs) == Some(8)
+}
+
+fn fp(off: i32) -> IExpr {
+    offset(IExpr::TempUse(Temp::PointerReg(RiscvPointerReg::Fp)), off)
+}
+
+fn offset(addr: IExpr, off: i32) -> IExpr {
+    match off {
+        0 => addr,
+        _ => IExpr::BinOp(IBinOp::Add, Box::new(addr), Box::new(IExpr::Const(off))),
+    }
+}
+
+fn width(t: &Type) -> Width {
+    match t.unqual() {
+        Type::SChar => Width::B,
+        Type::Char | Type::UChar => Width::BU, // plain char is unsigned on RV32
+        Type::Short => Width::H,
+        Type::UShort => Width::HU,
+        Type::Double | Type::LongDouble | Type::LongLong | Type::ULongLong => Width::D,
+        _ => Width::W,
+    }
+}
+
+fn typ(e: &SExpr) -> &Type {
+    e.typ.as_ref().expect("translating an untyped expression")
+}
+
+fn num(t: &Type) -> INum {
+    match t.unqual() {
+        Type::UInt | Type::ULong => INum::U32,
+        Type::Float => INum::F32,
+        Type::Double | Type::LongDouble => INum::F64,
+        _ => INum::I32,
+    }
+}
+
 #[cfg(test)]
 mod test_arith {
     use crate::lexer;
@@ -95,21 +1299,20 @@ mod test_arith {
 
     #[test]
     fn add() {
-        let chars = fs::read(format!("{TEST_DIR}/add.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/add.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let src_tree = parser_ast::parse_prg(&tokens).unwrap();
-        let _ = typer::type_prg(&src_tree).unwrap();
-        let trgt_tree = super::translate(&src_tree);
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
 
         insta::assert_yaml_snapshot!(trgt_tree, @r###"
         ---
-        - Seq:
+        - Func:
             - UserLabel: main
+            - true
+            - 0
+            - 0
             - - Return:
                   BinOp:
                     - Add
@@ -119,6 +1322,488 @@ mod test_arith {
     }
 }
 
+#[cfg(test)]
+mod test_control {
+    use crate::lexer;
+    use crate::parser_ast;
+    use crate::typer;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/control";
+
+    #[test]
+    fn dowhile() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/dowhile.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: main
+            - true
+            - 0
+            - 0
+            - - Label:
+                  MachineLabel: 1
+              - Jump:
+                  MachineLabel: 2
+              - Return:
+                  Const: 1
+              - Jump:
+                  MachineLabel: 0
+              - Label:
+                  MachineLabel: 2
+              - CJump:
+                  - Const: 0
+                  - MachineLabel: 1
+                  - MachineLabel: 3
+              - Label:
+                  MachineLabel: 3
+              - Label:
+                  MachineLabel: 4
+              - Jump:
+                  MachineLabel: 6
+              - Label:
+                  MachineLabel: 5
+              - Jump:
+                  MachineLabel: 4
+              - Label:
+                  MachineLabel: 6
+              - Return:
+                  Const: 7
+              - Label:
+                  MachineLabel: 0
+        "###);
+    }
+
+    #[test]
+    fn switch() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/switch.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: pick
+            - true
+            - 12
+            - 0
+            - - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -12
+                  - TempUse:
+                      PointerReg: A0
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -16
+                  - Mem:
+                      - W
+                      - BinOp:
+                          - Add
+                          - TempUse:
+                              PointerReg: Fp
+                          - Const: -12
+              - CJump:
+                  - BinOp:
+                      - Sub
+                      - Mem:
+                          - W
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -16
+                      - Const: 0
+                  - MachineLabel: 6
+                  - MachineLabel: 1
+              - Label:
+                  MachineLabel: 6
+              - CJump:
+                  - BinOp:
+                      - Sub
+                      - Mem:
+                          - W
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -16
+                      - Const: 1
+                  - MachineLabel: 7
+                  - MachineLabel: 2
+              - Label:
+                  MachineLabel: 7
+              - CJump:
+                  - BinOp:
+                      - Sub
+                      - Mem:
+                          - W
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -16
+                      - Const: 2
+                  - MachineLabel: 8
+                  - MachineLabel: 3
+              - Label:
+                  MachineLabel: 8
+              - Jump:
+                  MachineLabel: 4
+              - Label:
+                  MachineLabel: 1
+              - Return:
+                  Const: 10
+              - Jump:
+                  MachineLabel: 0
+              - Label:
+                  MachineLabel: 2
+              - Label:
+                  MachineLabel: 3
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -20
+                  - Mem:
+                      - W
+                      - BinOp:
+                          - Add
+                          - TempUse:
+                              PointerReg: Fp
+                          - Const: -12
+              - CJump:
+                  - BinOp:
+                      - Sub
+                      - Mem:
+                          - W
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -20
+                      - Const: 1
+                  - MachineLabel: 12
+                  - MachineLabel: 9
+              - Label:
+                  MachineLabel: 12
+              - Jump:
+                  MachineLabel: 10
+              - Label:
+                  MachineLabel: 9
+              - Jump:
+                  MachineLabel: 11
+              - Label:
+                  MachineLabel: 10
+              - Return:
+                  Const: 20
+              - Jump:
+                  MachineLabel: 0
+              - Label:
+                  MachineLabel: 11
+              - Return:
+                  Const: 30
+              - Jump:
+                  MachineLabel: 0
+              - Label:
+                  MachineLabel: 4
+              - Jump:
+                  MachineLabel: 5
+              - Label:
+                  MachineLabel: 5
+              - Return:
+                  Const: 40
+              - Label:
+                  MachineLabel: 0
+        - Func:
+            - UserLabel: main
+            - true
+            - 0
+            - 0
+            - - Return:
+                  BinOp:
+                    - Add
+                    - BinOp:
+                        - Add
+                        - BinOp:
+                            - Add
+                            - Call:
+                                - UserLabel: pick
+                                - - Const: 0
+                                - 1
+                            - Call:
+                                - UserLabel: pick
+                                - - Const: 1
+                                - 1
+                        - Call:
+                            - UserLabel: pick
+                            - - Const: 2
+                            - 1
+                    - Call:
+                        - UserLabel: pick
+                        - - Const: 5
+                        - 1
+        "###);
+    }
+
+    #[test]
+    fn lteq_true() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/lteq_true.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: main
+            - true
+            - 0
+            - 0
+            - - Return:
+                  BinOp:
+                    - Xor
+                    - BinOp:
+                        - Lt
+                        - Const: 10
+                        - Const: 9
+                    - Const: 1
+        "###);
+    }
+
+    #[test]
+    fn and_false() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/and_false.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: main
+            - true
+            - 4
+            - 0
+            - - Return:
+                  ESeq:
+                    - Store:
+                        - W
+                        - BinOp:
+                            - Add
+                            - TempUse:
+                                PointerReg: Fp
+                            - Const: -12
+                        - Const: 0
+                    - ESeq:
+                        - CJump:
+                            - Const: 1
+                            - MachineLabel: 1
+                            - MachineLabel: 3
+                        - ESeq:
+                            - Label:
+                                MachineLabel: 1
+                            - ESeq:
+                                - CJump:
+                                    - Const: 0
+                                    - MachineLabel: 2
+                                    - MachineLabel: 3
+                                - ESeq:
+                                    - Label:
+                                        MachineLabel: 2
+                                    - ESeq:
+                                        - Store:
+                                            - W
+                                            - BinOp:
+                                                - Add
+                                                - TempUse:
+                                                    PointerReg: Fp
+                                                - Const: -12
+                                            - Const: 1
+                                        - ESeq:
+                                            - Label:
+                                                MachineLabel: 3
+                                            - Mem:
+                                                - W
+                                                - BinOp:
+                                                    - Add
+                                                    - TempUse:
+                                                        PointerReg: Fp
+                                                    - Const: -12
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_exprs {
+    use crate::lexer;
+    use crate::parser_ast;
+    use crate::typer;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/exprs";
+
+    #[test]
+    fn ternary() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/ternary.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: main
+            - true
+            - 12
+            - 0
+            - - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -12
+                  - BinOp:
+                      - Sub
+                      - Const: 0
+                      - Const: 5
+              - Return:
+                  ESeq:
+                    - CJump:
+                        - ESeq:
+                            - Exp:
+                                Mem:
+                                  - W
+                                  - BinOp:
+                                      - Add
+                                      - TempUse:
+                                          PointerReg: Fp
+                                      - Const: -12
+                            - Const: 0
+                        - MachineLabel: 1
+                        - MachineLabel: 2
+                    - ESeq:
+                        - Label:
+                            MachineLabel: 1
+                        - ESeq:
+                            - Store:
+                                - W
+                                - BinOp:
+                                    - Add
+                                    - TempUse:
+                                        PointerReg: Fp
+                                    - Const: -16
+                                - Const: 1
+                            - ESeq:
+                                - Jump:
+                                    MachineLabel: 3
+                                - ESeq:
+                                    - Label:
+                                        MachineLabel: 2
+                                    - ESeq:
+                                        - Store:
+                                            - W
+                                            - BinOp:
+                                                - Add
+                                                - TempUse:
+                                                    PointerReg: Fp
+                                                - Const: -16
+                                            - BinOp:
+                                                - Add
+                                                - BinOp:
+                                                    - Add
+                                                    - BinOp:
+                                                        - Sub
+                                                        - Const: 0
+                                                        - Mem:
+                                                            - W
+                                                            - BinOp:
+                                                                - Add
+                                                                - TempUse:
+                                                                    PointerReg: Fp
+                                                                - Const: -12
+                                                    - ESeq:
+                                                        - CJump:
+                                                            - Const: 1
+                                                            - MachineLabel: 4
+                                                            - MachineLabel: 5
+                                                        - ESeq:
+                                                            - Label:
+                                                                MachineLabel: 4
+                                                            - ESeq:
+                                                                - Store:
+                                                                    - W
+                                                                    - BinOp:
+                                                                        - Add
+                                                                        - TempUse:
+                                                                            PointerReg: Fp
+                                                                        - Const: -20
+                                                                    - Const: 2
+                                                                - ESeq:
+                                                                    - Jump:
+                                                                        MachineLabel: 6
+                                                                    - ESeq:
+                                                                        - Label:
+                                                                            MachineLabel: 5
+                                                                        - ESeq:
+                                                                            - Store:
+                                                                                - W
+                                                                                - BinOp:
+                                                                                    - Add
+                                                                                    - TempUse:
+                                                                                        PointerReg: Fp
+                                                                                    - Const: -20
+                                                                                - Const: 3
+                                                                            - ESeq:
+                                                                                - Label:
+                                                                                    MachineLabel: 6
+                                                                                - Mem:
+                                                                                    - W
+                                                                                    - BinOp:
+                                                                                        - Add
+                                                                                        - TempUse:
+                                                                                            PointerReg: Fp
+                                                                                        - Const: -20
+                                                - Const: 4
+                                        - ESeq:
+                                            - Label:
+                                                MachineLabel: 3
+                                            - Mem:
+                                                - W
+                                                - BinOp:
+                                                    - Add
+                                                    - TempUse:
+                                                        PointerReg: Fp
+                                                    - Const: -16
+        "###);
+    }
+}
+
 #[cfg(test)]
 mod test_bindings {
     use crate::lexer;
@@ -130,27 +1815,514 @@ mod test_bindings {
 
     #[test]
     fn asnmt() {
-        let chars = fs::read(format!("{TEST_DIR}/asnmt.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/asnmt.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let src_tree = parser_ast::parse_prg(&tokens).unwrap();
-        let _ = typer::type_prg(&src_tree).unwrap();
-        let trgt_tree = super::translate(&src_tree);
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
 
         insta::assert_yaml_snapshot!(trgt_tree, @r###"
         ---
-        - Seq:
+        - Func:
             - UserLabel: main
-            - - Compute:
-                  - UserTemp: x
+            - true
+            - 4
+            - 0
+            - - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -12
                   - Const: 8
               - Return:
-                  TempUse:
-                    UserTemp: x
+                  Mem:
+                    - W
+                    - BinOp:
+                        - Add
+                        - TempUse:
+                            PointerReg: Fp
+                        - Const: -12
+        "###);
+    }
+
+    #[test]
+    fn asnmt_pre_post() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/asnmt_pre_post.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: main
+            - true
+            - 56
+            - 0
+            - - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -12
+                  - Const: 5
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -16
+                  - ESeq:
+                      - Store:
+                          - W
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -20
+                          - Mem:
+                              - W
+                              - BinOp:
+                                  - Add
+                                  - TempUse:
+                                      PointerReg: Fp
+                                  - Const: -12
+                      - ESeq:
+                          - Store:
+                              - W
+                              - BinOp:
+                                  - Add
+                                  - TempUse:
+                                      PointerReg: Fp
+                                  - Const: -12
+                              - BinOp:
+                                  - Add
+                                  - Mem:
+                                      - W
+                                      - BinOp:
+                                          - Add
+                                          - TempUse:
+                                              PointerReg: Fp
+                                          - Const: -20
+                                  - Const: 1
+                          - Mem:
+                              - W
+                              - BinOp:
+                                  - Add
+                                  - TempUse:
+                                      PointerReg: Fp
+                                  - Const: -20
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -24
+                  - ESeq:
+                      - Store:
+                          - W
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -12
+                          - BinOp:
+                              - Add
+                              - Mem:
+                                  - W
+                                  - BinOp:
+                                      - Add
+                                      - TempUse:
+                                          PointerReg: Fp
+                                      - Const: -12
+                              - Const: 1
+                      - Mem:
+                          - W
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -12
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -28
+                  - ESeq:
+                      - Store:
+                          - W
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -32
+                          - Mem:
+                              - W
+                              - BinOp:
+                                  - Add
+                                  - TempUse:
+                                      PointerReg: Fp
+                                  - Const: -12
+                      - ESeq:
+                          - Store:
+                              - W
+                              - BinOp:
+                                  - Add
+                                  - TempUse:
+                                      PointerReg: Fp
+                                  - Const: -12
+                              - BinOp:
+                                  - Sub
+                                  - Mem:
+                                      - W
+                                      - BinOp:
+                                          - Add
+                                          - TempUse:
+                                              PointerReg: Fp
+                                          - Const: -32
+                                  - Const: 1
+                          - Mem:
+                              - W
+                              - BinOp:
+                                  - Add
+                                  - TempUse:
+                                      PointerReg: Fp
+                                  - Const: -32
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -36
+                  - ESeq:
+                      - Store:
+                          - W
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -12
+                          - BinOp:
+                              - Sub
+                              - Mem:
+                                  - W
+                                  - BinOp:
+                                      - Add
+                                      - TempUse:
+                                          PointerReg: Fp
+                                      - Const: -12
+                              - Const: 1
+                      - Mem:
+                          - W
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -12
+              - Store:
+                  - BU
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -37
+                  - Const: 255
+              - Store:
+                  - D
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -48
+                  - FConst:
+                      - F64
+                      - 1.5
+              - Exp:
+                  ESeq:
+                    - Store:
+                        - BU
+                        - BinOp:
+                            - Add
+                            - TempUse:
+                                PointerReg: Fp
+                            - Const: -49
+                        - Mem:
+                            - BU
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -37
+                    - ESeq:
+                        - Store:
+                            - BU
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -37
+                            - BinOp:
+                                - Add
+                                - Mem:
+                                    - BU
+                                    - BinOp:
+                                        - Add
+                                        - TempUse:
+                                            PointerReg: Fp
+                                        - Const: -49
+                                - Const: 1
+                        - Mem:
+                            - BU
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -49
+              - Exp:
+                  ESeq:
+                    - Store:
+                        - D
+                        - BinOp:
+                            - Add
+                            - TempUse:
+                                PointerReg: Fp
+                            - Const: -48
+                        - FBinOp:
+                            - F64
+                            - Add
+                            - Mem:
+                                - D
+                                - BinOp:
+                                    - Add
+                                    - TempUse:
+                                        PointerReg: Fp
+                                    - Const: -48
+                            - Cvt:
+                                - I32
+                                - F64
+                                - Const: 2
+                    - Mem:
+                        - D
+                        - BinOp:
+                            - Add
+                            - TempUse:
+                                PointerReg: Fp
+                            - Const: -48
+              - Exp:
+                  ESeq:
+                    - Store:
+                        - D
+                        - BinOp:
+                            - Add
+                            - TempUse:
+                                PointerReg: Fp
+                            - Const: -64
+                        - Mem:
+                            - D
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -48
+                    - ESeq:
+                        - Store:
+                            - D
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -48
+                            - FBinOp:
+                                - F64
+                                - Add
+                                - Mem:
+                                    - D
+                                    - BinOp:
+                                        - Add
+                                        - TempUse:
+                                            PointerReg: Fp
+                                        - Const: -64
+                                - FConst:
+                                    - F64
+                                    - 1
+                        - Mem:
+                            - D
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -64
+              - Return:
+                  BinOp:
+                    - Add
+                    - BinOp:
+                        - Add
+                        - BinOp:
+                            - Add
+                            - BinOp:
+                                - Add
+                                - BinOp:
+                                    - Add
+                                    - BinOp:
+                                        - Add
+                                        - Mem:
+                                            - W
+                                            - BinOp:
+                                                - Add
+                                                - TempUse:
+                                                    PointerReg: Fp
+                                                - Const: -16
+                                        - Mem:
+                                            - W
+                                            - BinOp:
+                                                - Add
+                                                - TempUse:
+                                                    PointerReg: Fp
+                                                - Const: -24
+                                    - Mem:
+                                        - W
+                                        - BinOp:
+                                            - Add
+                                            - TempUse:
+                                                PointerReg: Fp
+                                            - Const: -28
+                                - Mem:
+                                    - W
+                                    - BinOp:
+                                        - Add
+                                        - TempUse:
+                                            PointerReg: Fp
+                                        - Const: -36
+                            - Mem:
+                                - W
+                                - BinOp:
+                                    - Add
+                                    - TempUse:
+                                        PointerReg: Fp
+                                    - Const: -12
+                        - Mem:
+                            - BU
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -37
+                    - Cvt:
+                        - F64
+                        - I32
+                        - Mem:
+                            - D
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -48
+        "###);
+    }
+
+    #[test]
+    fn asnmt_once() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/asnmt_once.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: bump
+            - true
+            - 12
+            - 0
+            - - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -12
+                  - TempUse:
+                      PointerReg: A0
+              - Return:
+                  ESeq:
+                    - Store:
+                        - W
+                        - BinOp:
+                            - Add
+                            - TempUse:
+                                PointerReg: Fp
+                            - Const: -16
+                        - Mem:
+                            - W
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -12
+                    - ESeq:
+                        - Store:
+                            - W
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -20
+                            - Mem:
+                                - W
+                                - Mem:
+                                    - W
+                                    - BinOp:
+                                        - Add
+                                        - TempUse:
+                                            PointerReg: Fp
+                                        - Const: -16
+                        - ESeq:
+                            - Store:
+                                - W
+                                - Mem:
+                                    - W
+                                    - BinOp:
+                                        - Add
+                                        - TempUse:
+                                            PointerReg: Fp
+                                        - Const: -16
+                                - BinOp:
+                                    - Add
+                                    - Mem:
+                                        - W
+                                        - BinOp:
+                                            - Add
+                                            - TempUse:
+                                                PointerReg: Fp
+                                            - Const: -20
+                                    - Const: 1
+                            - Mem:
+                                - W
+                                - BinOp:
+                                    - Add
+                                    - TempUse:
+                                        PointerReg: Fp
+                                    - Const: -20
+        - Func:
+            - UserLabel: main
+            - true
+            - 0
+            - 0
+            - - Return:
+                  Const: 0
         "###);
     }
 }
@@ -166,25 +2338,27 @@ mod test_functions {
 
     #[test]
     fn composition() {
-        let chars = fs::read(format!("{TEST_DIR}/composition.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/composition.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let src_tree = parser_ast::parse_prg(&tokens).unwrap();
-        let _ = typer::type_prg(&src_tree).unwrap();
-        let trgt_tree = super::translate(&src_tree);
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
 
         insta::assert_yaml_snapshot!(trgt_tree, @r###"
         ---
-        - Seq:
+        - Func:
             - UserLabel: h
+            - true
+            - 0
+            - 0
             - - Return:
                   Const: 11
-        - Seq:
+        - Func:
             - UserLabel: g
+            - true
+            - 0
+            - 0
             - - Return:
                   BinOp:
                     - Add
@@ -192,8 +2366,12 @@ mod test_functions {
                     - Call:
                         - UserLabel: h
                         - []
-        - Seq:
+                        - 1
+        - Func:
             - UserLabel: f
+            - true
+            - 0
+            - 0
             - - Return:
                   BinOp:
                     - Add
@@ -201,28 +2379,985 @@ mod test_functions {
                     - Call:
                         - UserLabel: g
                         - []
-        - Seq:
+                        - 1
+        - Func:
             - UserLabel: main
+            - true
+            - 0
+            - 0
             - - Return:
                   Call:
                     - UserLabel: f
                     - []
+                    - 1
         "###);
     }
 
     #[test]
     fn formal_param() {
-        let chars = fs::read(format!("{TEST_DIR}/formal_param.c"))
-            .expect("file dne")
+        let chars = fs::read_to_string(format!("{TEST_DIR}/formal_param.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: f
+            - true
+            - 4
+            - 0
+            - - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -12
+                  - TempUse:
+                      PointerReg: A0
+              - Return:
+                  BinOp:
+                    - Add
+                    - Mem:
+                        - W
+                        - BinOp:
+                            - Add
+                            - TempUse:
+                                PointerReg: Fp
+                            - Const: -12
+                    - Const: 10
+        - Func:
+            - UserLabel: main
+            - true
+            - 0
+            - 0
+            - - Return:
+                  Call:
+                    - UserLabel: f
+                    - - Const: 9
+                    - 1
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_protos {
+    use crate::lexer;
+    use crate::parser_ast;
+    use crate::typer;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/protos";
+
+    #[test]
+    fn unnamed() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/unnamed.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: first
+            - true
+            - 0
+            - 32
+            - - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -32
+                  - TempUse:
+                      PointerReg: A0
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -28
+                  - TempUse:
+                      PointerReg: A1
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -24
+                  - TempUse:
+                      PointerReg: A2
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -20
+                  - TempUse:
+                      PointerReg: A3
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -16
+                  - TempUse:
+                      PointerReg: A4
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -12
+                  - TempUse:
+                      PointerReg: A5
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -8
+                  - TempUse:
+                      PointerReg: A6
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -4
+                  - TempUse:
+                      PointerReg: A7
+              - Return:
+                  Mem:
+                    - W
+                    - BinOp:
+                        - Add
+                        - TempUse:
+                            PointerReg: Fp
+                        - Const: -32
+        - Func:
+            - UserLabel: main
+            - true
+            - 0
+            - 0
+            - - Return:
+                  BinOp:
+                    - Add
+                    - Call:
+                        - UserLabel: first
+                        - - Const: 1
+                          - Const: 0
+                          - FConst:
+                              - F64
+                              - 2.5
+                        - 1
+                    - Call:
+                        - UserLabel: first
+                        - - Const: 2
+                          - Const: 1
+                          - FConst:
+                              - F64
+                              - 2.5
+                        - 1
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_floats {
+    use crate::lexer;
+    use crate::parser_ast;
+    use crate::typer;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/floats";
+
+    #[test]
+    fn mixed() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/mixed.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: main
+            - true
+            - 0
+            - 0
+            - - Return:
+                  Cvt:
+                    - F64
+                    - I32
+                    - FBinOp:
+                        - F64
+                        - Add
+                        - FConst:
+                            - F64
+                            - 1.5
+                        - Cvt:
+                            - I32
+                            - F64
+                            - Const: 2
+        "###);
+    }
+
+    #[test]
+    fn single() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/single.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: main
+            - true
+            - 0
+            - 0
+            - - Return:
+                  Cvt:
+                    - F32
+                    - I32
+                    - FBinOp:
+                        - F32
+                        - Sub
+                        - FBinOp:
+                            - F32
+                            - Mult
+                            - FConst:
+                                - F32
+                                - 2.5
+                            - FConst:
+                                - F32
+                                - 2
+                        - Cvt:
+                            - U32
+                            - F32
+                            - Const: 1
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_decls {
+    use crate::lexer;
+    use crate::parser_ast;
+    use crate::typer;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/decls";
+
+    #[test]
+    fn typenames() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/typenames.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: main
+            - true
+            - 20
+            - 0
+            - - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -12
+                  - Const: 4
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -16
+                  - Const: 16
+              - Store:
+                  - D
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -24
+                  - Cvt:
+                      - I32
+                      - F64
+                      - Mem:
+                          - W
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -12
+              - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -28
+                  - BinOp:
+                      - Add
+                      - Const: 4
+                      - Const: 4
+              - Return:
+                  Cvt:
+                    - F64
+                    - I32
+                    - FBinOp:
+                        - F64
+                        - Add
+                        - Mem:
+                            - D
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -24
+                        - FConst:
+                            - F64
+                            - 1.5
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_records {
+    use crate::lexer;
+    use crate::parser_ast;
+    use crate::typer;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/structs";
+
+    #[test]
+    fn members() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/members.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: dx
+            - true
+            - 24
+            - 0
+            - - Copy:
+                  - W
+                  - 6
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -32
+                  - TempUse:
+                      PointerReg: A0
+              - Return:
+                  BinOp:
+                    - Sub
+                    - Mem:
+                        - W
+                        - BinOp:
+                            - Add
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -32
+                            - Const: 8
+                    - Mem:
+                        - W
+                        - BinOp:
+                            - Add
+                            - TempUse:
+                                PointerReg: Fp
+                            - Const: -32
+        - Func:
+            - UserLabel: first
+            - true
+            - 4
+            - 0
+            - - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -12
+                  - TempUse:
+                      PointerReg: A0
+              - Return:
+                  Mem:
+                    - W
+                    - BinOp:
+                        - Add
+                        - Mem:
+                            - W
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -12
+                        - Const: 4
+        - Func:
+            - UserLabel: length
+            - true
+            - 24
+            - 0
+            - - Copy:
+                  - W
+                  - 6
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -32
+                  - TempUse:
+                      PointerReg: A0
+              - Return:
+                  Mem:
+                    - D
+                    - BinOp:
+                        - Add
+                        - BinOp:
+                            - Add
+                            - TempUse:
+                                PointerReg: Fp
+                            - Const: -32
+                        - Const: 16
+        - Func:
+            - UserLabel: main
+            - true
+            - 72
+            - 0
+            - - Copy:
+                  - W
+                  - 6
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -56
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -32
+              - Return:
+                  Call:
+                    - UserLabel: dx
+                    - - ESeq:
+                          - Copy:
+                              - W
+                              - 6
+                              - BinOp:
+                                  - Add
+                                  - TempUse:
+                                      PointerReg: Fp
+                                  - Const: -80
+                              - BinOp:
+                                  - Add
+                                  - TempUse:
+                                      PointerReg: Fp
+                                  - Const: -56
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -80
+                    - 1
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_pointers {
+    use crate::lexer;
+    use crate::parser_ast;
+    use crate::typer;
+    use crate::{IStmt, Label};
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/pointers";
+
+    // a parameter that points to a function is called through
+    #[test]
+    fn funcs() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/funcs.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        let apply = Label::UserLabel("apply".to_string());
+        let apply = trgt_tree
             .iter()
-            .map(|b| *b as char)
+            .find(|s| matches!(s, IStmt::Func(l, ..) if *l == apply));
+        insta::assert_yaml_snapshot!(apply, @r###"
+        ---
+        Func:
+          - UserLabel: apply
+          - true
+          - 8
+          - 0
+          - - Store:
+                - W
+                - BinOp:
+                    - Add
+                    - TempUse:
+                        PointerReg: Fp
+                    - Const: -12
+                - TempUse:
+                    PointerReg: A0
+            - Store:
+                - W
+                - BinOp:
+                    - Add
+                    - TempUse:
+                        PointerReg: Fp
+                    - Const: -16
+                - TempUse:
+                    PointerReg: A1
+            - Return:
+                CallPtr:
+                  - Mem:
+                      - W
+                      - BinOp:
+                          - Add
+                          - TempUse:
+                              PointerReg: Fp
+                          - Const: -12
+                  - - Mem:
+                        - W
+                        - BinOp:
+                            - Add
+                            - TempUse:
+                                PointerReg: Fp
+                            - Const: -16
+                  - 1
+        "###);
+    }
+
+    #[test]
+    fn index() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/index.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
+
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: main
+            - true
+            - 32
+            - 0
+            - - Store:
+                  - W
+                  - BinOp:
+                      - Add
+                      - TempUse:
+                          PointerReg: Fp
+                      - Const: -36
+                  - BinOp:
+                      - Add
+                      - BinOp:
+                          - Add
+                          - BinOp:
+                              - Add
+                              - TempUse:
+                                  PointerReg: Fp
+                              - Const: -32
+                          - Const: 12
+                      - Const: 8
+              - Exp:
+                  ESeq:
+                    - Store:
+                        - W
+                        - BinOp:
+                            - Add
+                            - TempUse:
+                                PointerReg: Fp
+                            - Const: -40
+                        - Mem:
+                            - W
+                            - BinOp:
+                                - Add
+                                - TempUse:
+                                    PointerReg: Fp
+                                - Const: -36
+                    - ESeq:
+                        - Store:
+                            - W
+                            - Mem:
+                                - W
+                                - BinOp:
+                                    - Add
+                                    - TempUse:
+                                        PointerReg: Fp
+                                    - Const: -40
+                            - Const: 7
+                        - Mem:
+                            - W
+                            - Mem:
+                                - W
+                                - BinOp:
+                                    - Add
+                                    - TempUse:
+                                        PointerReg: Fp
+                                    - Const: -40
+              - Return:
+                  BinOp:
+                    - Add
+                    - Mem:
+                        - W
+                        - BinOp:
+                            - Add
+                            - BinOp:
+                                - Add
+                                - BinOp:
+                                    - Add
+                                    - TempUse:
+                                        PointerReg: Fp
+                                    - Const: -32
+                                - Const: 12
+                            - Const: 8
+                    - BinOp:
+                        - Sar
+                        - BinOp:
+                            - Sub
+                            - Mem:
+                                - W
+                                - BinOp:
+                                    - Add
+                                    - TempUse:
+                                        PointerReg: Fp
+                                    - Const: -36
+                            - BinOp:
+                                - Add
+                                - BinOp:
+                                    - Add
+                                    - TempUse:
+                                        PointerReg: Fp
+                                    - Const: -32
+                                - Const: 0
+                        - Const: 2
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_globals {
+    use crate::lexer;
+    use crate::parser_ast;
+    use crate::typer;
+    use crate::IStmt;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/globals";
+
+    #[test]
+    fn addresses() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/addresses.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let src_tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let data = super::translate(&typed_tree)
+            .unwrap()
+            .into_iter()
+            .filter(|s| matches!(s, IStmt::Data(_)))
             .collect::<Vec<_>>();
 
+        insta::assert_yaml_snapshot!(data, @r###"
+        ---
+        - Data:
+            label:
+              MachineLabel: 3
+            section: RoData
+            align: 1
+            exported: false
+            data:
+              - Ascii:
+                  - 120
+              - Zero: 1
+        - Data:
+            label:
+              MachineLabel: 2
+            section: Data
+            align: 4
+            exported: false
+            data:
+              - Addr:
+                  - MachineLabel: 3
+                  - 0
+        - Data:
+            label:
+              MachineLabel: 4
+            section: Data
+            align: 4
+            exported: false
+            data:
+              - Addr:
+                  - UserLabel: x
+                  - 0
+        - Data:
+            label:
+              UserLabel: x
+            section: Data
+            align: 4
+            exported: true
+            data:
+              - Value:
+                  - W
+                  - 5
+        - Data:
+            label:
+              UserLabel: arr
+            section: Data
+            align: 4
+            exported: true
+            data:
+              - Value:
+                  - W
+                  - 1
+              - Value:
+                  - W
+                  - 2
+              - Value:
+                  - W
+                  - 3
+              - Value:
+                  - W
+                  - 4
+        - Data:
+            label:
+              UserLabel: pr
+            section: Bss
+            align: 4
+            exported: true
+            data:
+              - Zero: 8
+        - Data:
+            label:
+              UserLabel: msg
+            section: Data
+            align: 4
+            exported: true
+            data:
+              - Addr:
+                  - MachineLabel: 38
+                  - 0
+        - Data:
+            label:
+              UserLabel: p
+            section: Data
+            align: 4
+            exported: true
+            data:
+              - Addr:
+                  - UserLabel: x
+                  - 0
+        - Data:
+            label:
+              UserLabel: q
+            section: Data
+            align: 4
+            exported: true
+            data:
+              - Addr:
+                  - UserLabel: arr
+                  - 4
+        - Data:
+            label:
+              UserLabel: last
+            section: Data
+            align: 4
+            exported: true
+            data:
+              - Addr:
+                  - UserLabel: arr
+                  - 12
+        - Data:
+            label:
+              UserLabel: pb
+            section: Data
+            align: 4
+            exported: true
+            data:
+              - Addr:
+                  - UserLabel: pr
+                  - 4
+        - Data:
+            label:
+              UserLabel: size
+            section: Data
+            align: 4
+            exported: true
+            data:
+              - Value:
+                  - W
+                  - 8
+        - Data:
+            label:
+              UserLabel: half
+            section: Data
+            align: 4
+            exported: true
+            data:
+              - Value:
+                  - W
+                  - 2147483647
+        - Data:
+            label:
+              UserLabel: below
+            section: Bss
+            align: 4
+            exported: true
+            data:
+              - Zero: 4
+        - Data:
+            label:
+              UserLabel: shifted
+            section: Data
+            align: 4
+            exported: true
+            data:
+              - Value:
+                  - W
+                  - 2147483644
+        - Data:
+            label:
+              UserLabel: fp
+            section: Data
+            align: 4
+            exported: true
+            data:
+              - Addr:
+                  - UserLabel: seven
+                  - 0
+        - Data:
+            label:
+              MachineLabel: 38
+            section: RoData
+            align: 1
+            exported: false
+            data:
+              - Ascii:
+                  - 104
+                  - 105
+              - Zero: 1
+        "###);
+    }
+
+    #[test]
+    fn statics() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/statics.c")).expect("file dne");
+
         let tokens = lexer::lex(&chars).unwrap();
         let src_tree = parser_ast::parse_prg(&tokens).unwrap();
-        let _ = typer::type_prg(&src_tree).unwrap();
-        let trgt_tree = super::translate(&src_tree);
+        let typed_tree = typer::type_prg(&src_tree).unwrap().0;
+        let trgt_tree = super::translate(&typed_tree).unwrap();
 
-        insta::assert_yaml_snapshot!(trgt_tree, @r"");
+        insta::assert_yaml_snapshot!(trgt_tree, @r###"
+        ---
+        - Func:
+            - UserLabel: next
+            - true
+            - 0
+            - 0
+            - - Exp:
+                  ESeq:
+                    - Store:
+                        - W
+                        - Name:
+                            MachineLabel: 1
+                        - BinOp:
+                            - Add
+                            - Mem:
+                                - W
+                                - Name:
+                                    MachineLabel: 1
+                            - Const: 1
+                    - Mem:
+                        - W
+                        - Name:
+                            MachineLabel: 1
+              - Return:
+                  Mem:
+                    - W
+                    - Name:
+                        MachineLabel: 1
+        - Data:
+            label:
+              MachineLabel: 1
+            section: Data
+            align: 4
+            exported: false
+            data:
+              - Value:
+                  - W
+                  - 10
+        - Func:
+            - UserLabel: main
+            - true
+            - 0
+            - 0
+            - - Exp:
+                  Call:
+                    - UserLabel: next
+                    - []
+                    - 1
+              - Exp:
+                  Call:
+                    - UserLabel: next
+                    - []
+                    - 1
+              - Return:
+                  Call:
+                    - UserLabel: next
+                    - []
+                    - 1
+        - Data:
+            label:
+              MachineLabel: 3
+            section: Bss
+            align: 4
+            exported: false
+            data:
+              - Zero: 4
+        "###);
     }
 }
@@ -1,179 +1,1870 @@
-use crate::{LambdaType, SBinOp, SDef, SExpr, SFuncDef, SPrg, SStmt, Tnv, Type};
-use std::collections::HashMap;
+use crate::diagnostic::{self, Code, Diagnostic, Diagnostics};
+use crate::source::Span;
+use crate::{
+    LambdaType, Records, SAsnmtOp, SBinOp, SBitOp, SDef, SExpr, SExprKind, SFuncDef, SLogOp, SPrg,
+    SRelOp, SStmt, SStmtKind, SUnaryOp, SUpdateOp, SVarDef, StorageClass, Tnv, Type,
+};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::io;
 
-pub fn type_prg(prg: &SPrg) -> Result<Type, io::Error> {
+// functions are declared in a first pass, so that a call can come before the
+// definition or any declaration of its function. objects are declared in
+// order in the second, which also elaborates the program into its typed tree.
+// a definition with an error is left out, and the ones after it are still
+// checked, so that every error is reported
+pub fn type_prg(prg: &SPrg) -> Result<(SPrg, Vec<Diagnostic>), io::Error> {
+    type_unit(prg, true)
+}
+
+// a translation unit compiled on its own, to be linked into a program (like
+// the runtime's libc), need not define main
+pub fn type_unit(prg: &SPrg, main: bool) -> Result<(SPrg, Vec<Diagnostic>), io::Error> {
+    let mut errs = vec![];
+    let (fnv, fdecls) = declare_funcs(prg, &mut errs);
     let mut tnv = Tnv {
-        fnv: HashMap::new(),
+        fnv,
+        fdecls,
         vnv: HashMap::new(),
+        warnings: RefCell::new(vec![]),
+        records: prg.records.clone(),
     };
+    // the storage class of each global's first declaration, which fixes its
+    // linkage, and the globals that have had an initializer
+    let mut linkage = HashMap::new();
+    let mut defined = HashMap::new();
+
+    let mut typed = vec![];
+    for def in &prg.defs {
+        match type_def(def, &mut tnv, &mut linkage, &mut defined) {
+            Ok(def) => typed.push(def),
+            Err(e) => {
+                // what it declares stays in scope, so its uses are not errors too
+                if let SDef::VarDef(vd) = def {
+                    tnv.vnv
+                        .entry(vd.alias.clone())
+                        .or_insert_with(|| vd.typ.clone());
+                }
+                errs.extend(diagnostic::of(&e));
+            }
+        }
+    }
 
-    let _ = prg
+    // like a link error, a missing main is only reported for a program that
+    // is otherwise correct
+    let has_main = prg
+        .defs
         .iter()
-        .map(|def| match def {
+        .any(|def| matches!(def, SDef::FuncDef(fd) if fd.alias == "main"));
+    if main && !has_main && errs.is_empty() {
+        let msg = "main function not found";
+        errs.push(Diagnostic::new(Code::NoMain, Span::default(), msg));
+    }
+    let warnings = tnv.warnings.take();
+    match errs.is_empty() {
+        true => Ok((
+            SPrg {
+                defs: typed,
+                records: tnv.records,
+            },
+            warnings,
+        )),
+        false => Err(Diagnostics(warnings.into_iter().chain(errs).collect()).into()),
+    }
+}
+
+fn type_def(
+    def: &SDef,
+    tnv: &mut Tnv,
+    linkage: &mut HashMap<String, bool>,
+    defined: &mut HashMap<String, Span>,
+) -> Result<SDef, io::Error> {
+    match def {
+        SDef::FuncDef(fd) => type_func(fd, tnv, tnv.vnv.clone()).map(SDef::FuncDef),
+        SDef::VarDef(vd) if matches!(vd.typ, Type::Func(_)) => Ok(def.clone()),
+        SDef::VarDef(vd) if tnv.fnv.contains_key(&vd.alias) => {
+            let msg = format!("'{}' redeclared as different kind of symbol", vd.alias);
+            Err(Diagnostic::new(Code::Redeclared, vd.span, msg).into())
+        }
+        SDef::VarDef(vd) => {
+            // in scope in its own initializer, as at block scope
+            tnv.vnv
+                .entry(vd.alias.clone())
+                .or_insert_with(|| vd.typ.clone());
+            let (t, expr) = match &vd.expr {
+                Some(init) => {
+                    type_init(&vd.typ, init, Some(&HashSet::new()), tnv, &tnv.vnv)?;
+                    if let Some(prev) = defined.insert(vd.alias.clone(), vd.span) {
+                        let msg = format!("redefinition of '{}'", vd.alias);
+                        return Err(redeclared(vd.span, msg, prev, &vd.alias));
+                    }
+                    let (t, init) = elab_init(&vd.typ, init, tnv, &tnv.vnv)?;
+                    (t, Some(Box::new(init)))
+                }
+                None => (vd.typ.clone(), None),
+            };
+            let typed = SVarDef {
+                typ: t.clone(),
+                expr,
+                ..vd.clone()
+            };
+            let t = match tnv.vnv.get(&vd.alias) {
+                Some(prev) => composite(prev, &t).ok_or_else(|| conflicting(vd.span, &vd.alias))?,
+                None => t,
+            };
+            let internal = *linkage
+                .entry(vd.alias.clone())
+                .or_insert(vd.sc == Some(StorageClass::Static));
+            let (this, prev) = match vd.sc {
+                Some(StorageClass::Static) if !internal => ("static", "non-static"),
+                None if internal => ("non-static", "static"),
+                _ => ("", ""),
+            };
+            if !this.is_empty() {
+                let msg = format!(
+                    "{this} declaration of '{}' follows {prev} declaration",
+                    vd.alias
+                );
+                return Err(Diagnostic::new(Code::Conflicting, vd.span, msg).into());
+            }
+            // a tentative definition of an array of unknown size gets one element
+            let tentative = matches!(t.unqual(), Type::Array(_, None));
+            if t.size(&tnv.records).is_none() && !tentative && vd.sc != Some(StorageClass::Extern) {
+                let msg = format!("storage size of '{}' isn't known", vd.alias);
+                return Err(type_error(vd.span, msg));
+            }
+            tnv.vnv.insert(vd.alias.clone(), t); // Γ [x <- T]
+            Ok(SDef::VarDef(typed))
+        }
+    }
+}
+
+// the functions a program declares or defines, each with the type its
+// declarations agree on and the declaration that gave it its prototype (or
+// its first, without one). a declaration that disagrees is reported and
+// leaves the type as it was
+pub type FuncDecls = (HashMap<String, LambdaType>, HashMap<String, Span>);
+
+pub fn declare_funcs(prg: &SPrg, errs: &mut Vec<Diagnostic>) -> FuncDecls {
+    let mut fnv: HashMap<String, LambdaType> = HashMap::new();
+    let mut fdecls = HashMap::new();
+    let mut defined = HashMap::new();
+    for def in &prg.defs {
+        let (alias, lt, span) = match def {
             SDef::FuncDef(fd) => {
-                let ltnv = HashMap::new();
-                let type_check = type_func(fd, &tnv, ltnv).map(|t| {
-                    tnv.fnv.insert(
-                        fd.alias.clone(),
-                        LambdaType {
-                            fp: fd.fps.iter().map(|(_, t)| t.clone()).collect(),
-                            body: t,
-                        },
-                    );
-                });
-                type_check
-            }
-            SDef::VarDef(_vd) => todo!(),
-        })
-        .collect::<Result<Vec<_>, _>>()?;
+                if let Some(prev) = defined.insert(&fd.alias, fd.span) {
+                    let msg = format!("redefinition of '{}'", fd.alias);
+                    errs.extend(diagnostic::of(&redeclared(fd.span, msg, prev, &fd.alias)));
+                    continue;
+                }
+                (&fd.alias, lambda(fd), fd.span)
+            }
+            SDef::VarDef(vd) => match &vd.typ {
+                Type::Func(lt) => (&vd.alias, *lt.clone(), vd.span),
+                _ => continue,
+            },
+        };
+        let lt = match fnv.get(alias) {
+            Some(prev) => match composite_func(prev, &lt) {
+                Some(c) => c,
+                None => {
+                    let label = format!("previous declaration of '{alias}' was here");
+                    errs.push(conflicting(span, alias).label(fdecls[alias], label));
+                    continue;
+                }
+            },
+            None => lt,
+        };
+        let gives = match fnv.get(alias) {
+            Some(prev) => lt.proto && !prev.proto,
+            None => true,
+        };
+        if gives {
+            fdecls.insert(alias.clone(), span);
+        }
+        fnv.insert(alias.clone(), lt);
+    }
+    (fnv, fdecls)
+}
+
+// the type a function definition gives its function
+pub fn lambda(fd: &SFuncDef) -> LambdaType {
+    LambdaType {
+        fp: fd.fps.iter().map(|p| p.typ.clone()).collect(),
+        body: fd.typ.clone(),
+        variadic: fd.variadic,
+        proto: fd.proto,
+    }
+}
+
+// an expression may be typed more than once, but each warning is reported once
+fn warn(tnv: &Tnv, d: Diagnostic) {
+    let mut ws = tnv.warnings.borrow_mut();
+    if !ws.contains(&d) {
+        ws.push(d)
+    }
+}
+
+// a call to a function declared nowhere declares it as extern int f()
+// (C89 3.3.2.2), which this accepts with a warning
+pub fn implicit() -> LambdaType {
+    LambdaType {
+        fp: vec![],
+        body: Type::Int,
+        variadic: false,
+        proto: false,
+    }
+}
+
+// the type two declarations of one function agree on, the prototype's when
+// one has it (C89 3.5.4.3). a declaration without one agrees with a
+// prototype whose parameters are unchanged by the default argument
+// promotions, and an identifier list's with one whose parameters are the
+// promoted types of its own
+fn composite_func(prev: &LambdaType, next: &LambdaType) -> Option<LambdaType> {
+    if prev.body != next.body {
+        return None;
+    }
+    let same = |l: &[Type], r: &[Type]| {
+        l.len() == r.len() && l.iter().zip(r).all(|(l, r)| l.unqual() == r.unqual())
+    };
+    let promoted = |ts: &[Type]| {
+        ts.iter()
+            .map(|t| promote_arg(t.unqual()))
+            .collect::<Vec<_>>()
+    };
+    match (prev.proto, next.proto) {
+        (true, true) if prev.variadic == next.variadic && same(&prev.fp, &next.fp) => {
+            Some(prev.clone())
+        }
+        (true, true) => None,
+        (false, false) if prev.fp.is_empty() => Some(next.clone()),
+        (false, false) => Some(prev.clone()),
+        (true, false) | (false, true) => {
+            let (p, q) = if prev.proto {
+                (prev, next)
+            } else {
+                (next, prev)
+            };
+            let agree = match q.fp.is_empty() {
+                true => !p.variadic && same(&p.fp, &promoted(&p.fp)),
+                false => !p.variadic && same(&p.fp, &promoted(&q.fp)),
+            };
+            agree.then(|| p.clone())
+        }
+    }
+}
 
-    Ok(tnv
-        .fnv
-        .get("main")
-        .ok_or(io::Error::new(
-            io::ErrorKind::Other,
-            "main function not found",
-        ))?
-        .body
-        .clone())
+// the default argument promotions (C89 3.3.2.2)
+fn promote_arg(t: &Type) -> Type {
+    match t {
+        Type::Float => Type::Double,
+        t => promote(t),
+    }
+}
+
+// the type two declarations of one object agree on, where one may give the
+// size of an array the other left unknown
+pub fn composite(prev: &Type, next: &Type) -> Option<Type> {
+    match (prev, next) {
+        (prev, next) if prev == next => Some(prev.clone()),
+        (Type::Func(p), Type::Func(n)) => composite_func(p, n).map(|f| Type::Func(Box::new(f))),
+        (Type::Array(pe, None), Type::Array(ne, Some(_))) if pe == ne => Some(next.clone()),
+        (Type::Array(pe, Some(_)), Type::Array(ne, None)) if pe == ne => Some(prev.clone()),
+        _ => None,
+    }
 }
 
+// the definition, elaborated
 pub fn type_func(
     fd: &SFuncDef,
     gnv: &Tnv,
     mut ltnv: HashMap<String, Type>,
-) -> Result<Type, io::Error> {
+) -> Result<SFuncDef, io::Error> {
     //      Γ [e1 <- T1], ... [en <- Tn] ⊢ B : T2
     // -------------------------------------------------------
     //    Γ ⊢ (lambda e1:T1 ... en:Tn B) : (T1 * ... * Tn -> T2)
 
-    fd.fps.iter().for_each(|(a, t)| {
-        ltnv.insert(a.clone(), t.clone()); // Γ [e1 <- T1], ... [en <- Tn]
-    });
+    // parameters and results are passed by value, so their sizes must be known
+    if let Some(p) = fd.fps.iter().find(|p| p.typ.size(&gnv.records).is_none()) {
+        return Err(type_error(
+            p.span,
+            format!("parameter '{}' has incomplete type", p.alias),
+        ));
+    }
+    if *fd.typ.unqual() != Type::Void && fd.typ.size(&gnv.records).is_none() {
+        return Err(type_error(fd.span, "return type is an incomplete type"));
+    }
+
+    // the parameters are in the scope of the body's block
+    let mut scope = Scope::new(ltnv.clone());
+    for p in &fd.fps {
+        if let Some((_, prev)) = scope.own.get(&p.alias) {
+            let msg = format!("redefinition of parameter '{}'", p.alias);
+            return Err(redeclared(p.span, msg, *prev, &p.alias));
+        }
+        scope.declare(&p.alias, &None, p.typ.clone(), p.span)?; // Γ [e1 <- T1], ... [en <- Tn]
+        ltnv.insert(p.alias.clone(), p.typ.clone());
+    }
+
+    type_stmts(&fd.body, fd.span, &fd.typ, gnv, &mut scope).and_then(|(bt, bs)| {
+        // ⊢ B : T2
+        if !assignable(&fd.typ, &bt) {
+            return Err(mismatch(bs, &fd.typ, &bt));
+        }
+        // Γ ⊢ (lambda e1:T1 ... en:Tn B) : (T1 * ... * Tn -> T2)
+        // a definition without a prototype has one when a declaration gives it
+        let body = elab_stmts(&fd.body, &fd.typ, gnv, &mut ltnv)?;
+        let proto = gnv.fnv.get(&fd.alias).is_some_and(|f| f.proto);
+        Ok(SFuncDef {
+            body,
+            proto: fd.proto || proto,
+            ..fd.clone()
+        })
+    })
+}
 
-    fd.body
+// a statement's type is what it returns, Void when it cannot. statements in
+// sequence (or on either branch) must return agreeing types, and the result
+// is the first one's, with the span it was found at. rt is the function's
+// return type
+fn type_stmts(
+    stmts: &[SStmt],
+    span: Span,
+    rt: &Type,
+    gnv: &Tnv,
+    scope: &mut Scope,
+) -> Result<(Type, Span), io::Error> {
+    stmts
         .iter()
-        .map(|stmt| type_stmt(stmt, gnv, &mut ltnv))
+        .map(|stmt| type_stmt(stmt, rt, gnv, scope).map(|t| (t, stmt)))
         .collect::<Result<Vec<_>, _>>()?
         .iter()
-        .try_fold(Type::Void, |prev_t, next_t| match (&prev_t, next_t) {
-            (_, Type::Void) => Ok(prev_t),
-            (Type::Void, _) => Ok(next_t.clone()),
-            (prev_t, next_t) => {
-                if prev_t == next_t {
-                    Ok(prev_t.clone())
-                } else {
-                    Err(io::Error::new(io::ErrorKind::Other, "type error"))
-                }
-            }
+        .try_fold((Type::Void, span), |(prev_t, prev_s), (next_t, stmt)| {
+            let s = match (&prev_t, next_t) {
+                (Type::Void, t) if *t != Type::Void => stmt.span,
+                _ => prev_s,
+            };
+            join(prev_t, next_t, stmt.span).map(|t| (t, s))
         })
-        .and_then(|bt| {
-            // ⊢ B : T2
-            if bt == fd.typ {
-                Ok(bt) // Γ ⊢ (lambda e1:T1 ... en:Tn B) : (T1 * ... * Tn -> T2)
+}
+
+fn join(prev_t: Type, next_t: &Type, span: Span) -> Result<Type, io::Error> {
+    match (&prev_t, next_t) {
+        (_, Type::Void) => Ok(prev_t),
+        (Type::Void, _) => Ok(next_t.clone()),
+        (prev_t, next_t) => {
+            if assignable(prev_t, next_t) {
+                Ok(prev_t.clone())
             } else {
-                Err(io::Error::new(io::ErrorKind::Other, "type error"))
+                Err(mismatch(span, prev_t, next_t))
             }
-        })
+        }
+    }
 }
 
-pub fn type_stmt(
-    stmt: &SStmt,
-    gnv: &Tnv,
-    ltnv: &mut HashMap<String, Type>,
-) -> Result<Type, io::Error> {
-    match stmt {
-        SStmt::IfEls { cond, then, els } => {
-            let ct = type_expr(cond, gnv, ltnv)?;
-            let tt = type_stmt(then, gnv, ltnv)?;
-            let et = els
-                .as_ref()
-                .map(|els| type_stmt(els, gnv, ltnv))
-                .transpose()?;
-
-            // todo (for now):
-            // 3. tt and et are Stmt, not Vec<Stmt>
-            // 4. must return expression Type. not void/stmt/valid/cmd Type
-
-            match et {
-                Some(et) => {
-                    if ct == Type::Bool && tt == et {
-                        Ok(tt)
-                    } else {
-                        Err(io::Error::new(io::ErrorKind::Other, "type error"))
-                    }
-                }
-                None => Ok(tt.clone()),
+// the objects a block sees: the enclosing blocks' and its own, which shadow
+// them. only its own are checked for redeclaration (C89 3.1.2.1, 3.5)
+#[derive(Clone, Debug)]
+pub struct Scope {
+    pub vnv: HashMap<String, Type>,
+    own: HashMap<String, (Option<StorageClass>, Span)>,
+    autos: HashSet<String>, // the objects with automatic storage, parameters included
+}
+
+impl Scope {
+    pub fn new(vnv: HashMap<String, Type>) -> Self {
+        Scope {
+            vnv,
+            own: HashMap::new(),
+            autos: HashSet::new(),
+        }
+    }
+
+    // the scope of a block inside this one
+    fn nested(&self) -> Scope {
+        Scope {
+            autos: self.autos.clone(),
+            ..Scope::new(self.vnv.clone())
+        }
+    }
+
+    // an identifier without linkage is declared once in its scope. an extern
+    // one can be declared again, with a compatible type
+    fn declare(
+        &mut self,
+        alias: &str,
+        sc: &Option<StorageClass>,
+        t: Type,
+        span: Span,
+    ) -> Result<(), io::Error> {
+        let t = match self.own.get(alias) {
+            None => t,
+            Some((Some(StorageClass::Extern), _)) if *sc == Some(StorageClass::Extern) => {
+                composite(&self.vnv[alias], &t).ok_or_else(|| conflicting(span, alias))?
             }
+            Some((_, prev)) => {
+                let msg = format!("redeclaration of '{alias}' with no linkage");
+                return Err(redeclared(span, msg, *prev, alias));
+            }
+        };
+        match sc {
+            Some(StorageClass::Static | StorageClass::Extern) => self.autos.remove(alias),
+            _ => self.autos.insert(alias.to_owned()),
+        };
+        self.own.insert(alias.to_owned(), (sc.clone(), span));
+        self.vnv.insert(alias.to_owned(), t);
+        Ok(())
+    }
+}
+
+pub fn type_stmt(stmt: &SStmt, rt: &Type, gnv: &Tnv, scope: &mut Scope) -> Result<Type, io::Error> {
+    match &stmt.kind {
+        SStmtKind::IfEls { cond, then, els } => {
+            cond_expr(cond, gnv, &scope.vnv)?;
+            let tt = type_stmt(then, rt, gnv, scope)?;
+            match els {
+                Some(els) => join(tt, &type_stmt(els, rt, gnv, scope)?, els.span),
+                None => Ok(tt),
+            }
+        }
+        SStmtKind::Switch { cond, body } => match type_expr(cond, gnv, &scope.vnv)? {
+            ct if is_integral(&ct) => type_stmt(body, rt, gnv, scope),
+            _ => Err(type_error(cond.span, "switch quantity not an integer")),
+        },
+        SStmtKind::While { cond, body } | SStmtKind::DoWhile { body, cond } => {
+            cond_expr(cond, gnv, &scope.vnv)?;
+            type_stmt(body, rt, gnv, scope)
+        }
+        SStmtKind::For {
+            init,
+            cond,
+            update,
+            body,
+        } => {
+            if let Some(cond) = cond {
+                cond_expr(cond, gnv, &scope.vnv)?;
+            }
+            for e in init.iter().chain(update) {
+                type_expr(e, gnv, &scope.vnv)?;
+            }
+            type_stmt(body, rt, gnv, scope)
+        }
+        SStmtKind::Case(_, s) | SStmtKind::Default(s) | SStmtKind::Labeled(_, s) => {
+            type_stmt(s, rt, gnv, scope)
         }
-        SStmt::While { cond: _, body: _ } => todo!(),
-        SStmt::Asnmt(vd) => {
-            let et = type_expr(&vd.expr, gnv, ltnv)?;
-            ltnv.insert(vd.alias.clone(), et.clone()); // Γ [x <- T]
-            Ok(et)
+        SStmtKind::Break | SStmtKind::Continue | SStmtKind::Goto(_) | SStmtKind::Empty => {
+            Ok(Type::Void)
         }
-        SStmt::Return(expr) => {
-            let rt = type_expr(expr, gnv, ltnv)?;
-            Ok(rt)
+        // the block's declarations go out of scope with it
+        SStmtKind::Block(stmts) => {
+            type_stmts(stmts, stmt.span, rt, gnv, &mut scope.nested()).map(|(t, _)| t)
         }
+        SStmtKind::Expr(e) => type_expr(e, gnv, &scope.vnv).map(|_| Type::Void),
+        SStmtKind::Decl(vds) => vds.iter().try_fold(Type::Void, |_, vd| {
+            if let Type::Func(_) = vd.typ {
+                return Ok(Type::Void); // block-scope function declarations are not entered yet
+            }
+            // the name is in scope from the end of its declarator, so its
+            // own initializer sees it (C89 3.1.2.1), with the type that the
+            // initializer may then complete
+            scope.declare(&vd.alias, &vd.sc, vd.typ.clone(), vd.span)?; // Γ [x <- T]
+            let t = match &vd.expr {
+                Some(init) => {
+                    let is_static = vd.sc == Some(StorageClass::Static);
+                    let statics = is_static.then_some(&scope.autos);
+                    let t = type_init(&vd.typ, init, statics, gnv, &scope.vnv)?;
+                    scope.vnv.insert(vd.alias.clone(), t.clone());
+                    t
+                }
+                None => vd.typ.clone(),
+            };
+            if t.size(&gnv.records).is_none() && vd.sc != Some(StorageClass::Extern) {
+                let msg = format!("storage size of '{}' isn't known", vd.alias);
+                return Err(type_error(vd.span, msg));
+            }
+            Ok(Type::Void) // a declaration binds, the block's type comes from its returns
+        }),
+        // the value is converted to the return type as if by assignment
+        SStmtKind::Return(Some(expr)) => match type_expr(expr, gnv, &scope.vnv)? {
+            t if convertible(rt, expr, &t, gnv, &scope.vnv) => Ok(rt.clone()),
+            t => Err(mismatch(expr.span, rt, &t)),
+        },
+        SStmtKind::Return(None) => Ok(Type::Void),
+    }
+}
+
+// the controlling expression of an if or a loop is compared against 0
+// (C89 3.6.4.1, 3.6.5), so it can be any scalar
+fn cond_expr(e: &SExpr, gnv: &Tnv, ltnv: &HashMap<String, Type>) -> Result<(), io::Error> {
+    match type_expr(e, gnv, ltnv)? {
+        t if is_arith(&t) || matches!(t, Type::Pointer(_) | Type::Bool) => Ok(()),
+        t => Err(type_error(
+            e.span,
+            format!("used {:?} where scalar is required", t),
+        )),
     }
 }
 
 pub fn type_expr(e: &SExpr, gtnv: &Tnv, ltnv: &HashMap<String, Type>) -> Result<Type, io::Error> {
-    match e {
+    match &e.kind {
         // ---------------------intros (axioms)-------------------------
-        SExpr::Int(_) => Ok(Type::Int),   // ⊢ n : Int
-        SExpr::Bool(_) => Ok(Type::Bool), // ⊢ b : Bool
+        SExprKind::Int(_) => Ok(Type::Int),   // ⊢ n : Int
+        SExprKind::UInt(_) => Ok(Type::UInt), // ⊢ nu : UInt
+        SExprKind::Long(_) => Ok(Type::Long), // ⊢ nl : Long
+        SExprKind::ULong(_) => Ok(Type::ULong),
+        SExprKind::Float(_) => Ok(Type::Float), // ⊢ r : Float
+        SExprKind::Double(_) => Ok(Type::Double),
+        SExprKind::LongDouble(_) => Ok(Type::LongDouble),
+        SExprKind::Bool(_) => Ok(Type::Bool), // ⊢ b : Bool
         // ---------------------elims (rules)--------------------------
-        SExpr::UnaryE { op: _, l } => type_expr(l, gtnv, ltnv),
-        SExpr::BinE { op, l, r } => match op {
+        SExprKind::UnaryE { op, l } => match (op, type_expr(l, gtnv, ltnv)?) {
+            (SUnaryOp::Add | SUnaryOp::Sub, t) if is_arith(&t) => Ok(promote(&t)),
+            (SUnaryOp::BitNot, t) if is_integral(&t) => Ok(promote(&t)),
+            (SUnaryOp::Not, t) if is_arith(&t) || matches!(t, Type::Pointer(_) | Type::Bool) => {
+                Ok(Type::Int) // !e is e == 0
+            }
+            (_, t) => Err(type_error(
+                e.span,
+                format!("wrong type argument to unary operator (have {:?})", t),
+            )),
+        },
+        SExprKind::BinE { op, l, r } => match op {
             // ignoring distinctions within types
-            SBinOp::Add | SBinOp::Sub | SBinOp::Mult | SBinOp::Div | SBinOp::Mod => {
-                // ⊢ e1 : Int, ⊢ e2 : Int
+            SBinOp::Add | SBinOp::Sub | SBinOp::Mult | SBinOp::Div => {
+                // ⊢ e1 : T1, ⊢ e2 : T2
                 // ------------------------ BIN_OP
-                //     ⊢ e1 + e2 : Int
+                //  ⊢ e1 + e2 : T1 ⊔ T2
                 match (type_expr(l, gtnv, ltnv)?, type_expr(r, gtnv, ltnv)?) {
-                    (Type::Int, Type::Int) => Ok(Type::Int),
-                    _ => Err(io::Error::new(io::ErrorKind::Other, "type error")),
+                    (lt, rt) if is_arith(&lt) && is_arith(&rt) => Ok(arith(&lt, &rt)),
+                    // a pointer moves by whole elements, and two pointers
+                    // into the same array are some elements apart (C89 3.3.6)
+                    (Type::Pointer(t), it)
+                        if matches!(op, SBinOp::Add | SBinOp::Sub) && is_integral(&it) =>
+                    {
+                        element(&gtnv.records, &t, e.span).map(|_| Type::Pointer(t))
+                    }
+                    (it, Type::Pointer(t)) if *op == SBinOp::Add && is_integral(&it) => {
+                        element(&gtnv.records, &t, e.span).map(|_| Type::Pointer(t))
+                    }
+                    (Type::Pointer(a), Type::Pointer(b))
+                        if *op == SBinOp::Sub && compatible(&a, &b) =>
+                    {
+                        element(&gtnv.records, &a, e.span).map(|_| Type::Int) // ptrdiff_t is int on ILP32
+                    }
+                    (lt, rt) if is_pointer(&lt) || is_pointer(&rt) => {
+                        Err(invalid_operands(e.span, bin_op(op), &lt, &rt))
+                    }
+                    (lt, rt) if is_arith(&lt) => Err(mismatch(r.span, &Type::Int, &rt)),
+                    (lt, _) => Err(mismatch(l.span, &Type::Int, &lt)),
                 }
             } // perserves distinctions between types
+            SBinOp::Mod => match (type_expr(l, gtnv, ltnv)?, type_expr(r, gtnv, ltnv)?) {
+                (lt, rt) if is_integral(&lt) && is_integral(&rt) => Ok(arith(&lt, &rt)),
+                (lt, rt) if is_integral(&lt) => Err(mismatch(r.span, &Type::Int, &rt)),
+                (lt, _) => Err(mismatch(l.span, &Type::Int, &lt)),
+            },
         },
-        SExpr::VarApp(alias) => ltnv // Γ ⊢ x: Γ(x)
-            .get(alias)
-            .cloned()
-            .ok_or(io::Error::new(io::ErrorKind::Other, "type error")),
-        SExpr::FuncApp { alias, aps: ap } => {
+        // comparisons and logical operators yield an int, 1 when they hold
+        // (C89 3.3.8, 3.3.9, 3.3.13, 3.3.14)
+        SExprKind::RelE { op, l, r } => {
+            match (type_expr(l, gtnv, ltnv)?, type_expr(r, gtnv, ltnv)?) {
+                (lt, rt) if is_arith(&lt) && is_arith(&rt) => Ok(Type::Int),
+                // pointers to compatible types compare, and for equality a
+                // pointer also compares with void * and a null pointer constant
+                (Type::Pointer(a), Type::Pointer(b)) if compatible(&a, &b) => Ok(Type::Int),
+                (Type::Pointer(a), Type::Pointer(b))
+                    if matches!(op, SRelOp::Eq | SRelOp::Neq) && (is_void(&a) || is_void(&b)) =>
+                {
+                    Ok(Type::Int)
+                }
+                (Type::Pointer(_), t)
+                    if matches!(op, SRelOp::Eq | SRelOp::Neq) && null(r, &t, gtnv, ltnv) =>
+                {
+                    Ok(Type::Int)
+                }
+                (t, Type::Pointer(_))
+                    if matches!(op, SRelOp::Eq | SRelOp::Neq) && null(l, &t, gtnv, ltnv) =>
+                {
+                    Ok(Type::Int)
+                }
+                (lt, rt) => Err(invalid_operands(e.span, rel_op(op), &lt, &rt)),
+            }
+        }
+        SExprKind::LogE { l, r, .. } => {
+            cond_expr(l, gtnv, ltnv)?;
+            cond_expr(r, gtnv, ltnv)?;
+            Ok(Type::Int)
+        }
+        // the shifts have the type of their promoted left operand, the others
+        // that of the usual arithmetic conversions (C89 3.3.7, 3.3.10-12)
+        SExprKind::BitE { op, l, r } => {
+            match (type_expr(l, gtnv, ltnv)?, type_expr(r, gtnv, ltnv)?) {
+                (lt, rt) if !is_integral(&lt) || !is_integral(&rt) => {
+                    Err(invalid_operands(e.span, bit_op(op), &lt, &rt))
+                }
+                (lt, _) if matches!(op, SBitOp::Shl | SBitOp::Shr) => Ok(promote(&lt)),
+                (lt, rt) => Ok(arith(&lt, &rt)),
+            }
+        }
+        // Γ ⊢ e1 : T1, Γ ⊢ e2 : T2, Γ ⊢ e3 : T3 ⊢ e1 ? e2 : e3 : T2 ⊔ T3
+        SExprKind::Ternary { cond, then, els } => {
+            cond_expr(cond, gtnv, ltnv)?;
+            match (type_expr(then, gtnv, ltnv)?, type_expr(els, gtnv, ltnv)?) {
+                (tt, et) if is_arith(&tt) && is_arith(&et) => Ok(arith(&tt, &et)),
+                (tt, et) if tt == et => Ok(tt),
+                (Type::Pointer(a), Type::Pointer(b)) if compatible(&a, &b) => Ok(Type::Pointer(a)),
+                (Type::Pointer(a), Type::Pointer(b)) if is_void(&a) || is_void(&b) => {
+                    Ok(Type::Pointer(Box::new(Type::Void)))
+                }
+                (tt @ Type::Pointer(_), et) if null(els, &et, gtnv, ltnv) => Ok(tt),
+                (tt, et @ Type::Pointer(_)) if null(then, &tt, gtnv, ltnv) => Ok(et),
+                (tt, et) => Err(type_error(
+                    e.span,
+                    format!(
+                        "type mismatch in conditional expression ({:?} and {:?})",
+                        tt, et
+                    ),
+                )),
+            }
+        }
+        SExprKind::Comma { l, r } => type_expr(l, gtnv, ltnv).and(type_expr(r, gtnv, ltnv)),
+        // Γ ⊢ e1 : T1 (a modifiable lvalue), Γ ⊢ e2 : T2 ⊢ e1 = e2 : T1
+        SExprKind::Asnmt { op, l, r } => {
+            let lt = modifiable(l, "assignment", gtnv, ltnv)?;
+            let rt = type_expr(r, gtnv, ltnv)?;
+            match op {
+                None if convertible(&lt, r, &rt, gtnv, ltnv) => Ok(lt),
+                None => Err(mismatch(r.span, &lt, &rt)),
+                Some(SAsnmtOp::Add | SAsnmtOp::Sub) if is_pointer(&lt) && is_integral(&rt) => {
+                    let Type::Pointer(t) = &lt else {
+                        unreachable!()
+                    };
+                    element(&gtnv.records, t, e.span).map(|_| lt.clone())
+                }
+                Some(SAsnmtOp::Add | SAsnmtOp::Sub | SAsnmtOp::Mult | SAsnmtOp::Div)
+                    if is_arith(&lt) && is_arith(&rt) =>
+                {
+                    Ok(lt)
+                }
+                Some(_) if is_integral(&lt) && is_integral(&rt) => Ok(lt),
+                Some(_) => Err(type_error(
+                    e.span,
+                    format!(
+                        "invalid operands to compound assignment (have {:?} and {:?})",
+                        lt, rt
+                    ),
+                )),
+            }
+        }
+        SExprKind::Update { op, l } => {
+            let what = match op {
+                SUpdateOp::PreInc | SUpdateOp::PostInc => "increment",
+                SUpdateOp::PreDec | SUpdateOp::PostDec => "decrement",
+            };
+            match modifiable(l, what, gtnv, ltnv)? {
+                t if is_arith(&t) => Ok(t),
+                Type::Pointer(t) => element(&gtnv.records, &t, e.span).map(|_| Type::Pointer(t)),
+                t => Err(type_error(
+                    e.span,
+                    format!("wrong type argument to {what} (have {:?})", t),
+                )),
+            }
+        }
+        // Γ ⊢ x: Γ(x), without qualifiers as an rvalue. the objects that
+        // expressions designate are converted to their values
+        SExprKind::VarApp(_)
+        | SExprKind::Str(_)
+        | SExprKind::Deref(_)
+        | SExprKind::Index { .. } => lvalue(e, gtnv, ltnv).map(|t| decay(&t.expect("an object"))),
+        // Γ ⊢ e : T (an lvalue) ⊢ &e : T *
+        SExprKind::AddrOf(l) => match lvalue(l, gtnv, ltnv)? {
+            Some(t) => Ok(Type::Pointer(Box::new(t))),
+            None => Err(type_error(e.span, "lvalue required as unary '&' operand")),
+        },
+        SExprKind::FuncApp { f: g, aps: ap } => {
             //    Γ ⊢ f : (T1-> T2)      Γ ⊢ e : T1, ... Γ ⊢ e : Tn
             // ------------------------------------------------------- FUNC_APP
             //             Γ ⊢ f(e1, ... en) : T2
 
-            // Γ ⊢ f : (T1-> T2)
-            let f = gtnv
-                .fnv
-                .get(alias)
-                .cloned()
-                .ok_or(io::Error::new(io::ErrorKind::Other, "type error"))?;
-
-            f.fp.iter()
-                .zip(ap.iter())
-                .map(|(fpt, ap)| {
-                    type_expr(ap, gtnv, ltnv).and_then(|apt| {
-                        //Γ ⊢ e : T1, ... Γ ⊢ e : Tn
-                        if apt == *fpt {
-                            Ok(())
-                        } else {
-                            Err(io::Error::new(io::ErrorKind::Other, "type error"))
-                        }
-                    })
-                })
-                .collect::<Result<Vec<_>, _>>()
-                .map(|_| f.body) // Γ ⊢ f(e) : T2
-        }
-        _ => Err(io::Error::new(io::ErrorKind::Other, "type error")),
+            // Γ ⊢ f : (T1-> T2)
+            let (direct, f) = callee(g, gtnv, ltnv)?;
+            let f = f.unwrap_or_else(|| {
+                let alias = direct.expect("a function's name");
+                let msg = format!("implicit declaration of function '{alias}'");
+                let code = Code::ImplicitFunctionDeclaration;
+                warn(gtnv, Diagnostic::new(code, e.span, msg));
+                implicit()
+            });
+
+            // a function pointer is named after the object that holds it
+            let alias = match &g.kind {
+                SExprKind::VarApp(alias) => format!("'{alias}'"),
+                _ => "the called function".to_string(),
+            };
+
+            // a prototype fixes the number of arguments, or the least one when
+            // it ends in ...
+            let declared = |d: Diagnostic| match direct.and_then(|f| gtnv.fdecls.get(f)) {
+                Some(span) => d.label(*span, format!("{alias} declared here")),
+                None => d,
+            };
+            let (n, m) = (f.fp.len(), ap.len());
+            if f.proto && (m < n || m > n && !f.variadic) {
+                let (few, least) = match m < n {
+                    true => ("few", if f.variadic { "at least " } else { "" }),
+                    false => ("many", ""),
+                };
+                let msg = format!(
+                    "too {few} arguments to function {alias}: expected {least}{n}, have {m}"
+                );
+                return Err(declared(Diagnostic::new(Code::Arity, e.span, msg)).into());
+            }
+
+            // without a prototype the arguments are not checked against the
+            // parameters, only typed
+            let fp = match f.proto {
+                true => f.fp.clone(),
+                false => vec![],
+            };
+            for (i, ap) in ap.iter().enumerate() {
+                let apt = type_expr(ap, gtnv, ltnv)?;
+                //Γ ⊢ e : T1, ... Γ ⊢ e : Tn
+                match fp.get(i) {
+                    Some(fpt) if !convertible(fpt, ap, &apt, gtnv, ltnv) => {
+                        let msg = format!(
+                            "type error: passing {:?} to parameter {} of {alias}, which is {:?}",
+                            apt,
+                            i + 1,
+                            fpt
+                        );
+                        let d = Diagnostic::new(Code::Mismatch, ap.span, msg);
+                        return Err(declared(d).into());
+                    }
+                    _ => (),
+                }
+            }
+            Ok(f.body) // Γ ⊢ f(e) : T2
+        }
+        SExprKind::Cast { typ, l } => match (typ.unqual(), type_expr(l, gtnv, ltnv)?) {
+            (Type::Void, _) => Ok(Type::Void),
+            (to, from) if is_arith(to) && is_arith(&from) => Ok(to.clone()),
+            // pointers convert to each other and to and from integers
+            (to @ Type::Pointer(_), from) if is_pointer(&from) || is_integral(&from) => {
+                Ok(to.clone())
+            }
+            (to, Type::Pointer(_)) if is_integral(to) => Ok(to.clone()),
+            (to, from) => Err(type_error(
+                e.span,
+                format!("type error: cannot cast {:?} to {:?}", from, to),
+            )),
+        },
+        SExprKind::Dot { l, field } => {
+            member(&gtnv.records, &type_expr(l, gtnv, ltnv)?, field, e.span)
+        }
+        SExprKind::Arrow { l, field } => match type_expr(l, gtnv, ltnv)? {
+            Type::Pointer(t) => member(&gtnv.records, &t, field, e.span),
+            t => Err(type_error(
+                l.span,
+                format!("invalid type argument of '->' (have {:?})", t),
+            )),
+        },
+        // sizeof yields a size_t, which is unsigned int on ILP32
+        SExprKind::SizeOfT(t) => size_of(&gtnv.records, t, e.span).map(|_| Type::UInt),
+        SExprKind::SizeOfE(l) => {
+            size_of(&gtnv.records, &object(l, gtnv, ltnv)?, e.span).map(|_| Type::UInt)
+        }
+        _ => Err(type_error(e.span, "type error")),
+    }
+}
+
+// ---------------------elaboration--------------------------
+// a type checked function body becomes its typed tree: every expression is
+// annotated with its type, its implicit conversions are made casts, and the
+// values of its lvalues loads
+
+fn elab_stmts(
+    stmts: &[SStmt],
+    rt: &Type,
+    gnv: &Tnv,
+    ltnv: &mut HashMap<String, Type>,
+) -> Result<Vec<SStmt>, io::Error> {
+    stmts.iter().map(|s| elab_stmt(s, rt, gnv, ltnv)).collect()
+}
+
+fn elab_stmt(
+    stmt: &SStmt,
+    rt: &Type,
+    gnv: &Tnv,
+    ltnv: &mut HashMap<String, Type>,
+) -> Result<SStmt, io::Error> {
+    let kind = match &stmt.kind {
+        SStmtKind::IfEls { cond, then, els } => SStmtKind::IfEls {
+            cond: Box::new(value(cond, gnv, ltnv)?),
+            then: Box::new(elab_stmt(then, rt, gnv, ltnv)?),
+            els: match els {
+                Some(els) => Some(Box::new(elab_stmt(els, rt, gnv, ltnv)?)),
+                None => None,
+            },
+        },
+        SStmtKind::Switch { cond, body } => SStmtKind::Switch {
+            cond: Box::new(value(cond, gnv, ltnv)?),
+            body: Box::new(elab_stmt(body, rt, gnv, ltnv)?),
+        },
+        SStmtKind::While { cond, body } => SStmtKind::While {
+            cond: Box::new(value(cond, gnv, ltnv)?),
+            body: Box::new(elab_stmt(body, rt, gnv, ltnv)?),
+        },
+        SStmtKind::DoWhile { body, cond } => SStmtKind::DoWhile {
+            body: Box::new(elab_stmt(body, rt, gnv, ltnv)?),
+            cond: Box::new(value(cond, gnv, ltnv)?),
+        },
+        SStmtKind::For {
+            init,
+            cond,
+            update,
+            body,
+        } => {
+            let value = |e: &Option<Box<SExpr>>| match e {
+                Some(e) => value(e, gnv, ltnv).map(|e| Some(Box::new(e))),
+                None => Ok(None),
+            };
+            SStmtKind::For {
+                init: value(init)?,
+                cond: value(cond)?,
+                update: value(update)?,
+                body: Box::new(elab_stmt(body, rt, gnv, ltnv)?),
+            }
+        }
+        SStmtKind::Case(n, s) => SStmtKind::Case(*n, Box::new(elab_stmt(s, rt, gnv, ltnv)?)),
+        SStmtKind::Default(s) => SStmtKind::Default(Box::new(elab_stmt(s, rt, gnv, ltnv)?)),
+        SStmtKind::Labeled(l, s) => {
+            SStmtKind::Labeled(l.clone(), Box::new(elab_stmt(s, rt, gnv, ltnv)?))
+        }
+        SStmtKind::Break | SStmtKind::Continue | SStmtKind::Goto(_) | SStmtKind::Empty => {
+            stmt.kind.clone()
+        }
+        SStmtKind::Block(stmts) => SStmtKind::Block(elab_stmts(stmts, rt, gnv, &mut ltnv.clone())?),
+        SStmtKind::Expr(e) => SStmtKind::Expr(value(e, gnv, ltnv)?),
+        // a declaration's type is completed by its initializer
+        SStmtKind::Decl(vds) => SStmtKind::Decl(
+            vds.iter()
+                .map(|vd| {
+                    if matches!(vd.typ, Type::Func(_)) {
+                        return Ok(vd.clone());
+                    }
+                    ltnv.insert(vd.alias.clone(), vd.typ.clone()); // seen by its initializer
+                    let (typ, expr) = match &vd.expr {
+                        Some(init) => {
+                            let (t, init) = elab_init(&vd.typ, init, gnv, ltnv)?;
+                            (t, Some(Box::new(init)))
+                        }
+                        None => (vd.typ.clone(), None),
+                    };
+                    ltnv.insert(vd.alias.clone(), typ.clone());
+                    Ok(SVarDef {
+                        typ,
+                        expr,
+                        ..vd.clone()
+                    })
+                })
+                .collect::<Result<_, io::Error>>()?,
+        ),
+        SStmtKind::Return(Some(e)) => SStmtKind::Return(Some(conv(value(e, gnv, ltnv)?, rt))),
+        SStmtKind::Return(None) => SStmtKind::Return(None),
+    };
+    Ok(SStmt::new(kind, stmt.span))
+}
+
+// an initializer, with each of its elements converted to the type of the
+// subobject it initializes, and the type it completes. a braced list is not
+// an expression, so it has no type of its own
+fn elab_init(
+    t: &Type,
+    init: &SExpr,
+    gtnv: &Tnv,
+    ltnv: &HashMap<String, Type>,
+) -> Result<(Type, SExpr), io::Error> {
+    // the elements are the list's leaves, in order
+    fn leaves(init: &SExpr, elems: &mut impl Iterator<Item = SExpr>) -> SExpr {
+        match &init.kind {
+            SExprKind::InitList(items) => {
+                let items = items.iter().map(|i| leaves(i, elems)).collect();
+                SExpr::new(SExprKind::InitList(items), init.span)
+            }
+            _ => elems.next().expect("an element for each leaf"),
+        }
+    }
+
+    let (t, inits) = flatten(&gtnv.records, t, init)?;
+    let elems = inits
+        .into_iter()
+        .map(|(_, et, e)| match (&e.kind, et.unqual()) {
+            (SExprKind::Str(_), Type::Array(..)) => elab(e, gtnv, ltnv), // the array's contents
+            _ => Ok(conv(value(e, gtnv, ltnv)?, &et)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((t, leaves(init, &mut elems.into_iter())))
+}
+
+// an operand whose value is used. an lvalue's is loaded from the object it
+// designates
+fn value(e: &SExpr, gtnv: &Tnv, ltnv: &HashMap<String, Type>) -> Result<SExpr, io::Error> {
+    let typed = elab(e, gtnv, ltnv)?;
+    match lvalue(e, gtnv, ltnv)? {
+        Some(_) => Ok(SExpr::typed(
+            SExprKind::Load(Box::new(typed)),
+            type_expr(e, gtnv, ltnv)?,
+            e.span,
+        )),
+        None => Ok(typed),
+    }
+}
+
+// the typed expression converted to type to, by a cast when its type is
+// another. nothing converts to void or to a record
+fn conv(e: SExpr, to: &Type) -> SExpr {
+    let to = to.unqual();
+    match &e.typ {
+        Some(t) if t == to || *to == Type::Void || to.is_record() => e,
+        _ => {
+            let span = e.span;
+            let kind = SExprKind::Cast {
+                typ: to.clone(),
+                l: Box::new(e),
+            };
+            SExpr::typed(kind, to.clone(), span)
+        }
+    }
+}
+
+// an expression that type checks, typed. its operands are converted as the
+// operator requires (C89 3.2.1.5 and 3.3)
+fn elab(e: &SExpr, gtnv: &Tnv, ltnv: &HashMap<String, Type>) -> Result<SExpr, io::Error> {
+    let value = |e: &SExpr| value(e, gtnv, ltnv).map(Box::new);
+    let conv = |e: &SExpr, t: &Type| value(e).map(|e| Box::new(conv(*e, t)));
+    let type_of = |e: &SExpr| type_expr(e, gtnv, ltnv);
+
+    let kind = match &e.kind {
+        SExprKind::UnaryE {
+            op: SUnaryOp::Not,
+            l,
+        } => SExprKind::UnaryE {
+            op: SUnaryOp::Not,
+            l: value(l)?,
+        },
+        SExprKind::UnaryE { op, l } => SExprKind::UnaryE {
+            op: op.clone(),
+            l: conv(l, &type_of(e)?)?,
+        },
+        // arithmetic operands are brought to their common type. a pointer's
+        // integer operand is scaled by the backend
+        SExprKind::BinE { op, l, r } => {
+            let (l, r) = match (type_of(l)?, type_of(r)?) {
+                (lt, rt) if is_arith(&lt) && is_arith(&rt) => {
+                    let t = arith(&lt, &rt);
+                    (conv(l, &t)?, conv(r, &t)?)
+                }
+                _ => (value(l)?, value(r)?),
+            };
+            SExprKind::BinE {
+                op: op.clone(),
+                l,
+                r,
+            }
+        }
+        // a null pointer constant compared with a pointer becomes one
+        SExprKind::RelE { op, l, r } => {
+            let (l, r) = match (type_of(l)?, type_of(r)?) {
+                (lt, rt) if is_arith(&lt) && is_arith(&rt) => {
+                    let t = arith(&lt, &rt);
+                    (conv(l, &t)?, conv(r, &t)?)
+                }
+                (pt @ Type::Pointer(_), t) if !is_pointer(&t) => (value(l)?, conv(r, &pt)?),
+                (t, pt @ Type::Pointer(_)) if !is_pointer(&t) => (conv(l, &pt)?, value(r)?),
+                _ => (value(l)?, value(r)?),
+            };
+            SExprKind::RelE {
+                op: op.clone(),
+                l,
+                r,
+            }
+        }
+        SExprKind::LogE { op, l, r } => SExprKind::LogE {
+            op: op.clone(),
+            l: value(l)?,
+            r: value(r)?,
+        },
+        // each operand of a shift is promoted on its own
+        SExprKind::BitE { op, l, r } => {
+            let (lt, rt) = (type_of(l)?, type_of(r)?);
+            let (l, r) = match op {
+                SBitOp::Shl | SBitOp::Shr => (conv(l, &promote(&lt))?, conv(r, &promote(&rt))?),
+                _ => (conv(l, &arith(&lt, &rt))?, conv(r, &arith(&lt, &rt))?),
+            };
+            SExprKind::BitE {
+                op: op.clone(),
+                l,
+                r,
+            }
+        }
+        SExprKind::Ternary { cond, then, els } => {
+            let t = type_of(e)?;
+            SExprKind::Ternary {
+                cond: value(cond)?,
+                then: conv(then, &t)?,
+                els: conv(els, &t)?,
+            }
+        }
+        SExprKind::Comma { l, r } => SExprKind::Comma {
+            l: value(l)?,
+            r: value(r)?,
+        },
+        // e1 op= e2 computes in the type e1 op e2 would have, and stores
+        // that converted back
+        SExprKind::Asnmt { op, l, r } => {
+            let (lt, rt) = (object(l, gtnv, ltnv)?, type_of(r)?);
+            let r = match op {
+                None => conv(r, &lt)?,
+                Some(SAsnmtOp::Shl | SAsnmtOp::Shr) => conv(r, &promote(&rt))?,
+                Some(_) if is_pointer(&lt) => value(r)?,
+                Some(_) => conv(r, &arith(&lt, &rt))?,
+            };
+            SExprKind::Asnmt {
+                op: op.clone(),
+                l: Box::new(elab(l, gtnv, ltnv)?),
+                r,
+            }
+        }
+        SExprKind::Update { op, l } => SExprKind::Update {
+            op: op.clone(),
+            l: Box::new(elab(l, gtnv, ltnv)?),
+        },
+        // arguments convert to a prototype's parameters, and the others (all
+        // of them without one, or those matching ...) undergo the default
+        // argument promotions
+        // a function called by its name stays a designator, of function
+        // type, and anything else called is a pointer's value
+        SExprKind::FuncApp { f: g, aps } => {
+            let (f, g) = match callee(g, gtnv, ltnv)? {
+                (Some(_), f) => {
+                    let f = f.unwrap_or_else(implicit);
+                    let t = Type::Func(Box::new(f.clone()));
+                    (f, Box::new(SExpr::typed(g.kind.clone(), t, g.span)))
+                }
+                (None, f) => (f.expect("a function pointer"), value(g)?),
+            };
+            let fp = if f.proto { f.fp } else { vec![] };
+            let aps = aps
+                .iter()
+                .enumerate()
+                .map(|(i, ap)| match (fp.get(i), type_of(ap)?) {
+                    (Some(fpt), _) => conv(ap, fpt).map(|e| *e),
+                    (None, t) if is_arith(&t) => conv(ap, &promote_arg(t.unqual())).map(|e| *e),
+                    (None, _) => value(ap).map(|e| *e),
+                })
+                .collect::<Result<_, _>>()?;
+            SExprKind::FuncApp { f: g, aps }
+        }
+        SExprKind::Cast { typ, l } => SExprKind::Cast {
+            typ: typ.clone(),
+            l: value(l)?,
+        },
+        // the operands of . & and sizeof are not values
+        SExprKind::Dot { l, field } => SExprKind::Dot {
+            l: Box::new(elab(l, gtnv, ltnv)?),
+            field: field.clone(),
+        },
+        SExprKind::AddrOf(l) => SExprKind::AddrOf(Box::new(elab(l, gtnv, ltnv)?)),
+        SExprKind::SizeOfE(l) => SExprKind::SizeOfE(Box::new(elab(l, gtnv, ltnv)?)),
+        SExprKind::Arrow { l, field } => SExprKind::Arrow {
+            l: value(l)?,
+            field: field.clone(),
+        },
+        SExprKind::Deref(l) => SExprKind::Deref(value(l)?),
+        SExprKind::Index { l, r } => SExprKind::Index {
+            l: value(l)?,
+            r: value(r)?,
+        },
+        SExprKind::InitList(_) => unreachable!("braced initializers are elaborated by elab_init"),
+        SExprKind::Load(_) => unreachable!("elaborating a typed expression"),
+        kind => kind.clone(), // constants, strings, variables and sizeof a type
+    };
+    Ok(SExpr::typed(kind, object(e, gtnv, ltnv)?, e.span))
+}
+
+// the unqualified type of an lvalue that can be assigned to (C89 3.2.2.1).
+// what names the operation for the messages
+fn modifiable(
+    e: &SExpr,
+    what: &str,
+    gtnv: &Tnv,
+    ltnv: &HashMap<String, Type>,
+) -> Result<Type, io::Error> {
+    // a function designator is not one
+    let Some(t) = lvalue(e, gtnv, ltnv)?.filter(|t| !matches!(t, Type::Func(_))) else {
+        let msg = match what {
+            "assignment" => "lvalue required as left operand of assignment".to_string(),
+            _ => format!("lvalue required as {what} operand"),
+        };
+        return Err(type_error(e.span, msg));
+    };
+    match (&t, &e.kind) {
+        (Type::Const(_), SExprKind::VarApp(x)) => Err(type_error(
+            e.span,
+            format!("{what} of read-only variable '{x}'"),
+        )),
+        (Type::Const(_), SExprKind::Dot { field, .. } | SExprKind::Arrow { field, .. }) => Err(
+            type_error(e.span, format!("{what} of read-only member '{field}'")),
+        ),
+        (Type::Const(_), _) => Err(type_error(e.span, format!("{what} of read-only location"))),
+        (t, _) if matches!(t.unqual(), Type::Array(..)) => Err(type_error(
+            e.span,
+            format!("{what} to expression with array type"),
+        )),
+        (t, _) => Ok(t.unqual().clone()),
+    }
+}
+
+// the type of the object an lvalue designates, qualifiers kept. None for an
+// expression that designates none
+fn lvalue(e: &SExpr, gtnv: &Tnv, ltnv: &HashMap<String, Type>) -> Result<Option<Type>, io::Error> {
+    match &e.kind {
+        // a function's name designates it like an object's does, so that its
+        // value is its address and & takes that (C89 3.2.2.1, 3.3.3.2)
+        SExprKind::VarApp(alias) => match (ltnv.get(alias), gtnv.fnv.get(alias)) {
+            (Some(t), _) => Ok(Some(t.clone())),
+            (None, Some(f)) => Ok(Some(Type::Func(Box::new(f.clone())))),
+            (None, None) => {
+                let msg = format!("'{alias}' undeclared");
+                Err(Diagnostic::new(Code::Undeclared, e.span, msg).into())
+            }
+        },
+        // a member of a const record is const
+        SExprKind::Dot { l, field } => match lvalue(l, gtnv, ltnv)? {
+            Some(t) => member_of(&gtnv.records, &t, field, e.span).map(Some),
+            None => Ok(None),
+        },
+        SExprKind::Arrow { l, field } => match type_expr(l, gtnv, ltnv)? {
+            Type::Pointer(t) => member_of(&gtnv.records, &t, field, e.span).map(Some),
+            t => Err(type_error(
+                l.span,
+                format!("invalid type argument of '->' (have {:?})", t),
+            )),
+        },
+        // a string literal is an array of char with the NUL (C89 3.1.4)
+        SExprKind::Str(s) => Ok(Some(Type::Array(Box::new(Type::Char), Some(s.len() + 1)))),
+        // Γ ⊢ e : T * ⊢ *e : T
+        SExprKind::Deref(l) => match type_expr(l, gtnv, ltnv)? {
+            Type::Pointer(t) => Ok(Some(*t)),
+            t => Err(type_error(
+                e.span,
+                format!("invalid type argument of unary '*' (have {:?})", t),
+            )),
+        },
+        // e1[e2] is *(e1 + e2), so either operand can be the pointer (C89 3.3.2.1)
+        SExprKind::Index { l, r } => match (type_expr(l, gtnv, ltnv)?, type_expr(r, gtnv, ltnv)?) {
+            (Type::Pointer(t), it) | (it, Type::Pointer(t)) if is_integral(&it) => {
+                element(&gtnv.records, &t, e.span).map(|_| Some(*t))
+            }
+            (Type::Pointer(_), _) | (_, Type::Pointer(_)) => {
+                Err(type_error(r.span, "array subscript is not an integer"))
+            }
+            _ => Err(type_error(
+                e.span,
+                "subscripted value is neither array nor pointer",
+            )),
+        },
+        _ => Ok(None),
+    }
+}
+
+// the function a call designates, by its name when it has one, and its type.
+// None for a name declared nowhere, which the call declares (C89 3.3.2.2).
+// a name that is an object's, and every other callee, is a pointer's value
+fn callee<'a>(
+    f: &'a SExpr,
+    gtnv: &Tnv,
+    ltnv: &HashMap<String, Type>,
+) -> Result<(Option<&'a str>, Option<LambdaType>), io::Error> {
+    if let SExprKind::VarApp(alias) = &f.kind {
+        if !ltnv.contains_key(alias) {
+            return Ok((Some(alias), gtnv.fnv.get(alias).cloned()));
+        }
+    }
+    match type_expr(f, gtnv, ltnv)? {
+        Type::Pointer(t) => match t.unqual() {
+            Type::Func(f) => Ok((None, Some(*f.clone()))),
+            _ => Err(not_callable(f.span, &Type::Pointer(t))),
+        },
+        t => Err(not_callable(f.span, &t)),
+    }
+}
+
+fn not_callable(span: Span, t: &Type) -> io::Error {
+    let msg = format!("called object is not a function or function pointer (have {t:?})");
+    type_error(span, msg)
+}
+
+// the type of the object an expression designates, before its value is
+// taken: an array is still one. sizeof looks at this
+pub fn object(e: &SExpr, gtnv: &Tnv, ltnv: &HashMap<String, Type>) -> Result<Type, io::Error> {
+    match lvalue(e, gtnv, ltnv)? {
+        Some(t) => Ok(t.unqual().clone()),
+        None => type_expr(e, gtnv, ltnv),
+    }
+}
+
+// the value of an array is a pointer to its first element, and that of a
+// function a pointer to it (C89 3.2.2.1)
+fn decay(t: &Type) -> Type {
+    match t.unqual() {
+        Type::Array(et, _) => Type::Pointer(et.clone()),
+        t @ Type::Func(_) => Type::Pointer(Box::new(t.clone())),
+        t => t.clone(),
+    }
+}
+
+// the size of what a pointer moved by whole elements points to
+fn element(rs: &Records, t: &Type, span: Span) -> Result<usize, io::Error> {
+    t.size(rs).ok_or_else(|| {
+        let msg = format!(
+            "arithmetic on a pointer to an incomplete type (have {:?})",
+            t
+        );
+        type_error(span, msg)
+    })
+}
+
+// Γ ⊢ e : struct s, m : T ∈ s ⊢ e.m : T
+fn member(rs: &Records, t: &Type, field: &str, span: Span) -> Result<Type, io::Error> {
+    member_of(rs, t, field, span).map(|t| decay(&t))
+}
+
+// a member's declared type, const when the record is
+fn member_of(rs: &Records, t: &Type, field: &str, span: Span) -> Result<Type, io::Error> {
+    let id = match t.unqual() {
+        Type::Struct(_, id) | Type::Union(_, id) => *id,
+        _ => {
+            let msg = format!("request for member '{field}' in something not a structure or union");
+            return Err(type_error(span, msg));
+        }
+    };
+    let r = rs
+        .get(id)
+        .complete()
+        .ok_or_else(|| type_error(span, format!("invalid use of incomplete type '{}'", tag(t))))?;
+    let m = r
+        .member(field)
+        .ok_or_else(|| type_error(span, format!("'{}' has no member named '{field}'", tag(t))))?;
+    match (t, &m.typ) {
+        (Type::Const(_), mt @ Type::Const(_)) => Ok(mt.clone()),
+        (Type::Const(_), mt) => Ok(Type::Const(Box::new(mt.clone()))),
+        (_, mt) => Ok(mt.clone()),
+    }
+}
+
+fn tag(t: &Type) -> String {
+    match t.unqual() {
+        Type::Struct(Some(tag), _) => format!("struct {tag}"),
+        Type::Union(Some(tag), _) => format!("union {tag}"),
+        Type::Struct(None, _) => "struct <anonymous>".to_string(),
+        _ => "union <anonymous>".to_string(),
+    }
+}
+
+pub fn size_of(rs: &Records, t: &Type, span: Span) -> Result<usize, io::Error> {
+    match (t.unqual(), t.size(rs)) {
+        (Type::Func(_), _) => Err(type_error(
+            span,
+            "invalid application of 'sizeof' to a function type",
+        )),
+        (_, Some(n)) => Ok(n),
+        (_, None) => Err(type_error(
+            span,
+            "invalid application of 'sizeof' to an incomplete type",
+        )),
+    }
+}
+
+// checks an initializer against the declared type, which it may complete.
+// the initializer of an object with static storage must be constant, so it
+// is given the objects with automatic storage in scope
+fn type_init(
+    t: &Type,
+    init: &SExpr,
+    statics: Option<&HashSet<String>>,
+    gtnv: &Tnv,
+    ltnv: &HashMap<String, Type>,
+) -> Result<Type, io::Error> {
+    let (t, inits) = flatten(&gtnv.records, t, init)?;
+    for (_, et, e) in &inits {
+        if let (SExprKind::Str(_), Type::Array(..)) = (&e.kind, et.unqual()) {
+            continue; // checked against the array's size by flatten
+        }
+        let ft = type_expr(e, gtnv, ltnv)?;
+        if !convertible(et.unqual(), e, &ft, gtnv, ltnv) {
+            return Err(mismatch(e.span, et, &ft));
+        }
+        let Some(autos) = statics else {
+            continue;
+        };
+        if constant(&gtnv.records, &conv(value(e, gtnv, ltnv)?, et), autos).is_none() {
+            return Err(type_error(e.span, "initializer element is not constant"));
+        }
+    }
+    Ok(t)
+}
+
+// the scalars of an initializer (and the strings initializing char arrays,
+// and the records initialized by a value) with their types and the offsets
+// they initialize, in order. braces may be elided around a subobject, which
+// then takes as many of the enclosing list's items as it needs (C89 3.5.7).
+// the type is completed when the initializer gives an array its size
+#[allow(clippy::type_complexity)]
+pub fn flatten<'a>(
+    rs: &Records,
+    t: &Type,
+    init: &'a SExpr,
+) -> Result<(Type, Vec<(usize, Type, &'a SExpr)>), io::Error> {
+    let mut inits = vec![];
+    let t = match (t.unqual(), &init.kind) {
+        (Type::Array(et, None), SExprKind::InitList(items)) => {
+            let mut items = items.iter().peekable();
+            let n = fill(rs, t, &mut items, 0, &mut inits)?;
+            excess(t, items.next())?;
+            Type::Array(et.clone(), Some(n))
+        }
+        (Type::Array(et, None), SExprKind::Str(s)) if is_char(et) => {
+            let t = Type::Array(et.clone(), Some(s.len() + 1)); // and the NUL
+            init_one(rs, &t, init, 0, &mut inits)?;
+            t
+        }
+        _ => {
+            init_one(rs, t, init, 0, &mut inits)?;
+            t.clone()
+        }
+    };
+    Ok((t, inits))
+}
+
+type Items<'a> = std::iter::Peekable<std::slice::Iter<'a, SExpr>>;
+
+// the object of type t at off, from one initializer
+fn init_one<'a>(
+    rs: &Records,
+    t: &Type,
+    init: &'a SExpr,
+    off: usize,
+    inits: &mut Vec<(usize, Type, &'a SExpr)>,
+) -> Result<(), io::Error> {
+    match (t.unqual(), &init.kind) {
+        // {e} for a scalar is e
+        (_, SExprKind::InitList(items)) => {
+            let mut items = items.iter().peekable();
+            fill(rs, t, &mut items, off, inits)?;
+            excess(t, items.next())
+        }
+        (Type::Array(et, Some(n)), SExprKind::Str(s)) if is_char(et) => {
+            // the NUL is dropped when it does not fit (C89 3.5.7)
+            if s.len() > *n {
+                let msg = "initializer-string for array of chars is too long";
+                return Err(type_error(init.span, msg));
+            }
+            inits.push((off, t.clone(), init));
+            Ok(())
+        }
+        (Type::Array(..), _) => Err(type_error(init.span, "invalid initializer")),
+        _ => {
+            inits.push((off, t.clone(), init));
+            Ok(())
+        }
+    }
+}
+
+// the object of type t at off, from as many items as it takes. returns the
+// number of elements of an array initialized
+fn fill<'a>(
+    rs: &Records,
+    t: &Type,
+    items: &mut Items<'a>,
+    off: usize,
+    inits: &mut Vec<(usize, Type, &'a SExpr)>,
+) -> Result<usize, io::Error> {
+    match t.unqual() {
+        Type::Array(et, n) => {
+            let size = et.size(rs).unwrap();
+            let mut i = 0;
+            while n.is_none_or(|n| i < n) && items.peek().is_some() {
+                subobject(rs, et, items, off + i * size, inits)?;
+                i += 1;
+            }
+            Ok(i)
+        }
+        Type::Struct(_, id) | Type::Union(_, id) => {
+            let members = rs.get(*id).members.as_deref().unwrap_or_default();
+            let union = matches!(t.unqual(), Type::Union(..));
+            // a union is initialized through its first member
+            let members = members.iter().take(if union { 1 } else { members.len() });
+            for m in members {
+                if items.peek().is_none() {
+                    break;
+                }
+                subobject(rs, &m.typ, items, off + m.offset, inits)?;
+            }
+            Ok(1)
+        }
+        _ => {
+            if let Some(item) = items.next() {
+                init_one(rs, t, item, off, inits)?;
+            }
+            Ok(1)
+        }
+    }
+}
+
+// a subobject takes the next item when it is braced (or a string for a char
+// array, or any item for a scalar), and the items it needs otherwise
+fn subobject<'a>(
+    rs: &Records,
+    t: &Type,
+    items: &mut Items<'a>,
+    off: usize,
+    inits: &mut Vec<(usize, Type, &'a SExpr)>,
+) -> Result<(), io::Error> {
+    let item = *items.peek().unwrap();
+    match (t.unqual(), &item.kind) {
+        (_, SExprKind::InitList(_)) => (),
+        (Type::Array(et, _), SExprKind::Str(_)) if is_char(et) => (),
+        (Type::Array(..) | Type::Struct(..) | Type::Union(..), _) => {
+            return fill(rs, t, items, off, inits).map(|_| ());
+        }
+        _ => (),
+    }
+    items.next();
+    init_one(rs, t, item, off, inits)
+}
+
+fn excess(t: &Type, item: Option<&SExpr>) -> Result<(), io::Error> {
+    let Some(item) = item else { return Ok(()) };
+    let what = match t.unqual() {
+        Type::Array(..) => "array",
+        Type::Struct(..) => "struct",
+        Type::Union(..) => "union",
+        _ => "scalar",
+    };
+    Err(type_error(
+        item.span,
+        format!("excess elements in {what} initializer"),
+    ))
+}
+
+fn is_char(t: &Type) -> bool {
+    matches!(t.unqual(), Type::Char | Type::SChar | Type::UChar)
+}
+
+// the value of a constant expression: an arithmetic one's, as the bits of
+// its type, or an address constant's, an offset from the object or function
+// a name or a string literal designates (C89 3.4)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constant<'a> {
+    Int(i64),
+    Float(f64),
+    Addr(&'a SExpr, i64),
+}
+
+impl Constant<'_> {
+    // an address is never null
+    fn truth(self) -> bool {
+        match self {
+            Constant::Int(n) => n != 0,
+            Constant::Float(f) => f != 0.0,
+            Constant::Addr(..) => true,
+        }
+    }
+}
+
+// the value of a constant expression, which is what an object with static
+// storage is initialized with. None when not constant. e is typed, so each
+// operation folds in the type its operands were converted to. autos are the
+// objects with automatic storage in scope, whose addresses are not constant
+pub fn constant<'a>(rs: &Records, e: &'a SExpr, autos: &HashSet<String>) -> Option<Constant<'a>> {
+    use Constant::{Addr, Float, Int};
+    let t = e.typ.as_ref()?.unqual();
+    let int = |e| match constant(rs, e, autos)? {
+        Int(n) => Some(n),
+        _ => None,
+    };
+    let num = |e| match constant(rs, e, autos)? {
+        Addr(..) => None,
+        c => Some(c),
+    };
+    let c = match &e.kind {
+        SExprKind::Int(n) | SExprKind::Long(n) => Int(*n as i64),
+        SExprKind::UInt(n) | SExprKind::ULong(n) => Int(*n as i64),
+        SExprKind::Float(f) => Float(*f as f64),
+        SExprKind::Double(f) | SExprKind::LongDouble(f) => Float(*f),
+        SExprKind::UnaryE { op, l } => match (op, num(l)?) {
+            (SUnaryOp::Add, c) => c,
+            (SUnaryOp::Sub, Int(n)) => Int(n.wrapping_neg()),
+            (SUnaryOp::Sub, Float(f)) => Float(-f),
+            (SUnaryOp::Not, c) => Int(!c.truth() as i64),
+            (SUnaryOp::BitNot, Int(n)) => Int(!n),
+            _ => return None,
+        },
+        // an integer added to or subtracted from an address is scaled by
+        // the size of what it points to
+        SExprKind::BinE { op, l, r } => {
+            match (op, constant(rs, l, autos)?, constant(rs, r, autos)?) {
+                (SBinOp::Add, Addr(b, off), Int(n)) | (SBinOp::Add, Int(n), Addr(b, off)) => {
+                    Addr(b, off + n * pointee(rs, t)?)
+                }
+                (SBinOp::Sub, Addr(b, off), Int(n)) => Addr(b, off - n * pointee(rs, t)?),
+                (op, Int(l), Int(r)) if is_unsigned(t) => {
+                    let (l, r) = (l as u64, r as u64);
+                    Int(match op {
+                        SBinOp::Add => l.wrapping_add(r),
+                        SBinOp::Sub => l.wrapping_sub(r),
+                        SBinOp::Mult => l.wrapping_mul(r),
+                        SBinOp::Div => l.checked_div(r)?,
+                        SBinOp::Mod => l.checked_rem(r)?,
+                    } as i64)
+                }
+                (op, Int(l), Int(r)) => Int(match op {
+                    SBinOp::Add => l.wrapping_add(r),
+                    SBinOp::Sub => l.wrapping_sub(r),
+                    SBinOp::Mult => l.wrapping_mul(r),
+                    SBinOp::Div => l.checked_div(r)?,
+                    SBinOp::Mod => l.checked_rem(r)?,
+                }),
+                (op, Float(l), Float(r)) => Float(match op {
+                    SBinOp::Add => l + r,
+                    SBinOp::Sub => l - r,
+                    SBinOp::Mult => l * r,
+                    SBinOp::Div => l / r,
+                    SBinOp::Mod => return None,
+                }),
+                _ => return None,
+            }
+        }
+        // a right shift of an unsigned value shifts in zeros
+        SExprKind::BitE { op, l, r } => {
+            let (l, r) = (int(l)?, int(r)?);
+            let width = 8 * t.size(rs)? as u32;
+            let bits = u32::try_from(r).ok().filter(|&r| r < width);
+            Int(match op {
+                SBitOp::And => l & r,
+                SBitOp::Or => l | r,
+                SBitOp::Xor => l ^ r,
+                SBitOp::Shl => l << bits?,
+                SBitOp::Shr if is_unsigned(t) => (l as u64 >> bits?) as i64,
+                SBitOp::Shr => l >> bits?,
+            })
+        }
+        // the operands have their common type, which is unsigned or not
+        SExprKind::RelE { op, l, r } => {
+            let ordering = match (num(l)?, num(r)?) {
+                (Int(a), Int(b)) if is_unsigned(l.typ.as_ref()?) => {
+                    (a as u64).partial_cmp(&(b as u64))
+                }
+                (Int(a), Int(b)) => a.partial_cmp(&b),
+                (Float(a), Float(b)) => a.partial_cmp(&b),
+                _ => return None,
+            };
+            let b = match (op, ordering) {
+                (SRelOp::Neq, o) => o != Some(std::cmp::Ordering::Equal),
+                (_, None) => false, // NaN is unordered
+                (SRelOp::Eq, Some(o)) => o.is_eq(),
+                (SRelOp::LtEq, Some(o)) => o.is_le(),
+                (SRelOp::Lt, Some(o)) => o.is_lt(),
+                (SRelOp::GtEq, Some(o)) => o.is_ge(),
+                (SRelOp::Gt, Some(o)) => o.is_gt(),
+            };
+            Int(b as i64)
+        }
+        SExprKind::LogE { op, l, r } => {
+            let (l, r) = (
+                constant(rs, l, autos)?.truth(),
+                constant(rs, r, autos)?.truth(),
+            );
+            match op {
+                SLogOp::And => Int((l && r) as i64),
+                SLogOp::Or => Int((l || r) as i64),
+            }
+        }
+        SExprKind::Ternary { cond, then, els } => match constant(rs, cond, autos)?.truth() {
+            true => constant(rs, then, autos)?,
+            false => constant(rs, els, autos)?,
+        },
+        SExprKind::SizeOfT(st) => Int(st.size(rs)? as i64),
+        SExprKind::SizeOfE(l) => Int(l.typ.as_ref()?.size(rs)? as i64),
+        SExprKind::Cast { l, .. } => {
+            let from = l.typ.as_ref()?.unqual();
+            match constant(rs, l, autos)? {
+                Int(n) if is_float(t) && is_unsigned(from) => Float(n as u64 as f64),
+                Int(n) if is_float(t) => Float(n as f64),
+                Float(f) if is_float(t) => Float(f),
+                Float(f) if is_unsigned(t) => Int(f as u64 as i64),
+                Float(f) if is_integral(t) => Int(f as i64),
+                Int(n) if is_integral(t) || is_pointer(t) => Int(n),
+                Addr(b, off) if is_pointer(t) => Addr(b, off),
+                _ => return None,
+            }
+        }
+        // the value of an array or a function is its address
+        SExprKind::Load(l)
+            if matches!(l.typ.as_ref()?.unqual(), Type::Array(..) | Type::Func(_)) =>
+        {
+            address(rs, l, autos)?
+        }
+        SExprKind::AddrOf(l) => address(rs, l, autos)?,
+        _ => return None,
+    };
+    // the result holds only what its type can
+    Some(match c {
+        Int(n) => Int(truncate(t, n)),
+        Float(f) if *t == Type::Float => Float(f as f32 as f64),
+        c => c,
+    })
+}
+
+// the address of the object or function an lvalue designates, when it has
+// static storage
+fn address<'a>(rs: &Records, e: &'a SExpr, autos: &HashSet<String>) -> Option<Constant<'a>> {
+    use Constant::{Addr, Int};
+    match &e.kind {
+        SExprKind::VarApp(alias) if !autos.contains(alias) => Some(Addr(e, 0)),
+        SExprKind::Str(_) => Some(Addr(e, 0)),
+        SExprKind::Deref(l) => constant(rs, l, autos),
+        SExprKind::Index { l, r } => match (constant(rs, l, autos)?, constant(rs, r, autos)?) {
+            (Addr(b, off), Int(n)) | (Int(n), Addr(b, off)) => {
+                Some(Addr(b, off + n * e.typ.as_ref()?.size(rs)? as i64))
+            }
+            _ => None,
+        },
+        SExprKind::Dot { l, field } => match address(rs, l, autos)? {
+            Addr(b, off) => Some(Addr(b, off + offset_of(rs, l.typ.as_ref()?, field)?)),
+            _ => None,
+        },
+        SExprKind::Arrow { l, field } => {
+            match (constant(rs, l, autos)?, l.typ.as_ref()?.unqual()) {
+                (Addr(b, off), Type::Pointer(t)) => Some(Addr(b, off + offset_of(rs, t, field)?)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn offset_of(rs: &Records, t: &Type, field: &str) -> Option<i64> {
+    match t.unqual() {
+        Type::Struct(_, id) | Type::Union(_, id) => Some(rs.get(*id).member(field)?.offset as i64),
+        _ => None,
+    }
+}
+
+// the size of what a pointer points to
+fn pointee(rs: &Records, t: &Type) -> Option<i64> {
+    match t.unqual() {
+        Type::Pointer(t) => t.size(rs).map(|n| n as i64),
+        _ => None,
+    }
+}
+
+// the low bits of n that an integer of type t holds, zero or sign extended
+fn truncate(t: &Type, n: i64) -> i64 {
+    match t.unqual() {
+        Type::SChar => n as i8 as i64,
+        Type::Char | Type::UChar => n as u8 as i64, // plain char is unsigned on RV32
+        Type::Short => n as i16 as i64,
+        Type::UShort => n as u16 as i64,
+        Type::UInt | Type::ULong | Type::Pointer(_) => n as u32 as i64,
+        Type::LongLong | Type::ULongLong => n,
+        _ => n as i32 as i64,
+    }
+}
+
+fn is_unsigned(t: &Type) -> bool {
+    matches!(
+        t.unqual(),
+        Type::Char
+            | Type::UChar
+            | Type::UShort
+            | Type::UInt
+            | Type::ULong
+            | Type::ULongLong
+            | Type::Pointer(_)
+    )
+}
+
+// arithmetic values convert implicitly as if by assignment (C89 6.3.16.1),
+// and so do pointers to compatible types, or between void * and another
+// pointer, as long as what they point to loses no qualifiers
+fn assignable(to: &Type, from: &Type) -> bool {
+    match (to.unqual(), from.unqual()) {
+        (Type::Pointer(a), Type::Pointer(b)) => {
+            let (qa, qb) = (qualifiers(a), qualifiers(b));
+            let kept = (qa.0 || !qb.0) && (qa.1 || !qb.1);
+            kept && (compatible(a, b) || is_void(a) || is_void(b))
+        }
+        (to, from) => to == from || is_arith(to) && is_arith(from),
+    }
+}
+
+// a null pointer constant also converts to any pointer
+fn convertible(
+    to: &Type,
+    e: &SExpr,
+    from: &Type,
+    gtnv: &Tnv,
+    ltnv: &HashMap<String, Type>,
+) -> bool {
+    assignable(to, from) || is_pointer(to) && null(e, from, gtnv, ltnv)
+}
+
+// an integral constant expression equal to 0, or one cast to void *
+// (C89 3.2.2.3)
+fn null(e: &SExpr, t: &Type, gtnv: &Tnv, ltnv: &HashMap<String, Type>) -> bool {
+    let void_ptr = Type::Pointer(Box::new(Type::Void));
+    (is_integral(t) || *t == void_ptr)
+        && value(e, gtnv, ltnv).is_ok_and(|e| {
+            matches!(
+                constant(&gtnv.records, &e, &HashSet::new()),
+                Some(Constant::Int(0))
+            )
+        })
+}
+
+fn compatible(a: &Type, b: &Type) -> bool {
+    composite(a.unqual(), b.unqual()).is_some()
+}
+
+// const and volatile
+fn qualifiers(t: &Type) -> (bool, bool) {
+    match t {
+        Type::Const(t) => (true, qualifiers(t).1),
+        Type::Volatile(t) => (qualifiers(t).0, true),
+        _ => (false, false),
+    }
+}
+
+fn is_pointer(t: &Type) -> bool {
+    matches!(t.unqual(), Type::Pointer(_))
+}
+
+fn is_void(t: &Type) -> bool {
+    *t.unqual() == Type::Void
+}
+
+fn is_integral(t: &Type) -> bool {
+    matches!(
+        t,
+        Type::Char
+            | Type::SChar
+            | Type::UChar
+            | Type::Short
+            | Type::UShort
+            | Type::Int
+            | Type::UInt
+            | Type::Long
+            | Type::ULong
+    )
+}
+
+pub fn is_float(t: &Type) -> bool {
+    matches!(t, Type::Float | Type::Double | Type::LongDouble)
+}
+
+fn is_arith(t: &Type) -> bool {
+    is_integral(t) || is_float(t)
+}
+
+// the integral promotions (C89 3.2.1.1): every char and short fits in an int
+pub fn promote(t: &Type) -> Type {
+    match t {
+        Type::Char | Type::SChar | Type::UChar | Type::Short | Type::UShort => Type::Int,
+        t => t.clone(),
+    }
+}
+
+// usual arithmetic conversions (C89 6.2.1.5). long cannot hold every
+// unsigned int on ILP32, so the two meet at unsigned long
+pub fn arith(l: &Type, r: &Type) -> Type {
+    match (l, r) {
+        (Type::LongDouble, _) | (_, Type::LongDouble) => Type::LongDouble,
+        (Type::Double, _) | (_, Type::Double) => Type::Double,
+        (Type::Float, _) | (_, Type::Float) => Type::Float,
+        (Type::ULong, _) | (_, Type::ULong) => Type::ULong,
+        (Type::Long, Type::UInt) | (Type::UInt, Type::Long) => Type::ULong,
+        (Type::Long, _) | (_, Type::Long) => Type::Long,
+        (Type::UInt, _) | (_, Type::UInt) => Type::UInt,
+        _ => Type::Int,
+    }
+}
+
+fn invalid_operands(span: Span, op: &str, lt: &Type, rt: &Type) -> io::Error {
+    type_error(
+        span,
+        format!(
+            "invalid operands to binary {op} (have {:?} and {:?})",
+            lt, rt
+        ),
+    )
+}
+
+fn bin_op(op: &SBinOp) -> &'static str {
+    match op {
+        SBinOp::Add => "+",
+        SBinOp::Sub => "-",
+        SBinOp::Mult => "*",
+        SBinOp::Div => "/",
+        SBinOp::Mod => "%",
+    }
+}
+
+fn rel_op(op: &SRelOp) -> &'static str {
+    match op {
+        SRelOp::Eq => "==",
+        SRelOp::Neq => "!=",
+        SRelOp::LtEq => "<=",
+        SRelOp::Lt => "<",
+        SRelOp::GtEq => ">=",
+        SRelOp::Gt => ">",
+    }
+}
+
+fn bit_op(op: &SBitOp) -> &'static str {
+    match op {
+        SBitOp::And => "&",
+        SBitOp::Or => "|",
+        SBitOp::Xor => "^",
+        SBitOp::Shl => "<<",
+        SBitOp::Shr => ">>",
     }
 }
 
+fn type_error(span: Span, msg: impl Into<String>) -> io::Error {
+    Diagnostic::new(Code::Type, span, msg).into()
+}
+
+fn mismatch(span: Span, expected: &Type, found: &Type) -> io::Error {
+    let msg = format!("type error: expected {:?}, found {:?}", expected, found);
+    Diagnostic::new(Code::Mismatch, span, msg).into()
+}
+
+fn conflicting(span: Span, alias: &str) -> Diagnostic {
+    let msg = format!("conflicting types for '{alias}'");
+    Diagnostic::new(Code::Conflicting, span, msg)
+}
+
+// a declaration where there may be only one, pointing back at that one
+fn redeclared(span: Span, msg: String, prev: Span, alias: &str) -> io::Error {
+    let label = format!("previous declaration of '{alias}' was here");
+    Diagnostic::new(Code::Redeclared, span, msg)
+        .label(prev, label)
+        .into()
+}
+
 #[cfg(test)]
 mod test_arith {
     use crate::lexer;
@@ -184,18 +1875,24 @@ mod test_arith {
 
     #[test]
     fn lit() {
-        let chars = fs::read(format!("{TEST_DIR}/lit.c0"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/lit.c0")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = parser_ast::parse_prg(&tokens).unwrap();
-        let typ = super::type_prg(&tree).unwrap();
+        let typ = super::type_prg(&tree).unwrap().0;
         insta::assert_yaml_snapshot!(typ, @r###"
         ---
-        Int
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  Int: 9
+                  typ: Int
         "###);
     }
 }
@@ -210,30 +1907,43 @@ mod test_control {
 
     #[test]
     fn ifels() {
-        let chars = fs::read(format!("{TEST_DIR}/if.c0"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/if.c0")).expect("file dne");
 
-        let tokens = lexer::lex(&chars).unwrap();
+        let tokens = lexer::lex_c0(&chars).unwrap();
         let tree = parser_ast::parse_prg(&tokens).unwrap();
-        let typ = super::type_prg(&tree).unwrap();
+        let typ = super::type_prg(&tree).unwrap().0;
         insta::assert_yaml_snapshot!(typ, @r###"
         ---
-        Int
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - IfEls:
+                  cond:
+                    Bool: true
+                    typ: Bool
+                  then:
+                    Block:
+                      - Return:
+                          Int: 9
+                          typ: Int
+                  els:
+                    Block:
+                      - Return:
+                          Int: 10
+                          typ: Int
         "###);
     }
 
     #[test]
     fn ifels_wrong() {
-        let chars = fs::read(format!("{TEST_DIR}/if2.c0"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/if2.c0")).expect("file dne");
 
-        let tokens = lexer::lex(&chars).unwrap();
+        let tokens = lexer::lex_c0(&chars).unwrap();
         let tree = parser_ast::parse_prg(&tokens).unwrap();
         let typ = super::type_prg(&tree);
         assert!(typ.is_err())
@@ -241,30 +1951,83 @@ mod test_control {
 
     #[test]
     fn ifels_multi_side_effect() {
-        let chars = fs::read(format!("{TEST_DIR}/if4.c0"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/if4.c0")).expect("file dne");
 
-        let tokens = lexer::lex(&chars).unwrap();
+        let tokens = lexer::lex_c0(&chars).unwrap();
         let tree = parser_ast::parse_prg(&tokens).unwrap();
-        let typ = super::type_prg(&tree).unwrap();
+        let typ = super::type_prg(&tree).unwrap().0;
         insta::assert_yaml_snapshot!(typ, @r###"
         ---
-        Int
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - IfEls:
+                  cond:
+                    Bool: true
+                    typ: Bool
+                  then:
+                    Block:
+                      - Decl:
+                          - alias: x
+                            sc: ~
+                            typ: Int
+                            expr:
+                              Int: 8
+                              typ: Int
+                  els: ~
+              - IfEls:
+                  cond:
+                    Bool: false
+                    typ: Bool
+                  then:
+                    Block:
+                      - Return:
+                          Int: 9
+                          typ: Int
+                  els: ~
+              - IfEls:
+                  cond:
+                    Bool: false
+                    typ: Bool
+                  then:
+                    Block:
+                      - Return:
+                          Int: 10
+                          typ: Int
+                  els: ~
+              - IfEls:
+                  cond:
+                    Bool: false
+                    typ: Bool
+                  then:
+                    Block:
+                      - Return:
+                          Int: 11
+                          typ: Int
+                  els: ~
+              - IfEls:
+                  cond:
+                    Bool: true
+                    typ: Bool
+                  then:
+                    Block:
+                      - Return:
+                          Int: 12
+                          typ: Int
+                  els: ~
         "###);
     }
 
     #[test]
     fn ifels_multi_side_effect_wrong() {
-        let chars = fs::read(format!("{TEST_DIR}/if5.c0"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/if5.c0")).expect("file dne");
 
-        let tokens = lexer::lex(&chars).unwrap();
+        let tokens = lexer::lex_c0(&chars).unwrap();
         let tree = parser_ast::parse_prg(&tokens).unwrap();
         let typ = super::type_prg(&tree);
         assert!(typ.is_err())
@@ -281,30 +2044,32 @@ mod test_bindings {
 
     #[test]
     fn func() {
-        let chars = fs::read(format!("{TEST_DIR}/func.c0"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/func.c0")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = parser_ast::parse_prg(&tokens).unwrap();
-        let typ = super::type_prg(&tree).unwrap();
+        let typ = super::type_prg(&tree).unwrap().0;
         insta::assert_yaml_snapshot!(typ, @r###"
         ---
-        Int
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  Int: 10
+                  typ: Int
         "###);
     }
 
     #[test]
     fn func2() {
-        let chars = fs::read(format!("{TEST_DIR}/func2.c0"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/func2.c0")).expect("file dne");
 
-        let tokens = lexer::lex(&chars).unwrap();
+        let tokens = lexer::lex_c0(&chars).unwrap();
         let tree = parser_ast::parse_prg(&tokens).unwrap();
         let typ = super::type_prg(&tree);
         assert!(typ.is_err())
@@ -313,18 +2078,1847 @@ mod test_bindings {
     #[test]
     fn asnmt_expr() {
         const TEST_DIR: &str = "tests/fixtures/snap/shared/bindings";
-        let chars = fs::read(format!("{TEST_DIR}/asnmt_expr.c"))
-            .expect("file dne")
-            .iter()
-            .map(|b| *b as char)
-            .collect::<Vec<_>>();
+        let chars = fs::read_to_string(format!("{TEST_DIR}/asnmt_expr.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typ = super::type_prg(&tree).unwrap().0;
+        insta::assert_yaml_snapshot!(typ, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Decl:
+                  - alias: x
+                    sc: ~
+                    typ: Int
+                    expr:
+                      BinE:
+                        op: Add
+                        l:
+                          BinE:
+                            op: Mult
+                            l:
+                              Int: 9
+                              typ: Int
+                            r:
+                              Int: 10
+                              typ: Int
+                          typ: Int
+                        r:
+                          BinE:
+                            op: Mult
+                            l:
+                              Int: 11
+                              typ: Int
+                            r:
+                              Int: 12
+                              typ: Int
+                          typ: Int
+                      typ: Int
+              - Return:
+                  Load:
+                    VarApp: x
+                    typ: Int
+                  typ: Int
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_scopes {
+    use crate::diagnostic;
+    use crate::lexer::{self, LexOpts};
+    use crate::parser_ast;
+    use crate::source::SourceMap;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/bindings";
+
+    #[test]
+    fn errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/scope_errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| {
+            let tokens = lexer::lex(l)?;
+            let tree = parser_ast::parse_prg(&tokens)?;
+            super::type_prg(&tree)
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "int f() { if (1) { int x = 9; } return x; }: E0006 1:40 'x' undeclared"
+        - "int f() { int x; int x; return 0; }: E0007 1:22 redeclaration of 'x' with no linkage"
+        - "int f() { int x; char x; return 0; }: E0007 1:23 redeclaration of 'x' with no linkage"
+        - "int f() { static int x; static int x; return 0; }: E0007 1:36 redeclaration of 'x' with no linkage"
+        - "int f() { int x; extern int x; return 0; }: E0007 1:29 redeclaration of 'x' with no linkage"
+        - "int f() { extern int x; extern char x; return 0; }: E0008 1:37 conflicting types for 'x'"
+        - "int f(int a) { int a; return a; }: E0007 1:20 redeclaration of 'a' with no linkage"
+        - "int f(int a, int a) { return a; }: E0007 1:14 redefinition of parameter 'a'"
+        - "int f() { { int y; } y = 1; return 0; }: E0006 1:22 'y' undeclared"
+        - "int f() { for (;;) { int i; } return i; }: E0006 1:38 'i' undeclared"
+        - "int x = y;: E0006 1:9 'y' undeclared"
+        - "int f() { int n = sizeof n + m; return n; }: E0006 1:30 'm' undeclared"
+        - "int f() { void *p = &q, *q = &p; return 0; }: E0006 1:22 'q' undeclared"
+        "###);
+    }
+
+    #[test]
+    fn self_ref() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/self_ref.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typ = super::type_prg(&tree).unwrap().0;
+        insta::assert_yaml_snapshot!(typ, @r###"
+        ---
+        - VarDef:
+            alias: head
+            sc: ~
+            typ:
+              Struct:
+                - node
+                - 0
+            expr: ~
+        - VarDef:
+            alias: self
+            sc: ~
+            typ:
+              Pointer: Void
+            expr:
+              Cast:
+                typ:
+                  Pointer: Void
+                l:
+                  AddrOf:
+                    VarApp: self
+                    typ:
+                      Pointer: Void
+                  typ:
+                    Pointer:
+                      Pointer: Void
+              typ:
+                Pointer: Void
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Decl:
+                  - alias: n
+                    sc: ~
+                    typ:
+                      Pointer:
+                        Struct:
+                          - node
+                          - 0
+                    expr:
+                      Ternary:
+                        cond:
+                          RelE:
+                            op: Eq
+                            l:
+                              SizeOfE:
+                                Deref:
+                                  Load:
+                                    VarApp: n
+                                    typ:
+                                      Pointer:
+                                        Struct:
+                                          - node
+                                          - 0
+                                  typ:
+                                    Pointer:
+                                      Struct:
+                                        - node
+                                        - 0
+                                typ:
+                                  Struct:
+                                    - node
+                                    - 0
+                              typ: UInt
+                            r:
+                              SizeOfE:
+                                VarApp: head
+                                typ:
+                                  Struct:
+                                    - node
+                                    - 0
+                              typ: UInt
+                          typ: Int
+                        then:
+                          AddrOf:
+                            VarApp: head
+                            typ:
+                              Struct:
+                                - node
+                                - 0
+                          typ:
+                            Pointer:
+                              Struct:
+                                - node
+                                - 0
+                        els:
+                          Cast:
+                            typ:
+                              Pointer:
+                                Struct:
+                                  - node
+                                  - 0
+                            l:
+                              Int: 0
+                              typ: Int
+                          typ:
+                            Pointer:
+                              Struct:
+                                - node
+                                - 0
+                      typ:
+                        Pointer:
+                          Struct:
+                            - node
+                            - 0
+              - Decl:
+                  - alias: k
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Cast:
+                        typ: Int
+                        l:
+                          SizeOfE:
+                            VarApp: k
+                            typ: Int
+                          typ: UInt
+                      typ: Int
+              - Decl:
+                  - alias: p
+                    sc: ~
+                    typ:
+                      Pointer: Void
+                    expr:
+                      Cast:
+                        typ:
+                          Pointer: Void
+                        l:
+                          AddrOf:
+                            VarApp: p
+                            typ:
+                              Pointer: Void
+                          typ:
+                            Pointer:
+                              Pointer: Void
+                      typ:
+                        Pointer: Void
+              - IfEls:
+                  cond:
+                    LogE:
+                      op: Or
+                      l:
+                        LogE:
+                          op: Or
+                          l:
+                            RelE:
+                              op: Neq
+                              l:
+                                Load:
+                                  VarApp: n
+                                  typ:
+                                    Pointer:
+                                      Struct:
+                                        - node
+                                        - 0
+                                typ:
+                                  Pointer:
+                                    Struct:
+                                      - node
+                                      - 0
+                              r:
+                                AddrOf:
+                                  VarApp: head
+                                  typ:
+                                    Struct:
+                                      - node
+                                      - 0
+                                typ:
+                                  Pointer:
+                                    Struct:
+                                      - node
+                                      - 0
+                            typ: Int
+                          r:
+                            RelE:
+                              op: Neq
+                              l:
+                                Load:
+                                  VarApp: self
+                                  typ:
+                                    Pointer: Void
+                                typ:
+                                  Pointer: Void
+                              r:
+                                AddrOf:
+                                  VarApp: self
+                                  typ:
+                                    Pointer: Void
+                                typ:
+                                  Pointer:
+                                    Pointer: Void
+                            typ: Int
+                        typ: Int
+                      r:
+                        RelE:
+                          op: Neq
+                          l:
+                            Load:
+                              VarApp: p
+                              typ:
+                                Pointer: Void
+                            typ:
+                              Pointer: Void
+                          r:
+                            AddrOf:
+                              VarApp: p
+                              typ:
+                                Pointer: Void
+                            typ:
+                              Pointer:
+                                Pointer: Void
+                        typ: Int
+                    typ: Int
+                  then:
+                    Block:
+                      - Return:
+                          Int: 1
+                          typ: Int
+                  els: ~
+              - Expr:
+                  Asnmt:
+                    op: ~
+                    l:
+                      Arrow:
+                        l:
+                          Load:
+                            VarApp: n
+                            typ:
+                              Pointer:
+                                Struct:
+                                  - node
+                                  - 0
+                          typ:
+                            Pointer:
+                              Struct:
+                                - node
+                                - 0
+                        field: v
+                      typ: Int
+                    r:
+                      Load:
+                        VarApp: k
+                        typ: Int
+                      typ: Int
+                  typ: Int
+              - Return:
+                  Cast:
+                    typ: Int
+                    l:
+                      BinE:
+                        op: Add
+                        l:
+                          Cast:
+                            typ: UInt
+                            l:
+                              Load:
+                                Arrow:
+                                  l:
+                                    Load:
+                                      VarApp: n
+                                      typ:
+                                        Pointer:
+                                          Struct:
+                                            - node
+                                            - 0
+                                    typ:
+                                      Pointer:
+                                        Struct:
+                                          - node
+                                          - 0
+                                  field: v
+                                typ: Int
+                              typ: Int
+                          typ: UInt
+                        r:
+                          SizeOfE:
+                            Deref:
+                              Load:
+                                Arrow:
+                                  l:
+                                    Load:
+                                      VarApp: n
+                                      typ:
+                                        Pointer:
+                                          Struct:
+                                            - node
+                                            - 0
+                                    typ:
+                                      Pointer:
+                                        Struct:
+                                          - node
+                                          - 0
+                                  field: next
+                                typ:
+                                  Pointer:
+                                    Struct:
+                                      - node
+                                      - 0
+                              typ:
+                                Pointer:
+                                  Struct:
+                                    - node
+                                    - 0
+                            typ:
+                              Struct:
+                                - node
+                                - 0
+                          typ: UInt
+                      typ: UInt
+                  typ: Int
+        "###);
+    }
+
+    // a redeclared parameter points back at the parameter
+    #[test]
+    fn parameters() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/scope_errors.c")).expect("file dne");
+
+        let errs = chars
+            .lines()
+            .filter(|l| l.starts_with("int f(int a"))
+            .map(|l| {
+                let mut sm = SourceMap::new();
+                let file = sm.add("scope_errors.c", l);
+                let tokens = lexer::lex_file(l, file, LexOpts::default()).unwrap();
+                let tree = parser_ast::parse_prg(&tokens).unwrap();
+                diagnostic::render(&super::type_prg(&tree).unwrap_err(), &sm)
+            })
+            .collect::<String>();
+        insta::assert_snapshot!(errs, @r###"
+        error[E0007]: redeclaration of 'a' with no linkage
+         --> scope_errors.c:1:20
+          |
+        1 | int f(int a) { int a; return a; }
+          |                    ^
+        note: previous declaration of 'a' was here
+         --> scope_errors.c:1:7
+          |
+        1 | int f(int a) { int a; return a; }
+          |       ^^^^^
+        error[E0007]: redefinition of parameter 'a'
+         --> scope_errors.c:1:14
+          |
+        1 | int f(int a, int a) { return a; }
+          |              ^^^^^
+        note: previous declaration of 'a' was here
+         --> scope_errors.c:1:7
+          |
+        1 | int f(int a, int a) { return a; }
+          |       ^^^^^
+        "###);
+    }
+
+    #[test]
+    fn if_scope() {
+        const TEST_DIR: &str = "tests/fixtures/snap/lexical";
+        let src = fs::read_to_string(format!("{TEST_DIR}/if_scope.c")).expect("file dne");
+        let mut sm = SourceMap::new();
+        let file = sm.add("if_scope.c", &src);
+
+        let tokens = lexer::lex_file(&src, file, LexOpts::default()).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let err = super::type_prg(&tree).unwrap_err();
+        insta::assert_snapshot!(diagnostic::render(&err, &sm), @r###"
+        error[E0006]: 'x' undeclared
+         --> if_scope.c:8:12
+          |
+        8 |     return x;
+          |            ^
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_floats {
+    use crate::lexer;
+    use crate::parser_ast;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/floats";
+
+    #[test]
+    fn single() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/single.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typ = super::type_prg(&tree).unwrap().0;
+        insta::assert_yaml_snapshot!(typ, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  Cast:
+                    typ: Int
+                    l:
+                      BinE:
+                        op: Sub
+                        l:
+                          BinE:
+                            op: Mult
+                            l:
+                              Float: 2.5
+                              typ: Float
+                            r:
+                              Float: 2
+                              typ: Float
+                          typ: Float
+                        r:
+                          Cast:
+                            typ: Float
+                            l:
+                              UInt: 1
+                              typ: UInt
+                          typ: Float
+                      typ: Float
+                  typ: Int
+        "###);
+    }
+
+    #[test]
+    fn conversions() {
+        use crate::Type::*;
+        let arith = [
+            (Int, Float),
+            (Float, Double),
+            (Double, LongDouble),
+            (UInt, Long),
+            (UInt, Int),
+            (Long, Int),
+        ]
+        .iter()
+        .map(|(l, r)| super::arith(l, r))
+        .collect::<Vec<_>>();
+        insta::assert_yaml_snapshot!(arith, @r###"
+        ---
+        - Float
+        - Double
+        - LongDouble
+        - ULong
+        - UInt
+        - Long
+        "###);
+    }
+
+    #[test]
+    fn mod_float() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/mod.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let err = super::type_prg(&tree).unwrap_err();
+        insta::assert_snapshot!(err.to_string(), @"type error: expected Int, found Double");
+    }
+}
+
+#[cfg(test)]
+mod test_errors {
+    use crate::diagnostic;
+    use crate::lexer::{self, LexOpts};
+    use crate::parser_ast;
+    use crate::source::SourceMap;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/statics-c0/control";
+
+    #[test]
+    fn ifels_wrong() {
+        let src = fs::read_to_string(format!("{TEST_DIR}/if2.c0")).expect("file dne");
+        let mut sm = SourceMap::new();
+        let file = sm.add("if2.c0", &src);
+        let opts = LexOpts {
+            c0: true,
+            ..LexOpts::default()
+        };
+
+        let tokens = lexer::lex_file(&src, file, opts).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let err = super::type_prg(&tree).unwrap_err();
+        insta::assert_snapshot!(diagnostic::render(&err, &sm), @r###"
+        error[E0005]: type error: expected Int, found Bool
+         --> if2.c0:3:16
+          |
+        3 |         return true;
+          |                ^^^^
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_records {
+    use crate::diagnostic;
+    use crate::lexer;
+    use crate::parser_ast;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/structs";
+
+    #[test]
+    fn members() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/members.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typ = super::type_prg(&tree).unwrap().0;
+        insta::assert_yaml_snapshot!(typ, @r###"
+        ---
+        - FuncDef:
+            alias: dx
+            sc: ~
+            typ: Int
+            fps:
+              - alias: s
+                typ:
+                  Struct:
+                    - segment
+                    - 1
+            variadic: false
+            proto: true
+            body:
+              - Return:
+                  BinE:
+                    op: Sub
+                    l:
+                      Load:
+                        Dot:
+                          l:
+                            Dot:
+                              l:
+                                VarApp: s
+                                typ:
+                                  Struct:
+                                    - segment
+                                    - 1
+                              field: to
+                            typ:
+                              Struct:
+                                - point
+                                - 0
+                          field: x
+                        typ: Int
+                      typ: Int
+                    r:
+                      Load:
+                        Dot:
+                          l:
+                            Dot:
+                              l:
+                                VarApp: s
+                                typ:
+                                  Struct:
+                                    - segment
+                                    - 1
+                              field: from
+                            typ:
+                              Struct:
+                                - point
+                                - 0
+                          field: x
+                        typ: Int
+                      typ: Int
+                  typ: Int
+        - FuncDef:
+            alias: first
+            sc: ~
+            typ: Int
+            fps:
+              - alias: s
+                typ:
+                  Pointer:
+                    Struct:
+                      - segment
+                      - 1
+            variadic: false
+            proto: true
+            body:
+              - Return:
+                  Load:
+                    Dot:
+                      l:
+                        Arrow:
+                          l:
+                            Load:
+                              VarApp: s
+                              typ:
+                                Pointer:
+                                  Struct:
+                                    - segment
+                                    - 1
+                            typ:
+                              Pointer:
+                                Struct:
+                                  - segment
+                                  - 1
+                          field: from
+                        typ:
+                          Struct:
+                            - point
+                            - 0
+                      field: y
+                    typ: Int
+                  typ: Int
+        - FuncDef:
+            alias: length
+            sc: ~
+            typ: Double
+            fps:
+              - alias: s
+                typ:
+                  Struct:
+                    - segment
+                    - 1
+            variadic: false
+            proto: true
+            body:
+              - Return:
+                  Load:
+                    Dot:
+                      l:
+                        VarApp: s
+                        typ:
+                          Struct:
+                            - segment
+                            - 1
+                      field: len
+                    typ: Double
+                  typ: Double
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Decl:
+                  - alias: s
+                    sc: ~
+                    typ:
+                      Struct:
+                        - segment
+                        - 1
+                    expr: ~
+              - Decl:
+                  - alias: t
+                    sc: ~
+                    typ:
+                      Struct:
+                        - segment
+                        - 1
+                    expr:
+                      Load:
+                        VarApp: s
+                        typ:
+                          Struct:
+                            - segment
+                            - 1
+                      typ:
+                        Struct:
+                          - segment
+                          - 1
+              - Return:
+                  FuncApp:
+                    f:
+                      VarApp: dx
+                      typ:
+                        Func:
+                          fp:
+                            - Struct:
+                                - segment
+                                - 1
+                          body: Int
+                          variadic: false
+                          proto: true
+                    aps:
+                      - Load:
+                          VarApp: t
+                          typ:
+                            Struct:
+                              - segment
+                              - 1
+                        typ:
+                          Struct:
+                            - segment
+                            - 1
+                  typ: Int
+        "###);
+    }
+
+    #[test]
+    fn errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/access_errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| {
+            let tokens = lexer::lex(l)?;
+            let tree = parser_ast::parse_prg(&tokens)?;
+            super::type_prg(&tree)
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "struct s; int main() { struct s x; return 0; }: E0004 1:33 storage size of 'x' isn't known"
+        - "struct s; int f(struct s x) { return 0; }: E0004 1:17 parameter 'x' has incomplete type"
+        - "struct s; struct s f() { return f(); }: E0004 1:11 return type is an incomplete type"
+        - "int main() { int i; return i.x; }: E0004 1:28 request for member 'x' in something not a structure or union"
+        - "int main() { int i; return i->x; }: E0004 1:28 invalid type argument of '->' (have Int)"
+        - "struct p { int x; }; int main() { struct p v; return v.y; }: E0004 1:54 'struct p' has no member named 'y'"
+        - "struct s; int f(struct s *p) { return p->x; }: E0004 1:39 invalid use of incomplete type 'struct s'"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_asnmts {
+    use crate::diagnostic;
+    use crate::lexer;
+    use crate::parser_ast;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/bindings";
+
+    #[test]
+    fn compound() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/asnmt_compound.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typ = super::type_prg(&tree).unwrap().0;
+        insta::assert_yaml_snapshot!(typ, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Decl:
+                  - alias: n
+                    sc: ~
+                    typ: Int
+                    expr:
+                      Int: 1
+                      typ: Int
+              - Decl:
+                  - alias: s
+                    sc: ~
+                    typ: Int
+                    expr:
+                      UnaryE:
+                        op: Sub
+                        l:
+                          Int: 16
+                          typ: Int
+                      typ: Int
+              - Decl:
+                  - alias: u
+                    sc: ~
+                    typ: UInt
+                    expr:
+                      UInt: 4294967295
+                      typ: UInt
+              - Expr:
+                  Asnmt:
+                    op: Add
+                    l:
+                      VarApp: n
+                      typ: Int
+                    r:
+                      Int: 10
+                      typ: Int
+                  typ: Int
+              - Expr:
+                  Asnmt:
+                    op: Sub
+                    l:
+                      VarApp: n
+                      typ: Int
+                    r:
+                      Int: 3
+                      typ: Int
+                  typ: Int
+              - Expr:
+                  Asnmt:
+                    op: Shl
+                    l:
+                      VarApp: n
+                      typ: Int
+                    r:
+                      Int: 2
+                      typ: Int
+                  typ: Int
+              - Expr:
+                  Asnmt:
+                    op: Shr
+                    l:
+                      VarApp: n
+                      typ: Int
+                    r:
+                      Int: 1
+                      typ: Int
+                  typ: Int
+              - Expr:
+                  Asnmt:
+                    op: Or
+                    l:
+                      VarApp: n
+                      typ: Int
+                    r:
+                      Int: 5
+                      typ: Int
+                  typ: Int
+              - Expr:
+                  Asnmt:
+                    op: And
+                    l:
+                      VarApp: n
+                      typ: Int
+                    r:
+                      Int: 13
+                      typ: Int
+                  typ: Int
+              - Expr:
+                  Asnmt:
+                    op: Xor
+                    l:
+                      VarApp: n
+                      typ: Int
+                    r:
+                      Int: 6
+                      typ: Int
+                  typ: Int
+              - Expr:
+                  Asnmt:
+                    op: Shr
+                    l:
+                      VarApp: s
+                      typ: Int
+                    r:
+                      Int: 2
+                      typ: Int
+                  typ: Int
+              - Expr:
+                  Asnmt:
+                    op: Shr
+                    l:
+                      VarApp: u
+                      typ: UInt
+                    r:
+                      Int: 28
+                      typ: Int
+                  typ: UInt
+              - Return:
+                  Cast:
+                    typ: Int
+                    l:
+                      BinE:
+                        op: Add
+                        l:
+                          Cast:
+                            typ: UInt
+                            l:
+                              BinE:
+                                op: Add
+                                l:
+                                  Load:
+                                    VarApp: n
+                                    typ: Int
+                                  typ: Int
+                                r:
+                                  Load:
+                                    VarApp: s
+                                    typ: Int
+                                  typ: Int
+                              typ: Int
+                          typ: UInt
+                        r:
+                          Load:
+                            VarApp: u
+                            typ: UInt
+                          typ: UInt
+                      typ: UInt
+                  typ: Int
+        "###);
+    }
+
+    #[test]
+    fn errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/asnmt_errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| {
+            let tokens = lexer::lex(l)?;
+            let tree = parser_ast::parse_prg(&tokens)?;
+            super::type_prg(&tree)
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "int f() { 1 = 2; }: E0004 1:11 lvalue required as left operand of assignment"
+        - "int f() { int x; x + 1 = 2; }: E0004 1:18 lvalue required as left operand of assignment"
+        - "int f() { const int x = 1; x = 2; }: E0004 1:28 assignment of read-only variable 'x'"
+        - "int f() { const int x = 1; x++; }: E0004 1:28 increment of read-only variable 'x'"
+        - "int f() { int x; --(x + 1); }: E0004 1:20 lvalue required as decrement operand"
+        - "int f() { struct s { const int a; } v; v.a = 1; }: E0004 1:40 assignment of read-only member 'a'"
+        - "int f() { const struct s { int a; } v; v.a += 1; }: E0004 1:40 assignment of read-only member 'a'"
+        - "int f() { double d; d %= 2; }: E0004 1:21 invalid operands to compound assignment (have Double and Int)"
+        - "int f() { double d; d <<= 1; }: E0004 1:21 invalid operands to compound assignment (have Double and Int)"
+        - "int f() { struct s { int a; } v; v++; }: E0004 1:34 wrong type argument to increment (have Struct(Some(\"s\"), 0))"
+        - "int f() { struct s { int a; } v; int x; x = v; }: E0005 1:45 type error: expected Int, found Struct(Some(\"s\"), 0)"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_operators {
+    use crate::diagnostic;
+    use crate::lexer;
+    use crate::parser_ast;
+    use crate::{SDef, SStmtKind, Tnv};
+    use std::collections::HashMap;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/arith";
+
+    #[test]
+    fn promotions() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/promotions.c")).expect("file dne");
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let _ = super::type_prg(&tree).unwrap().0;
+
+        // the type of each expression, returned from a function of its own
+        let t = |e: &str| {
+            let tokens = lexer::lex(&format!("int f() {{ return {e}; }}")).unwrap();
+            let tree = parser_ast::parse_prg(&tokens).unwrap();
+            let SDef::FuncDef(fd) = &tree.defs[0] else {
+                unreachable!()
+            };
+            let SStmtKind::Return(Some(e)) = &fd.body[0].kind else {
+                unreachable!()
+            };
+            let tnv = Tnv {
+                fnv: HashMap::new(),
+                fdecls: HashMap::new(),
+                vnv: HashMap::new(),
+                warnings: std::cell::RefCell::new(vec![]),
+                records: tree.records.clone(),
+            };
+            format!("{:?}", super::type_expr(e, &tnv, &HashMap::new()).unwrap())
+        };
+        #[rustfmt::skip]
+        let types = ["'a' + 'b'", "1 < 2.0", "1u >> 1", "1 << 1u", "~'a'", "!1.5", "1 && 2.0", "1u ^ 1", "-1u", "(char)1 - (short)1"]
+            .map(|e| format!("{e}: {}", t(e)));
+        insta::assert_yaml_snapshot!(types, @r###"
+        ---
+        - "'a' + 'b': Int"
+        - "1 < 2.0: Int"
+        - "1u >> 1: UInt"
+        - "1 << 1u: Int"
+        - "~'a': Int"
+        - "!1.5: Int"
+        - "1 && 2.0: Int"
+        - "1u ^ 1: UInt"
+        - "-1u: UInt"
+        - "(char)1 - (short)1: Int"
+        "###);
+    }
+
+    #[test]
+    fn errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/operand_errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| {
+            let tokens = lexer::lex(l)?;
+            let tree = parser_ast::parse_prg(&tokens)?;
+            super::type_prg(&tree)
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "int f() { double d; return d & 1; }: E0004 1:28 invalid operands to binary & (have Double and Int)"
+        - "int f() { double d; return 1 << d; }: E0004 1:28 invalid operands to binary << (have Int and Double)"
+        - "int f() { float x; return x ^ x; }: E0004 1:27 invalid operands to binary ^ (have Float and Float)"
+        - "int f() { struct s { int a; } v; return v < 1; }: E0004 1:41 invalid operands to binary < (have Struct(Some(\"s\"), 0) and Int)"
+        - "int f() { struct s { int a; } v; return 1 == v; }: E0004 1:41 invalid operands to binary == (have Int and Struct(Some(\"s\"), 0))"
+        - "int f() { struct s { int a; } v; return v && 1; }: E0004 1:41 used Struct(Some(\"s\"), 0) where scalar is required"
+        - "int f() { struct s { int a; } v; return !v; }: E0004 1:41 wrong type argument to unary operator (have Struct(Some(\"s\"), 0))"
+        - "int f() { double d; return ~d; }: E0004 1:28 wrong type argument to unary operator (have Double)"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_pointers {
+    use crate::diagnostic;
+    use crate::lexer;
+    use crate::parser_ast;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/pointers";
+
+    #[test]
+    fn errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| {
+            let tokens = lexer::lex(l)?;
+            let tree = parser_ast::parse_prg(&tokens)?;
+            super::type_prg(&tree)
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "int f() { int x; return *x; }: E0004 1:25 invalid type argument of unary '*' (have Int)"
+        - "int f() { return &1; }: E0004 1:18 lvalue required as unary '&' operand"
+        - "int f() { int x; return x[0]; }: E0004 1:25 subscripted value is neither array nor pointer"
+        - "int f() { int a[2]; double d; return a[d]; }: E0004 1:40 array subscript is not an integer"
+        - "int f() { int *p; int *q; return p + q; }: E0004 1:34 invalid operands to binary + (have Pointer(Int) and Pointer(Int))"
+        - "int f() { int *p; char *q; return p - q; }: E0004 1:35 invalid operands to binary - (have Pointer(Int) and Pointer(Char))"
+        - "int f() { int *p; char *q; return p < q; }: E0004 1:35 invalid operands to binary < (have Pointer(Int) and Pointer(Char))"
+        - "int f() { int *p; return p == 1; }: E0004 1:26 invalid operands to binary == (have Pointer(Int) and Int)"
+        - "int f() { int *p; p = 1; }: E0005 1:23 type error: expected Pointer(Int), found Int"
+        - "int f() { int *p; char *q; p = q; }: E0005 1:32 type error: expected Pointer(Int), found Pointer(Char)"
+        - "int f() { const int *c; int *p; p = c; }: E0005 1:37 type error: expected Pointer(Int), found Pointer(Const(Int))"
+        - "int f() { int a[2]; int b[2]; a = b; }: E0004 1:31 assignment to expression with array type"
+        - "int f() { struct s *p; p + 1; }: E0004 1:24 arithmetic on a pointer to an incomplete type (have Struct(Some(\"s\"), 0))"
+        - "int f() { void *p; p++; }: E0004 1:20 arithmetic on a pointer to an incomplete type (have Void)"
+        - "int *f() { return 1; }: E0005 1:19 type error: expected Pointer(Int), found Int"
+        - "int f() { int *p; double d; d = (double)p; }: E0004 1:33 type error: cannot cast Pointer(Int) to Double"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_globals {
+    use crate::diagnostic;
+    use crate::lexer;
+    use crate::parser_ast;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/globals";
+
+    #[test]
+    fn tentative() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/tentative.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typ = super::type_prg(&tree).unwrap().0;
+        insta::assert_yaml_snapshot!(typ, @r###"
+        ---
+        - VarDef:
+            alias: n
+            sc: ~
+            typ: Int
+            expr: ~
+        - VarDef:
+            alias: total
+            sc: Extern
+            typ: Int
+            expr: ~
+        - VarDef:
+            alias: n
+            sc: ~
+            typ: Int
+            expr: ~
+        - VarDef:
+            alias: total
+            sc: ~
+            typ: Int
+            expr:
+              Int: 5
+              typ: Int
+        - VarDef:
+            alias: hidden
+            sc: Static
+            typ: Int
+            expr: ~
+        - VarDef:
+            alias: hidden
+            sc: Extern
+            typ: Int
+            expr: ~
+        - FuncDef:
+            alias: bump
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: true
+            body:
+              - Expr:
+                  Asnmt:
+                    op: Add
+                    l:
+                      VarApp: n
+                      typ: Int
+                    r:
+                      Int: 1
+                      typ: Int
+                  typ: Int
+              - Expr:
+                  Asnmt:
+                    op: Add
+                    l:
+                      VarApp: hidden
+                      typ: Int
+                    r:
+                      Int: 2
+                      typ: Int
+                  typ: Int
+              - Return:
+                  BinE:
+                    op: Add
+                    l:
+                      Load:
+                        VarApp: n
+                        typ: Int
+                      typ: Int
+                    r:
+                      Load:
+                        VarApp: hidden
+                        typ: Int
+                      typ: Int
+                  typ: Int
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: true
+            body:
+              - Expr:
+                  FuncApp:
+                    f:
+                      VarApp: bump
+                      typ:
+                        Func:
+                          fp: []
+                          body: Int
+                          variadic: false
+                          proto: true
+                    aps: []
+                  typ: Int
+              - Return:
+                  BinE:
+                    op: Add
+                    l:
+                      FuncApp:
+                        f:
+                          VarApp: bump
+                          typ:
+                            Func:
+                              fp: []
+                              body: Int
+                              variadic: false
+                              proto: true
+                        aps: []
+                      typ: Int
+                    r:
+                      Load:
+                        VarApp: total
+                        typ: Int
+                      typ: Int
+                  typ: Int
+        "###);
+    }
+
+    #[test]
+    fn errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| {
+            let tokens = lexer::lex(l)?;
+            let tree = parser_ast::parse_prg(&tokens)?;
+            super::type_prg(&tree)
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "int x = 1; int x = 2;: E0007 1:16 redefinition of 'x'"
+        - "int x; char x;: E0008 1:13 conflicting types for 'x'"
+        - "int x; static int x;: E0008 1:19 static declaration of 'x' follows non-static declaration"
+        - "static int x; int x;: E0008 1:19 non-static declaration of 'x' follows static declaration"
+        - "int a[2] = {1, 2, 3};: E0004 1:19 excess elements in array initializer"
+        - "struct s { int a; } x = {1, 2};: E0004 1:29 excess elements in struct initializer"
+        - "int x = {1, 2};: E0004 1:13 excess elements in scalar initializer"
+        - "char s[2] = \"abc\";: E0004 1:13 initializer-string for array of chars is too long"
+        - "int a[2] = 3;: E0004 1:12 invalid initializer"
+        - "int y; int x = y;: E0004 1:16 initializer element is not constant"
+        - "int g(void) { return 1; } int f(void) { static int s = g(); return s; }: E0004 1:56 initializer element is not constant"
+        - "struct t x;: E0004 1:10 storage size of 'x' isn't known"
+        - "int f(void) { int a[] = {1, {2, 3}}; return 0; }: E0004 1:33 excess elements in scalar initializer"
+        - "int f(void) { int x = {1, 2}; return x; }: E0004 1:27 excess elements in scalar initializer"
+        - "int f(void) { int a; static int *p = &a; return 0; }: E0004 1:38 initializer element is not constant"
+        "###);
+    }
+
+    #[test]
+    fn object() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/object.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let err = super::type_prg(&tree).unwrap_err();
+        let (typ, warnings) = super::type_unit(&tree, false).unwrap();
+        insta::assert_yaml_snapshot!((err.to_string(), typ.defs.len(), warnings.len()), @r###"
+        ---
+        - main function not found
+        - 2
+        - 0
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_protos {
+    use crate::diagnostic;
+    use crate::lexer::{self, LexOpts};
+    use crate::parser_ast;
+    use crate::source::SourceMap;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/protos";
+
+    #[test]
+    fn order() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/order.c")).expect("file dne");
 
         let tokens = lexer::lex(&chars).unwrap();
         let tree = parser_ast::parse_prg(&tokens).unwrap();
-        let typ = super::type_prg(&tree).unwrap();
+        let typ = super::type_prg(&tree).unwrap().0;
         insta::assert_yaml_snapshot!(typ, @r###"
         ---
-        Int
+        - VarDef:
+            alias: add
+            sc: Extern
+            typ:
+              Func:
+                fp:
+                  - Int
+                  - Int
+                body: Int
+                variadic: false
+                proto: true
+            expr: ~
+        - VarDef:
+            alias: sub
+            sc: ~
+            typ:
+              Func:
+                fp: []
+                body: Int
+                variadic: false
+                proto: false
+            expr: ~
+        - VarDef:
+            alias: is_odd
+            sc: ~
+            typ:
+              Func:
+                fp:
+                  - Int
+                body: Int
+                variadic: false
+                proto: true
+            expr: ~
+        - FuncDef:
+            alias: is_even
+            sc: ~
+            typ: Int
+            fps:
+              - alias: n
+                typ: Int
+            variadic: false
+            proto: true
+            body:
+              - IfEls:
+                  cond:
+                    Load:
+                      VarApp: n
+                      typ: Int
+                    typ: Int
+                  then:
+                    Return:
+                      FuncApp:
+                        f:
+                          VarApp: is_odd
+                          typ:
+                            Func:
+                              fp:
+                                - Int
+                              body: Int
+                              variadic: false
+                              proto: true
+                        aps:
+                          - BinE:
+                              op: Sub
+                              l:
+                                Load:
+                                  VarApp: n
+                                  typ: Int
+                                typ: Int
+                              r:
+                                Int: 1
+                                typ: Int
+                            typ: Int
+                      typ: Int
+                  els: ~
+              - Return:
+                  Int: 1
+                  typ: Int
+        - FuncDef:
+            alias: is_odd
+            sc: ~
+            typ: Int
+            fps:
+              - alias: n
+                typ: Int
+            variadic: false
+            proto: true
+            body:
+              - IfEls:
+                  cond:
+                    Load:
+                      VarApp: n
+                      typ: Int
+                    typ: Int
+                  then:
+                    Return:
+                      FuncApp:
+                        f:
+                          VarApp: is_even
+                          typ:
+                            Func:
+                              fp:
+                                - Int
+                              body: Int
+                              variadic: false
+                              proto: true
+                        aps:
+                          - BinE:
+                              op: Sub
+                              l:
+                                Load:
+                                  VarApp: n
+                                  typ: Int
+                                typ: Int
+                              r:
+                                Int: 1
+                                typ: Int
+                            typ: Int
+                      typ: Int
+                  els: ~
+              - Return:
+                  Int: 0
+                  typ: Int
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: true
+            body:
+              - Return:
+                  BinE:
+                    op: Add
+                    l:
+                      BinE:
+                        op: Add
+                        l:
+                          FuncApp:
+                            f:
+                              VarApp: add
+                              typ:
+                                Func:
+                                  fp:
+                                    - Int
+                                    - Int
+                                  body: Int
+                                  variadic: false
+                                  proto: true
+                            aps:
+                              - FuncApp:
+                                  f:
+                                    VarApp: is_even
+                                    typ:
+                                      Func:
+                                        fp:
+                                          - Int
+                                        body: Int
+                                        variadic: false
+                                        proto: true
+                                  aps:
+                                    - Int: 10
+                                      typ: Int
+                                typ: Int
+                              - FuncApp:
+                                  f:
+                                    VarApp: is_odd
+                                    typ:
+                                      Func:
+                                        fp:
+                                          - Int
+                                        body: Int
+                                        variadic: false
+                                        proto: true
+                                  aps:
+                                    - Int: 7
+                                      typ: Int
+                                typ: Int
+                          typ: Int
+                        r:
+                          FuncApp:
+                            f:
+                              VarApp: sub
+                              typ:
+                                Func:
+                                  fp:
+                                    - Int
+                                    - Int
+                                  body: Int
+                                  variadic: false
+                                  proto: false
+                            aps:
+                              - Int: 9
+                                typ: Int
+                              - Int: 2
+                                typ: Int
+                          typ: Int
+                      typ: Int
+                    r:
+                      FuncApp:
+                        f:
+                          VarApp: half
+                          typ:
+                            Func:
+                              fp:
+                                - Double
+                              body: Int
+                              variadic: false
+                              proto: true
+                        aps:
+                          - Double: 9
+                            typ: Double
+                      typ: Int
+                  typ: Int
+        - FuncDef:
+            alias: add
+            sc: ~
+            typ: Int
+            fps:
+              - alias: a
+                typ: Int
+              - alias: b
+                typ: Int
+            variadic: false
+            proto: true
+            body:
+              - Return:
+                  BinE:
+                    op: Add
+                    l:
+                      Load:
+                        VarApp: a
+                        typ: Int
+                      typ: Int
+                    r:
+                      Load:
+                        VarApp: b
+                        typ: Int
+                      typ: Int
+                  typ: Int
+        - FuncDef:
+            alias: sub
+            sc: ~
+            typ: Int
+            fps:
+              - alias: a
+                typ: Int
+              - alias: b
+                typ: Int
+            variadic: false
+            proto: false
+            body:
+              - Return:
+                  BinE:
+                    op: Sub
+                    l:
+                      Load:
+                        VarApp: a
+                        typ: Int
+                      typ: Int
+                    r:
+                      Load:
+                        VarApp: b
+                        typ: Int
+                      typ: Int
+                  typ: Int
+        - FuncDef:
+            alias: half
+            sc: ~
+            typ: Int
+            fps:
+              - alias: d
+                typ: Double
+            variadic: false
+            proto: true
+            body:
+              - Return:
+                  Cast:
+                    typ: Int
+                    l:
+                      BinE:
+                        op: Div
+                        l:
+                          Load:
+                            VarApp: d
+                            typ: Double
+                          typ: Double
+                        r:
+                          Cast:
+                            typ: Double
+                            l:
+                              Int: 2
+                              typ: Int
+                          typ: Double
+                      typ: Double
+                  typ: Int
+        "###);
+    }
+
+    #[test]
+    fn implicit() {
+        let src = fs::read_to_string(format!("{TEST_DIR}/implicit.c")).expect("file dne");
+        let mut sm = SourceMap::new();
+        let file = sm.add("implicit.c", &src);
+
+        let tokens = lexer::lex_file(&src, file, LexOpts::default()).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let (_, warnings) = super::type_prg(&tree).unwrap();
+        let warnings = warnings.iter().map(|w| w.render(&sm)).collect::<String>();
+        insta::assert_snapshot!(warnings, @r###"
+        warning[W0001]: implicit declaration of function 'abs' [-Wimplicit-function-declaration]
+         --> implicit.c:2:12
+          |
+        2 |     return abs(-3) + labs(-4);
+          |            ^^^^^^^
+        warning[W0001]: implicit declaration of function 'labs' [-Wimplicit-function-declaration]
+         --> implicit.c:2:22
+          |
+        2 |     return abs(-3) + labs(-4);
+          |                      ^^^^^^^^
+        "###);
+    }
+
+    #[test]
+    fn conflicts() {
+        let src = fs::read_to_string(format!("{TEST_DIR}/conflicts.c")).expect("file dne");
+        let mut sm = SourceMap::new();
+        let file = sm.add("conflicts.c", &src);
+
+        let tokens = lexer::lex_file(&src, file, LexOpts::default()).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let err = super::type_prg(&tree).unwrap_err();
+        insta::assert_snapshot!(diagnostic::render(&err, &sm), @r###"
+        error[E0008]: conflicting types for 'f'
+         --> conflicts.c:4:1
+          |
+        4 | int f(char c) {
+          | ^^^^^^^^^^^^^^^
+        note: previous declaration of 'f' was here
+         --> conflicts.c:1:5
+          |
+        1 | int f(int);
+          |     ^^^^^^
+        error[E0008]: conflicting types for 'g'
+         --> conflicts.c:8:1
+          |
+        8 | int g(x)
+          | ^^^^^^^^
+        note: previous declaration of 'g' was here
+         --> conflicts.c:2:5
+          |
+        2 | int g(double);
+          |     ^^^^^^^^^
+        "###);
+    }
+
+    #[test]
+    fn arity() {
+        let src = fs::read_to_string(format!("{TEST_DIR}/arity.c")).expect("file dne");
+        let mut sm = SourceMap::new();
+        let file = sm.add("arity.c", &src);
+
+        let tokens = lexer::lex_file(&src, file, LexOpts::default()).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let err = super::type_prg(&tree).unwrap_err();
+        insta::assert_snapshot!(diagnostic::render(&err, &sm), @r###"
+        error[E0011]: too few arguments to function 'add': expected 2, have 1
+         --> arity.c:5:24
+          |
+        5 | int few(void) { return add(1); }
+          |                        ^^^^^^
+        note: 'add' declared here
+         --> arity.c:1:5
+          |
+        1 | int add(int a, int b);
+          |     ^^^^^^^^^^^^^^^^^
+        error[E0011]: too many arguments to function 'add': expected 2, have 5
+         --> arity.c:6:25
+          |
+        6 | int many(void) { return add(1, 2, 3, 4, 5); }
+          |                         ^^^^^^^^^^^^^^^^^^
+        note: 'add' declared here
+         --> arity.c:1:5
+          |
+        1 | int add(int a, int b);
+          |     ^^^^^^^^^^^^^^^^^
+        error[E0011]: too few arguments to function 'print': expected at least 1, have 0
+         --> arity.c:7:25
+          |
+        7 | int none(void) { return print(); }
+          |                         ^^^^^^^
+        note: 'print' declared here
+         --> arity.c:2:5
+          |
+        2 | int print(const char *fmt, ...);
+          |     ^^^^^^^^^^^^^^^^^^^^^^^^^^^
+        error[E0005]: type error: passing Pointer(Char) to parameter 2 of 'add', which is Int
+         --> arity.c:8:33
+          |
+        8 | int wrong(void) { return add(1, "two"); }
+          |                                 ^^^^^
+        note: 'add' declared here
+         --> arity.c:1:5
+          |
+        1 | int add(int a, int b);
+          |     ^^^^^^^^^^^^^^^^^
+        "###);
+    }
+
+    #[test]
+    fn errors() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/errors.c")).expect("file dne");
+
+        let errs = diagnostic::diags(chars.lines(), |l| {
+            let tokens = lexer::lex(l)?;
+            let tree = parser_ast::parse_prg(&tokens)?;
+            super::type_prg(&tree)
+        });
+        insta::assert_yaml_snapshot!(errs, @r###"
+        ---
+        - "int f(int); double f(int);: E0008 1:20 conflicting types for 'f'"
+        - "int f(int); int f(int, int);: E0008 1:17 conflicting types for 'f'"
+        - "int f(int); int f(char c) { return c; }: E0008 1:13 conflicting types for 'f'"
+        - "int f(char); int f();: E0008 1:18 conflicting types for 'f'"
+        - "int f(float x); int f(x) float x; { return 0; }: E0008 1:17 conflicting types for 'f'"
+        - "int f(int, ...); int f();: E0008 1:22 conflicting types for 'f'"
+        - "int f(void) { return 0; } int f(void) { return 1; }: E0007 1:27 redefinition of 'f'"
+        - "int f(void); int f;: E0007 1:18 'f' redeclared as different kind of symbol"
+        - "int f; int f(void);: E0007 1:5 'f' redeclared as different kind of symbol"
+        "###);
+    }
+}
+
+#[cfg(test)]
+mod test_elaboration {
+    use crate::lexer;
+    use crate::parser_ast;
+    use std::fs;
+
+    const TEST_DIR: &str = "tests/fixtures/snap/shared/arith";
+
+    #[test]
+    fn conversions() {
+        let chars = fs::read_to_string(format!("{TEST_DIR}/conversions.c")).expect("file dne");
+
+        let tokens = lexer::lex(&chars).unwrap();
+        let tree = parser_ast::parse_prg(&tokens).unwrap();
+        let typed_tree = super::type_prg(&tree).unwrap().0;
+        insta::assert_yaml_snapshot!(typed_tree, @r###"
+        ---
+        - FuncDef:
+            alias: main
+            sc: ~
+            typ: Int
+            fps: []
+            variadic: false
+            proto: false
+            body:
+              - Decl:
+                  - alias: c
+                    sc: ~
+                    typ: Char
+                    expr:
+                      Cast:
+                        typ: Char
+                        l:
+                          Int: 97
+                          typ: Int
+                      typ: Char
+              - Decl:
+                  - alias: u
+                    sc: ~
+                    typ: UInt
+                    expr:
+                      Cast:
+                        typ: UInt
+                        l:
+                          Int: 2
+                          typ: Int
+                      typ: UInt
+              - Return:
+                  Cast:
+                    typ: Int
+                    l:
+                      BinE:
+                        op: Add
+                        l:
+                          Cast:
+                            typ: UInt
+                            l:
+                              Load:
+                                VarApp: c
+                                typ: Char
+                              typ: Char
+                          typ: UInt
+                        r:
+                          Load:
+                            VarApp: u
+                            typ: UInt
+                          typ: UInt
+                      typ: UInt
+                  typ: Int
         "###);
     }
 }
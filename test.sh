@@ -1,4 +1,22 @@
 #!/bin/bash
+shopt -s nullglob
+
+# the libc in runtime/ is compiled by picoc itself (each file on its own,
+# without main) into build/runtime and linked ahead of the toolchain's, so
+# its definitions win. a source picoc cannot compile fails the run
+build_runtime() {
+  mkdir -p build/runtime
+  rm -f build/runtime/*.s
+  for src in runtime/libc/*.c; do
+    if ! ./target/release/picoc089 compilec89 "$src" O0 -c > /dev/null; then
+      echo "picoc-test: runtime: failed to compile $src"
+      exit 1
+    fi
+    mv tmp.s "build/runtime/$(basename "$src" .c).s"
+  done
+  # the wrappers the libc traps through
+  cp runtime/syscall.s build/runtime/
+}
 
 compile_and_eval() {
   input="$1"
@@ -6,7 +24,8 @@ compile_and_eval() {
 
   if [ "$compiler" = "din" ]; then
     ./target/release/picoc089 compilec89 "$input" O0 > /dev/null
-    riscv64-unknown-elf-gcc -o tmp tmp.s
+    # the program starts at the runtime's _start, which exits through its exit
+    riscv64-unknown-elf-gcc -nostartfiles -o tmp runtime/crt0.s tmp.s build/runtime/*.s
   elif [ "$compiler" = "gcc" ]; then
     riscv64-unknown-elf-gcc -o tmp "$input"
   # elif [ "$compiler" = "clang" ]; then
@@ -31,6 +50,8 @@ assert() {
   fi
 }
 
+build_runtime
+
 #
 #
 #
@@ -43,48 +64,81 @@ assert "./tests/fixtures/snap/shared/arith/lit.c"
 assert "./tests/fixtures/snap/shared/arith/add.c"
 assert "./tests/fixtures/snap/shared/arith/add_multi.c"
 assert "./tests/fixtures/snap/shared/arith/sub.c"
-# assert "./tests/fixtures/snap/shared/arith/mult.c"
-# assert "./tests/fixtures/snap/shared/arith/div.c"
+assert "./tests/fixtures/snap/shared/arith/mult.c"
+assert "./tests/fixtures/snap/shared/arith/div.c"
 
 assert "./tests/fixtures/snap/shared/arith/add_associative.c"
 assert "./tests/fixtures/snap/shared/arith/sub_associative.c"
-# assert "./tests/fixtures/legal/snap/shared/arith/mult_add_precedence.c"
-# assert "./tests/fixtures/legal/arithmetic_precedence/mult_add_precedence_multi.c"
+assert "./tests/fixtures/snap/shared/exprs/ternary.c"
+assert "./tests/fixtures/snap/shared/arith/promotions.c"
+assert "./tests/fixtures/snap/shared/arith/conversions.c"
+assert "./tests/fixtures/snap/shared/arith/bitwise.c"
+assert "./tests/fixtures/snap/shared/arith/mult_add_precedence.c"
+assert "./tests/fixtures/snap/shared/arith/mult_add_precedence_multi.c"
 
 # --- control flow ---
-# assert "./tests/fixtures/snap/shared/control/eq_true.c"
-# assert "./tests/fixtures/snap/shared/control/eq_false.c"
-# assert "./tests/fixtures/snap/shared/control/neq_true.c"
-# assert "./tests/fixtures/snap/shared/control/neq_false.c"
-
-# assert "./tests/fixtures/snap/shared/control/and_true.c"
-# assert "./tests/fixtures/snap/shared/control/or_true.c"
-# assert "./tests/fixtures/snap/shared/control/and_false.c"
-# assert "./tests/fixtures/snap/shared/control/or_false.c"
-
-# assert "./tests/fixtures/snap/shared/control/lt_true.c"
-# assert "./tests/fixtures/snap/shared/control/lteq_true.c"
-# assert "./tests/fixtures/snap/shared/control/lteq2_true.c"
-# assert "./tests/fixtures/snap/shared/control/gt_true.c"
-# assert "./tests/fixtures/snap/shared/control/gteq_true.c"
-# assert "./tests/fixtures/snap/shared/control/gteq2_true.c"
-
-# assert "./tests/fixtures/snap/shared/control/ifels_then.c"
-# assert "./tests/fixtures/snap/shared/control/ifels_els.c"
+assert "./tests/fixtures/snap/shared/control/eq_true.c"
+assert "./tests/fixtures/snap/shared/control/eq_false.c"
+assert "./tests/fixtures/snap/shared/control/neq_true.c"
+assert "./tests/fixtures/snap/shared/control/neq_false.c"
+
+assert "./tests/fixtures/snap/shared/control/and_true.c"
+assert "./tests/fixtures/snap/shared/control/or_true.c"
+assert "./tests/fixtures/snap/shared/control/and_false.c"
+assert "./tests/fixtures/snap/shared/control/or_false.c"
+
+assert "./tests/fixtures/snap/shared/control/lt_true.c"
+assert "./tests/fixtures/snap/shared/control/lteq_true.c"
+assert "./tests/fixtures/snap/shared/control/lteq2_true.c"
+assert "./tests/fixtures/snap/shared/control/gt_true.c"
+assert "./tests/fixtures/snap/shared/control/gteq_true.c"
+assert "./tests/fixtures/snap/shared/control/gteq2_true.c"
+
+assert "./tests/fixtures/snap/shared/control/ifels_then.c"
+assert "./tests/fixtures/snap/shared/control/ifels_els.c"
+assert "./tests/fixtures/snap/shared/control/block.c"
+assert "./tests/fixtures/snap/shared/control/dowhile.c"
+assert "./tests/fixtures/snap/shared/control/switch.c"
+assert "./tests/fixtures/snap/shared/control/goto.c"
 
 # assert "./tests/fixtures/snap/shared/control/for.c"
 
+# --- pointers and arrays ---
+assert "./tests/fixtures/snap/shared/pointers/basics.c"
+assert "./tests/fixtures/snap/shared/pointers/arrays.c"
+assert "./tests/fixtures/snap/shared/pointers/index.c"
+assert "./tests/fixtures/snap/shared/pointers/large_frame.c"
+assert "./tests/fixtures/snap/shared/pointers/funcs.c"
+
 # --- bindings ---
-# assert "./tests/fixtures/legal/data_flow/asnmt.c"
-# assert "./tests/fixtures/legal/data_flow/asnmt_multi.c"
-# assert "./tests/fixtures/legal/data_flow/asnmt_multi_expr.c"
-# assert "./tests/fixtures/legal/data_flow/asnmt_multi_expr_var.c"
-# assert "./tests/fixtures/legal/data_flow/asnmt_update.c"
-# assert "./tests/fixtures/legal/data_flow/asnmt_update_inc.c"
-# assert "./tests/fixtures/legal/data_flow/asnmt_update_dec.c"
+assert "./tests/fixtures/snap/shared/bindings/asnmt.c"
+assert "./tests/fixtures/snap/shared/bindings/asnmt_multi.c"
+assert "./tests/fixtures/snap/shared/bindings/asnmt_multi_expr.c"
+assert "./tests/fixtures/snap/shared/bindings/asnmt_multi_expr_var.c"
+assert "./tests/fixtures/snap/shared/bindings/asnmt_update.c"
+assert "./tests/fixtures/snap/shared/bindings/asnmt_update_inc.c"
+assert "./tests/fixtures/snap/shared/bindings/asnmt_update_dec.c"
+assert "./tests/fixtures/snap/shared/bindings/asnmt_compound.c"
+assert "./tests/fixtures/snap/shared/bindings/asnmt_pre_post.c"
+assert "./tests/fixtures/snap/shared/bindings/asnmt_chain.c"
+assert "./tests/fixtures/snap/shared/bindings/shadow.c"
+assert "./tests/fixtures/snap/shared/bindings/self_ref.c"
+
+# -- globals and statics
+assert "./tests/fixtures/snap/shared/globals/tentative.c"
+assert "./tests/fixtures/snap/shared/globals/sections.c"
+assert "./tests/fixtures/snap/shared/globals/statics.c"
+assert "./tests/fixtures/snap/shared/globals/locals.c"
+assert "./tests/fixtures/snap/shared/globals/addresses.c"
+assert "./tests/fixtures/snap/shared/globals/linkage.c"
 
 # -- functions
 assert "./tests/fixtures/snap/shared/bindings/composition.c"
+assert "./tests/fixtures/snap/shared/protos/order.c"
+assert "./tests/fixtures/snap/shared/protos/args.c"
+assert "./tests/fixtures/snap/shared/protos/variadic.c"
+assert "./tests/fixtures/snap/shared/protos/printf_short.c"
+assert "./tests/fixtures/snap/shared/floats/compare.c"
 # assert "./tests/fixtures/snap/shared/bindings/formal_param.c"
 ## --- heap ---
 # -- malloc/free
@@ -0,0 +1 @@
+1.0 .5 1e5 1.5e-3f 2.L 0x1e+1 1..2 x.5
@@ -0,0 +1,10 @@
+typedef enum { false, true } bool;
+
+int main() {
+    int x1 = 1;
+    int my_var = 2;
+    int _Tmp = x1 + my_var;
+    int Returns = _Tmp;
+    bool t = true;
+    return Returns + t;
+}
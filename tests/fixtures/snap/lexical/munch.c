@@ -0,0 +1 @@
+x+++++y a<<=b>>=c a- -b x..y p->q
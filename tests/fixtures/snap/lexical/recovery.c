@@ -0,0 +1,18 @@
+int one(void) {
+    int x = 1 +;
+    return x;
+}
+
+int two(void) {
+    if (x {
+        return 1;
+    }
+    x = 2;
+    return 3 4;
+}
+
+int three(void) {
+    return 3;
+}
+
+float = 1;
@@ -0,0 +1,12 @@
+__LINE__ __STDC__
+__DATE__ __TIME__
+#define LINE __LINE__
+#define f(x) x
+f(
+__LINE__)
+#line 100
+__LINE__ __FILE__
+#line 7 "foo.c"
+__FILE__ LINE
+??=define T ??( ??) ??< ??> ??! ??' ??- ???=
+T
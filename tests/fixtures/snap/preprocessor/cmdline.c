@@ -0,0 +1 @@
+N M F(N)
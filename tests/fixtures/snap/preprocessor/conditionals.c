@@ -0,0 +1,39 @@
+#define ONE 1
+#if ONE + 1 == 2 && defined ONE && defined(ONE) && !defined TWO
+yes1
+#else
+no1
+#endif
+#if -1 > 0u
+yes2
+#endif
+#if -1 > 0
+no3
+#elif (7 / 2) * 2 == 6 && 7 % -2 == 1 && -7 >> 1 == -4
+yes3
+#elif 1
+no3
+#else
+no3
+#endif
+#ifdef TWO
+no4
+#elif 0 || 0 ? 1 / 0 : 'a' == 97
+yes4
+#endif
+#ifndef TWO
+#if 0
+it's skipped, so ' and @ are fine
+#bogus directive
+#if 1
+#else
+#endif
+#elif undefined_is_zero
+no5
+#else
+yes5
+#endif
+#endif
+#if 0x7fffffff + 1 < 0 && 1 << 31 < 0 && 0xffffffff == -1 && ~0u == 4294967295
+yes6
+#endif
@@ -0,0 +1,4 @@
+#if 0
+#elif 1
+#endif
+#elif 1
@@ -0,0 +1 @@
+#include "nope.h"
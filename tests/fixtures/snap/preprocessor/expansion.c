@@ -0,0 +1,5 @@
+#define HALF 0.5
+#define MOD(a) a % HALF
+int main() {
+    return MOD(7);
+}
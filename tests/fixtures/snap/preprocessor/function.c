@@ -0,0 +1,17 @@
+/* C89 3.8.3.5 */
+#define x 3
+#define f(a) f(x * (a))
+#undef x
+#define x 2
+#define g f
+#define z z[0]
+#define h g(~
+#define m(a) a(w)
+#define w 0,1
+#define t(a) a
+f(y+1) + f(f(z)) % t(t(g)(0) + t)(1);
+g(x+(3,4)-w) | h 5) & m
+    (f)^m(m);
+#define max(a, b) ((a) > (b) ? (a) : (b))
+#define nil() 0
+max(f(1), (2, 3)) max nil() nil
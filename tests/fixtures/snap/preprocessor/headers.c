@@ -0,0 +1,14 @@
+#include <stddef.h>
+#include <limits.h>
+#include <stdarg.h>
+#include <float.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <ctype.h>
+#include "stddef.h"
+#if CHAR_BIT == 8 && CHAR_MIN == 0 && INT_MIN < 0 && UINT_MAX == -1 && LONG_MAX == INT_MAX
+int ilp32;
+#endif
+NULL EOF EXIT_FAILURE offsetof(struct s, m) FLT_MAX DBL_EPSILON
+va_arg(ap, double)
@@ -0,0 +1,5 @@
+#ifndef A_H
+#define A_H
+#include "b.h"
+#define A 1
+#endif
@@ -0,0 +1,8 @@
+#include "inc/a.h"
+#include "inc/a.h"
+#include <sys.h>
+#define HEADER "inc/once.h"
+#include HEADER
+#include HEADER
+#pragma pack (1)
+int main() { return A + B + SYS + ONCE; }
@@ -0,0 +1,13 @@
+#include "inc/b.h"
+#define NEG -1
+int x = -NEG;
+
+int y;
+
+
+
+int z;
+#include <sys.h>
+#line 40 "renamed.c"
+int w;
+    #pragma weak w
@@ -0,0 +1,12 @@
+#define N 8
+#define TWICE N + N
+#define EMPTY
+#define foo foo
+#define a b
+#define b a
+int main() {
+    return TWICE EMPTY;
+}
+foo a b
+#undef N
+N
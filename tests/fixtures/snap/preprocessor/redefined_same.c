@@ -0,0 +1,5 @@
+#define N 1
+#define N  1
+#define N /* */ 1
+#define N 1 
+N
@@ -0,0 +1 @@
+#define SYS 4
@@ -0,0 +1,25 @@
+int main() {
+    int a = 12;
+    int b = 10;
+    unsigned int u = 0x80000000;
+    int s = -16;
+    int n = 0;
+
+    if ((a & b) == 8 && (a | b) == 14 && (a ^ b) == 6)
+        n += 1;
+    if (~a == -13 && !a == 0 && !!b == 1)
+        n += 2;
+    if ((1 << 4) == 16 && (s >> 2) == -4 && (u >> 31) == 1)
+        n += 4;
+    if ((a > b) + (a >= b) + (b < a) + (b <= a) + (a != b) + (a == b) == 5)
+        n += 8;
+
+    /* the right operand of && and || runs only when it decides the result */
+    if (a == 0 && (b = 0))
+        n = 0;
+    if (a == 12 || (b = 0))
+        n += 16;
+    if (b == 10)
+        n += 32;
+    return n;
+}
@@ -0,0 +1,5 @@
+int main() {
+    char c = 'a';
+    unsigned u = 2;
+    return c + u;
+}
@@ -0,0 +1,8 @@
+int f() { double d; return d & 1; }
+int f() { double d; return 1 << d; }
+int f() { float x; return x ^ x; }
+int f() { struct s { int a; } v; return v < 1; }
+int f() { struct s { int a; } v; return 1 == v; }
+int f() { struct s { int a; } v; return v && 1; }
+int f() { struct s { int a; } v; return !v; }
+int f() { double d; return ~d; }
@@ -0,0 +1,38 @@
+unsigned char low(int x) {
+    return x;
+}
+
+short narrow(long x) {
+    return x;
+}
+
+int main() {
+    unsigned char uc = 200;
+    signed char sc = -56;
+    unsigned short us = 65535;
+    unsigned int u = 1;
+    int n = 0;
+
+    /* operands narrower than int are promoted before the operation */
+    if (uc + uc == 400)
+        n += 1;
+    if (sc < 0)
+        n += 2;
+    if (us + 1 == 65536)
+        n += 4;
+
+    /* -1 converts to unsigned, so it compares greater */
+    if (-1 > u)
+        n += 8;
+
+    /* assignment, return and argument passing convert to the target type */
+    uc = 300;
+    if (uc == 44)
+        n += 16;
+    sc = 255;
+    if (sc == -1)
+        n += 32;
+    if (low(511) == 255 && narrow(98304) == -32768)
+        n += 64;
+    return n;
+}
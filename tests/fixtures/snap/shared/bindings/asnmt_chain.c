@@ -0,0 +1,17 @@
+struct p {
+    int x;
+    int y;
+};
+
+int main() {
+    struct p a;
+    struct p b;
+    int i;
+    int j;
+    i = j = 3;
+    a.x = 1;
+    a.y = 2;
+    b = a;
+    b.y += a.x = 5;
+    return b.x + b.y + a.x + i + j;
+}
@@ -0,0 +1,15 @@
+int main() {
+    int n = 1;
+    int s = -16;
+    unsigned u = 4294967295u;
+    n += 10;
+    n -= 3;
+    n <<= 2;
+    n >>= 1;
+    n |= 5;
+    n &= 13;
+    n ^= 6;
+    s >>= 2;
+    u >>= 28;
+    return n + s + u;
+}
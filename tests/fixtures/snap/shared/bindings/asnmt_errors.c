@@ -0,0 +1,11 @@
+int f() { 1 = 2; }
+int f() { int x; x + 1 = 2; }
+int f() { const int x = 1; x = 2; }
+int f() { const int x = 1; x++; }
+int f() { int x; --(x + 1); }
+int f() { struct s { const int a; } v; v.a = 1; }
+int f() { const struct s { int a; } v; v.a += 1; }
+int f() { double d; d %= 2; }
+int f() { double d; d <<= 1; }
+int f() { struct s { int a; } v; v++; }
+int f() { struct s { int a; } v; int x; x = v; }
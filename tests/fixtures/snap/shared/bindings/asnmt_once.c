@@ -0,0 +1,11 @@
+struct p {
+    int x;
+};
+
+int bump(struct p *q) {
+    return q->x++;
+}
+
+int main() {
+    return 0;
+}
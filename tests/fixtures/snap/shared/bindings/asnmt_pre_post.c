@@ -0,0 +1,13 @@
+int main() {
+    int n = 5;
+    int a = n++;
+    int b = ++n;
+    int c = n--;
+    int d = --n;
+    char ch = 255;
+    double x = 1.5;
+    ch++;
+    x += 2;
+    x++;
+    return a + b + c + d + n + ch + (int)x;
+}
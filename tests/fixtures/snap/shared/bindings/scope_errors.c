@@ -0,0 +1,13 @@
+int f() { if (1) { int x = 9; } return x; }
+int f() { int x; int x; return 0; }
+int f() { int x; char x; return 0; }
+int f() { static int x; static int x; return 0; }
+int f() { int x; extern int x; return 0; }
+int f() { extern int x; extern char x; return 0; }
+int f(int a) { int a; return a; }
+int f(int a, int a) { return a; }
+int f() { { int y; } y = 1; return 0; }
+int f() { for (;;) { int i; } return i; }
+int x = y;
+int f() { int n = sizeof n + m; return n; }
+int f() { void *p = &q, *q = &p; return 0; }
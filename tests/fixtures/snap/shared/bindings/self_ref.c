@@ -0,0 +1,18 @@
+struct node {
+    int v;
+    struct node *next;
+};
+
+struct node head;
+void *self = &self;
+
+int main() {
+    struct node *n = sizeof *n == sizeof head ? &head : 0;
+    int k = sizeof k;
+    void *p = &p;
+    if (n != &head || self != &self || p != &p) {
+        return 1;
+    }
+    n->v = k;
+    return n->v + sizeof *n->next;
+}
@@ -0,0 +1,29 @@
+int x = 1;
+
+int f(int y) {
+    {
+        int y = 10;
+        x = x + y;
+    }
+    return x + y;
+}
+
+int main() {
+    int x = 2;
+    extern int y;
+    extern int y;
+    {
+        int x = 3;
+        {
+            char x = 4;
+            x = x + 1;
+        }
+        x = x * 10;
+        if (x != 30) {
+            return 1;
+        }
+    }
+    return x + f(5);
+}
+
+int y;
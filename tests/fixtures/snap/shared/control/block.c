@@ -0,0 +1,12 @@
+int main() {
+    int x = 1;
+    {
+        int x = 2;
+        ;
+        {
+            int y = 3;
+        }
+        if (x) if (0) return 4; else return x;
+    }
+    return x;
+}
@@ -0,0 +1,9 @@
+int main() {
+    do {
+        continue;
+        return 1;
+    } while (0);
+    for (;;)
+        break;
+    return 7;
+}
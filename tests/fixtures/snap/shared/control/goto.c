@@ -0,0 +1,21 @@
+int one() {
+    return 1;
+}
+
+void skip() {
+    goto out;
+    one();
+out:
+    return;
+}
+
+int main() {
+    skip();
+    goto end;
+    return one();
+end:
+    {
+    inner:
+        return 5;
+    }
+}
@@ -0,0 +1,10 @@
+int f() { a: a: return 0; }
+int f() { goto b; }
+int f() { for (int i = 0; i; ) ; }
+int f() { int x; switch (x) { case x: ; } }
+int f() { case 1: ; }
+int f() { switch (1) { case 1: case 1: ; } }
+int f() { default: ; }
+int f() { switch (1) { default: default: ; } }
+int f() { break; }
+int f() { switch (1) { continue; } }
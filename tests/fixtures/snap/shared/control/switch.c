@@ -0,0 +1,22 @@
+int pick(int n) {
+    switch (n) {
+    case 0:
+        return 10;
+    case 1:
+    case 2:
+        switch (n) {
+        case 1:
+            break;
+        default:
+            return 20;
+        }
+        return 30;
+    default:
+        break;
+    }
+    return 40;
+}
+
+int main() {
+    return pick(0) + pick(1) + pick(2) + pick(5);
+}
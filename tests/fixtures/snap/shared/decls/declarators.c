@@ -0,0 +1,6 @@
+int *p, **pp, a[3], (*pa)[5], m[2][3];
+const char *volatile s, *const *cs;
+int (*fp)(int, char *);
+void (*signal(int sig, void (*handler)(int)))(int);
+int f(void), g(), h(int a[], int m[][3], int k(void));
+int printf(const char *fmt, ...);
@@ -0,0 +1,19 @@
+int char x;
+unsigned float x;
+static extern int x;
+const const int x;
+int *const const p;
+auto int x;
+int f()[3];
+int f()();
+int a[2]();
+void v[3];
+int m[3][];
+int a[0];
+int a[4 - 5];
+int a[n];
+int f(void, int);
+int f(static int x);
+int f(int) { return 0; }
+int f(a) int b; { return 0; }
+int f(void) = 0;
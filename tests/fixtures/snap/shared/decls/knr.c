@@ -0,0 +1,10 @@
+int add(a, b, c)
+int a;
+char *b, c[];
+{
+    return a;
+}
+
+main() {
+    return 0;
+}
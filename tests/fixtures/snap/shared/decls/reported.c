@@ -0,0 +1,9 @@
+int f(void) {
+    int x = 1;
+    char x;
+    return x;
+}
+
+int main() {
+    return g(2) + y;
+}
@@ -0,0 +1,14 @@
+extern int errno;
+static const char c = 'a';
+unsigned long int ul;
+
+int main() {
+    register short s;
+    auto long unsigned int lu;
+    signed char sc;
+    const volatile unsigned u = 1;
+    long double ld;
+    static x;
+    int i, j = 2;
+    return 0;
+}
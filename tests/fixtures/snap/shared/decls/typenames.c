@@ -0,0 +1,7 @@
+int main() {
+    int n = sizeof(int);
+    unsigned long m = sizeof(char *[4]);
+    double d = (double)n;
+    int k = sizeof n + sizeof(int (*)(void));
+    return (int)(d + 1.5);
+}
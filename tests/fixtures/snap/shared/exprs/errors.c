@@ -0,0 +1,8 @@
+a +
+(a + b
+a ? b
+f(a,
+a[1
+x.
++
+sizeof
@@ -0,0 +1,6 @@
+1
+{1}
+{1, 2,}
+{{1, 2}, 3, {4}}
+{a = 1, b ? c : d}
+{"ab", {x}}
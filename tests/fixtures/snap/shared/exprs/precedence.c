@@ -0,0 +1,30 @@
+a, b = c, d
+a = b = c += d
+a ? b : c ? d : e
+a ? b, c : d
+a || b && c | d ^ e & f
+a == b != c < d
+a < b << c + d
+a - b - c
+a * b / c % d + e
+a + b * c
+-a * b
+!a && ~b
+(a + b) * c
+*p++
+&a[1]
+++*p
+--p->x
+a.b.c[i][j]
+(int)a + b
+(unsigned long)-a
+sizeof a + 1
+sizeof (int) * 2
+sizeof -a
+f(a, (b, c), d = e)
+a ? b : c = d
+x = a < b ? a : b
+a & b == c
+(*g)(1, 1)
+s->fn(x)(y)
+fs[0]()
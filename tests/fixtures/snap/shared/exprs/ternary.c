@@ -0,0 +1,4 @@
+int main() {
+    int neg = -5;
+    return (neg, 0) ? 1 : -neg + (1 ? 2 : 3) + +4;
+}
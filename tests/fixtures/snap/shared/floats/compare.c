@@ -0,0 +1,6 @@
+int main() {
+    double d = -0.5;
+    if (d)
+        return (d < 0.25) + (-d >= 0.5) + (d != d);
+    return 0;
+}
@@ -0,0 +1,9 @@
+int main() {
+    double a = 1.0;
+    double b = .5;
+    double c = 1e3;
+    float d = 1.5e-3f;
+    long double e = 2.L;
+    double f = 0.1;
+    return 0;
+}
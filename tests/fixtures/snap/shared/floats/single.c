@@ -0,0 +1,3 @@
+int main() {
+    return 2.5f * 2.0f - 1u;
+}
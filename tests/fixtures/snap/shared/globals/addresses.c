@@ -0,0 +1,37 @@
+struct pair {
+    int a;
+    int b;
+};
+
+int x = 5;
+int arr[4] = {1, 2, 3, 4};
+struct pair pr;
+char *msg = "hi";
+int *p = &x;
+int *q = arr + 1;
+int *last = &arr[3];
+int *pb = &pr.b;
+int size = sizeof pr;
+unsigned half = -1 / 2u;
+int below = -1 < 0u;
+int shifted = (unsigned)-8 >> 1;
+
+int seven(void) {
+    return 7;
+}
+
+int (*fp)(void) = seven;
+
+int main(void) {
+    static char *lp = "x";
+    static int *lq = &x;
+    if (msg[1] != 'i' || lp[0] != 'x')
+        return 1;
+    if (*p != 5 || *q != 2 || *last != 4 || *lq != 5)
+        return 2;
+    if (pb != &pr.b || size != 8)
+        return 3;
+    if (half != 2147483647u || below != 0 || shifted != 2147483644)
+        return 4;
+    return fp() + 35;
+}
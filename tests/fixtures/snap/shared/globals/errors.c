@@ -0,0 +1,15 @@
+int x = 1; int x = 2;
+int x; char x;
+int x; static int x;
+static int x; int x;
+int a[2] = {1, 2, 3};
+struct s { int a; } x = {1, 2};
+int x = {1, 2};
+char s[2] = "abc";
+int a[2] = 3;
+int y; int x = y;
+int g(void) { return 1; } int f(void) { static int s = g(); return s; }
+struct t x;
+int f(void) { int a[] = {1, {2, 3}}; return 0; }
+int f(void) { int x = {1, 2}; return x; }
+int f(void) { int a; static int *p = &a; return 0; }
@@ -0,0 +1,13 @@
+static int helper(int x);
+
+int helper(int x) {
+    return x + 1;
+}
+
+int api(int x) {
+    return helper(x) * 2;
+}
+
+int main(void) {
+    return api(20);
+}
@@ -0,0 +1,18 @@
+struct pair {
+    char c;
+    int n;
+    short s;
+};
+
+struct line {
+    struct pair a;
+    int w;
+};
+
+int main(void) {
+    struct pair p = {'a', 5};
+    struct line l = {1, 2, 3, 4};
+    struct line m = {{7}, 8};
+    int x = {9};
+    return p.c - 90 + p.n + p.s + l.a.c + l.a.n + l.a.s + l.w + m.a.c + m.a.n + m.w + x;
+}
@@ -0,0 +1,6 @@
+static int calls;
+
+int counted(int x) {
+    calls += 1;
+    return x + calls;
+}
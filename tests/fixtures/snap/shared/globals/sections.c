@@ -0,0 +1,21 @@
+struct point {
+    int x;
+    short y;
+    char tag;
+};
+
+int zeros[4];
+int primes[] = {2, 3, 5, 7, 11};
+int grid[2][3] = {1, 2, 3, {4}};
+char name[8] = "ada";
+char word[] = "hi";
+double ratio = 1.5;
+float half = 0.5f;
+struct point p = {3, -4, 'p'};
+const struct point origin = {1};
+static int limit = 16 - sizeof(struct point);
+unsigned char mask = -1;
+
+int main(void) {
+    return p.x + p.y + p.tag + origin.x + limit;
+}
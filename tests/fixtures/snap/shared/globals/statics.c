@@ -0,0 +1,12 @@
+int next(void) {
+    static int count = 10;
+    count += 1;
+    return count;
+}
+
+int main(void) {
+    static int unused;
+    next();
+    next();
+    return next();
+}
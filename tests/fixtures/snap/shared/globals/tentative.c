@@ -0,0 +1,17 @@
+int n;
+extern int total;
+int n;
+int total = 5;
+static int hidden;
+extern int hidden;
+
+int bump(void) {
+    n += 1;
+    hidden += 2;
+    return n + hidden;
+}
+
+int main(void) {
+    bump();
+    return bump() + total;
+}
@@ -0,0 +1,11 @@
+int main() {
+    int a = 'a';
+    int b = '\n';
+    int c = '\x41';
+    int d = '\101';
+    int e = '\0';
+    int f = '\'';
+    int g = '\xff';
+    int h = '"';
+    return a;
+}
@@ -0,0 +1,16 @@
+1foo
+08
+0x
+0xfu8
+4294967296
+99999999999999999999999
+''
+'ab'
+'\q'
+'\x100'
+'\777'
+"\x"
+1e
+1.5x
+1e99999
+1e39f
@@ -0,0 +1,12 @@
+int main() {
+    int a = 0x1F;
+    int b = 017;
+    int c = 10u;
+    int d = 10L;
+    int e = 10ul;
+    int f = 2147483647;
+    int g = 2147483648;
+    int h = 0x80000000;
+    int i = 4294967295U;
+    return 0;
+}
@@ -0,0 +1,5 @@
+int main() {
+    int s = "hello, " "world\n"
+            "tab\there \"quoted\" \x41\101\0";
+    return 0;
+}
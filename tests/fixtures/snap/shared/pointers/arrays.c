@@ -0,0 +1,56 @@
+struct rec {
+    int key;
+    short tag;
+    char name[5];
+};
+
+int sum(int *xs, int n) {
+    int total = 0;
+    int *end = xs + n;
+    while (xs < end)
+        total += *xs++;
+    return total;
+}
+
+int length(char s[]) {
+    char *p = s;
+    while (*p)
+        p++;
+    return p - s;
+}
+
+int main() {
+    int xs[5];
+    short hs[3];
+    struct rec rs[3];
+    int grid[3][4];
+    int i, j;
+    int n = 0;
+
+    for (i = 0; i < 5; i++)
+        xs[i] = i + 1;
+    if (sum(xs, 5) == 15 && sum(xs + 2, 2) == 7 && 3[xs] == 4)
+        n += 1;
+
+    /* pointers move by the size of what they point to, 12 bytes for a rec */
+    hs[2] = -2;
+    rs[2].tag = 9;
+    if (*(hs + 2) == -2 && (rs + 2)->tag == 9 && &rs[2] - rs == 2 && &hs[2] - &hs[0] == 2)
+        n += 2;
+
+    for (i = 0; i < 3; i++)
+        for (j = 0; j < 4; j++)
+            grid[i][j] = i + j;
+    if (grid[2][3] == 5 && *(grid[1] + 2) == 3 && sizeof(grid) == 48 && sizeof(grid[0]) == 16)
+        n += 4;
+
+    if (length("hello") == 5 && "abc"[1] == 'b' && sizeof("abc") == 4)
+        n += 8;
+
+    rs[1].name[0] = 'o';
+    rs[1].name[1] = 'k';
+    rs[1].name[2] = 0;
+    if (length(rs[1].name) == 2 && sizeof(rs[1].name) == 5)
+        n += 16;
+    return n;
+}
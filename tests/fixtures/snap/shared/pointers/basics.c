@@ -0,0 +1,52 @@
+struct point {
+    int x, y;
+};
+
+void swap(int *a, int *b) {
+    int t = *a;
+    *a = *b;
+    *b = t;
+}
+
+void *pass(void *p) {
+    return p;
+}
+
+int *nothing() {
+    return 0;
+}
+
+int main() {
+    int a = 3;
+    int b = 4;
+    int *p = &a;
+    int **pp = &p;
+    struct point pt;
+    struct point *q = &pt;
+    char c = 'x';
+    char *s = (char *)pass(&c);
+    int n = 0;
+
+    swap(&a, &b);
+    if (a == 4 && b == 3)
+        n += 1;
+
+    **pp = 7;
+    *p += 1;
+    if (a == 8)
+        n += 2;
+
+    q->x = 5;
+    (*q).y = 6;
+    if (pt.x + pt.y == 11 && &pt.y == &q->y)
+        n += 4;
+
+    /* void * converts to and from any object pointer */
+    p = pass(&b);
+    if (*p == 3 && *s == 'x')
+        n += 8;
+
+    if (nothing() == 0 && !nothing() && p != (void *)0 && p)
+        n += 16;
+    return n;
+}
@@ -0,0 +1,16 @@
+int f() { int x; return *x; }
+int f() { return &1; }
+int f() { int x; return x[0]; }
+int f() { int a[2]; double d; return a[d]; }
+int f() { int *p; int *q; return p + q; }
+int f() { int *p; char *q; return p - q; }
+int f() { int *p; char *q; return p < q; }
+int f() { int *p; return p == 1; }
+int f() { int *p; p = 1; }
+int f() { int *p; char *q; p = q; }
+int f() { const int *c; int *p; p = c; }
+int f() { int a[2]; int b[2]; a = b; }
+int f() { struct s *p; p + 1; }
+int f() { void *p; p++; }
+int *f() { return 1; }
+int f() { int *p; double d; d = (double)p; }
@@ -0,0 +1,8 @@
+int inc(int x) { return x + 1; }
+int twice(int x) { return 2 * x; }
+int apply(int (*f)(int), int x) { return f(x); }
+int main() {
+    int (*g)(int) = inc;
+    g = g == &inc ? twice : inc;
+    return apply(inc, 1) + (*g)(20);
+}
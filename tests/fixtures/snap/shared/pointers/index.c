@@ -0,0 +1,6 @@
+int main() {
+    int grid[2][3];
+    int *p = &grid[1][2];
+    *p = 7;
+    return grid[1][2] + (p - grid[0]);
+}
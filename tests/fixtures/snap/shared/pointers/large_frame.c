@@ -0,0 +1,14 @@
+int fill(int n) {
+    int a[1000];
+    int i, s;
+    for (i = 0; i < 1000; i++)
+        a[i] = i % n;
+    s = 0;
+    for (i = 0; i < 1000; i++)
+        s += a[i];
+    return s % 256;
+}
+
+int main(void) {
+    return fill(7);
+}
@@ -0,0 +1,33 @@
+struct pair {
+    int a;
+    int b;
+};
+
+int sum(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j) {
+    return a + b + c + d + e + f + g + h + 2 * i + 3 * j;
+}
+
+int split(int a, int b, int c, int d, int e, int f, int g, double x, int y) {
+    return a + b + c + d + e + f + g + (int)x + y;
+}
+
+int pairs(struct pair p, struct pair q, struct pair r, struct pair s, struct pair t) {
+    return p.a + q.b + r.a + s.b + t.a * t.b;
+}
+
+int old(c, x)
+int c;
+double x;
+{
+    return c + (int)(x * 2);
+}
+
+int main(void) {
+    struct pair p, t;
+    p.a = 1;
+    p.b = 2;
+    t.a = 3;
+    t.b = 4;
+    return sum(1, 1, 1, 1, 1, 1, 1, 1, 4, 5) + split(1, 1, 1, 1, 1, 1, 1, 2.5, 3) +
+           pairs(p, p, p, p, t) + old((char)5, 1.5f);
+}
@@ -0,0 +1,12 @@
+int add(int a, int b);
+int print(const char *fmt, ...);
+int old();
+
+int few(void) { return add(1); }
+int many(void) { return add(1, 2, 3, 4, 5); }
+int none(void) { return print(); }
+int wrong(void) { return add(1, "two"); }
+
+int main(void) {
+    return old(1, 2.0f) + print("%d %f", 1, 2.0f);
+}
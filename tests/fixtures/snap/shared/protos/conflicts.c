@@ -0,0 +1,16 @@
+int f(int);
+int g(double);
+
+int f(char c) {
+    return c;
+}
+
+int g(x)
+char *x;
+{
+    return 0;
+}
+
+int main(void) {
+    return f(1) + g(2.0);
+}
@@ -0,0 +1,9 @@
+int f(int); double f(int);
+int f(int); int f(int, int);
+int f(int); int f(char c) { return c; }
+int f(char); int f();
+int f(float x); int f(x) float x; { return 0; }
+int f(int, ...); int f();
+int f(void) { return 0; } int f(void) { return 1; }
+int f(void); int f;
+int f; int f(void);
@@ -0,0 +1,3 @@
+int main(void) {
+    return abs(-3) + labs(-4);
+}
@@ -0,0 +1,33 @@
+extern int add(int a, int b);
+int sub();
+int is_odd(int);
+
+int is_even(int n) {
+    if (n)
+        return is_odd(n - 1);
+    return 1;
+}
+
+int is_odd(int n) {
+    if (n)
+        return is_even(n - 1);
+    return 0;
+}
+
+int main(void) {
+    return add(is_even(10), is_odd(7)) + sub(9, 2) + half(9.0);
+}
+
+int add(int a, int b) {
+    return a + b;
+}
+
+int sub(a, b)
+int a, b;
+{
+    return a - b;
+}
+
+int half(double d) {
+    return d / 2;
+}
@@ -0,0 +1,10 @@
+#include <stdio.h>
+
+int main(void) {
+    short s = -2;
+    unsigned short us = 65535;
+    printf("[%hd] [%hi] [%hd] [%hd]\n", 65537, 98303, s, -32769);
+    printf("[%hu] [%hx] [%hX] [%ho] [%hu]\n", -1, 0x12345, 0xabcdef, 65536 + 8, us);
+    printf("[%6hd] [%-6hu|] [%#hx] [%+hd] [%d] [%u]\n", 70000, 70000, 0x1ffff, 32767, 65537, 65537u);
+    return 7;
+}
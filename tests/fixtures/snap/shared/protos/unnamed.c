@@ -0,0 +1,7 @@
+int first(int n, ...) {
+    return n;
+}
+
+int main(void) {
+    return first(1, 2.5) + first(2, 1, 2.5);
+}
@@ -0,0 +1,35 @@
+#include <stdarg.h>
+
+int printf(const char *fmt, ...);
+
+double sum(int n, ...) {
+    va_list ap;
+    double s = 0;
+    int i;
+    va_start(ap, n);
+    for (i = 0; i < n; i++)
+        s += va_arg(ap, double);
+    va_end(ap);
+    return s;
+}
+
+int count(const char *tag, int n, ...) {
+    va_list ap;
+    int s = 0;
+    va_start(ap, n);
+    while (n--)
+        s += va_arg(ap, int);
+    va_end(ap);
+    return s + tag[0];
+}
+
+int main(void) {
+    printf("%d %.1f\n", 1, 2.5);
+    if (sum(3, 1.0, 2.0, 3.5) != 6.5)
+        return 1;
+    if (sum(5, 1.0, 2.0, 3.0, 4.0, 5.0) != 15.0)
+        return 2;
+    if (count("a", 10, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10) != 55 + 'a')
+        return 3;
+    return 42;
+}
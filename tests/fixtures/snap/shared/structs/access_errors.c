@@ -0,0 +1,7 @@
+struct s; int main() { struct s x; return 0; }
+struct s; int f(struct s x) { return 0; }
+struct s; struct s f() { return f(); }
+int main() { int i; return i.x; }
+int main() { int i; return i->x; }
+struct p { int x; }; int main() { struct p v; return v.y; }
+struct s; int f(struct s *p) { return p->x; }
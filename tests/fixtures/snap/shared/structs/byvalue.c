@@ -0,0 +1,18 @@
+struct small { int a; int b; };
+struct large { int a; int b; int c; };
+
+struct small pass(struct small s) {
+    return s;
+}
+
+struct large pass_large(struct large l) {
+    return l;
+}
+
+int main() {
+    struct small s;
+    struct large l;
+    struct small t = pass(s);
+    struct large m = pass_large(l);
+    return t.b + m.c;
+}
@@ -0,0 +1,16 @@
+struct s { int x; int x; };
+struct s { int a : 3; };
+struct s { void v; };
+struct s { int f(void); };
+struct s { struct s inner; };
+struct s { static int x; };
+struct s { };
+struct s { int x; }; struct s { int y; };
+struct s { int x; }; union s u;
+enum e { A, B }; enum e { C };
+enum e x;
+enum { A, A };
+enum { A = 1.5 };
+enum { A = 2147483647, B };
+int A; enum { A };
+struct s; union s *p;
@@ -0,0 +1,11 @@
+struct padded { char c; int i; };
+struct wide { char a; double d; char b; };
+struct halves { char a; short s; char b; };
+union word { char bytes[5]; int i; };
+struct nested { struct halves h; char c; union word w; };
+struct node { int v; struct node *next; };
+struct { int x, y; } origin;
+enum color { RED, GREEN = 5, BLUE };
+enum { SMALL = sizeof(struct padded) * 2, LARGE = SMALL + BLUE };
+
+int squares[LARGE];
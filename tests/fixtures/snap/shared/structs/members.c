@@ -0,0 +1,20 @@
+struct point { int x; int y; };
+struct segment { struct point from; struct point to; double len; };
+
+int dx(struct segment s) {
+    return s.to.x - s.from.x;
+}
+
+int first(struct segment *s) {
+    return s->from.y;
+}
+
+double length(struct segment s) {
+    return s.len;
+}
+
+int main() {
+    struct segment s;
+    struct segment t = s;
+    return dx(t);
+}
@@ -0,0 +1,17 @@
+struct list;
+struct list *head;
+struct list { int len; struct list *next; };
+union u { int i; float f; };
+enum dir { NORTH, EAST = NORTH + 90, SOUTH = EAST * 2 };
+
+int main() {
+    struct list { char tag; };
+    enum { NORTH = 7 };
+    struct list l;
+    int a[NORTH];
+    return sizeof(l) + sizeof(a) + SOUTH;
+}
+
+int f(int SOUTH) {
+    return SOUTH;
+}
@@ -0,0 +1,11 @@
+typedef int T; typedef int T;
+typedef int T; int T;
+int T; typedef int T;
+typedef int T = 1;
+typedef int f(void) { return 0; }
+typedef static int T;
+int f(typedef int x);
+struct s { typedef int T; };
+typedef const int C; const C x;
+typedef volatile int V; const volatile V x;
+typedef int T; unsigned T x;
@@ -0,0 +1,29 @@
+typedef int T;
+typedef T *TP, TA[3];
+typedef struct point { T x, y; } point;
+typedef int (*binop)(int, int);
+typedef char name[16];
+typedef const T ct;
+typedef unsigned long long u64;
+
+int add(int a, int b);
+
+int apply(binop f, point p) {
+    return sizeof(f) + p.x;
+}
+
+int shadow(int T) {
+    return T * 2;
+}
+
+int main() {
+    T * p;
+    TP q;
+    TA a;
+    point pt;
+    name n;
+    volatile ct c = 3;
+    u64 big;
+    int T = sizeof(a) + sizeof(n) + sizeof(big);
+    return T * (int)sizeof(point);
+}
@@ -0,0 +1,5 @@
+/* a block comment
+ * spanning lines */
+int main() {
+    /**/ return /* inline * / */ 9; /* trailing ***/
+}
@@ -0,0 +1,5 @@
+// gnu89 only
+int main() {
+    return 9; // the answer \
+    is spliced into this comment
+}
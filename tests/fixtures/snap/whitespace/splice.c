@@ -0,0 +1,6 @@
+int ma\
+in() {
+    ret\
+urn 1\
+0;
+}
@@ -0,0 +1,4 @@
+int main() {
+    return 9; /* this comment
+    never ends
+}